@@ -29,13 +29,26 @@ pub struct Artifact {
 
     #[getset(get = "pub", get_mut = "pub", set = "pub")]
     content: ArtifactContent,
+
+    /// Binary content of packaged formats (e.g. EPUB), if it is set it is dumped in place of `content`
+    #[getset(get = "pub", set = "pub")]
+    binary_content: Option<Vec<u8>>,
 }
 
 impl Artifact {
     pub fn new(content: ArtifactContent) -> Self {
 
         Self {
-            content
+            content,
+            binary_content: None,
+        }
+    }
+
+    pub fn new_binary(binary_content: Vec<u8>) -> Self {
+
+        Self {
+            content: ArtifactContent::new(),
+            binary_content: Some(binary_content),
         }
     }
 }
@@ -77,7 +90,10 @@ impl Dumpable for Artifact {
             disk_resource.create_parents_dir()?;
         }
 
-        disk_resource.write(&self.content)?;
+        match &self.binary_content {
+            Some(binary_content) => disk_resource.write_bytes(binary_content)?,
+            None => disk_resource.write(&self.content)?,
+        }
 
         Ok(())
     }
@@ -2,12 +2,16 @@
 
 pub mod assembler_configuration;
 pub mod html_assembler;
+pub mod epub_assembler;
+pub mod docx_assembler;
+pub mod text_assembler;
+pub mod man_assembler;
 
 
 use std::fmt::Debug;
 use assembler_configuration::AssemblerConfiguration;
 use thiserror::Error;
-use crate::{compilation::compilation_outcome::CompilationOutcome, dossier::{document::chapter::chapter_tag::ChapterTag, dossier_configuration::DossierConfiguration}, resource::ResourceError};
+use crate::{compilation::compilation_outcome::CompilationOutcome, dossier::{document::{chapter::chapter_tag::ChapterTag, document_metadata::DocumentMetadata}, dossier_configuration::DossierConfiguration}, resource::ResourceError, table_of_contents::TableOfContents};
 use super::artifact::ArtifactError;
 
 
@@ -24,19 +28,33 @@ pub enum AssemblerError {
 
     #[error(transparent)]
     ResourceError(#[from] ResourceError),
+
+    #[error("package cannot be built: {0}")]
+    PackageError(String),
 }
 
 
 pub trait Assembler: Debug + Sync + Send {
 
-    /// Assemble dossier
-    fn assemble_dossier(&self, compiled_documents: &Vec<CompilationOutcome>, compiled_toc: Option<&CompilationOutcome>, compiled_bib: Option<&CompilationOutcome>, dossier_configuration: &DossierConfiguration, configuration: &AssemblerConfiguration) -> Result<String, AssemblerError>;
+    /// Assemble dossier: table of contents, documents, bibliography and index
+    fn assemble_dossier(&self, compiled_documents: &Vec<CompilationOutcome>, compiled_toc: Option<&CompilationOutcome>, compiled_bib: Option<&CompilationOutcome>, compiled_index: Option<&CompilationOutcome>, dossier_configuration: &DossierConfiguration, configuration: &AssemblerConfiguration) -> Result<String, AssemblerError>;
+
+    /// Assemble dossier as a package of files (e.g. EPUB). `table_of_contents` has all dossier headings, also if it is not included in output.
+    /// By default, package is the assembled dossier
+    #[allow(clippy::too_many_arguments)]
+    fn assemble_dossier_package(&self, compiled_documents: &Vec<CompilationOutcome>, compiled_toc: Option<&CompilationOutcome>, compiled_bib: Option<&CompilationOutcome>, compiled_index: Option<&CompilationOutcome>, _table_of_contents: &TableOfContents, dossier_configuration: &DossierConfiguration, configuration: &AssemblerConfiguration) -> Result<Vec<u8>, AssemblerError> {
+        Ok(self.assemble_dossier(compiled_documents, compiled_toc, compiled_bib, compiled_index, dossier_configuration, configuration)?.into_bytes())
+    }
+
+    /// Assemble a group of documents of a sub-dossier, i.e. a section with `title` (which has `id`) followed by documents
+    fn assemble_dossier_section(&self, title: &str, id: &str, compiled_documents: &[CompilationOutcome], configuration: &AssemblerConfiguration) -> Result<String, AssemblerError>;
 
-    /// Assemble document
-    // fn assemble_document(&self, document: &CompilationOutcome) -> Result<String, AssemblerError>;
+    /// Assemble document, i.e. its title block (built from metadata) and its compiled content
+    fn assemble_document(&self, document_metadata: &DocumentMetadata, compiled_content: &CompilationOutcome, configuration: &AssemblerConfiguration) -> Result<String, AssemblerError>;
 
-    /// Assemble a standalone document, so `page_title`, `styles_references`, `toc` and `bibliography` are needed
-    fn assemble_document_standalone(&self, page_title: &str, complied_document: &CompilationOutcome, compiled_toc: Option<&CompilationOutcome>, compiled_bib: Option<&CompilationOutcome>, configuration: &AssemblerConfiguration) -> Result<String, AssemblerError>;
+    /// Assemble a standalone document, so `page_title`, `styles_references`, `toc` and `bibliography` are needed.
+    /// Metadata title (if any) takes precedence over `page_title`
+    fn assemble_document_standalone(&self, page_title: &str, document_metadata: &DocumentMetadata, complied_document: &CompilationOutcome, compiled_toc: Option<&CompilationOutcome>, compiled_bib: Option<&CompilationOutcome>, configuration: &AssemblerConfiguration) -> Result<String, AssemblerError>;
 
     fn assemble_bundle(&self, compiled_document: &Vec<CompilationOutcome>, compiled_chapters: &Vec<CompilationOutcome>, configuration: &AssemblerConfiguration) -> Result<String, AssemblerError>;
 
@@ -3,6 +3,10 @@ use getset::{CopyGetters, Getters, MutGetters, Setters};
 use crate::{dossier::dossier_configuration::DossierConfiguration, theme::Theme};
 
 
+/// Default maximum line width of plain text outputs
+pub const DEFAULT_TEXT_WIDTH: usize = 80;
+
+
 #[derive(Debug, Clone, Getters, CopyGetters, MutGetters, Setters)]
 pub struct AssemblerConfiguration {
 
@@ -26,6 +30,10 @@ pub struct AssemblerConfiguration {
 
     #[getset(get = "pub", get_mut = "pub", set = "pub")]
     external_scripts: Vec<String>,
+
+    /// Maximum line width (in characters) of plain text outputs
+    #[getset(get_copy = "pub", set = "pub")]
+    text_width: usize,
 }
 
 impl AssemblerConfiguration {
@@ -51,6 +59,7 @@ impl Default for AssemblerConfiguration {
             external_styles: Vec::new(),
             external_scripts_paths: Vec::new(),
             external_scripts: Vec::new(),
+            text_width: DEFAULT_TEXT_WIDTH,
         }
     }
 }
@@ -61,6 +70,7 @@ impl From<&DossierConfiguration> for AssemblerConfiguration {
             theme: dossier_configuration.style().theme().clone(),
             use_remote_addons: dossier_configuration.compilation().use_remote_addons(),
             parallelization: dossier_configuration.compilation().parallelization(),
+            text_width: dossier_configuration.style().text_width(),
 
             ..Default::default()
         }
@@ -0,0 +1,1410 @@
+//! DOCX (Office Open XML) assembler: documents are compiled as HTML (using `HtmlAssembler`), then they are converted
+//! in WordprocessingML and packaged together with styles, numbering definitions and images
+
+use std::{collections::HashMap, io::{Cursor, Write}};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use zip::{write::SimpleFileOptions, ZipWriter};
+use crate::{compilation::compilation_outcome::CompilationOutcome, dossier::{document::{chapter::chapter_tag::ChapterTag, document_metadata::DocumentMetadata}, dossier_configuration::{dossier_configuration_table_of_contents::DossierConfigurationTableOfContents, DossierConfiguration}}, resource::variable, table_of_contents::TableOfContents, utility::{html_utility::{self, escape_xml, focus_block_kind, is_block, split_focus_block_title, HtmlElement, HtmlNode, TableSlot, BIBLIOGRAPHY_ITEM_FIELD_CLASSES, WHITESPACES_REGEX}, image_utility}};
+use super::{assembler_configuration::AssemblerConfiguration, html_assembler::HtmlAssembler, Assembler, AssemblerError};
+
+
+const STYLES_XML: &str = include_str!("docx_assembler/styles.xml");
+
+const DOCUMENT_NAMESPACES: &str = concat!(
+    r#"xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main" "#,
+    r#"xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships" "#,
+    r#"xmlns:wp="http://schemas.openxmlformats.org/drawingml/2006/wordprocessingDrawing" "#,
+    r#"xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" "#,
+    r#"xmlns:pic="http://schemas.openxmlformats.org/drawingml/2006/picture""#,
+);
+
+const RELATIONSHIP_TYPE_PREFIX: &str = "http://schemas.openxmlformats.org/officeDocument/2006/relationships";
+
+/// Letter page with 1 inch margins
+const SECTION_PROPERTIES: &str = r#"<w:sectPr><w:pgSz w:w="12240" w:h="15840"/><w:pgMar w:top="1440" w:right="1440" w:bottom="1440" w:left="1440" w:header="708" w:footer="708" w:gutter="0"/></w:sectPr>"#;
+
+/// Width of text area (page width without margins) in twentieths of a point
+const TEXT_WIDTH_TWIPS: u64 = 9360;
+
+const EMU_PER_PIXEL: u64 = 9525;
+
+/// Width of text area in EMU, larger images are scaled
+const MAX_IMAGE_WIDTH_EMU: u64 = TEXT_WIDTH_TWIPS * 635;
+
+/// Size (in pixels) of images whose size cannot be read
+const DEFAULT_IMAGE_SIZE: (u32, u32) = (480, 320);
+
+const MAX_LIST_LEVEL: usize = 8;
+
+const BULLETS: [&str; 3] = ["\u{2022}", "\u{25E6}", "\u{25AA}"];
+
+/// Numbering id of bullet lists
+const BULLET_NUMBERING_ID: usize = 1;
+
+/// Formats of ordered lists, the position is the abstract numbering id (`0` is used by bullets)
+const ORDERED_NUMBERING_FORMATS: [&str; 5] = ["decimal", "lowerLetter", "upperLetter", "lowerRoman", "upperRoman"];
+
+const FIXED_RELATIONSHIPS: [(&str, &str); 3] = [("styles", "styles.xml"), ("numbering", "numbering.xml"), ("settings", "settings.xml")];
+
+/// Shading and border color of focus blocks by type
+const FOCUS_BLOCK_COLORS: [(&str, &str, &str); 7] = [
+    ("note", "E7F0FD", "2F6FDE"),
+    ("tip", "E6F6EA", "2DA44E"),
+    ("important", "F1E9FD", "8250DF"),
+    ("warning", "FFF5DB", "BF8700"),
+    ("caution", "FDE8E8", "CF222E"),
+    ("todo", "FFF5DB", "BF8700"),
+    ("quote", "F2F2F2", "8C8C8C"),
+];
+
+static ORDERED_BULLET_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"^\s*(\d+|[a-zA-Z]|[ivxlcdm]+|[IVXLCDM]+)[.)]\s*$").unwrap());
+
+static CSS_WIDTH_PERCENTAGE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"width:\s*([\d.]+)%").unwrap());
+
+
+/// Remove characters which are not allowed in XML
+fn escape_text(s: &str) -> String {
+    escape_xml(&s.chars().filter(|c| !c.is_control() || ['\t', '\n', '\r'].contains(c)).collect::<String>())
+}
+
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+fn roman_to_number(roman: &str) -> Option<usize> {
+
+    let value = |c: char| match c.to_ascii_lowercase() {
+        'i' => Some(1), 'v' => Some(5), 'x' => Some(10), 'l' => Some(50), 'c' => Some(100), 'd' => Some(500), 'm' => Some(1000),
+        _ => None,
+    };
+
+    let values: Vec<isize> = roman.chars().map(value).collect::<Option<Vec<isize>>>()?;
+
+    let number = values.iter().enumerate().fold(0, |total, (index, v)| {
+        if values.get(index + 1).is_some_and(|next| next > v) { total - v } else { total + v }
+    });
+
+    usize::try_from(number).ok()
+}
+
+/// Numbering format (index of `ORDERED_NUMBERING_FORMATS`) and start of an ordered list bullet (e.g. `1.`, `b)`, `IV.`)
+fn ordered_bullet(bullet: &str) -> Option<(usize, usize)> {
+
+    let value = ORDERED_BULLET_REGEX.captures(bullet)?.get(1)?.as_str();
+
+    if let Ok(n) = value.parse::<usize>() {
+        return Some((0, n))
+    }
+
+    let is_lower = value.chars().all(|c| c.is_lowercase());
+
+    // single letters are roman numbers only if they are `i`
+    if value.len() == 1 && !value.eq_ignore_ascii_case("i") {
+        let n = value.to_ascii_lowercase().chars().next()? as usize - 'a' as usize + 1;
+        return Some((if is_lower { 1 } else { 2 }, n))
+    }
+
+    Some((if is_lower { 3 } else { 4 }, roman_to_number(value)?))
+}
+
+
+/// Properties of a WordprocessingML paragraph (serialized in schema order)
+#[derive(Debug, Clone, Default)]
+struct ParagraphProperties {
+    style: Option<String>,
+    keep_next: bool,
+    numbering: Option<(usize, usize)>,
+    border: Option<String>,
+    shading: Option<String>,
+    indentation: Option<usize>,
+    alignment: Option<String>,
+}
+
+impl ParagraphProperties {
+
+    fn with_style(style: &str) -> Self {
+        Self {
+            style: Some(style.to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn to_xml(&self) -> String {
+
+        let mut xml = String::new();
+
+        if let Some(style) = &self.style {
+            xml.push_str(&format!(r#"<w:pStyle w:val="{}"/>"#, style));
+        }
+
+        if self.keep_next {
+            xml.push_str("<w:keepNext/>");
+        }
+
+        if let Some((numbering_id, level)) = self.numbering {
+            xml.push_str(&format!(r#"<w:numPr><w:ilvl w:val="{}"/><w:numId w:val="{}"/></w:numPr>"#, level, numbering_id));
+        }
+
+        if let Some(border) = &self.border {
+            xml.push_str(&format!("<w:pBdr>{}</w:pBdr>", border));
+        }
+
+        if let Some(fill) = &self.shading {
+            xml.push_str(&format!(r#"<w:shd w:val="clear" w:color="auto" w:fill="{}"/>"#, fill));
+        }
+
+        if let Some(indentation) = self.indentation {
+            xml.push_str(&format!(r#"<w:ind w:left="{}"/>"#, indentation));
+        }
+
+        if let Some(alignment) = &self.alignment {
+            xml.push_str(&format!(r#"<w:jc w:val="{}"/>"#, alignment));
+        }
+
+        if xml.is_empty() {
+            return xml
+        }
+
+        format!("<w:pPr>{}</w:pPr>", xml)
+    }
+}
+
+
+/// Properties of a WordprocessingML run (serialized in schema order)
+#[derive(Debug, Clone, Default)]
+struct RunProperties {
+    hyperlink: bool,
+    code: bool,
+    bold: bool,
+    italic: bool,
+    strike: bool,
+    highlight: bool,
+    underline: bool,
+    superscript: bool,
+    subscript: bool,
+}
+
+impl RunProperties {
+
+    fn to_xml(&self) -> String {
+
+        let mut xml = String::new();
+
+        if self.hyperlink {
+            xml.push_str(r#"<w:rStyle w:val="Hyperlink"/>"#);
+        } else if self.code {
+            xml.push_str(r#"<w:rStyle w:val="InlineCode"/>"#);
+        }
+
+        if self.bold {
+            xml.push_str("<w:b/>");
+        }
+
+        if self.italic {
+            xml.push_str("<w:i/>");
+        }
+
+        if self.strike {
+            xml.push_str("<w:strike/>");
+        }
+
+        if self.highlight {
+            xml.push_str(r#"<w:highlight w:val="yellow"/>"#);
+        }
+
+        if self.underline {
+            xml.push_str(r#"<w:u w:val="single"/>"#);
+        }
+
+        if self.superscript {
+            xml.push_str(r#"<w:vertAlign w:val="superscript"/>"#);
+        } else if self.subscript {
+            xml.push_str(r#"<w:vertAlign w:val="subscript"/>"#);
+        }
+
+        if xml.is_empty() {
+            return xml
+        }
+
+        format!("<w:rPr>{}</w:rPr>", xml)
+    }
+}
+
+
+#[derive(Debug, Clone)]
+struct DocxRelationship {
+    id: String,
+    kind: String,
+    target: String,
+    external: bool,
+}
+
+#[derive(Debug, Clone)]
+struct DocxMedia {
+    file_name: String,
+    bytes: Vec<u8>,
+}
+
+/// Embedded (or linked) image: relationship id and size in EMU
+#[derive(Debug, Clone)]
+struct DocxImage {
+    relationship_id: String,
+    external: bool,
+    size: (u64, u64),
+}
+
+/// Converter of compiled HTML in WordprocessingML, it collects relationships, media and numbering instances
+#[derive(Debug, Default)]
+struct DocxConverter {
+
+    /// HTML ids and their bookmark names
+    bookmarks: HashMap<String, String>,
+
+    /// Bookmarks which will be placed at the beginning of next paragraph
+    pending_bookmarks: Vec<String>,
+
+    next_bookmark_id: usize,
+
+    relationships: Vec<DocxRelationship>,
+
+    media: Vec<DocxMedia>,
+
+    images: HashMap<String, DocxImage>,
+
+    /// Abstract numbering and start of ordered lists (numbering ids start from `BULLET_NUMBERING_ID + 1`)
+    ordered_numberings: Vec<(usize, usize)>,
+
+    next_drawing_id: usize,
+
+    toc_maximum_heading_level: usize,
+}
+
+impl DocxConverter {
+
+    fn new(toc_maximum_heading_level: usize) -> Self {
+        Self {
+            toc_maximum_heading_level,
+            ..Default::default()
+        }
+    }
+
+    fn add_relationship(&mut self, kind: &str, target: String, external: bool) -> String {
+
+        let id = format!("rId{}", FIXED_RELATIONSHIPS.len() + self.relationships.len() + 1);
+
+        self.relationships.push(DocxRelationship {
+            id: id.clone(),
+            kind: kind.to_string(),
+            target,
+            external,
+        });
+
+        id
+    }
+
+    /// Assign a bookmark name to each element which has `id`
+    fn collect_bookmarks(&mut self, element: &HtmlElement) {
+
+        if let Some(id) = element.attribute("id") {
+            if !id.is_empty() && !self.bookmarks.contains_key(id) {
+                let name = format!("nmd_{}", self.bookmarks.len() + 1);
+                self.bookmarks.insert(id.clone(), name);
+            }
+        }
+
+        for child in element.child_elements() {
+            self.collect_bookmarks(child);
+        }
+    }
+
+    fn bookmark_xml(&mut self, name: &str) -> String {
+
+        self.next_bookmark_id += 1;
+
+        format!(r#"<w:bookmarkStart w:id="{id}" w:name="{name}"/><w:bookmarkEnd w:id="{id}"/>"#, id = self.next_bookmark_id, name = name)
+    }
+
+    fn mark_bookmark(&mut self, element: &HtmlElement) {
+        if let Some(name) = element.attribute("id").and_then(|id| self.bookmarks.get(id)) {
+            self.pending_bookmarks.push(name.clone());
+        }
+    }
+
+    fn take_pending_bookmarks(&mut self) -> String {
+        std::mem::take(&mut self.pending_bookmarks).iter().map(|name| self.bookmark_xml(name)).collect()
+    }
+
+    fn paragraph(&mut self, properties: &ParagraphProperties, runs: &str) -> String {
+        format!("<w:p>{}{}{}</w:p>", properties.to_xml(), self.take_pending_bookmarks(), runs)
+    }
+
+    fn text_run(text: &str, properties: &RunProperties, preserve: bool) -> String {
+
+        if text.is_empty() {
+            return String::new()
+        }
+
+        let mut content = String::new();
+
+        if preserve {
+
+            for (index, line) in text.split('\n').enumerate() {
+
+                if index > 0 {
+                    content.push_str("<w:br/>");
+                }
+
+                for (index, part) in line.split('\t').enumerate() {
+
+                    if index > 0 {
+                        content.push_str("<w:tab/>");
+                    }
+
+                    if !part.is_empty() {
+                        content.push_str(&format!(r#"<w:t xml:space="preserve">{}</w:t>"#, escape_text(part)));
+                    }
+                }
+            }
+
+        } else {
+            content.push_str(&format!(r#"<w:t xml:space="preserve">{}</w:t>"#, escape_text(&WHITESPACES_REGEX.replace_all(text, " "))));
+        }
+
+        format!("<w:r>{}{}</w:r>", properties.to_xml(), content)
+    }
+
+    fn is_blank(nodes: &[&HtmlNode]) -> bool {
+        nodes.iter().all(|node| match node {
+            HtmlNode::Text(text) => text.trim().is_empty(),
+            HtmlNode::Element(_) => false,
+        })
+    }
+
+    fn load_image(&mut self, src: &str) -> Option<DocxImage> {
+
+        if let Some(image) = self.images.get(src) {
+            return Some(image.clone())
+        }
+
+        let image = if src.starts_with("http://") || src.starts_with("https://") {
+
+            let relationship_id = self.add_relationship("image", src.to_string(), true);
+
+            DocxImage { relationship_id, external: true, size: Self::image_size(DEFAULT_IMAGE_SIZE) }
+
+        } else {
+
+            let (media_type, bytes) = image_utility::read_image_src(src)?;
+
+            if !["image/png", "image/jpeg", "image/gif", "image/bmp", "image/tiff"].contains(&media_type.as_str()) {
+                log::warn!("image '{}' has an unsupported type ({}) for DOCX output", src, media_type);
+                return None
+            }
+
+            let size = Self::image_size(image_utility::image_dimensions(&bytes).unwrap_or(DEFAULT_IMAGE_SIZE));
+
+            let file_name = format!("image{}.{}", self.media.len() + 1, image_utility::media_type_extension(&media_type));
+
+            let relationship_id = self.add_relationship("image", format!("media/{}", file_name), false);
+
+            self.media.push(DocxMedia { file_name, bytes });
+
+            DocxImage { relationship_id, external: false, size }
+        };
+
+        self.images.insert(src.to_string(), image.clone());
+
+        Some(image)
+    }
+
+    fn image_size((width, height): (u32, u32)) -> (u64, u64) {
+
+        let width = (width.max(1) as u64) * EMU_PER_PIXEL;
+        let height = (height.max(1) as u64) * EMU_PER_PIXEL;
+
+        if width <= MAX_IMAGE_WIDTH_EMU {
+            return (width, height)
+        }
+
+        (MAX_IMAGE_WIDTH_EMU, height * MAX_IMAGE_WIDTH_EMU / width)
+    }
+
+    fn image_run(&mut self, element: &HtmlElement) -> String {
+
+        let src = element.attribute("src").cloned().unwrap_or_default();
+        let alt = element.attribute("alt").cloned().unwrap_or_default();
+
+        let image = match self.load_image(&src) {
+            Some(image) => image,
+            None => {
+                log::warn!("image '{}' cannot be added to DOCX package", src);
+                return Self::text_run(&format!("[{}]", if alt.is_empty() { &src } else { &alt }), &RunProperties::default(), false)
+            },
+        };
+
+        self.next_drawing_id += 1;
+
+        let (cx, cy) = image.size;
+
+        format!(
+            concat!(
+                r#"<w:r><w:drawing><wp:inline distT="0" distB="0" distL="0" distR="0"><wp:extent cx="{cx}" cy="{cy}"/>"#,
+                r#"<wp:docPr id="{id}" name="Picture {id}" descr="{alt}"/>"#,
+                r#"<wp:cNvGraphicFramePr><a:graphicFrameLocks noChangeAspect="1"/></wp:cNvGraphicFramePr>"#,
+                r#"<a:graphic><a:graphicData uri="http://schemas.openxmlformats.org/drawingml/2006/picture"><pic:pic>"#,
+                r#"<pic:nvPicPr><pic:cNvPr id="{id}" name="Picture {id}"/><pic:cNvPicPr/></pic:nvPicPr>"#,
+                r#"<pic:blipFill><a:blip {blip_attribute}="{rid}"/><a:stretch><a:fillRect/></a:stretch></pic:blipFill>"#,
+                r#"<pic:spPr><a:xfrm><a:off x="0" y="0"/><a:ext cx="{cx}" cy="{cy}"/></a:xfrm><a:prstGeom prst="rect"><a:avLst/></a:prstGeom></pic:spPr>"#,
+                r#"</pic:pic></a:graphicData></a:graphic></wp:inline></w:drawing></w:r>"#,
+            ),
+            cx = cx,
+            cy = cy,
+            id = self.next_drawing_id,
+            alt = escape_text(&alt),
+            blip_attribute = if image.external { "r:link" } else { "r:embed" },
+            rid = image.relationship_id,
+        )
+    }
+
+    fn runs(&mut self, nodes: &[&HtmlNode], properties: &RunProperties, preserve: bool) -> String {
+        nodes.iter().map(|node| self.node_runs(node, properties, preserve)).collect()
+    }
+
+    fn element_runs(&mut self, element: &HtmlElement, properties: &RunProperties, preserve: bool) -> String {
+        let children: Vec<&HtmlNode> = element.children.iter().collect();
+        self.runs(&children, properties, preserve)
+    }
+
+    /// Convert inline content in runs
+    fn node_runs(&mut self, node: &HtmlNode, properties: &RunProperties, preserve: bool) -> String {
+
+        let element = match node {
+            HtmlNode::Text(text) => return Self::text_run(text, properties, preserve),
+            HtmlNode::Element(element) => element,
+        };
+
+        let mut runs = String::new();
+
+        // inline elements with id (e.g. identifiers) are bookmarked in place
+        if let Some(name) = element.attribute("id").and_then(|id| self.bookmarks.get(id)).cloned() {
+            runs.push_str(&self.bookmark_xml(&name));
+        }
+
+        let mut properties = properties.clone();
+
+        match element.name.as_str() {
+            "strong" | "b" => properties.bold = true,
+            "em" | "i" => properties.italic = true,
+            "u" | "ins" => properties.underline = true,
+            "del" | "s" | "strike" => properties.strike = true,
+            "mark" => properties.highlight = true,
+            "sup" => properties.superscript = true,
+            "sub" => properties.subscript = true,
+            "code" | "kbd" | "samp" => properties.code = true,
+            "span" if element.has_class("inline-math") => properties.code = true,
+            "br" => return format!("{}<w:r><w:br/></w:r>", runs),
+            "img" => return format!("{}{}", runs, self.image_run(element)),
+            "input" if element.attribute("type").is_some_and(|t| t.eq("checkbox")) => {
+                let symbol = if element.attribute("checked").is_some() { "\u{2611} " } else { "\u{2610} " };
+                return format!("{}{}", runs, Self::text_run(symbol, &properties, false))
+            },
+            "div" if element.has_class("checkbox") => {
+                let symbol = if element.has_class("checkbox-checked") { "\u{2611} " } else { "\u{2610} " };
+                return format!("{}{}", runs, Self::text_run(symbol, &properties, false))
+            },
+            "a" => return format!("{}{}", runs, self.link_runs(element, &properties, preserve)),
+            _ => (),
+        }
+
+        runs.push_str(&self.element_runs(element, &properties, preserve));
+
+        runs
+    }
+
+    fn link_runs(&mut self, element: &HtmlElement, properties: &RunProperties, preserve: bool) -> String {
+
+        // cites are plain references to bibliography
+        if element.has_class("cite") {
+            return Self::text_run(&format!("[{}]", element.text().trim()), properties, preserve)
+        }
+
+        let href = element.attribute("href").cloned().unwrap_or_default();
+
+        if properties.hyperlink || href.is_empty() {
+            return self.element_runs(element, properties, preserve)
+        }
+
+        let mut link_properties = properties.clone();
+        link_properties.hyperlink = true;
+
+        if let Some(id) = href.strip_prefix('#') {
+
+            return match self.bookmarks.get(id).cloned() {
+                Some(name) => format!(r#"<w:hyperlink w:anchor="{}">{}</w:hyperlink>"#, name, self.element_runs(element, &link_properties, preserve)),
+                None => self.element_runs(element, properties, preserve),
+            }
+        }
+
+        let relationship_id = self.add_relationship("hyperlink", href, true);
+
+        format!(r#"<w:hyperlink r:id="{}">{}</w:hyperlink>"#, relationship_id, self.element_runs(element, &link_properties, preserve))
+    }
+
+    /// Convert content of `element` in block-level elements (paragraphs and tables)
+    fn blocks(&mut self, element: &HtmlElement, properties: &ParagraphProperties) -> String {
+
+        let mut xml = String::new();
+        let mut pending: Vec<&HtmlNode> = Vec::new();
+
+        for child in &element.children {
+
+            if !is_block(child) {
+                pending.push(child);
+                continue;
+            }
+
+            xml.push_str(&self.flush_inline(&mut pending, properties));
+
+            if let HtmlNode::Element(child) = child {
+                xml.push_str(&self.block(child, properties));
+            }
+        }
+
+        xml.push_str(&self.flush_inline(&mut pending, properties));
+
+        xml
+    }
+
+    fn flush_inline(&mut self, pending: &mut Vec<&HtmlNode>, properties: &ParagraphProperties) -> String {
+
+        if Self::is_blank(pending) {
+            pending.clear();
+            return String::new()
+        }
+
+        let runs = self.runs(pending, &RunProperties::default(), false);
+
+        pending.clear();
+
+        self.paragraph(properties, &runs)
+    }
+
+    fn block(&mut self, element: &HtmlElement, properties: &ParagraphProperties) -> String {
+
+        self.mark_bookmark(element);
+
+        match element.name.as_str() {
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+
+                let style = if element.has_class("document-title") {
+                    String::from("Title")
+                } else {
+                    format!("Heading{}", &element.name[1..])
+                };
+
+                let runs = self.element_runs(element, &RunProperties::default(), false);
+
+                self.paragraph(&ParagraphProperties::with_style(&style), &runs)
+            },
+            "p" if element.has_class("math-block") => self.preformatted(element),
+            "p" if element.has_class("document-authors") || element.has_class("document-date") => {
+                let runs = self.element_runs(element, &RunProperties::default(), false);
+                self.paragraph(&ParagraphProperties::with_style("Subtitle"), &runs)
+            },
+            "p" | "dt" | "dd" | "address" => {
+                let runs = self.element_runs(element, &RunProperties::default(), false);
+                self.paragraph(properties, &runs)
+            },
+            "pre" => self.preformatted(element),
+            "hr" => self.paragraph(&ParagraphProperties {
+                border: Some(String::from(r#"<w:bottom w:val="single" w:sz="6" w:space="1" w:color="auto"/>"#)),
+                ..Default::default()
+            }, ""),
+            "ul" | "ol" => self.list(element, properties),
+            "table" => self.table(element),
+            "figure" => self.figure(element),
+            "figcaption" | "caption" => {
+                let runs = self.element_runs(element, &RunProperties::default(), false);
+                self.paragraph(&ParagraphProperties::with_style("Caption"), &runs)
+            },
+            "blockquote" => self.blocks(element, &ParagraphProperties::with_style("Quote")),
+            "section" if element.has_class("toc") => self.table_of_contents(element),
+            "section" if element.has_class("bibliography") => self.bibliography(element),
+            "div" if element.has_class("page-break") => format!(r#"<w:p>{}<w:r><w:br w:type="page"/></w:r></w:p>"#, self.take_pending_bookmarks()),
+            "div" if element.has_class("table-caption") => {
+                let runs = self.element_runs(element, &RunProperties::default(), false);
+                self.paragraph(&ParagraphProperties::with_style("Caption"), &runs)
+            },
+            "div" if focus_block_kind(element).is_some() => self.focus_block(element),
+            _ => self.blocks(element, properties),
+        }
+    }
+
+    /// Code and math blocks, whitespaces are preserved
+    fn preformatted(&mut self, element: &HtmlElement) -> String {
+
+        let text = element.text();
+
+        let runs = Self::text_run(text.trim_matches('\n'), &RunProperties::default(), true);
+
+        self.paragraph(&ParagraphProperties::with_style("CodeBlock"), &runs)
+    }
+
+    fn figure(&mut self, element: &HtmlElement) -> String {
+
+        let mut xml = String::new();
+
+        let images: Vec<&HtmlNode> = element.children.iter().filter(|child| matches!(child, HtmlNode::Element(e) if e.name.eq("img"))).collect();
+
+        if !images.is_empty() {
+
+            let runs = self.runs(&images, &RunProperties::default(), false);
+
+            xml.push_str(&self.paragraph(&ParagraphProperties { alignment: Some(String::from("center")), keep_next: true, ..Default::default() }, &runs));
+        }
+
+        for child in element.child_elements().filter(|child| !child.name.eq("img")) {
+            xml.push_str(&self.block(child, &ParagraphProperties::default()));
+        }
+
+        xml
+    }
+
+    fn focus_block(&mut self, element: &HtmlElement) -> String {
+
+        let kind = focus_block_kind(element).unwrap_or(String::from("quote"));
+
+        let (_, fill, color) = FOCUS_BLOCK_COLORS.iter().find(|(name, _, _)| kind.eq(name)).unwrap_or(FOCUS_BLOCK_COLORS.last().unwrap());
+
+        let properties = ParagraphProperties {
+            style: Some(String::from("FocusBlock")),
+            border: Some(format!(r#"<w:left w:val="single" w:sz="24" w:space="8" w:color="{}"/>"#, color)),
+            shading: Some(fill.to_string()),
+            ..Default::default()
+        };
+
+        let (title, content) = split_focus_block_title(element);
+
+        let title = title.unwrap_or(capitalize(&kind));
+
+        let title_runs = Self::text_run(&title, &RunProperties { bold: true, ..Default::default() }, false);
+
+        let mut xml = self.paragraph(&ParagraphProperties { keep_next: true, ..properties.clone() }, &title_runs);
+
+        xml.push_str(&self.blocks(&content, &properties));
+
+        xml
+    }
+
+    fn list(&mut self, element: &HtmlElement, properties: &ParagraphProperties) -> String {
+
+        let mut xml = String::new();
+
+        let mut ordered_numbering_id: Option<usize> = None;
+
+        for item in element.child_elements() {
+
+            if !item.name.eq("li") {
+                xml.push_str(&self.block(item, properties));
+                continue;
+            }
+
+            self.mark_bookmark(item);
+
+            let level = item.child_elements().filter(|child| child.has_class("list-item-indentation")).count().min(MAX_LIST_LEVEL);
+
+            let bullet = item.child_elements().find(|child| child.has_class("list-item-bullet"));
+
+            let content: Vec<&HtmlNode> = match item.child_elements().find(|child| child.has_class("list-item-content")) {
+                Some(content) => content.children.iter().collect(),
+                None => item.children.iter().collect(),
+            };
+
+            let mut item_properties = ParagraphProperties {
+                style: Some(String::from("ListParagraph")),
+                shading: properties.shading.clone(),
+                ..Default::default()
+            };
+
+            let mut runs = String::new();
+
+            match bullet {
+
+                // checkbox bullets are kept as symbols
+                Some(bullet) if bullet.child_elements().any(|child| child.has_class("checkbox")) => {
+                    item_properties.indentation = Some(720 * (level + 1));
+                    runs.push_str(&self.element_runs(bullet, &RunProperties::default(), false));
+                },
+
+                Some(bullet) if ordered_bullet(&bullet.text()).is_some() => {
+
+                    let numbering_id = *ordered_numbering_id.get_or_insert_with(|| {
+
+                        let (format, start) = ordered_bullet(&bullet.text()).unwrap();
+
+                        self.ordered_numberings.push((format + 1, start));
+
+                        BULLET_NUMBERING_ID + self.ordered_numberings.len()
+                    });
+
+                    item_properties.numbering = Some((numbering_id, level));
+                },
+
+                _ => item_properties.numbering = Some((BULLET_NUMBERING_ID, level)),
+            }
+
+            runs.push_str(&self.runs(&content, &RunProperties::default(), false));
+
+            xml.push_str(&self.paragraph(&item_properties, &runs));
+        }
+
+        xml
+    }
+
+    fn table(&mut self, element: &HtmlElement) -> String {
+
+        let (rows, captions) = html_utility::table_rows(element);
+
+        if rows.is_empty() {
+            return String::new()
+        }
+
+        let layout = html_utility::layout_table(&rows.iter().map(|(row, _)| *row).collect::<Vec<&HtmlElement>>());
+
+        let columns = layout.iter().map(|slots| slots.iter().map(TableSlot::span).sum::<usize>()).max().unwrap_or(1).max(1);
+
+        // column widths from cells which span only one column
+        let mut percentages: Vec<Option<f64>> = vec![None; columns];
+
+        for slots in &layout {
+
+            let mut column = 0;
+
+            for slot in slots {
+                match slot {
+                    TableSlot::Cell { element, span, .. } => {
+
+                        if *span == 1 && percentages[column].is_none() {
+                            percentages[column] = element.attribute("style")
+                                                    .and_then(|style| CSS_WIDTH_PERCENTAGE_REGEX.captures(style))
+                                                    .and_then(|captures| captures[1].parse().ok());
+                        }
+
+                        column += span;
+                    },
+                    TableSlot::Continuation { span } => column += span,
+                }
+            }
+        }
+
+        let widths: Vec<u64> = if percentages.iter().all(Option::is_some) {
+            percentages.iter().map(|percentage| (percentage.unwrap() * TEXT_WIDTH_TWIPS as f64 / 100.0) as u64).collect()
+        } else {
+            vec![TEXT_WIDTH_TWIPS / columns as u64; columns]
+        };
+
+        let has_header = rows.first().is_some_and(|(_, header)| *header);
+
+        let mut xml = format!(
+            r#"<w:tbl><w:tblPr><w:tblStyle w:val="TableGrid"/><w:tblW w:w="{}" w:type="dxa"/><w:tblLook w:val="{}" w:firstRow="{}" w:lastRow="0" w:firstColumn="0" w:lastColumn="0" w:noHBand="1" w:noVBand="1"/></w:tblPr>"#,
+            widths.iter().sum::<u64>(),
+            if has_header { "0420" } else { "0400" },
+            if has_header { 1 } else { 0 },
+        );
+
+        xml.push_str("<w:tblGrid>");
+
+        for width in &widths {
+            xml.push_str(&format!(r#"<w:gridCol w:w="{}"/>"#, width));
+        }
+
+        xml.push_str("</w:tblGrid>");
+
+        for ((_, header), slots) in rows.iter().zip(layout.iter()) {
+
+            xml.push_str("<w:tr>");
+
+            if *header {
+                xml.push_str("<w:trPr><w:tblHeader/></w:trPr>");
+            }
+
+            let mut column = 0;
+
+            for slot in slots {
+
+                let (span, vertical_merge, cell) = match slot {
+                    TableSlot::Cell { element, span, row_span } => (*span, if *row_span > 1 { Some(r#"<w:vMerge w:val="restart"/>"#) } else { None }, Some(*element)),
+                    TableSlot::Continuation { span } => (*span, Some("<w:vMerge/>"), None),
+                };
+
+                let width: u64 = widths.iter().skip(column).take(span).sum();
+
+                column += span;
+
+                xml.push_str(&format!(r#"<w:tc><w:tcPr><w:tcW w:w="{}" w:type="dxa"/>"#, width));
+
+                if span > 1 {
+                    xml.push_str(&format!(r#"<w:gridSpan w:val="{}"/>"#, span));
+                }
+
+                if let Some(vertical_merge) = vertical_merge {
+                    xml.push_str(vertical_merge);
+                }
+
+                xml.push_str("</w:tcPr>");
+
+                let mut content = String::new();
+
+                if let Some(cell) = cell {
+
+                    let alignment = cell.classes().iter().find_map(|class| match *class {
+                        "table-center-cell" => Some(String::from("center")),
+                        "table-right-cell" => Some(String::from("right")),
+                        _ => None,
+                    });
+
+                    content = self.blocks(cell, &ParagraphProperties { alignment, ..Default::default() });
+                }
+
+                // each cell must end with a paragraph
+                if !content.ends_with("</w:p>") {
+                    content.push_str("<w:p/>");
+                }
+
+                xml.push_str(&content);
+                xml.push_str("</w:tc>");
+            }
+
+            xml.push_str("</w:tr>");
+        }
+
+        xml.push_str("</w:tbl>");
+
+        for caption in captions {
+            xml.push_str(&self.block(caption, &ParagraphProperties::default()));
+        }
+
+        xml
+    }
+
+    /// Table of contents is a Word field (updated when document is opened), entries are its cached result
+    fn table_of_contents(&mut self, element: &HtmlElement) -> String {
+
+        let mut xml = String::new();
+
+        if let Some(title) = element.child_elements().find(|child| child.has_class("toc-title")) {
+            let runs = self.element_runs(title, &RunProperties::default(), false);
+            xml.push_str(&self.paragraph(&ParagraphProperties::with_style("TOCHeading"), &runs));
+        }
+
+        let field_begin = format!(
+            r#"<w:r><w:fldChar w:fldCharType="begin"/></w:r><w:r><w:instrText xml:space="preserve"> TOC \o "1-{}" \h \z \u </w:instrText></w:r><w:r><w:fldChar w:fldCharType="separate"/></w:r>"#,
+            self.toc_maximum_heading_level.clamp(1, 9)
+        );
+
+        let field_end = r#"<w:r><w:fldChar w:fldCharType="end"/></w:r>"#;
+
+        let items: Vec<&HtmlElement> = element.child_elements()
+                                            .filter(|child| child.has_class("toc-body"))
+                                            .flat_map(|body| body.child_elements())
+                                            .filter(|item| item.has_class("toc-item"))
+                                            .collect();
+
+        if items.is_empty() {
+            xml.push_str(&self.paragraph(&ParagraphProperties::default(), &format!("{}{}", field_begin, field_end)));
+            return xml
+        }
+
+        for (index, item) in items.iter().enumerate() {
+
+            let level = item.child_elements().filter(|child| child.has_class("toc-item-indentation")).count().clamp(1, 6);
+
+            let content: Vec<&HtmlNode> = match item.child_elements().find(|child| child.has_class("toc-item-content")) {
+                Some(content) => content.children.iter().collect(),
+                None => item.children.iter().collect(),
+            };
+
+            let mut runs = String::new();
+
+            if index == 0 {
+                runs.push_str(&field_begin);
+            }
+
+            runs.push_str(&self.runs(&content, &RunProperties::default(), false));
+
+            if index == items.len() - 1 {
+                runs.push_str(field_end);
+            }
+
+            xml.push_str(&self.paragraph(&ParagraphProperties::with_style(&format!("TOC{}", level)), &runs));
+        }
+
+        xml
+    }
+
+    /// Bibliography records are plain numbered references (as cites)
+    fn bibliography(&mut self, element: &HtmlElement) -> String {
+
+        let mut xml = String::new();
+
+        if let Some(title) = element.child_elements().find(|child| child.has_class("bibliography-title")) {
+            let runs = self.element_runs(title, &RunProperties::default(), false);
+            xml.push_str(&self.paragraph(&ParagraphProperties::with_style("Heading1"), &runs));
+        }
+
+        let items: Vec<&HtmlElement> = element.child_elements()
+                                            .filter(|child| child.has_class("bibliography-body"))
+                                            .flat_map(|body| body.child_elements())
+                                            .filter(|item| item.has_class("bibliography-item"))
+                                            .collect();
+
+        for (index, item) in items.iter().enumerate() {
+
+            self.mark_bookmark(item);
+
+            let field = |class: &str| item.child_elements().find(|child| child.has_class(class)).map(|child| child.text().trim().to_string()).filter(|text| !text.is_empty());
+
+            let mut runs = Self::text_run(&format!("[{}] ", index + 1), &RunProperties::default(), false);
+
+            let [authors_class, title_class, other_classes @ ..] = BIBLIOGRAPHY_ITEM_FIELD_CLASSES;
+
+            if let Some(authors) = field(authors_class) {
+                runs.push_str(&Self::text_run(&format!("{}. ", authors), &RunProperties::default(), false));
+            }
+
+            if let Some(title) = field(title_class) {
+                runs.push_str(&Self::text_run(&title, &RunProperties { italic: true, ..Default::default() }, false));
+                runs.push_str(&Self::text_run(". ", &RunProperties::default(), false));
+            }
+
+            for class in other_classes {
+                if let Some(value) = field(class) {
+                    runs.push_str(&Self::text_run(&format!("{}. ", value), &RunProperties::default(), false));
+                }
+            }
+
+            xml.push_str(&self.paragraph(&ParagraphProperties::with_style("Bibliography"), runs.trim_end()));
+        }
+
+        xml
+    }
+
+    /// Convert compiled HTML in the body of `word/document.xml`
+    fn convert(&mut self, html: &str) -> String {
+
+        let root = html_utility::parse_html(html);
+
+        self.collect_bookmarks(&root);
+
+        let mut body = self.blocks(&root, &ParagraphProperties::default());
+
+        // bookmarks of trailing elements
+        if !self.pending_bookmarks.is_empty() {
+            body.push_str(&self.paragraph(&ParagraphProperties::default(), ""));
+        }
+
+        body
+    }
+
+    fn document_xml(body: &str) -> String {
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>{}<w:document {}><w:body>{}{}</w:body></w:document>"#,
+            "\n", DOCUMENT_NAMESPACES, body, SECTION_PROPERTIES
+        )
+    }
+
+    fn numbering_xml(&self) -> String {
+
+        let level_xml = |level: usize, format: &str, text: &str| format!(
+            r#"<w:lvl w:ilvl="{level}"><w:start w:val="1"/><w:numFmt w:val="{format}"/><w:lvlText w:val="{text}"/><w:lvlJc w:val="left"/><w:pPr><w:ind w:left="{left}" w:hanging="360"/></w:pPr></w:lvl>"#,
+            level = level,
+            format = format,
+            text = text,
+            left = 720 * (level + 1),
+        );
+
+        let mut xml = format!(r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>{}<w:numbering xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main">"#, "\n");
+
+        xml.push_str(r#"<w:abstractNum w:abstractNumId="0"><w:multiLevelType w:val="hybridMultilevel"/>"#);
+
+        for level in 0..=MAX_LIST_LEVEL {
+            xml.push_str(&level_xml(level, "bullet", BULLETS[level % BULLETS.len()]));
+        }
+
+        xml.push_str("</w:abstractNum>");
+
+        for (index, format) in ORDERED_NUMBERING_FORMATS.iter().enumerate() {
+
+            xml.push_str(&format!(r#"<w:abstractNum w:abstractNumId="{}"><w:multiLevelType w:val="hybridMultilevel"/>"#, index + 1));
+
+            for level in 0..=MAX_LIST_LEVEL {
+                xml.push_str(&level_xml(level, format, &format!("%{}.", level + 1)));
+            }
+
+            xml.push_str("</w:abstractNum>");
+        }
+
+        xml.push_str(&format!(r#"<w:num w:numId="{}"><w:abstractNumId w:val="0"/></w:num>"#, BULLET_NUMBERING_ID));
+
+        for (index, (abstract_numbering_id, start)) in self.ordered_numberings.iter().enumerate() {
+            xml.push_str(&format!(
+                r#"<w:num w:numId="{}"><w:abstractNumId w:val="{}"/><w:lvlOverride w:ilvl="0"><w:startOverride w:val="{}"/></w:lvlOverride></w:num>"#,
+                BULLET_NUMBERING_ID + index + 1, abstract_numbering_id, start
+            ));
+        }
+
+        xml.push_str("</w:numbering>");
+
+        xml
+    }
+
+    fn document_relationships_xml(&self) -> String {
+
+        let mut xml = format!(r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>{}<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">"#, "\n");
+
+        for (index, (kind, target)) in FIXED_RELATIONSHIPS.iter().enumerate() {
+            xml.push_str(&format!(r#"<Relationship Id="rId{}" Type="{}/{}" Target="{}"/>"#, index + 1, RELATIONSHIP_TYPE_PREFIX, kind, target));
+        }
+
+        for relationship in &self.relationships {
+            xml.push_str(&format!(
+                r#"<Relationship Id="{}" Type="{}/{}" Target="{}"{}/>"#,
+                relationship.id, RELATIONSHIP_TYPE_PREFIX, relationship.kind, escape_xml(&relationship.target),
+                if relationship.external { r#" TargetMode="External""# } else { "" }
+            ));
+        }
+
+        xml.push_str("</Relationships>");
+
+        xml
+    }
+
+    fn content_types_xml(&self) -> String {
+
+        let mut xml = format!(
+            concat!(
+                r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>{}"#,
+                r#"<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">"#,
+                r#"<Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/>"#,
+                r#"<Default Extension="xml" ContentType="application/xml"/>"#,
+            ),
+            "\n"
+        );
+
+        let mut extensions: Vec<&str> = self.media.iter().filter_map(|media| media.file_name.rsplit('.').next()).collect();
+        extensions.sort();
+        extensions.dedup();
+
+        for extension in extensions {
+            let media_type = image_utility::image_media_type(extension, &[]).unwrap_or(format!("image/{}", extension));
+            xml.push_str(&format!(r#"<Default Extension="{}" ContentType="{}"/>"#, extension, media_type));
+        }
+
+        for (part, content_type) in [
+            ("/word/document.xml", "application/vnd.openxmlformats-officedocument.wordprocessingml.document.main+xml"),
+            ("/word/styles.xml", "application/vnd.openxmlformats-officedocument.wordprocessingml.styles+xml"),
+            ("/word/numbering.xml", "application/vnd.openxmlformats-officedocument.wordprocessingml.numbering+xml"),
+            ("/word/settings.xml", "application/vnd.openxmlformats-officedocument.wordprocessingml.settings+xml"),
+            ("/docProps/core.xml", "application/vnd.openxmlformats-package.core-properties+xml"),
+        ] {
+            xml.push_str(&format!(r#"<Override PartName="{}" ContentType="{}"/>"#, part, content_type));
+        }
+
+        xml.push_str("</Types>");
+
+        xml
+    }
+}
+
+
+fn package_relationships_xml() -> String {
+    format!(
+        concat!(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>{}"#,
+            r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">"#,
+            r#"<Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="word/document.xml"/>"#,
+            r#"<Relationship Id="rId2" Type="http://schemas.openxmlformats.org/package/2006/relationships/metadata/core-properties" Target="docProps/core.xml"/>"#,
+            "</Relationships>",
+        ),
+        "\n"
+    )
+}
+
+/// `updateFields` makes Word update table of contents (e.g. its page numbers) when document is opened
+fn settings_xml() -> String {
+    format!(
+        concat!(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>{}"#,
+            r#"<w:settings xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main">"#,
+            r#"<w:defaultTabStop w:val="720"/><w:updateFields w:val="true"/>"#,
+            r#"<w:compat><w:compatSetting w:name="compatibilityMode" w:uri="http://schemas.microsoft.com/office/word" w:val="15"/></w:compat>"#,
+            "</w:settings>",
+        ),
+        "\n"
+    )
+}
+
+fn core_properties_xml(dossier_configuration: &DossierConfiguration) -> String {
+
+    let metadata = dossier_configuration.metadata();
+
+    let mut properties = format!("<dc:title>{}</dc:title>", escape_xml(dossier_configuration.name()));
+
+    if !metadata.authors().is_empty() {
+        properties.push_str(&format!("<dc:creator>{}</dc:creator>", escape_xml(&metadata.authors().join("; "))));
+    }
+
+    if let Some(description) = metadata.description() {
+        properties.push_str(&format!("<dc:description>{}</dc:description>", escape_xml(description)));
+    }
+
+    if let Some(language) = metadata.language() {
+        properties.push_str(&format!("<dc:language>{}</dc:language>", escape_xml(language)));
+    }
+
+    if let Some(identifier) = metadata.identifier() {
+        properties.push_str(&format!("<dc:identifier>{}</dc:identifier>", escape_xml(identifier)));
+    }
+
+    let timestamp = variable::build_timestamp();
+
+    properties.push_str(&format!(r#"<dcterms:created xsi:type="dcterms:W3CDTF">{0}</dcterms:created><dcterms:modified xsi:type="dcterms:W3CDTF">{0}</dcterms:modified>"#, timestamp));
+
+    format!(
+        concat!(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>{}"#,
+            r#"<cp:coreProperties xmlns:cp="http://schemas.openxmlformats.org/package/2006/metadata/core-properties" xmlns:dc="http://purl.org/dc/elements/1.1/" xmlns:dcterms="http://purl.org/dc/terms/" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance">"#,
+            "{}</cp:coreProperties>",
+        ),
+        "\n", properties
+    )
+}
+
+
+#[derive(Debug)]
+pub struct DocxAssembler {
+    html_assembler: HtmlAssembler,
+}
+
+impl DocxAssembler {
+
+    pub fn new() -> Self {
+        Self {
+            html_assembler: HtmlAssembler::new(),
+        }
+    }
+
+    fn dossier_html(compiled_documents: &[CompilationOutcome], compiled_toc: Option<&CompilationOutcome>, compiled_bib: Option<&CompilationOutcome>, compiled_index: Option<&CompilationOutcome>) -> String {
+
+        let mut html = String::new();
+
+        if let Some(toc) = compiled_toc {
+            html.push_str(toc.content());
+        }
+
+        for document in compiled_documents {
+            html.push_str(&format!(r#"<section class="document">{}</section>"#, document.content()));
+        }
+
+        if let Some(bib) = compiled_bib {
+            html.push_str(bib.content());
+        }
+
+        if let Some(index) = compiled_index {
+            html.push_str(&format!(r#"<section class="document">{}</section>"#, index.content()));
+        }
+
+        html
+    }
+
+    fn write_package(converter: &DocxConverter, body: &str, dossier_configuration: &DossierConfiguration) -> Result<Vec<u8>, zip::result::ZipError> {
+
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+
+        let mut parts: Vec<(String, Vec<u8>)> = vec![
+            (String::from("[Content_Types].xml"), converter.content_types_xml().into_bytes()),
+            (String::from("_rels/.rels"), package_relationships_xml().into_bytes()),
+            (String::from("docProps/core.xml"), core_properties_xml(dossier_configuration).into_bytes()),
+            (String::from("word/document.xml"), DocxConverter::document_xml(body).into_bytes()),
+            (String::from("word/_rels/document.xml.rels"), converter.document_relationships_xml().into_bytes()),
+            (String::from("word/styles.xml"), STYLES_XML.as_bytes().to_vec()),
+            (String::from("word/numbering.xml"), converter.numbering_xml().into_bytes()),
+            (String::from("word/settings.xml"), settings_xml().into_bytes()),
+        ];
+
+        for media in &converter.media {
+            parts.push((format!("word/media/{}", media.file_name), media.bytes.clone()));
+        }
+
+        for (name, content) in parts {
+            writer.start_file(name, SimpleFileOptions::default())?;
+            writer.write_all(&content)?;
+        }
+
+        Ok(writer.finish()?.into_inner())
+    }
+}
+
+impl Default for DocxAssembler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Assembler for DocxAssembler {
+
+    /// Assemble dossier as WordprocessingML main part (i.e. `word/document.xml`, use `assemble_dossier_package` to build DOCX package)
+    fn assemble_dossier(&self, compiled_documents: &Vec<CompilationOutcome>, compiled_toc: Option<&CompilationOutcome>, compiled_bib: Option<&CompilationOutcome>, compiled_index: Option<&CompilationOutcome>, dossier_configuration: &DossierConfiguration, _configuration: &AssemblerConfiguration) -> Result<String, AssemblerError> {
+
+        let mut converter = DocxConverter::new(dossier_configuration.table_of_contents_configuration().maximum_heading_level());
+
+        Ok(DocxConverter::document_xml(&converter.convert(&Self::dossier_html(compiled_documents, compiled_toc, compiled_bib, compiled_index))))
+    }
+
+    fn assemble_dossier_package(&self, compiled_documents: &Vec<CompilationOutcome>, compiled_toc: Option<&CompilationOutcome>, compiled_bib: Option<&CompilationOutcome>, compiled_index: Option<&CompilationOutcome>, table_of_contents: &TableOfContents, dossier_configuration: &DossierConfiguration, _configuration: &AssemblerConfiguration) -> Result<Vec<u8>, AssemblerError> {
+
+        let mut converter = DocxConverter::new(table_of_contents.maximum_heading_level());
+
+        let body = converter.convert(&Self::dossier_html(compiled_documents, compiled_toc, compiled_bib, compiled_index));
+
+        Self::write_package(&converter, &body, dossier_configuration).map_err(|err| AssemblerError::PackageError(err.to_string()))
+    }
+
+    fn assemble_dossier_section(&self, title: &str, id: &str, compiled_documents: &[CompilationOutcome], configuration: &AssemblerConfiguration) -> Result<String, AssemblerError> {
+        self.html_assembler.assemble_dossier_section(title, id, compiled_documents, configuration)
+    }
+
+    fn assemble_document(&self, document_metadata: &DocumentMetadata, compiled_content: &CompilationOutcome, configuration: &AssemblerConfiguration) -> Result<String, AssemblerError> {
+        self.html_assembler.assemble_document(document_metadata, compiled_content, configuration)
+    }
+
+    /// Assemble document as WordprocessingML main part
+    fn assemble_document_standalone(&self, _page_title: &str, _document_metadata: &DocumentMetadata, compiled_document: &CompilationOutcome, compiled_toc: Option<&CompilationOutcome>, compiled_bib: Option<&CompilationOutcome>, _configuration: &AssemblerConfiguration) -> Result<String, AssemblerError> {
+
+        // standalone document has not a dossier configuration, so default table of contents is used
+        let mut converter = DocxConverter::new(DossierConfigurationTableOfContents::default().maximum_heading_level());
+
+        Ok(DocxConverter::document_xml(&converter.convert(&Self::dossier_html(std::slice::from_ref(compiled_document), compiled_toc, compiled_bib, None))))
+    }
+
+    fn assemble_bundle(&self, compiled_preamble: &Vec<CompilationOutcome>, compiled_chapters: &Vec<CompilationOutcome>, configuration: &AssemblerConfiguration) -> Result<String, AssemblerError> {
+        self.html_assembler.assemble_bundle(compiled_preamble, compiled_chapters, configuration)
+    }
+
+    fn assemble_chapter(&self, chapter_tags: &Vec<ChapterTag>, compiled_heading: &CompilationOutcome, compiled_paragraphs: &Vec<CompilationOutcome>, configuration: &AssemblerConfiguration) -> Result<String, AssemblerError> {
+        self.html_assembler.assemble_chapter(chapter_tags, compiled_heading, compiled_paragraphs, configuration)
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use std::io::{Cursor, Read};
+    use crate::{assembler::{assembler_configuration::AssemblerConfiguration, Assembler}, compilation::compilation_outcome::CompilationOutcome, dossier::{document::document_metadata::DocumentMetadata, dossier_configuration::{dossier_configuration_table_of_contents::DossierConfigurationTableOfContents, DossierConfiguration}}, table_of_contents::TableOfContents};
+    use super::{ordered_bullet, DocxAssembler, DocxConverter};
+
+
+    #[test]
+    fn ordered_bullets() {
+        assert_eq!(ordered_bullet("3."), Some((0, 3)));
+        assert_eq!(ordered_bullet("b)"), Some((1, 2)));
+        assert_eq!(ordered_bullet("IV."), Some((4, 4)));
+        assert_eq!(ordered_bullet("i."), Some((3, 1)));
+        assert_eq!(ordered_bullet("\u{2022}"), None);
+    }
+
+    #[test]
+    fn convert_blocks() {
+
+        let mut converter = DocxConverter::new(3);
+
+        let body = converter.convert(concat!(
+            r#"<h2 class="heading-2" id="intro">Intro</h2>"#,
+            r##"<p class="paragraph">a <strong class="bold">b <em>c</strong></em> <a href="#intro">back</a> <a class="cite" href="bib">1</a></p>"##,
+            r#"<ul class="list"><li class="list-item"><span class="list-item-bullet">1.</span><span class="list-item-content">one</span></li>"#,
+            r#"<li class="list-item"><span class="list-item-indentation"></span><span class="list-item-bullet">&bull;</span><span class="list-item-content">nested</span></li></ul>"#,
+            r#"<table class="table"><thead><tr><td>A</td><td rowspan="2">B</td></tr></thead><tbody><tr><td colspan="1">x</td></tr></tbody></table>"#,
+            r#"<div class="focus-block focus-block-warning"><div class="focus-block-title"></div><div class="focus-block-description"><p>Careful</p></div></div>"#,
+        ));
+
+        assert!(body.contains(r#"<w:pStyle w:val="Heading2"/></w:pPr><w:bookmarkStart w:id="1" w:name="nmd_1"/>"#));
+        assert!(body.contains(r#"<w:rPr><w:b/><w:i/></w:rPr><w:t xml:space="preserve">c</w:t>"#));
+        assert!(body.contains(r#"<w:hyperlink w:anchor="nmd_1">"#));
+        assert!(body.contains(r#"<w:t xml:space="preserve">[1]</w:t>"#));
+        assert!(body.contains(r#"<w:numPr><w:ilvl w:val="0"/><w:numId w:val="2"/></w:numPr>"#));
+        assert!(body.contains(r#"<w:numPr><w:ilvl w:val="1"/><w:numId w:val="1"/></w:numPr>"#));
+        assert!(body.contains(r#"<w:vMerge w:val="restart"/>"#));
+        assert!(body.contains(r#"<w:vMerge/></w:tcPr><w:p/>"#));
+        assert!(body.contains(r#"<w:shd w:val="clear" w:color="auto" w:fill="FFF5DB"/>"#));
+        assert!(body.contains(r#"<w:t xml:space="preserve">Warning</w:t>"#));
+
+        assert_eq!(converter.ordered_numberings, vec![(1, 1)]);
+    }
+
+    #[test]
+    fn docx_package() {
+
+        let mut dossier_configuration = DossierConfiguration::default();
+        dossier_configuration.set_name(String::from("Manual"));
+
+        let documents = vec![
+            CompilationOutcome::from(r#"<h1 class="heading-1">Title</h1><p>See <a href="https://example.com">site</a></p><img src="data:image/png;base64,iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAYAAAAfFcSJAAAADUlEQVR42mNk+M9QDwADhgGAWjR9awAAAABJRU5ErkJggg==">"#),
+        ];
+
+        let table_of_contents = TableOfContents::new(String::from("Contents"), false, false, 3, Vec::new());
+
+        let package = DocxAssembler::new().assemble_dossier_package(&documents, None, None, None, &table_of_contents, &dossier_configuration, &AssemblerConfiguration::default()).unwrap();
+
+        let mut archive = zip::ZipArchive::new(Cursor::new(package)).unwrap();
+
+        let mut read = |name: &str| {
+            let mut content = String::new();
+            archive.by_name(name).unwrap().read_to_string(&mut content).unwrap();
+            content
+        };
+
+        assert!(read("[Content_Types].xml").contains(r#"<Default Extension="png" ContentType="image/png"/>"#));
+        assert!(read("docProps/core.xml").contains("<dc:title>Manual</dc:title>"));
+
+        let relationships = read("word/_rels/document.xml.rels");
+        assert!(relationships.contains(r#"Target="media/image1.png""#));
+        assert!(relationships.contains(r#"Target="https://example.com" TargetMode="External""#));
+
+        let document = read("word/document.xml");
+        assert!(document.contains(r#"<wp:extent cx="9525" cy="9525"/>"#));
+        assert!(document.contains(r#"<w:pStyle w:val="Heading1"/>"#));
+
+        assert!(archive.by_name("word/media/image1.png").is_ok());
+    }
+
+    #[test]
+    fn standalone_table_of_contents_levels() {
+
+        let toc = CompilationOutcome::from(r##"<section class="toc"><div class="toc-title">Contents</div><ul class="toc-body"><li class="toc-item"><span class="toc-item-bullet"></span><span class="toc-item-content"><a href="#title" class="link">Title</a></span></li></ul></section>"##);
+
+        let document = DocxAssembler::new().assemble_document_standalone("test", &DocumentMetadata::default(), &CompilationOutcome::from(r#"<h1 class="heading-1" id="title">Title</h1>"#), Some(&toc), None, &AssemblerConfiguration::default()).unwrap();
+
+        assert!(document.contains(&format!(r#"TOC \o "1-{}""#, DossierConfigurationTableOfContents::default().maximum_heading_level())));
+    }
+
+    #[test]
+    fn standalone_document_title_once() {
+
+        let assembler = DocxAssembler::new();
+        let configuration = AssemblerConfiguration::default();
+
+        let metadata = DocumentMetadata::load_front_matter("---\ntitle: My Title\n---\n").unwrap().0.unwrap();
+
+        let document = CompilationOutcome::from(assembler.assemble_document(&metadata, &CompilationOutcome::from("<p>Body</p>"), &configuration).unwrap());
+
+        let document = assembler.assemble_document_standalone("test", &metadata, &document, None, None, &configuration).unwrap();
+
+        assert_eq!(document.matches(r#"w:val="Title""#).count(), 1);
+    }
+}
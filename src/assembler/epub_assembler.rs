@@ -0,0 +1,711 @@
+//! EPUB 3 assembler: documents are compiled as HTML (using `HtmlAssembler`), then they are serialized as XHTML
+//! and packaged together with navigation document, styles and images
+
+use std::{collections::HashMap, io::{Cursor, Write}, path::PathBuf};
+use once_cell::sync::Lazy;
+use regex::{Captures, Regex};
+use zip::{write::SimpleFileOptions, CompressionMethod, ZipWriter};
+use crate::{compilation::compilation_outcome::CompilationOutcome, dossier::{document::{chapter::{chapter_tag::ChapterTag, heading::HeadingLevel}, document_metadata::DocumentMetadata}, dossier_configuration::DossierConfiguration}, resource::{disk_resource::DiskResource, variable, Resource}, table_of_contents::TableOfContents, utility::{html_utility::{decode_entities, escape_xml, parse_start_tag, tag_end, HtmlElement, RAW_TEXT_ELEMENTS, VOID_ELEMENTS}, image_utility}};
+use super::{assembler_configuration::AssemblerConfiguration, html_assembler::HtmlAssembler, Assembler, AssemblerError};
+
+
+const EPUB_MIME_TYPE: &str = "application/epub+zip";
+
+const DEFAULT_LANGUAGE: &str = "en";
+
+const CONTAINER_XML: &str = concat!(
+    r#"<?xml version="1.0" encoding="UTF-8"?>"#, "\n",
+    r#"<container version="1.0" xmlns="urn:oasis:names:tc:opendocument:xmlns:container">"#, "\n",
+    r#"<rootfiles><rootfile full-path="OEBPS/content.opf" media-type="application/oebps-package+xml"/></rootfiles>"#, "\n",
+    "</container>\n",
+);
+
+static END_TAG_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"^</([A-Za-z][A-Za-z0-9:-]*)\s*>").unwrap());
+
+static ID_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r#"\sid="([^"]+)""#).unwrap());
+
+static INTERNAL_HREF_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r##"href="#([^"]+)""##).unwrap());
+
+static IMG_SRC_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r#"(<img\b[^>]*?\ssrc=")([^"]*)(")"#).unwrap());
+
+static FIRST_HEADING_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?s)<h[1-6][^>]*>(.*?)</h[1-6]>").unwrap());
+
+static TAG_STRIP_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"<[^>]*>").unwrap());
+
+
+/// Text which can be used in XML content, characters not allowed by XML are removed
+fn xml_text(text: &str) -> String {
+    escape_xml(&text.chars().filter(|c| matches!(c, '\t' | '\n' | '\r') || *c >= ' ').collect::<String>())
+}
+
+fn is_xml_name(name: &str) -> bool {
+
+    let mut chars = name.chars();
+
+    chars.next().is_some_and(|c| c.is_alphabetic() || c == '_')
+        && chars.all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '.' | ':'))
+}
+
+fn xhtml_start_tag(element: &HtmlElement, self_closed: bool) -> String {
+
+    let mut tag = format!("<{}", element.name);
+    let mut names: Vec<&str> = Vec::new();
+
+    for (name, value) in &element.attributes {
+
+        // attributes must be unique in XML
+        if !is_xml_name(name) || names.contains(&name.as_str()) {
+            continue;
+        }
+
+        names.push(name);
+
+        tag.push_str(&format!(r#" {}="{}""#, name, xml_text(value)));
+    }
+
+    tag.push_str(if self_closed { "/>" } else { ">" });
+
+    tag
+}
+
+/// Serialize HTML (as generated by HTML codex) as well-formed XHTML: text and attributes are escaped, void elements are self-closed
+/// and elements are closed in order (unexpected end tags are dropped and elements not closed are closed by end tag of their ancestors)
+pub fn to_xhtml(html: &str) -> String {
+
+    let mut xhtml = String::new();
+    let mut open_elements: Vec<String> = Vec::new();
+    let mut position: usize = 0;
+
+    while let Some(offset) = html[position..].find('<') {
+
+        let start = position + offset;
+        let rest = &html[start..];
+
+        xhtml.push_str(&xml_text(&decode_entities(&html[position..start])));
+
+        if rest.starts_with("<!--") {
+
+            // comments are dropped, they could contain `--`
+            position = rest.find("-->").map(|end| start + end + 3).unwrap_or(html.len());
+
+            continue;
+        }
+
+        if rest.starts_with("<!") || rest.starts_with("<?") {
+
+            if rest[2..].to_ascii_lowercase().starts_with("doctype") {
+                xhtml.push_str("<!DOCTYPE html>");
+            }
+
+            position = rest.find('>').map(|end| start + end + 1).unwrap_or(html.len());
+
+            continue;
+        }
+
+        if let Some(captures) = END_TAG_REGEX.captures(rest) {
+
+            let name = captures[1].to_lowercase();
+
+            if let Some(index) = open_elements.iter().rposition(|element| *element == name) {
+                for element in open_elements.drain(index..).rev() {
+                    xhtml.push_str(&format!("</{}>", element));
+                }
+            }
+
+            position = start + captures[0].len();
+
+            continue;
+        }
+
+        let Some((element, end)) = parse_start_tag(html, start) else {
+
+            // it is not a tag (e.g. `a < b`)
+            xhtml.push_str("&lt;");
+            position = start + 1;
+
+            continue;
+        };
+
+        position = end;
+
+        if RAW_TEXT_ELEMENTS.contains(&element.name.as_str()) {
+
+            let close_tag = format!("</{}", element.name);
+
+            let content_end = html[position..].to_ascii_lowercase().find(&close_tag).map(|index| position + index).unwrap_or(html.len());
+
+            xhtml.push_str(&xhtml_start_tag(&element, false));
+            xhtml.push_str(&xml_text(&html[position..content_end]));
+            xhtml.push_str(&format!("</{}>", element.name));
+
+            position = tag_end(html, content_end).map(|index| index + 1).unwrap_or(html.len());
+
+            continue;
+        }
+
+        let self_closed = html[..end - 1].ends_with('/') || VOID_ELEMENTS.contains(&element.name.as_str());
+
+        xhtml.push_str(&xhtml_start_tag(&element, self_closed));
+
+        if !self_closed {
+            open_elements.push(element.name);
+        }
+    }
+
+    xhtml.push_str(&xml_text(&decode_entities(&html[position..])));
+
+    for element in open_elements.into_iter().rev() {
+        xhtml.push_str(&format!("</{}>", element));
+    }
+
+    xhtml
+}
+
+fn xhtml_page(title: &str, language: &str, stylesheet: Option<&str>, body: &str) -> String {
+
+    let stylesheet = stylesheet.map(|href| format!(r#"<link rel="stylesheet" type="text/css" href="{}"/>"#, href)).unwrap_or_default();
+
+    format!(
+        concat!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>"#, "\n",
+            "<!DOCTYPE html>\n",
+            r#"<html xmlns="http://www.w3.org/1999/xhtml" xmlns:epub="http://www.idpf.org/2007/ops" xml:lang="{language}" lang="{language}">"#, "\n",
+            r#"<head><meta charset="utf-8"/><title>{title}</title>{stylesheet}</head>"#, "\n",
+            "<body>\n{body}\n</body>\n",
+            "</html>\n",
+        ),
+        language = escape_xml(language),
+        title = escape_xml(title),
+        stylesheet = stylesheet,
+        body = body,
+    )
+}
+
+fn slug(s: &str) -> String {
+    s.chars()
+        .map(|c| if c.is_alphanumeric() { c.to_ascii_lowercase() } else { '-' })
+        .collect::<String>()
+        .split('-')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<&str>>()
+        .join("-")
+}
+
+/// File of EPUB package (path is relative to `OEBPS` directory)
+#[derive(Debug, Clone)]
+struct PackageItem {
+    id: String,
+    href: String,
+    media_type: String,
+    properties: Option<String>,
+    content: Vec<u8>,
+}
+
+/// Images embedded in documents, they are collected as package items
+#[derive(Debug, Default)]
+struct PackageImages {
+    items: Vec<PackageItem>,
+    hrefs: HashMap<String, String>,
+}
+
+impl PackageImages {
+
+    fn is_remote(src: &str) -> bool {
+        src.starts_with("http://") || src.starts_with("https://")
+    }
+
+    /// Move image `src` in package, returning its new `src` (relative to text directory).
+    /// Remote images are downloaded, because EPUB allows only images inside package
+    fn collect(&mut self, src: &str) -> Result<String, AssemblerError> {
+
+        if let Some(href) = self.hrefs.get(src) {
+            return Ok(format!("../{}", href))
+        }
+
+        let (media_type, bytes) = if Self::is_remote(src) {
+
+            image_utility::download_image(src)?
+
+        } else {
+
+            match image_utility::read_image_src(src) {
+                Some(image) => image,
+                None => {
+                    log::warn!("image '{}' cannot be added to package", src);
+                    return Ok(src.to_string())
+                }
+            }
+        };
+
+        let index = self.items.len() + 1;
+        let href = format!("images/image-{}.{}", index, image_utility::media_type_extension(&media_type));
+
+        self.items.push(PackageItem {
+            id: format!("image-{}", index),
+            href: href.clone(),
+            media_type,
+            properties: None,
+            content: bytes,
+        });
+
+        self.hrefs.insert(src.to_string(), href.clone());
+
+        Ok(format!("../{}", href))
+    }
+}
+
+
+#[derive(Debug)]
+pub struct EpubAssembler {
+    html_assembler: HtmlAssembler,
+}
+
+impl EpubAssembler {
+
+    pub fn new() -> Self {
+        Self {
+            html_assembler: HtmlAssembler::new(),
+        }
+    }
+
+    fn build_style(dossier_configuration: &DossierConfiguration, configuration: &AssemblerConfiguration) -> Result<String, AssemblerError> {
+
+        let mut styles: Vec<String> = HtmlAssembler::theme_styles(configuration.theme()).into_iter().map(String::from).collect();
+
+        let mut styles_paths: Vec<PathBuf> = dossier_configuration.style().styles_references().iter().map(PathBuf::from).collect();
+        styles_paths.extend(configuration.external_styles_paths().iter().cloned());
+
+        for style_path in styles_paths {
+            styles.push(DiskResource::new(style_path)?.read()?);
+        }
+
+        styles.extend(configuration.external_styles().iter().cloned());
+
+        Ok(styles.join("\n"))
+    }
+
+    fn build_nav(table_of_contents: &TableOfContents, ids: &HashMap<String, String>, spine: &[(String, String)], title: &str, language: &str) -> String {
+
+        let mut nav = String::new();
+        let mut open_levels: Vec<u32> = Vec::new();
+
+        for heading in table_of_contents.headings() {
+
+            let level = match heading.level() {
+                HeadingLevel::Explicit(level) => *level,
+                _ => continue,
+            };
+
+            if level as usize > table_of_contents.maximum_heading_level() {
+                continue;
+            }
+
+            match open_levels.last() {
+                Some(last_level) if level <= *last_level => {
+
+                    nav.push_str("</li>");
+
+                    while open_levels.len() > 1 && level < *open_levels.last().unwrap() {
+                        open_levels.pop();
+                        nav.push_str("</ol></li>");
+                    }
+                },
+                _ => {
+                    nav.push_str("<ol>");
+                    open_levels.push(level);
+                }
+            }
+
+            let label = escape_xml(heading.title());
+
+            let href = heading.resource_reference().as_ref()
+                            .map(|reference| reference.build_without_internal_sharp())
+                            .and_then(|id| ids.get(&id).map(|file| format!("text/{}#{}", file, id)));
+
+            match href {
+                Some(href) => nav.push_str(&format!(r#"<li><a href="{}">{}</a>"#, escape_xml(&href), label)),
+                None => nav.push_str(&format!("<li><span>{}</span>", label)),
+            }
+        }
+
+        if open_levels.is_empty() {
+
+            // nav document must have at least an entry, so documents are listed
+            nav.push_str("<ol>");
+
+            for (file, file_title) in spine {
+                nav.push_str(&format!(r#"<li><a href="text/{}">{}</a></li>"#, file, escape_xml(file_title)));
+            }
+
+            nav.push_str("</ol>");
+
+        } else {
+
+            nav.push_str("</li>");
+
+            while open_levels.pop().is_some() {
+                nav.push_str("</ol>");
+
+                if !open_levels.is_empty() {
+                    nav.push_str("</li>");
+                }
+            }
+        }
+
+        xhtml_page(title, language, None, &format!(r#"<nav epub:type="toc" id="toc"><h1>{}</h1>{}</nav>"#, escape_xml(table_of_contents.title()), nav))
+    }
+
+    fn build_opf(dossier_configuration: &DossierConfiguration, language: &str, items: &[PackageItem], spine: &[(String, String)]) -> String {
+
+        let metadata = dossier_configuration.metadata();
+
+        let identifier = metadata.identifier().clone().unwrap_or(format!("urn:nmd:{}", slug(dossier_configuration.name())));
+
+        let mut opf_metadata = format!(
+            concat!(
+                r#"<dc:identifier id="publication-id">{}</dc:identifier>"#, "\n",
+                "<dc:title>{}</dc:title>\n",
+                "<dc:language>{}</dc:language>\n",
+            ),
+            escape_xml(&identifier),
+            escape_xml(dossier_configuration.name()),
+            escape_xml(language),
+        );
+
+        for author in metadata.authors() {
+            opf_metadata.push_str(&format!("<dc:creator>{}</dc:creator>\n", escape_xml(author)));
+        }
+
+        if let Some(publisher) = metadata.publisher() {
+            opf_metadata.push_str(&format!("<dc:publisher>{}</dc:publisher>\n", escape_xml(publisher)));
+        }
+
+        if let Some(description) = metadata.description() {
+            opf_metadata.push_str(&format!("<dc:description>{}</dc:description>\n", escape_xml(description)));
+        }
+
+        if let Some(date) = metadata.date() {
+            opf_metadata.push_str(&format!("<dc:date>{}</dc:date>\n", escape_xml(date)));
+        }
+
+        opf_metadata.push_str(&format!(r#"<meta property="dcterms:modified">{}</meta>"#, variable::build_timestamp()));
+
+        let manifest: String = items.iter().map(|item| {
+            format!(
+                r#"<item id="{}" href="{}" media-type="{}"{}/>"#,
+                item.id, item.href, item.media_type,
+                item.properties.as_ref().map(|properties| format!(r#" properties="{}""#, properties)).unwrap_or_default()
+            )
+        }).collect::<Vec<String>>().join("\n");
+
+        let spine: String = spine.iter().map(|(file, _)| {
+            format!(r#"<itemref idref="{}"/>"#, file.trim_end_matches(".xhtml"))
+        }).collect::<Vec<String>>().join("\n");
+
+        format!(
+            concat!(
+                r#"<?xml version="1.0" encoding="UTF-8"?>"#, "\n",
+                r#"<package xmlns="http://www.idpf.org/2007/opf" version="3.0" unique-identifier="publication-id" xml:lang="{language}">"#, "\n",
+                r#"<metadata xmlns:dc="http://purl.org/dc/elements/1.1/">"#, "\n{metadata}\n</metadata>\n",
+                "<manifest>\n{manifest}\n</manifest>\n",
+                "<spine>\n{spine}\n</spine>\n",
+                "</package>\n",
+            ),
+            language = escape_xml(language),
+            metadata = opf_metadata,
+            manifest = manifest,
+            spine = spine,
+        )
+    }
+
+    fn write_package(items: &[PackageItem]) -> Result<Vec<u8>, zip::result::ZipError> {
+
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+
+        // mimetype must be the first file and it must be not compressed
+        writer.start_file("mimetype", SimpleFileOptions::default().compression_method(CompressionMethod::Stored))?;
+        writer.write_all(EPUB_MIME_TYPE.as_bytes())?;
+
+        writer.start_file("META-INF/container.xml", SimpleFileOptions::default())?;
+        writer.write_all(CONTAINER_XML.as_bytes())?;
+
+        for item in items {
+            writer.start_file(format!("OEBPS/{}", item.href), SimpleFileOptions::default())?;
+            writer.write_all(&item.content)?;
+        }
+
+        Ok(writer.finish()?.into_inner())
+    }
+}
+
+impl Default for EpubAssembler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Assembler for EpubAssembler {
+
+    /// Assemble dossier as a single XHTML page (use `assemble_dossier_package` to build EPUB package)
+    fn assemble_dossier(&self, compiled_documents: &Vec<CompilationOutcome>, compiled_toc: Option<&CompilationOutcome>, compiled_bib: Option<&CompilationOutcome>, compiled_index: Option<&CompilationOutcome>, dossier_configuration: &DossierConfiguration, configuration: &AssemblerConfiguration) -> Result<String, AssemblerError> {
+        Ok(to_xhtml(&self.html_assembler.assemble_dossier(compiled_documents, compiled_toc, compiled_bib, compiled_index, dossier_configuration, configuration)?))
+    }
+
+    fn assemble_dossier_package(&self, compiled_documents: &Vec<CompilationOutcome>, compiled_toc: Option<&CompilationOutcome>, compiled_bib: Option<&CompilationOutcome>, compiled_index: Option<&CompilationOutcome>, table_of_contents: &TableOfContents, dossier_configuration: &DossierConfiguration, configuration: &AssemblerConfiguration) -> Result<Vec<u8>, AssemblerError> {
+
+        let language = dossier_configuration.metadata().language().clone().unwrap_or(String::from(DEFAULT_LANGUAGE));
+
+        // (file name, title, XHTML body)
+        let mut texts: Vec<(String, String, String)> = Vec::new();
+
+        if let Some(toc) = compiled_toc {
+            texts.push((String::from("toc.xhtml"), table_of_contents.title().clone(), to_xhtml(toc.content())));
+        }
+
+        for (index, document) in compiled_documents.iter().enumerate() {
+
+            let body = to_xhtml(&format!(r#"<section class="document">{}</section>"#, document.content()));
+
+            let title = FIRST_HEADING_REGEX.captures(&body)
+                            .map(|captures| TAG_STRIP_REGEX.replace_all(&captures[1], "").trim().to_string())
+                            .filter(|title| !title.is_empty())
+                            .unwrap_or(dossier_configuration.name().clone());
+
+            texts.push((format!("document-{}.xhtml", index + 1), title, body));
+        }
+
+        if let Some(bib) = compiled_bib {
+            texts.push((String::from("bibliography.xhtml"), String::from("Bibliography"), to_xhtml(bib.content())));
+        }
+
+        if let Some(index) = compiled_index {
+            texts.push((String::from("index.xhtml"), String::from("Index"), to_xhtml(&format!(r#"<section class="document">{}</section>"#, index.content()))));
+        }
+
+        // ids are used to resolve internal links, which can point to other files
+        let mut ids: HashMap<String, String> = HashMap::new();
+
+        for (file, _, body) in &texts {
+            for captures in ID_REGEX.captures_iter(body) {
+                ids.entry(captures[1].to_string()).or_insert(file.clone());
+            }
+        }
+
+        let mut images = PackageImages::default();
+        let mut items: Vec<PackageItem> = Vec::new();
+        let mut spine: Vec<(String, String)> = Vec::new();
+
+        for (file, title, body) in &texts {
+
+            let body = INTERNAL_HREF_REGEX.replace_all(body, |captures: &Captures| {
+                match ids.get(&captures[1]) {
+                    Some(target) if target != file => format!(r#"href="{}#{}""#, target, &captures[1]),
+                    _ => captures[0].to_string(),
+                }
+            });
+
+            let mut srcs: HashMap<String, String> = HashMap::new();
+
+            for captures in IMG_SRC_REGEX.captures_iter(&body) {
+
+                let src = decode_entities(&captures[2]);
+
+                if !srcs.contains_key(&src) {
+                    srcs.insert(src.clone(), images.collect(&src)?);
+                }
+            }
+
+            let body = IMG_SRC_REGEX.replace_all(&body, |captures: &Captures| {
+                format!("{}{}{}", &captures[1], escape_xml(&srcs[&decode_entities(&captures[2])]), &captures[3])
+            });
+
+            items.push(PackageItem {
+                id: file.trim_end_matches(".xhtml").to_string(),
+                href: format!("text/{}", file),
+                media_type: String::from("application/xhtml+xml"),
+                properties: None,
+                content: xhtml_page(title, &language, Some("../styles/style.css"), &body).into_bytes(),
+            });
+
+            spine.push((file.clone(), title.clone()));
+        }
+
+        items.push(PackageItem {
+            id: String::from("nav"),
+            href: String::from("nav.xhtml"),
+            media_type: String::from("application/xhtml+xml"),
+            properties: Some(String::from("nav")),
+            content: Self::build_nav(table_of_contents, &ids, &spine, dossier_configuration.name(), &language).into_bytes(),
+        });
+
+        items.push(PackageItem {
+            id: String::from("style"),
+            href: String::from("styles/style.css"),
+            media_type: String::from("text/css"),
+            properties: None,
+            content: Self::build_style(dossier_configuration, configuration)?.into_bytes(),
+        });
+
+        items.append(&mut images.items);
+
+        let opf = Self::build_opf(dossier_configuration, &language, &items, &spine);
+
+        items.push(PackageItem {
+            id: String::from("opf"),
+            href: String::from("content.opf"),
+            media_type: String::from("application/oebps-package+xml"),
+            properties: None,
+            content: opf.into_bytes(),
+        });
+
+        Self::write_package(&items).map_err(|err| AssemblerError::PackageError(err.to_string()))
+    }
+
+    fn assemble_dossier_section(&self, title: &str, id: &str, compiled_documents: &[CompilationOutcome], configuration: &AssemblerConfiguration) -> Result<String, AssemblerError> {
+        self.html_assembler.assemble_dossier_section(title, id, compiled_documents, configuration)
+    }
+
+    fn assemble_document(&self, document_metadata: &DocumentMetadata, compiled_content: &CompilationOutcome, configuration: &AssemblerConfiguration) -> Result<String, AssemblerError> {
+        self.html_assembler.assemble_document(document_metadata, compiled_content, configuration)
+    }
+
+    fn assemble_document_standalone(&self, page_title: &str, document_metadata: &DocumentMetadata, compiled_document: &CompilationOutcome, compiled_toc: Option<&CompilationOutcome>, compiled_bib: Option<&CompilationOutcome>, configuration: &AssemblerConfiguration) -> Result<String, AssemblerError> {
+        Ok(to_xhtml(&self.html_assembler.assemble_document_standalone(page_title, document_metadata, compiled_document, compiled_toc, compiled_bib, configuration)?))
+    }
+
+    fn assemble_bundle(&self, compiled_preamble: &Vec<CompilationOutcome>, compiled_chapters: &Vec<CompilationOutcome>, configuration: &AssemblerConfiguration) -> Result<String, AssemblerError> {
+        self.html_assembler.assemble_bundle(compiled_preamble, compiled_chapters, configuration)
+    }
+
+    fn assemble_chapter(&self, chapter_tags: &Vec<ChapterTag>, compiled_heading: &CompilationOutcome, compiled_paragraphs: &Vec<CompilationOutcome>, configuration: &AssemblerConfiguration) -> Result<String, AssemblerError> {
+        self.html_assembler.assemble_chapter(chapter_tags, compiled_heading, compiled_paragraphs, configuration)
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use std::{io::{Cursor, Read, Write}, net::TcpListener, thread};
+    use crate::{assembler::{assembler_configuration::AssemblerConfiguration, Assembler}, compilation::compilation_outcome::CompilationOutcome, dossier::{document::chapter::heading::{Heading, HeadingLevel}, dossier_configuration::DossierConfiguration}, resource::resource_reference::ResourceReference, table_of_contents::TableOfContents, theme::Theme};
+    use super::{to_xhtml, EpubAssembler};
+
+
+    #[test]
+    fn xhtml_serialization() {
+
+        let html = r#"<p class=note>a&nbsp;b &amp; c & d<br><img src="x.png" alt='i'><input type="checkbox" checked></p>"#;
+
+        assert_eq!(
+            to_xhtml(html),
+            "<p class=\"note\">a\u{a0}b &amp; c &amp; d<br/><img src=\"x.png\" alt=\"i\"/><input type=\"checkbox\" checked=\"\"/></p>"
+        );
+    }
+
+    #[test]
+    fn xhtml_serialization_of_malformed_html() {
+
+        let html = r#"<figure id="a" class="x" class="y" data-note='"quoted" <b>'><p>a < b</span></figure><div><em>open"#;
+
+        assert_eq!(
+            to_xhtml(html),
+            r#"<figure id="a" class="x" data-note="&quot;quoted&quot; &lt;b&gt;"><p>a &lt; b</p></figure><div><em>open</em></div>"#
+        );
+    }
+
+    #[test]
+    fn epub_package() {
+
+        let mut dossier_configuration = DossierConfiguration::default();
+        dossier_configuration.set_name(String::from("Manual"));
+
+        let mut configuration = AssemblerConfiguration::default();
+        configuration.set_theme(Theme::None);
+
+        let references: Vec<ResourceReference> = ["intro", "usage"].iter()
+                                                    .map(|id| ResourceReference::of_internal_from_without_sharp(id, Some(&"doc")).unwrap())
+                                                    .collect();
+
+        let intro_id = references[0].build_without_internal_sharp();
+        let usage_id = references[1].build_without_internal_sharp();
+
+        let documents = vec![
+            CompilationOutcome::from(format!(r#"<h1 id="{}">Intro</h1><p>See <a href="{}">usage</a></p><img src="data:image/png;base64,iVBORw0KGgo=">"#, intro_id, references[1].build())),
+            CompilationOutcome::from(format!(r#"<h1 id="{}">Usage</h1><img src="{}">"#, usage_id, serve_image())),
+        ];
+
+        let headings: Vec<Heading> = references.iter().zip(["Intro", "Usage"]).map(|(reference, title)| {
+            let mut heading = Heading::new(HeadingLevel::Explicit(1), title.to_string());
+            heading.set_resource_reference(Some(reference.clone()));
+            heading
+        }).collect();
+
+        let table_of_contents = TableOfContents::new(String::from("Contents"), false, false, 3, headings);
+
+        let package = EpubAssembler::new().assemble_dossier_package(&documents, None, None, None, &table_of_contents, &dossier_configuration, &configuration).unwrap();
+
+        let mut archive = zip::ZipArchive::new(Cursor::new(package)).unwrap();
+
+        let read = |archive: &mut zip::ZipArchive<Cursor<Vec<u8>>>, name: &str| {
+            let mut content = String::new();
+            archive.by_name(name).unwrap().read_to_string(&mut content).unwrap();
+            content
+        };
+
+        assert_eq!(archive.by_index(0).unwrap().name(), "mimetype");
+        assert_eq!(read(&mut archive, "mimetype"), "application/epub+zip");
+        assert!(read(&mut archive, "META-INF/container.xml").contains("OEBPS/content.opf"));
+
+        let opf = read(&mut archive, "OEBPS/content.opf");
+        assert!(opf.contains("<dc:title>Manual</dc:title>"));
+        assert!(opf.contains(r#"href="images/image-1.png" media-type="image/png""#));
+        assert!(opf.contains(r#"<itemref idref="document-2"/>"#));
+        assert!(opf.contains(r#"<item id="document-1" href="text/document-1.xhtml" media-type="application/xhtml+xml"/>"#));
+        assert!(opf.contains(r#"<item id="document-2" href="text/document-2.xhtml" media-type="application/xhtml+xml"/>"#));
+        assert!(opf.contains(r#"href="images/image-2.png" media-type="image/png""#));
+        assert!(!opf.contains("remote-resources"));
+
+        let nav = read(&mut archive, "OEBPS/nav.xhtml");
+        assert!(nav.contains(&format!(r#"<a href="text/document-2.xhtml#{}">Usage</a>"#, usage_id)));
+
+        let document = read(&mut archive, "OEBPS/text/document-1.xhtml");
+        assert!(document.contains(&format!(r#"href="document-2.xhtml#{}""#, usage_id)));
+        assert!(document.contains(r#"src="../images/image-1.png"/>"#));
+
+        let document = read(&mut archive, "OEBPS/text/document-2.xhtml");
+        assert!(document.contains(r#"src="../images/image-2.png"/>"#));
+    }
+
+    #[test]
+    fn fail_on_unavailable_remote_image() {
+
+        let documents = vec![CompilationOutcome::from(r#"<h1>Usage</h1><img src="http://127.0.0.1:1/logo.png">"#)];
+
+        let table_of_contents = TableOfContents::new(String::from("Contents"), false, false, 3, Vec::new());
+
+        let package = EpubAssembler::new().assemble_dossier_package(&documents, None, None, None, &table_of_contents, &DossierConfiguration::default(), &AssemblerConfiguration::default());
+
+        assert!(package.is_err());
+    }
+
+    /// Serve a PNG image once, returning its URL
+    fn serve_image() -> String {
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/logo.png", listener.local_addr().unwrap());
+
+        thread::spawn(move || {
+
+            let (mut stream, _) = listener.accept().unwrap();
+
+            let mut request = [0u8; 1024];
+            let _ = stream.read(&mut request).unwrap();
+
+            let image = b"\x89PNG\r\n\x1a\n";
+
+            stream.write_all(format!("HTTP/1.1 200 OK\r\nContent-Type: image/png\r\nContent-Length: {}\r\nConnection: close\r\n\r\n", image.len()).as_bytes()).unwrap();
+            stream.write_all(image).unwrap();
+        });
+
+        url
+    }
+}
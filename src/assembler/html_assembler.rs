@@ -1,7 +1,7 @@
 use std::path::PathBuf;
 use build_html::{HtmlPage, HtmlContainer, Html, Container};
 use getset::{Getters, Setters};
-use crate::{compilation::compilation_outcome::CompilationOutcome, dossier::{document::chapter::chapter_tag::{ChapterTag, ChapterTagKey}, dossier_configuration::DossierConfiguration}, resource::{disk_resource::DiskResource, Resource}, theme::Theme};
+use crate::{compilation::{compilation_outcome::CompilationOutcome, compilation_rule::constants::ESCAPE_HTML}, dossier::{document::{chapter::chapter_tag::{ChapterTag, ChapterTagKey}, document_metadata::DocumentMetadata}, dossier_configuration::DossierConfiguration}, resource::{disk_resource::DiskResource, Resource}, theme::Theme, utility::{html_utility::escape_xml, text_utility}};
 
 use super::{assembler_configuration::AssemblerConfiguration, Assembler, AssemblerError};
 
@@ -141,26 +141,36 @@ impl HtmlAssembler {
         page
     }
 
-    fn apply_theme_style(mut page: HtmlPage, theme: &Theme) -> HtmlPage {
+    /// Default style and style of `theme` (if any)
+    pub fn theme_styles(theme: &Theme) -> Vec<&'static str> {
 
-        page.add_style(include_str!("html_assembler/style/default_style.css"));
+        let mut styles = vec![include_str!("html_assembler/style/default_style.css")];
 
         match theme {
-            Theme::Light => page.add_style(include_str!("html_assembler/style/light_theme.css")),
-            Theme::Dark => page.add_style(include_str!("html_assembler/style/dark_theme.css")),
-            Theme::Scientific => page.add_style(include_str!("html_assembler/style/scientific_theme.css")),
-            Theme::Vintage => page.add_style(include_str!("html_assembler/style/vintage_theme.css")),
-            Theme::HighContrast => page.add_style(include_str!("html_assembler/style/high_contrast_theme.css")),
+            Theme::Light => styles.push(include_str!("html_assembler/style/light_theme.css")),
+            Theme::Dark => styles.push(include_str!("html_assembler/style/dark_theme.css")),
+            Theme::Scientific => styles.push(include_str!("html_assembler/style/scientific_theme.css")),
+            Theme::Vintage => styles.push(include_str!("html_assembler/style/vintage_theme.css")),
+            Theme::HighContrast => styles.push(include_str!("html_assembler/style/high_contrast_theme.css")),
             Theme::None => ()       // nothing,
         }
 
+        styles
+    }
+
+    fn apply_theme_style(mut page: HtmlPage, theme: &Theme) -> HtmlPage {
+
+        for style in Self::theme_styles(theme) {
+            page.add_style(style);
+        }
+
         page
     }
 
     fn create_default_html_page(page_title: &str, external_styles_paths: &Vec<PathBuf>, external_styles: &Vec<String>, external_scripts_paths: &Vec<PathBuf>, external_scripts: &Vec<String>, theme: &Theme, use_remote_addons: bool) -> Result<HtmlPage, AssemblerError> {
 
         let mut page = HtmlPage::new()
-                                    .with_title(page_title)
+                                    .with_title(escape_xml(page_title))
                                     .with_meta(vec![("charset", "utf-8")]);
 
         if use_remote_addons {
@@ -202,7 +212,7 @@ impl HtmlAssembler {
 
 impl Assembler for HtmlAssembler {
 
-    fn assemble_dossier(&self, compiled_documents: &Vec<CompilationOutcome>, compiled_toc: Option<&CompilationOutcome>, compiled_bib: Option<&CompilationOutcome>, dossier_configuration: &DossierConfiguration, configuration: &AssemblerConfiguration) -> Result<String, AssemblerError> {
+    fn assemble_dossier(&self, compiled_documents: &Vec<CompilationOutcome>, compiled_toc: Option<&CompilationOutcome>, compiled_bib: Option<&CompilationOutcome>, compiled_index: Option<&CompilationOutcome>, dossier_configuration: &DossierConfiguration, configuration: &AssemblerConfiguration) -> Result<String, AssemblerError> {
                
         let mut styles_references: Vec<PathBuf> = dossier_configuration.style().styles_references().iter()
                                                         .map(|p| PathBuf::from(p))
@@ -241,9 +251,38 @@ impl Assembler for HtmlAssembler {
             page.add_raw(bib.content());
         }
 
+        if let Some(index) = compiled_index {
+            page.add_container(Container::new(build_html::ContainerType::Section)
+                                            .with_attributes(vec![
+                                                ("class", "document")
+                                            ])
+                                            .with_raw(index.content()));
+        }
+
         Ok(page.to_html_string())
     }
     
+    fn assemble_dossier_section(&self, title: &str, id: &str, compiled_documents: &[CompilationOutcome], _configuration: &AssemblerConfiguration) -> Result<String, AssemblerError> {
+
+        let mut div_section = Container::new(build_html::ContainerType::Div)
+                                    .with_attributes(vec![
+                                        ("class", "sub-dossier")
+                                    ])
+                                    .with_raw(format!(r#"<h1 class="heading-1 sub-dossier-title" id="{}">{}</h1>"#, id, text_utility::replace(title, &ESCAPE_HTML)));
+
+        for document in compiled_documents {
+            let section = Container::new(build_html::ContainerType::Section)
+                                            .with_attributes(vec![
+                                                ("class", "document")
+                                            ])
+                                            .with_raw(document.content());
+
+            div_section.add_container(section);
+        }
+
+        Ok(div_section.to_html_string())
+    }
+
     fn assemble_bundle(&self, compiled_preamble: &Vec<CompilationOutcome>, compiled_chapters: &Vec<CompilationOutcome>, _configuration: &AssemblerConfiguration) -> Result<String, AssemblerError> {
 
         let mut result = String::new();
@@ -261,10 +300,34 @@ impl Assembler for HtmlAssembler {
         Ok(result)
     }
 
-    fn assemble_document_standalone(&self, page_title: &str, compiled_document: &CompilationOutcome, compiled_toc: Option<&CompilationOutcome>, compiled_bib: Option<&CompilationOutcome>, configuration: &AssemblerConfiguration) -> Result<String, AssemblerError> {
+    fn assemble_document(&self, document_metadata: &DocumentMetadata, compiled_content: &CompilationOutcome, _configuration: &AssemblerConfiguration) -> Result<String, AssemblerError> {
+
+        if !document_metadata.has_title_block() {
+            return Ok(compiled_content.content().clone())
+        }
+
+        let mut title_block = Container::new(build_html::ContainerType::Header)
+                                            .with_attributes(vec![("class", "document-title-block")]);
+
+        if let Some(title) = document_metadata.title() {
+            title_block.add_raw(format!(r#"<h1 class="document-title">{}</h1>"#, text_utility::replace(title, &ESCAPE_HTML)));
+        }
+
+        if !document_metadata.authors().is_empty() {
+            title_block.add_raw(format!(r#"<p class="document-authors">{}</p>"#, text_utility::replace(&document_metadata.authors().join(", "), &ESCAPE_HTML)));
+        }
+
+        if let Some(date) = document_metadata.date() {
+            title_block.add_raw(format!(r#"<p class="document-date">{}</p>"#, text_utility::replace(date, &ESCAPE_HTML)));
+        }
+
+        Ok(format!("{}{}", title_block.to_html_string(), compiled_content.content()))
+    }
+
+    fn assemble_document_standalone(&self, page_title: &str, document_metadata: &DocumentMetadata, compiled_document: &CompilationOutcome, compiled_toc: Option<&CompilationOutcome>, compiled_bib: Option<&CompilationOutcome>, configuration: &AssemblerConfiguration) -> Result<String, AssemblerError> {
         
         let mut page = Self::create_default_html_page(
-                                    page_title,
+                                    document_metadata.title().as_ref().map(|title| title.as_str()).unwrap_or(page_title),
                                     configuration.external_styles_paths(),
                                     configuration.external_styles(),
                                     configuration.external_scripts_paths(),
@@ -283,7 +346,25 @@ impl Assembler for HtmlAssembler {
             page.add_raw(bib.content());
         }
 
-        Ok(page.to_html_string())
+        if !document_metadata.authors().is_empty() {
+            page.add_meta(vec![("name", "author"), ("content", &escape_xml(&document_metadata.authors().join(", ")))]);
+        }
+
+        if !document_metadata.tags().is_empty() {
+            page.add_meta(vec![("name", "keywords"), ("content", &escape_xml(&document_metadata.tags().join(", ")))]);
+        }
+
+        if let Some(date) = document_metadata.date() {
+            page.add_meta(vec![("name", "date"), ("content", &escape_xml(date))]);
+        }
+
+        let html = page.to_html_string();
+
+        if let Some(language) = document_metadata.language() {
+            return Ok(html.replacen("<html>", &format!(r#"<html lang="{}">"#, escape_xml(language)), 1))
+        }
+
+        Ok(html)
     }
     
     fn assemble_chapter(&self, chapter_tags: &Vec<ChapterTag>, compiled_heading: &CompilationOutcome, compiled_paragraphs: &Vec<CompilationOutcome>, _configuration: &AssemblerConfiguration) -> Result<String, AssemblerError> {
@@ -329,4 +410,53 @@ impl Assembler for HtmlAssembler {
 
 #[cfg(test)]
 mod test {
+    use crate::{assembler::{assembler_configuration::AssemblerConfiguration, Assembler}, compilation::compilation_outcome::CompilationOutcome, dossier::{document::document_metadata::DocumentMetadata, dossier_configuration::DossierConfiguration}};
+    use super::HtmlAssembler;
+
+
+    #[test]
+    fn assemble_document_with_metadata() {
+
+        let (metadata, _) = DocumentMetadata::load_front_matter("---\ntitle: Title <1>\nauthors: [Alice, Bob]\nlanguage: it\n---\n").unwrap();
+        let metadata = metadata.unwrap();
+
+        let assembler = HtmlAssembler::new();
+        let configuration = AssemblerConfiguration::default();
+
+        let document = assembler.assemble_document(&metadata, &CompilationOutcome::from("<p>content</p>"), &configuration).unwrap();
+
+        assert!(document.starts_with(r#"<header class="document-title-block"><h1 class="document-title">Title &lt;1&gt;</h1><p class="document-authors">Alice, Bob</p></header>"#));
+
+        let page = assembler.assemble_document_standalone("fallback", &metadata, &CompilationOutcome::from(document), None, None, &configuration).unwrap();
+
+        assert!(page.contains(r#"<html lang="it">"#));
+        assert!(page.contains("<title>Title "));
+        assert!(page.contains(r#"<meta name="author" content="Alice, Bob">"#));
+    }
+
+    #[test]
+    fn escape_standalone_page_metadata() {
+
+        let (metadata, _) = DocumentMetadata::load_front_matter("---\ntitle: 'A < B & \"C\"'\nauthors: ['\"Al\" <al@x.org>']\ndate: '2024 & later'\nlanguage: 'it\"><script>'\n---\n").unwrap();
+        let metadata = metadata.unwrap();
+
+        let page = HtmlAssembler::new().assemble_document_standalone("fallback", &metadata, &CompilationOutcome::from("<p>content</p>"), None, None, &AssemblerConfiguration::default()).unwrap();
+
+        assert!(page.contains("<title>A &lt; B &amp; &quot;C&quot;</title>"));
+        assert!(page.contains(r#"<meta name="author" content="&quot;Al&quot; &lt;al@x.org&gt;">"#));
+        assert!(page.contains(r#"<meta name="date" content="2024 &amp; later">"#));
+        assert!(page.contains(r#"<html lang="it&quot;&gt;&lt;script&gt;">"#));
+    }
+
+    #[test]
+    fn wrap_index_as_document() {
+
+        let documents = vec![CompilationOutcome::from("<p>content</p>")];
+        let index = CompilationOutcome::from(r#"<section class="index"></section>"#);
+
+        let page = HtmlAssembler::new().assemble_dossier(&documents, None, None, Some(&index), &DossierConfiguration::default(), &AssemblerConfiguration::default()).unwrap();
+
+        assert!(page.contains(r#"<section class="document"><p>content</p></section>"#));
+        assert!(page.contains(r#"<section class="document"><section class="index"></section></section>"#));
+    }
 }
\ No newline at end of file
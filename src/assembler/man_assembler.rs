@@ -0,0 +1,525 @@
+//! Man page assembler: documents are compiled as HTML (using `HtmlAssembler`), then they are converted in roff
+//! (`man` macros). Top level headings become sections, tables are drawn as ASCII grids in no-fill mode
+
+use crate::{compilation::compilation_outcome::CompilationOutcome, dossier::{document::{chapter::chapter_tag::ChapterTag, document_metadata::DocumentMetadata}, dossier_configuration::DossierConfiguration}, resource::variable, utility::html_utility::{self, is_block, focus_block_kind, split_focus_block_title, HtmlElement, HtmlNode, LinkFootnotes, BIBLIOGRAPHY_ITEM_FIELD_CLASSES, WHITESPACES_REGEX}};
+use super::{assembler_configuration::AssemblerConfiguration, html_assembler::HtmlAssembler, text_assembler, Assembler, AssemblerError};
+
+
+/// Section of generated man pages (miscellaneous)
+const MAN_SECTION: &str = "7";
+
+const INDENTATION: usize = 4;
+
+/// Escape backslashes, so text is not interpreted as roff escapes
+fn escape_roff(text: &str) -> String {
+    text.replace('\\', "\\e")
+}
+
+/// Text line which is not interpreted as a request (i.e. it does not start with `.` or `'`)
+fn text_line(line: &str) -> String {
+
+    let line = line.trim();
+
+    if line.starts_with('.') || line.starts_with('\'') {
+        return format!("\\&{}", line)
+    }
+
+    line.to_string()
+}
+
+/// Argument of a macro (e.g. `.SH`), it is quoted
+fn macro_argument(text: &str) -> String {
+    format!("\"{}\"", WHITESPACES_REGEX.replace_all(text.trim(), " ").replace('"', "\\(dq"))
+}
+
+/// Copy of element with uppercase text, so escapes added later are not uppercased
+fn uppercase(element: &HtmlElement) -> HtmlElement {
+
+    let mut element = element.clone();
+
+    for child in element.children.iter_mut() {
+        match child {
+            HtmlNode::Text(text) => *text = text.to_uppercase(),
+            HtmlNode::Element(child_element) => *child_element = uppercase(child_element),
+        }
+    }
+
+    element
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct Font {
+    bold: bool,
+    italic: bool,
+}
+
+impl Font {
+    fn escape(&self) -> &'static str {
+        match (self.bold, self.italic) {
+            (false, false) => "\\fR",
+            (true, false) => "\\fB",
+            (false, true) => "\\fI",
+            (true, true) => "\\f(BI",
+        }
+    }
+}
+
+
+/// Converter of compiled HTML in roff, it collects URLs of links as footnotes
+#[derive(Debug, Default)]
+struct ManConverter {
+    width: usize,
+    footnotes: LinkFootnotes,
+}
+
+impl ManConverter {
+
+    fn new(width: usize) -> Self {
+        Self {
+            width,
+            footnotes: LinkFootnotes::new(),
+        }
+    }
+
+    fn inline(&mut self, nodes: &[&HtmlNode], font: Font) -> String {
+        nodes.iter().map(|node| self.inline_node(node, font)).collect()
+    }
+
+    fn element_inline(&mut self, element: &HtmlElement, font: Font) -> String {
+        let children: Vec<&HtmlNode> = element.children.iter().collect();
+        self.inline(&children, font)
+    }
+
+    /// Inline content with font escapes, line breaks are `\n`
+    fn inline_node(&mut self, node: &HtmlNode, font: Font) -> String {
+
+        let element = match node {
+            HtmlNode::Text(text) => return escape_roff(&WHITESPACES_REGEX.replace_all(text, " ")),
+            HtmlNode::Element(element) => element,
+        };
+
+        let mut inner_font = font;
+
+        match element.name.as_str() {
+            "strong" | "b" | "code" | "kbd" | "samp" => inner_font.bold = true,
+            "em" | "i" | "u" => inner_font.italic = true,
+            "span" if element.has_class("inline-math") => inner_font.italic = true,
+            "br" => return String::from("\n"),
+            "img" => return escape_roff(&format!("[Image: {}]", element.attribute("alt").filter(|alt| !alt.is_empty()).or(element.attribute("src")).cloned().unwrap_or_default())),
+            "input" if element.attribute("type").is_some_and(|t| t.eq("checkbox")) => return String::from(if element.attribute("checked").is_some() { "[x] " } else { "[ ] " }),
+            "div" if element.has_class("checkbox") => return String::from(if element.has_class("checkbox-checked") { "[x] " } else { "[ ] " }),
+            "a" if element.has_class("cite") => return format!("[{}]", escape_roff(element.text().trim())),
+            "a" => {
+
+                let text = self.element_inline(element, font);
+
+                let href = element.attribute("href").cloned().unwrap_or_default();
+
+                if href.is_empty() || href.starts_with('#') || text.trim().eq(&escape_roff(&href)) {
+                    return text
+                }
+
+                return format!("{} [^{}]", text.trim_end(), self.footnotes.footnote(&href))
+            },
+            _ => (),
+        }
+
+        let content = self.element_inline(element, inner_font);
+
+        if inner_font == font || content.trim().is_empty() {
+            return content
+        }
+
+        format!("{}{}{}", inner_font.escape(), content, font.escape())
+    }
+
+    /// Filled text lines, line breaks become `.br` requests
+    fn text_lines(text: &str) -> Vec<String> {
+        text.split('\n')
+            .map(|line| text_line(&WHITESPACES_REGEX.replace_all(line, " ")))
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<String>>()
+            .join("\n.br\n")
+            .lines()
+            .map(String::from)
+            .collect()
+    }
+
+    fn paragraph(&mut self, element: &HtmlElement) -> Vec<String> {
+
+        let lines = Self::text_lines(&self.element_inline(element, Font::default()));
+
+        if lines.is_empty() {
+            return lines
+        }
+
+        std::iter::once(String::from(".PP")).chain(lines).collect()
+    }
+
+    /// Lines of no-fill mode (e.g. code), they are indented
+    fn no_fill(lines: Vec<String>) -> Vec<String> {
+
+        let mut roff = vec![String::from(".PP"), format!(".RS {}", INDENTATION), String::from(".nf")];
+
+        roff.extend(lines.iter().map(|line| {
+            let line = escape_roff(line);
+            if line.starts_with('.') || line.starts_with('\'') { format!("\\&{}", line) } else { line }
+        }));
+
+        roff.extend([String::from(".fi"), String::from(".RE")]);
+
+        roff
+    }
+
+    fn blocks(&mut self, element: &HtmlElement) -> Vec<String> {
+
+        let mut lines: Vec<String> = Vec::new();
+        let mut pending: Vec<&HtmlNode> = Vec::new();
+
+        for child in &element.children {
+
+            if !is_block(child) {
+                pending.push(child);
+                continue;
+            }
+
+            lines.extend(self.flush_inline(&mut pending));
+
+            if let HtmlNode::Element(child) = child {
+                lines.extend(self.block(child));
+            }
+        }
+
+        lines.extend(self.flush_inline(&mut pending));
+
+        lines
+    }
+
+    fn flush_inline(&mut self, pending: &mut Vec<&HtmlNode>) -> Vec<String> {
+
+        let text = self.inline(pending, Font::default());
+
+        pending.clear();
+
+        let lines = Self::text_lines(&text);
+
+        if lines.is_empty() {
+            return lines
+        }
+
+        std::iter::once(String::from(".PP")).chain(lines).collect()
+    }
+
+    fn heading(&mut self, element: &HtmlElement, level: usize) -> Vec<String> {
+
+        let text = match level {
+            1 => self.element_inline(&uppercase(element), Font::default()),
+            _ => self.element_inline(element, Font::default()),
+        };
+
+        if text.trim().is_empty() {
+            return Vec::new()
+        }
+
+        match level {
+            1 => vec![format!(".SH {}", macro_argument(&text))],
+            2 => vec![format!(".SS {}", macro_argument(&text))],
+            _ => vec![String::from(".PP"), format!("\\fB{}\\fR", text_line(&text))],
+        }
+    }
+
+    fn block(&mut self, element: &HtmlElement) -> Vec<String> {
+
+        if let Some(kind) = focus_block_kind(element) {
+            return self.focus_block(element, &kind)
+        }
+
+        match element.name.as_str() {
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                let level = element.name[1..].parse().unwrap_or(1);
+                self.heading(element, level)
+            },
+            "p" if element.has_class("math-block") => Self::no_fill(element.text().trim_matches('\n').lines().map(String::from).collect()),
+            "pre" => Self::no_fill(element.text().trim_matches('\n').lines().map(String::from).collect()),
+            "p" | "dt" | "dd" | "address" | "figcaption" | "caption" => self.paragraph(element),
+            "hr" => vec![String::from(".sp")],
+            "ul" | "ol" => self.list(element),
+            "table" => self.table(element),
+            "figure" => self.figure(element),
+            "blockquote" => {
+                let mut lines = vec![format!(".RS {}", INDENTATION)];
+                lines.extend(self.blocks(element));
+                lines.push(String::from(".RE"));
+                lines
+            },
+            // man pages have no table of contents
+            "section" if element.has_class("toc") => Vec::new(),
+            "section" if element.has_class("bibliography") => self.bibliography(element),
+            "div" if element.has_class("page-break") => vec![String::from(".bp")],
+            _ => self.blocks(element),
+        }
+    }
+
+    fn focus_block(&mut self, element: &HtmlElement, kind: &str) -> Vec<String> {
+
+        let (title, content) = split_focus_block_title(element);
+
+        let title = title.unwrap_or(kind.to_uppercase());
+
+        let mut lines = vec![String::from(".PP"), format!("\\fB{}\\fR", text_line(&escape_roff(&title))), format!(".RS {}", INDENTATION)];
+
+        lines.extend(self.blocks(&content));
+        lines.push(String::from(".RE"));
+
+        lines
+    }
+
+    /// Items are indented paragraphs, nested levels are relative insets
+    fn list(&mut self, element: &HtmlElement) -> Vec<String> {
+
+        let mut lines: Vec<String> = Vec::new();
+
+        let mut current_level: usize = 0;
+
+        for item in element.child_elements().filter(|item| item.name.eq("li")) {
+
+            let level = item.child_elements().filter(|child| child.has_class("list-item-indentation")).count();
+
+            while current_level < level {
+                lines.push(String::from(".RS"));
+                current_level += 1;
+            }
+
+            while current_level > level {
+                lines.push(String::from(".RE"));
+                current_level -= 1;
+            }
+
+            let bullet = match item.child_elements().find(|child| child.has_class("list-item-bullet")) {
+                Some(bullet) => WHITESPACES_REGEX.replace_all(&self.element_inline(bullet, Font::default()), " ").trim().replace('\u{2022}', "\\(bu"),
+                None => String::from("\\(bu"),
+            };
+
+            let content = match item.child_elements().find(|child| child.has_class("list-item-content")) {
+                Some(content) => self.element_inline(content, Font::default()),
+                None => self.element_inline(item, Font::default()),
+            };
+
+            lines.push(format!(".IP {} {}", macro_argument(&bullet), INDENTATION));
+            lines.extend(Self::text_lines(&content));
+        }
+
+        while current_level > 0 {
+            lines.push(String::from(".RE"));
+            current_level -= 1;
+        }
+
+        lines
+    }
+
+    fn table(&mut self, element: &HtmlElement) -> Vec<String> {
+
+        let grid = text_assembler::ascii_table(element, self.width.saturating_sub(INDENTATION), &mut |cell| WHITESPACES_REGEX.replace_all(&cell.text(), " ").trim().to_string());
+
+        let mut lines = Self::no_fill(grid);
+
+        for caption in element.child_elements().filter(|child| child.name.eq("caption")) {
+            lines.extend(self.paragraph(caption));
+        }
+
+        lines
+    }
+
+    fn figure(&mut self, element: &HtmlElement) -> Vec<String> {
+
+        let caption = element.child_elements()
+                        .find(|child| child.name.eq("figcaption"))
+                        .map(|caption| self.element_inline(caption, Font::default()))
+                        .filter(|caption| !caption.trim().is_empty());
+
+        match caption {
+            Some(caption) if element.child_elements().any(|child| child.name.eq("img")) => {
+                std::iter::once(String::from(".PP")).chain(Self::text_lines(&format!("[Image: {}]", caption.trim()))).collect()
+            },
+            _ => self.blocks(element),
+        }
+    }
+
+    fn bibliography(&mut self, element: &HtmlElement) -> Vec<String> {
+
+        let mut lines: Vec<String> = Vec::new();
+
+        if let Some(title) = element.child_elements().find(|child| child.has_class("bibliography-title")) {
+            lines.extend(self.heading(title, 1));
+        }
+
+        let items: Vec<&HtmlElement> = element.child_elements()
+                                            .filter(|child| child.has_class("bibliography-body"))
+                                            .flat_map(|body| body.child_elements())
+                                            .filter(|item| item.has_class("bibliography-item"))
+                                            .collect();
+
+        for (index, item) in items.iter().enumerate() {
+
+            let fields: Vec<String> = BIBLIOGRAPHY_ITEM_FIELD_CLASSES.iter()
+                                            .filter_map(|class| item.child_elements().find(|child| child.has_class(class)))
+                                            .map(|field| WHITESPACES_REGEX.replace_all(&field.text(), " ").trim().to_string())
+                                            .filter(|field| !field.is_empty())
+                                            .collect();
+
+            lines.push(format!(".IP \"[{}]\" {}", index + 1, INDENTATION));
+            lines.push(text_line(&escape_roff(&format!("{}.", fields.join(". ")))));
+        }
+
+        lines
+    }
+
+    /// Convert compiled HTML in roff (without page header), footnotes of links are appended as a section
+    fn convert(&mut self, html: &str) -> Vec<String> {
+
+        let root = html_utility::parse_html(html);
+
+        let mut lines = self.blocks(&root);
+
+        if !self.footnotes.is_empty() {
+
+            lines.push(String::from(".SH LINKS"));
+
+            for (index, link) in self.footnotes.links().iter().enumerate() {
+                lines.push(format!(".IP \"[^{}]\" {}", index + 1, INDENTATION));
+                lines.push(text_line(&escape_roff(link)));
+            }
+        }
+
+        lines
+    }
+}
+
+
+#[derive(Debug)]
+pub struct ManAssembler {
+    html_assembler: HtmlAssembler,
+}
+
+impl ManAssembler {
+
+    pub fn new() -> Self {
+        Self {
+            html_assembler: HtmlAssembler::new(),
+        }
+    }
+
+    /// Page with title header (`.TH`), each piece is converted separately (so it has its own footnotes)
+    fn man_page(title: &str, date: &str, pieces: &[&CompilationOutcome], configuration: &AssemblerConfiguration) -> String {
+
+        let mut lines = vec![format!(".TH {} {} {} \"\" {}", macro_argument(&escape_roff(&title.to_uppercase())), MAN_SECTION, macro_argument(&escape_roff(date)), macro_argument(&escape_roff(title)))];
+
+        for piece in pieces {
+            lines.extend(ManConverter::new(configuration.text_width()).convert(piece.content()));
+        }
+
+        let mut page = lines.join("\n");
+
+        page.push('\n');
+
+        page
+    }
+}
+
+impl Default for ManAssembler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Assembler for ManAssembler {
+
+    fn assemble_dossier(&self, compiled_documents: &Vec<CompilationOutcome>, compiled_toc: Option<&CompilationOutcome>, compiled_bib: Option<&CompilationOutcome>, compiled_index: Option<&CompilationOutcome>, dossier_configuration: &DossierConfiguration, configuration: &AssemblerConfiguration) -> Result<String, AssemblerError> {
+
+        let pieces: Vec<&CompilationOutcome> = compiled_toc.into_iter().chain(compiled_documents.iter()).chain(compiled_bib).chain(compiled_index).collect();
+
+        Ok(Self::man_page(dossier_configuration.name(), &variable::build_date(), &pieces, configuration))
+    }
+
+    fn assemble_dossier_section(&self, title: &str, id: &str, compiled_documents: &[CompilationOutcome], configuration: &AssemblerConfiguration) -> Result<String, AssemblerError> {
+        self.html_assembler.assemble_dossier_section(title, id, compiled_documents, configuration)
+    }
+
+    fn assemble_document(&self, document_metadata: &DocumentMetadata, compiled_content: &CompilationOutcome, configuration: &AssemblerConfiguration) -> Result<String, AssemblerError> {
+        self.html_assembler.assemble_document(document_metadata, compiled_content, configuration)
+    }
+
+    /// Title block is not included, because metadata are in page header
+    fn assemble_document_standalone(&self, page_title: &str, document_metadata: &DocumentMetadata, compiled_document: &CompilationOutcome, compiled_toc: Option<&CompilationOutcome>, compiled_bib: Option<&CompilationOutcome>, configuration: &AssemblerConfiguration) -> Result<String, AssemblerError> {
+
+        let title = document_metadata.title().clone().unwrap_or(page_title.to_string());
+
+        let date = document_metadata.date().clone().unwrap_or(variable::build_date());
+
+        let pieces: Vec<&CompilationOutcome> = compiled_toc.into_iter().chain(std::iter::once(compiled_document)).chain(compiled_bib).collect();
+
+        Ok(Self::man_page(&title, &date, &pieces, configuration))
+    }
+
+    fn assemble_bundle(&self, compiled_preamble: &Vec<CompilationOutcome>, compiled_chapters: &Vec<CompilationOutcome>, configuration: &AssemblerConfiguration) -> Result<String, AssemblerError> {
+        self.html_assembler.assemble_bundle(compiled_preamble, compiled_chapters, configuration)
+    }
+
+    fn assemble_chapter(&self, chapter_tags: &Vec<ChapterTag>, compiled_heading: &CompilationOutcome, compiled_paragraphs: &Vec<CompilationOutcome>, configuration: &AssemblerConfiguration) -> Result<String, AssemblerError> {
+        self.html_assembler.assemble_chapter(chapter_tags, compiled_heading, compiled_paragraphs, configuration)
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use crate::assembler::assembler_configuration::AssemblerConfiguration;
+    use super::{ManAssembler, ManConverter};
+
+
+    #[test]
+    fn convert_blocks() {
+
+        let roff = ManConverter::new(80).convert(concat!(
+            r#"<h1 class="heading-1">Options</h1>"#,
+            r#"<p class="paragraph">Use <strong>--force</strong> to <em>overwrite</em>.<br>.dot C:\path</p>"#,
+            r#"<ul class="list"><li class="list-item"><span class="list-item-bullet">&bull;</span><span class="list-item-content">one</span></li>"#,
+            r#"<li class="list-item"><span class="list-item-indentation"></span><span class="list-item-bullet">&bull;</span><span class="list-item-content">nested</span></li></ul>"#,
+            r#"<pre><code class="language-shell">.hidden
+nmd compile</code></pre>"#,
+        ));
+
+        assert_eq!(roff, vec![
+            ".SH \"OPTIONS\"",
+            ".PP",
+            "Use \\fB--force\\fR to \\fIoverwrite\\fR.",
+            ".br",
+            "\\&.dot C:\\epath",
+            ".IP \"\\(bu\" 4",
+            "one",
+            ".RS",
+            ".IP \"\\(bu\" 4",
+            "nested",
+            ".RE",
+            ".PP",
+            ".RS 4",
+            ".nf",
+            "\\&.hidden",
+            "nmd compile",
+            ".fi",
+            ".RE",
+        ]);
+    }
+
+    #[test]
+    fn escape_headers() {
+
+        let roff = ManConverter::new(80).convert(r#"<h1 class="heading-1">Use <code>C:\path</code> files</h1>"#);
+
+        assert_eq!(roff, vec![".SH \"USE \\fBC:\\ePATH\\fR FILES\""]);
+
+        let page = ManAssembler::man_page(r#"a\b "c""#, "2024", &[], &AssemblerConfiguration::default());
+
+        assert!(page.starts_with(r#".TH "A\eB \(dqC\(dq" 7 "2024" "" "a\eb \(dqc\(dq""#));
+    }
+}
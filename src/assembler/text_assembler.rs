@@ -0,0 +1,725 @@
+//! Plain text assembler: documents are compiled as HTML (using `HtmlAssembler`), then they are converted in text
+//! wrapped at `AssemblerConfiguration::text_width`. Styling is stripped, links become numbered footnotes
+//! and tables are drawn as ASCII grids
+
+use crate::{compilation::compilation_outcome::CompilationOutcome, dossier::{document::{chapter::chapter_tag::ChapterTag, document_metadata::DocumentMetadata}, dossier_configuration::DossierConfiguration}, utility::html_utility::{self, is_block, focus_block_kind, split_focus_block_title, HtmlElement, HtmlNode, LinkFootnotes, TableSlot, BIBLIOGRAPHY_ITEM_FIELD_CLASSES, WHITESPACES_REGEX}};
+use super::{assembler_configuration::AssemblerConfiguration, html_assembler::HtmlAssembler, Assembler, AssemblerError};
+
+
+/// Underline characters of headings, by level
+const HEADING_RULES: [char; 6] = ['=', '-', '~', '^', '"', '\''];
+
+/// Width used if configured one is too small
+const MIN_TEXT_WIDTH: usize = 20;
+
+const CODE_INDENTATION: &str = "    ";
+
+const LIST_INDENTATION: &str = "  ";
+
+const QUOTE_PREFIX: &str = "> ";
+
+const FOCUS_BLOCK_PREFIX: &str = "| ";
+
+/// Form feed, i.e. page break of plain text
+const PAGE_BREAK: &str = "\u{c}";
+
+fn text_width(s: &str) -> usize {
+    s.chars().count()
+}
+
+/// Collapse whitespaces of text, line breaks (`\n`) are kept
+fn collapse_whitespaces(text: &str) -> String {
+    text.split('\n')
+        .map(|line| WHITESPACES_REGEX.replace_all(line, " ").trim().to_string())
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Wrap `text` in lines not longer than `width` (words longer than `width` are kept whole), `\n` are hard line breaks
+pub fn wrap(text: &str, width: usize) -> Vec<String> {
+
+    let width = width.max(1);
+
+    let mut lines: Vec<String> = Vec::new();
+
+    for hard_line in text.split('\n') {
+
+        let mut line = String::new();
+
+        for word in hard_line.split_whitespace() {
+
+            if !line.is_empty() && text_width(&line) + 1 + text_width(word) > width {
+                lines.push(std::mem::take(&mut line));
+            }
+
+            if !line.is_empty() {
+                line.push(' ');
+            }
+
+            line.push_str(word);
+        }
+
+        lines.push(line);
+    }
+
+    while lines.last().is_some_and(|line| line.is_empty()) {
+        lines.pop();
+    }
+
+    lines
+}
+
+/// Prefix each line (`first` for the first one), trailing whitespaces are removed
+fn prefix_lines(lines: Vec<String>, first: &str, rest: &str) -> Vec<String> {
+    lines.into_iter().enumerate()
+        .map(|(index, line)| format!("{}{}", if index == 0 { first } else { rest }, line).trim_end().to_string())
+        .collect()
+}
+
+/// Join blocks of lines with an empty line, empty blocks are skipped
+fn join_blocks(blocks: Vec<Vec<String>>) -> Vec<String> {
+
+    let mut lines: Vec<String> = Vec::new();
+
+    for block in blocks.into_iter().filter(|block| !block.is_empty()) {
+
+        if !lines.is_empty() {
+            lines.push(String::new());
+        }
+
+        lines.extend(block);
+    }
+
+    lines
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CellAlignment {
+    Left,
+    Center,
+    Right,
+}
+
+/// Slot of an ASCII grid row: a cell (or the continuation of a cell of previous rows, if `content` is `None`)
+#[derive(Debug)]
+struct GridSlot {
+    column: usize,
+    span: usize,
+    content: Option<(String, CellAlignment)>,
+}
+
+#[derive(Debug)]
+struct GridRow {
+    header: bool,
+    slots: Vec<GridSlot>,
+}
+
+fn grid_rows(element: &HtmlElement, cell_text: &mut dyn FnMut(&HtmlElement) -> String) -> Vec<GridRow> {
+
+    let (rows, _) = html_utility::table_rows(element);
+
+    let layout = html_utility::layout_table(&rows.iter().map(|(row, _)| *row).collect::<Vec<&HtmlElement>>());
+
+    rows.iter().zip(layout).map(|((_, header), table_slots)| {
+
+        let mut slots: Vec<GridSlot> = Vec::new();
+        let mut column: usize = 0;
+
+        for table_slot in table_slots {
+
+            let span = table_slot.span();
+
+            let content = match table_slot {
+                TableSlot::Cell { element: cell, .. } => {
+
+                    let alignment = if cell.has_class("table-center-cell") {
+                        CellAlignment::Center
+                    } else if cell.has_class("table-right-cell") {
+                        CellAlignment::Right
+                    } else {
+                        CellAlignment::Left
+                    };
+
+                    Some((cell_text(cell), alignment))
+                },
+                TableSlot::Continuation { .. } => None,
+            };
+
+            slots.push(GridSlot { column, span, content });
+
+            column += span;
+        }
+
+        GridRow { header: *header, slots }
+
+    }).collect()
+}
+
+fn align(text: &str, width: usize, alignment: CellAlignment) -> String {
+
+    let padding = width.saturating_sub(text_width(text));
+
+    match alignment {
+        CellAlignment::Left => format!("{}{}", text, " ".repeat(padding)),
+        CellAlignment::Right => format!("{}{}", " ".repeat(padding), text),
+        CellAlignment::Center => format!("{}{}{}", " ".repeat(padding / 2), text, " ".repeat(padding - padding / 2)),
+    }
+}
+
+/// Draw table as an ASCII grid not wider than `width` (if it is possible without breaking words).
+/// `cell_text` returns text of a cell
+pub fn ascii_table(element: &HtmlElement, width: usize, cell_text: &mut dyn FnMut(&HtmlElement) -> String) -> Vec<String> {
+
+    let rows = grid_rows(element, cell_text);
+
+    let columns = rows.iter()
+                    .filter_map(|row| row.slots.last().map(|slot| slot.column + slot.span))
+                    .max()
+                    .unwrap_or(0);
+
+    if columns == 0 {
+        return Vec::new()
+    }
+
+    // natural and minimum widths, from cells which span only one column
+    let mut widths: Vec<usize> = vec![1; columns];
+    let mut min_widths: Vec<usize> = vec![1; columns];
+
+    for slot in rows.iter().flat_map(|row| row.slots.iter()).filter(|slot| slot.span == 1) {
+        if let Some((text, _)) = &slot.content {
+            widths[slot.column] = widths[slot.column].max(text.split('\n').map(text_width).max().unwrap_or(0));
+            min_widths[slot.column] = min_widths[slot.column].max(text.split_whitespace().map(text_width).max().unwrap_or(0));
+        }
+    }
+
+    // each column has 3 characters of borders and padding, plus the last border
+    let available = width.saturating_sub(3 * columns + 1);
+
+    while widths.iter().sum::<usize>() > available {
+
+        let widest = (0..columns).filter(|column| widths[*column] > min_widths[*column]).max_by_key(|column| widths[*column]);
+
+        match widest {
+            Some(column) => widths[column] -= 1,
+            None => break,
+        }
+    }
+
+    let slot_width = |slot: &GridSlot| widths[slot.column..slot.column + slot.span].iter().sum::<usize>() + 3 * (slot.span - 1);
+
+    // border above `next` row, columns of continuing cells are left open
+    let border = |rule: char, next: Option<&GridRow>| {
+
+        let mut line = String::from("+");
+
+        for (column, column_width) in widths.iter().enumerate() {
+
+            let open = next.is_some_and(|row| row.slots.iter().any(|slot| slot.content.is_none() && slot.column <= column && column < slot.column + slot.span));
+
+            line.push_str(&(if open { ' ' } else { rule }).to_string().repeat(column_width + 2));
+            line.push('+');
+        }
+
+        line
+    };
+
+    let mut lines: Vec<String> = vec![border('-', None)];
+
+    for (index, row) in rows.iter().enumerate() {
+
+        let cells: Vec<(Vec<String>, usize, CellAlignment)> = row.slots.iter().map(|slot| {
+
+            let width = slot_width(slot);
+
+            match &slot.content {
+                Some((text, alignment)) => (wrap(text, width), width, *alignment),
+                None => (Vec::new(), width, CellAlignment::Left),
+            }
+
+        }).collect();
+
+        let height = cells.iter().map(|(cell_lines, _, _)| cell_lines.len()).max().unwrap_or(0).max(1);
+
+        // empty columns after last cell
+        let filled: usize = row.slots.last().map(|slot| slot.column + slot.span).unwrap_or(0);
+
+        for line_index in 0..height {
+
+            let mut line = String::from("|");
+
+            for (cell_lines, width, alignment) in &cells {
+                line.push_str(&format!(" {} |", align(cell_lines.get(line_index).map(String::as_str).unwrap_or(""), *width, *alignment)));
+            }
+
+            for column_width in &widths[filled..] {
+                line.push_str(&format!(" {} |", " ".repeat(*column_width)));
+            }
+
+            lines.push(line);
+        }
+
+        let next = rows.get(index + 1);
+
+        let rule = if row.header && next.is_some_and(|next| !next.header) { '=' } else { '-' };
+
+        lines.push(border(rule, next));
+    }
+
+    lines
+}
+
+
+/// Converter of compiled HTML in plain text, it collects URLs of links as footnotes
+#[derive(Debug, Default)]
+struct TextConverter {
+    width: usize,
+    footnotes: LinkFootnotes,
+}
+
+impl TextConverter {
+
+    fn new(width: usize) -> Self {
+        Self {
+            width: width.max(MIN_TEXT_WIDTH),
+            footnotes: LinkFootnotes::new(),
+        }
+    }
+
+    fn inline(&mut self, nodes: &[&HtmlNode]) -> String {
+        nodes.iter().map(|node| self.inline_node(node)).collect()
+    }
+
+    fn element_inline(&mut self, element: &HtmlElement) -> String {
+        let children: Vec<&HtmlNode> = element.children.iter().collect();
+        self.inline(&children)
+    }
+
+    fn inline_node(&mut self, node: &HtmlNode) -> String {
+
+        let element = match node {
+            HtmlNode::Text(text) => return WHITESPACES_REGEX.replace_all(text, " ").to_string(),
+            HtmlNode::Element(element) => element,
+        };
+
+        match element.name.as_str() {
+            "br" => String::from("\n"),
+            "img" => format!("[Image: {}]", element.attribute("alt").filter(|alt| !alt.is_empty()).or(element.attribute("src")).cloned().unwrap_or_default()),
+            "input" if element.attribute("type").is_some_and(|t| t.eq("checkbox")) => String::from(if element.attribute("checked").is_some() { "[x] " } else { "[ ] " }),
+            "div" if element.has_class("checkbox") => String::from(if element.has_class("checkbox-checked") { "[x] " } else { "[ ] " }),
+            "a" if element.has_class("cite") => format!("[{}]", element.text().trim()),
+            "a" => {
+
+                let text = self.element_inline(element);
+
+                let href = element.attribute("href").cloned().unwrap_or_default();
+
+                if href.is_empty() || href.starts_with('#') || text.trim().eq(&href) {
+                    return text
+                }
+
+                format!("{} [^{}]", text.trim_end(), self.footnotes.footnote(&href))
+            },
+            _ => self.element_inline(element),
+        }
+    }
+
+    fn paragraph(&mut self, element: &HtmlElement, width: usize) -> Vec<String> {
+        let text = self.element_inline(element);
+        wrap(&collapse_whitespaces(&text), width)
+    }
+
+    /// Convert content of `element` in blocks of lines
+    fn blocks(&mut self, element: &HtmlElement, width: usize) -> Vec<Vec<String>> {
+
+        let mut blocks: Vec<Vec<String>> = Vec::new();
+        let mut pending: Vec<&HtmlNode> = Vec::new();
+
+        for child in &element.children {
+
+            if !is_block(child) {
+                pending.push(child);
+                continue;
+            }
+
+            blocks.push(self.flush_inline(&mut pending, width));
+
+            if let HtmlNode::Element(child) = child {
+                blocks.extend(self.block(child, width));
+            }
+        }
+
+        blocks.push(self.flush_inline(&mut pending, width));
+
+        blocks
+    }
+
+    fn flush_inline(&mut self, pending: &mut Vec<&HtmlNode>, width: usize) -> Vec<String> {
+
+        let text = self.inline(pending);
+
+        pending.clear();
+
+        wrap(&collapse_whitespaces(&text), width)
+    }
+
+    fn heading(&mut self, element: &HtmlElement, level: usize, width: usize) -> Vec<String> {
+
+        let mut lines = self.paragraph(element, width);
+
+        if lines.is_empty() {
+            return lines
+        }
+
+        lines.push(HEADING_RULES[level.clamp(1, HEADING_RULES.len()) - 1].to_string().repeat(width));
+
+        lines
+    }
+
+    fn block(&mut self, element: &HtmlElement, width: usize) -> Vec<Vec<String>> {
+
+        if let Some(kind) = focus_block_kind(element) {
+            return vec![self.focus_block(element, &kind, width)]
+        }
+
+        let lines = match element.name.as_str() {
+            "h1" if element.has_class("document-title") => {
+
+                let mut lines = self.heading(element, 1, width);
+
+                if !lines.is_empty() {
+                    lines.insert(0, HEADING_RULES[0].to_string().repeat(width));
+                }
+
+                lines
+            },
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                let level = element.name[1..].parse().unwrap_or(1);
+                self.heading(element, level, width)
+            },
+            "p" if element.has_class("math-block") => Self::preformatted(element),
+            "pre" => Self::preformatted(element),
+            "p" | "dt" | "dd" | "address" | "figcaption" | "caption" => self.paragraph(element, width),
+            "hr" => vec!["-".repeat(width)],
+            "ul" | "ol" => self.list(element, width),
+            "table" => self.table(element, width),
+            "figure" => self.figure(element, width),
+            "blockquote" => prefix_lines(join_blocks(self.blocks(element, width.saturating_sub(QUOTE_PREFIX.len()))), QUOTE_PREFIX, QUOTE_PREFIX),
+            "section" if element.has_class("toc") => self.table_of_contents(element, width),
+            "section" if element.has_class("bibliography") => self.bibliography(element, width),
+            "div" if element.has_class("page-break") => vec![String::from(PAGE_BREAK)],
+            _ => return self.blocks(element, width),
+        };
+
+        vec![lines]
+    }
+
+    /// Code and math blocks are indented, whitespaces are preserved
+    fn preformatted(element: &HtmlElement) -> Vec<String> {
+        element.text().trim_matches('\n').lines()
+            .map(|line| format!("{}{}", CODE_INDENTATION, line).trim_end().to_string())
+            .collect()
+    }
+
+    fn focus_block(&mut self, element: &HtmlElement, kind: &str, width: usize) -> Vec<String> {
+
+        let (title, content) = split_focus_block_title(element);
+
+        let title = title.unwrap_or(kind.to_uppercase());
+
+        let mut lines = vec![title];
+
+        lines.extend(join_blocks(self.blocks(&content, width.saturating_sub(FOCUS_BLOCK_PREFIX.len()))));
+
+        prefix_lines(lines, FOCUS_BLOCK_PREFIX, FOCUS_BLOCK_PREFIX)
+    }
+
+    fn list(&mut self, element: &HtmlElement, width: usize) -> Vec<String> {
+
+        let mut lines: Vec<String> = Vec::new();
+
+        for item in element.child_elements() {
+
+            if !item.name.eq("li") {
+                lines.extend(join_blocks(self.block(item, width)));
+                continue;
+            }
+
+            let level = item.child_elements().filter(|child| child.has_class("list-item-indentation")).count();
+
+            let bullet = match item.child_elements().find(|child| child.has_class("list-item-bullet")) {
+                Some(bullet) => collapse_whitespaces(&self.element_inline(bullet)),
+                None => String::new(),
+            };
+
+            let content = match item.child_elements().find(|child| child.has_class("list-item-content")) {
+                Some(content) => self.element_inline(content),
+                None => self.element_inline(item),
+            };
+
+            let first = format!("{}{} ", LIST_INDENTATION.repeat(level), bullet);
+            let rest = " ".repeat(text_width(&first));
+
+            let mut item_lines = wrap(&collapse_whitespaces(&content), width.saturating_sub(text_width(&first)));
+
+            if item_lines.is_empty() {
+                item_lines.push(String::new());
+            }
+
+            lines.extend(prefix_lines(item_lines, &first, &rest));
+        }
+
+        lines
+    }
+
+    fn table(&mut self, element: &HtmlElement, width: usize) -> Vec<String> {
+
+        let mut lines = ascii_table(element, width, &mut |cell| collapse_whitespaces(&self.element_inline(cell)));
+
+        for caption in element.child_elements().filter(|child| child.name.eq("caption")) {
+            lines.extend(self.paragraph(caption, width));
+        }
+
+        lines
+    }
+
+    fn figure(&mut self, element: &HtmlElement, width: usize) -> Vec<String> {
+
+        let caption = element.child_elements().find(|child| child.name.eq("figcaption")).map(|caption| collapse_whitespaces(&self.element_inline(caption)));
+
+        let images = element.child_elements().filter(|child| child.name.eq("img")).count();
+
+        match caption.filter(|caption| !caption.is_empty()) {
+            Some(caption) if images > 0 => wrap(&format!("[Image: {}]", caption), width),
+            Some(caption) => wrap(&caption, width),
+            None => join_blocks(self.blocks(element, width)),
+        }
+    }
+
+    fn table_of_contents(&mut self, element: &HtmlElement, width: usize) -> Vec<String> {
+
+        let mut lines: Vec<String> = Vec::new();
+
+        if let Some(title) = element.child_elements().find(|child| child.has_class("toc-title")) {
+            lines.extend(self.heading(title, 1, width));
+        }
+
+        let items: Vec<&HtmlElement> = element.child_elements()
+                                            .filter(|child| child.has_class("toc-body"))
+                                            .flat_map(|body| body.child_elements())
+                                            .filter(|item| item.has_class("toc-item"))
+                                            .collect();
+
+        for item in items {
+
+            // top level items have one indentation
+            let level = item.child_elements().filter(|child| child.has_class("toc-item-indentation")).count().saturating_sub(1);
+
+            let content = match item.child_elements().find(|child| child.has_class("toc-item-content")) {
+                Some(content) => self.element_inline(content),
+                None => self.element_inline(item),
+            };
+
+            let indentation = LIST_INDENTATION.repeat(level);
+
+            lines.extend(prefix_lines(wrap(&collapse_whitespaces(&content), width.saturating_sub(indentation.len())), &indentation, &indentation));
+        }
+
+        lines
+    }
+
+    /// Bibliography records are numbered (as cites)
+    fn bibliography(&mut self, element: &HtmlElement, width: usize) -> Vec<String> {
+
+        let mut lines: Vec<String> = Vec::new();
+
+        if let Some(title) = element.child_elements().find(|child| child.has_class("bibliography-title")) {
+            lines.extend(self.heading(title, 1, width));
+            lines.push(String::new());
+        }
+
+        let items: Vec<&HtmlElement> = element.child_elements()
+                                            .filter(|child| child.has_class("bibliography-body"))
+                                            .flat_map(|body| body.child_elements())
+                                            .filter(|item| item.has_class("bibliography-item"))
+                                            .collect();
+
+        for (index, item) in items.iter().enumerate() {
+
+            let fields: Vec<String> = BIBLIOGRAPHY_ITEM_FIELD_CLASSES.iter()
+                                            .filter_map(|class| item.child_elements().find(|child| child.has_class(class)))
+                                            .map(|field| collapse_whitespaces(&field.text()))
+                                            .filter(|field| !field.is_empty())
+                                            .collect();
+
+            let first = format!("[{}] ", index + 1);
+            let rest = " ".repeat(first.len());
+
+            lines.extend(prefix_lines(wrap(&format!("{}.", fields.join(". ")), width.saturating_sub(first.len())), &first, &rest));
+        }
+
+        lines
+    }
+
+    /// Convert compiled HTML in plain text, footnotes of links are appended
+    fn convert(&mut self, html: &str) -> String {
+
+        let root = html_utility::parse_html(html);
+
+        let mut blocks = self.blocks(&root, self.width);
+
+        if !self.footnotes.is_empty() {
+            blocks.push(self.footnotes.links().iter().enumerate().map(|(index, link)| format!("[^{}]: {}", index + 1, link)).collect());
+        }
+
+        join_blocks(blocks).join("\n")
+    }
+}
+
+
+#[derive(Debug)]
+pub struct TextAssembler {
+    html_assembler: HtmlAssembler,
+}
+
+impl TextAssembler {
+
+    pub fn new() -> Self {
+        Self {
+            html_assembler: HtmlAssembler::new(),
+        }
+    }
+
+    /// Convert each piece separately (so each document has its own footnotes) and join them
+    fn convert_pieces(pieces: &[&CompilationOutcome], configuration: &AssemblerConfiguration) -> String {
+
+        let mut text = pieces.iter()
+                        .map(|piece| TextConverter::new(configuration.text_width()).convert(piece.content()))
+                        .filter(|text| !text.is_empty())
+                        .collect::<Vec<String>>()
+                        .join("\n\n\n");
+
+        text.push('\n');
+
+        text
+    }
+}
+
+impl Default for TextAssembler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Assembler for TextAssembler {
+
+    fn assemble_dossier(&self, compiled_documents: &Vec<CompilationOutcome>, compiled_toc: Option<&CompilationOutcome>, compiled_bib: Option<&CompilationOutcome>, compiled_index: Option<&CompilationOutcome>, _dossier_configuration: &DossierConfiguration, configuration: &AssemblerConfiguration) -> Result<String, AssemblerError> {
+
+        let pieces: Vec<&CompilationOutcome> = compiled_toc.into_iter().chain(compiled_documents.iter()).chain(compiled_bib).chain(compiled_index).collect();
+
+        Ok(Self::convert_pieces(&pieces, configuration))
+    }
+
+    fn assemble_dossier_section(&self, title: &str, id: &str, compiled_documents: &[CompilationOutcome], configuration: &AssemblerConfiguration) -> Result<String, AssemblerError> {
+        self.html_assembler.assemble_dossier_section(title, id, compiled_documents, configuration)
+    }
+
+    fn assemble_document(&self, document_metadata: &DocumentMetadata, compiled_content: &CompilationOutcome, configuration: &AssemblerConfiguration) -> Result<String, AssemblerError> {
+        self.html_assembler.assemble_document(document_metadata, compiled_content, configuration)
+    }
+
+    fn assemble_document_standalone(&self, _page_title: &str, _document_metadata: &DocumentMetadata, compiled_document: &CompilationOutcome, compiled_toc: Option<&CompilationOutcome>, compiled_bib: Option<&CompilationOutcome>, configuration: &AssemblerConfiguration) -> Result<String, AssemblerError> {
+
+        let pieces: Vec<&CompilationOutcome> = compiled_toc.into_iter().chain(std::iter::once(compiled_document)).chain(compiled_bib).collect();
+
+        Ok(Self::convert_pieces(&pieces, configuration))
+    }
+
+    fn assemble_bundle(&self, compiled_preamble: &Vec<CompilationOutcome>, compiled_chapters: &Vec<CompilationOutcome>, configuration: &AssemblerConfiguration) -> Result<String, AssemblerError> {
+        self.html_assembler.assemble_bundle(compiled_preamble, compiled_chapters, configuration)
+    }
+
+    fn assemble_chapter(&self, chapter_tags: &Vec<ChapterTag>, compiled_heading: &CompilationOutcome, compiled_paragraphs: &Vec<CompilationOutcome>, configuration: &AssemblerConfiguration) -> Result<String, AssemblerError> {
+        self.html_assembler.assemble_chapter(chapter_tags, compiled_heading, compiled_paragraphs, configuration)
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use crate::{assembler::{assembler_configuration::AssemblerConfiguration, Assembler}, compilation::compilation_outcome::CompilationOutcome, dossier::document::document_metadata::DocumentMetadata};
+    use super::{wrap, TextAssembler, TextConverter};
+
+
+    #[test]
+    fn wrap_text() {
+        assert_eq!(wrap("the quick brown fox jumps over the lazy dog", 10), vec!["the quick", "brown fox", "jumps over", "the lazy", "dog"]);
+        assert_eq!(wrap("a\nb  c", 10), vec!["a", "b c"]);
+        assert_eq!(wrap("averyveryverylongword x", 5), vec!["averyveryverylongword", "x"]);
+    }
+
+    #[test]
+    fn convert_blocks() {
+
+        let text = TextConverter::new(30).convert(concat!(
+            r#"<h1 class="heading-1">Release notes</h1>"#,
+            r##"<p class="paragraph">New <strong>parser</strong>, see <a class="link" href="https://example.com">docs</a> and <a href="#x">this</a>.</p>"##,
+            r#"<ul class="list"><li class="list-item"><span class="list-item-bullet">&bull;</span><span class="list-item-content">fast builds and small outputs</span></li>"#,
+            r#"<li class="list-item"><span class="list-item-indentation"></span><span class="list-item-bullet"><div class="checkbox checkbox-checked"></div></span><span class="list-item-content">done</span></li></ul>"#,
+            r#"<div class="focus-block focus-block-warning"><div class="focus-block-title"></div><div class="focus-block-description"><p>Careful</p></div></div>"#,
+        ));
+
+        assert_eq!(text, concat!(
+            "Release notes\n",
+            "==============================\n",
+            "\n",
+            "New parser, see docs [^1] and\n",
+            "this.\n",
+            "\n",
+            "\u{2022} fast builds and small\n",
+            "  outputs\n",
+            "  [x] done\n",
+            "\n",
+            "| WARNING\n",
+            "| Careful\n",
+            "\n",
+            "[^1]: https://example.com",
+        ));
+    }
+
+    #[test]
+    fn standalone_document_title_once() {
+
+        let assembler = TextAssembler::new();
+        let configuration = AssemblerConfiguration::default();
+
+        let metadata = DocumentMetadata::load_front_matter("---\ntitle: My Title\n---\n").unwrap().0.unwrap();
+
+        let document = CompilationOutcome::from(assembler.assemble_document(&metadata, &CompilationOutcome::from("<p>Body</p>"), &configuration).unwrap());
+
+        let text = assembler.assemble_document_standalone("page", &metadata, &document, None, None, &configuration).unwrap();
+
+        assert_eq!(text.matches("My Title").count(), 1);
+    }
+
+    #[test]
+    fn ascii_table() {
+
+        let text = TextConverter::new(40).convert(concat!(
+            r#"<table class="table"><thead><tr><td>Name</td><td class="table-right-cell">Size</td></tr></thead>"#,
+            r#"<tbody><tr><td rowspan="2">core</td><td class="table-right-cell">12</td></tr><tr><td class="table-right-cell">3</td></tr>"#,
+            r#"<tr><td colspan="2">total</td></tr></tbody></table>"#,
+        ));
+
+        assert_eq!(text, concat!(
+            "+------+------+\n",
+            "| Name | Size |\n",
+            "+======+======+\n",
+            "| core |   12 |\n",
+            "+      +------+\n",
+            "|      |    3 |\n",
+            "+------+------+\n",
+            "| total       |\n",
+            "+------+------+",
+        ));
+    }
+}
@@ -62,7 +62,7 @@ impl Compilable for Bibliography {
         log::info!("compiling bibliography...");
 
         match format {
-            OutputFormat::Html => {
+            OutputFormat::Html | OutputFormat::Epub | OutputFormat::Docx | OutputFormat::Text | OutputFormat::Man => {
                 let mut compilation_result = CompilableText::new_empty();
 
                 let mut compiled_title = CompilableText::from(self.title.clone());
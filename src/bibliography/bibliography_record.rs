@@ -1,7 +1,8 @@
 use getset::{Getters, Setters};
 use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
 
-#[derive(Debug, Clone, Deserialize, Serialize, Getters, Setters)]
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, Getters, Setters)]
 pub struct BibliographyRecord {
 
     #[getset(get = "pub", set = "pub")]
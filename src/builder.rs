@@ -0,0 +1,410 @@
+//! `DossierBuilder` runs the whole load → compile → assemble → dump pipeline of a dossier,
+//! deriving every configuration from `DossierConfiguration`
+
+pub mod build_report;
+
+
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::time::Instant;
+use build_report::BuildReport;
+use getset::{CopyGetters, Getters, Setters};
+use thiserror::Error;
+use crate::artifact::Artifact;
+use crate::assembler::assembler_configuration::AssemblerConfiguration;
+use crate::codex::{Codex, CodexError};
+use crate::compilation::compilable::Compilable;
+use crate::compilation::compilation_configuration::compilation_configuration_overlay::CompilationConfigurationOverLay;
+use crate::compilation::compilation_configuration::CompilationConfiguration;
+use crate::compilation::compilation_error::CompilationError;
+use crate::dossier::dossier_configuration::DossierConfiguration;
+use crate::dossier::Dossier;
+use crate::dumpable::{DumpConfiguration, DumpError, Dumpable};
+use crate::load::{LoadConfiguration, LoadConfigurationOverLay, LoadError};
+use crate::output_format::OutputFormat;
+use crate::resource::ResourceError;
+use crate::theme::Theme;
+use crate::utility::file_utility;
+
+
+#[derive(Error, Debug)]
+pub enum BuildError {
+
+    #[error(transparent)]
+    LoadError(#[from] LoadError),
+
+    #[error(transparent)]
+    CompilationError(#[from] CompilationError),
+
+    #[error(transparent)]
+    DumpError(#[from] DumpError),
+
+    #[error(transparent)]
+    ResourceError(#[from] ResourceError),
+
+    #[error(transparent)]
+    CodexError(#[from] CodexError),
+}
+
+
+/// Builder-style facade to build a dossier in one call:
+///
+/// ```ignore
+/// let report = DossierBuilder::try_from(&dossier_path)?
+///                 .with_theme(Theme::Dark)
+///                 .with_fast_draft(true)
+///                 .build()?;
+/// ```
+#[derive(Debug, Clone, Getters, CopyGetters, Setters)]
+pub struct DossierBuilder {
+
+    #[getset(get = "pub", set = "pub")]
+    dossier_configuration: DossierConfiguration,
+
+    #[getset(get = "pub", set = "pub")]
+    input_location: PathBuf,
+
+    #[getset(get = "pub", set = "pub")]
+    output_location: Option<PathBuf>,
+
+    #[getset(get = "pub", set = "pub")]
+    format: OutputFormat,
+
+    #[getset(get = "pub", set = "pub")]
+    theme: Option<Theme>,
+
+    #[getset(get_copy = "pub", set = "pub")]
+    fast_draft: bool,
+
+    #[getset(get = "pub", set = "pub")]
+    compile_only_documents: Option<HashSet<String>>,
+
+    #[getset(get_copy = "pub", set = "pub")]
+    parallelization: Option<bool>,
+
+    #[getset(get_copy = "pub", set = "pub")]
+    force_dump: bool,
+
+    #[getset(get = "pub", set = "pub")]
+    variables: HashMap<String, String>,
+
+    /// Build tags added to dossier configuration ones
+    #[getset(get = "pub", set = "pub")]
+    tags: HashSet<String>,
+
+    #[getset(get_copy = "pub", set = "pub")]
+    text_width: Option<usize>,
+}
+
+impl DossierBuilder {
+
+    /// Create a new builder, `input_location` is the dossier root directory
+    pub fn new(dossier_configuration: DossierConfiguration, input_location: PathBuf) -> Self {
+        Self {
+            dossier_configuration,
+            input_location,
+            output_location: None,
+            format: OutputFormat::default(),
+            theme: None,
+            fast_draft: false,
+            compile_only_documents: None,
+            parallelization: None,
+            force_dump: false,
+            variables: HashMap::new(),
+            tags: HashSet::new(),
+            text_width: None,
+        }
+    }
+
+    /// Output file or directory. If it is a directory (or it is not provided), output file name is inferred from dossier name
+    pub fn with_output_location(mut self, output_location: PathBuf) -> Self {
+        self.output_location = Some(output_location);
+        self
+    }
+
+    pub fn with_format(mut self, format: OutputFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Override dossier configuration theme
+    pub fn with_theme(mut self, theme: Theme) -> Self {
+        self.theme = Some(theme);
+        self
+    }
+
+    pub fn with_fast_draft(mut self, fast_draft: bool) -> Self {
+        self.fast_draft = fast_draft;
+        self
+    }
+
+    /// Compile only a subset of documents (it works only in fast draft)
+    pub fn with_compile_only_documents(mut self, documents: HashSet<String>) -> Self {
+        self.compile_only_documents = Some(documents);
+        self
+    }
+
+    /// Override dossier configuration parallelization
+    pub fn with_parallelization(mut self, parallelization: bool) -> Self {
+        self.parallelization = Some(parallelization);
+        self
+    }
+
+    /// Create output parent directories if they don't exist
+    pub fn with_force_dump(mut self, force_dump: bool) -> Self {
+        self.force_dump = force_dump;
+        self
+    }
+
+    /// Override a variable value (it has precedence over environment variables and dossier configuration)
+    pub fn with_variable(mut self, key: String, value: String) -> Self {
+        self.variables.insert(key, value);
+        self
+    }
+
+    /// Add a build tag used by conditional content
+    pub fn with_tag(mut self, tag: String) -> Self {
+        self.tags.insert(tag);
+        self
+    }
+
+    /// Override dossier configuration line width of plain text outputs
+    pub fn with_text_width(mut self, text_width: usize) -> Self {
+        self.text_width = Some(text_width);
+        self
+    }
+
+    /// Apply a named profile of dossier configuration (tags, documents, variables and compilation flags)
+    pub fn with_profile(mut self, profile_name: &str) -> Result<Self, BuildError> {
+        self.dossier_configuration.apply_profile(profile_name)?;
+        Ok(self)
+    }
+
+    pub fn load_configuration(&self) -> LoadConfiguration {
+
+        let mut load_configuration = LoadConfiguration::from(&self.dossier_configuration);
+
+        load_configuration.set_input_location(self.input_location.clone());
+
+        if let Some(parallelization) = self.parallelization {
+            load_configuration.set_parallelization(parallelization);
+        }
+
+        let compilation_configuration = self.compilation_configuration();
+
+        load_configuration.set_tags(compilation_configuration.tags().clone());
+        load_configuration.set_variables(compilation_configuration.variables().clone());
+
+        load_configuration
+    }
+
+    pub fn compilation_configuration(&self) -> CompilationConfiguration {
+
+        let mut compilation_configuration = CompilationConfiguration::from(&self.dossier_configuration);
+
+        compilation_configuration.set_input_location(self.input_location.clone());
+        compilation_configuration.set_output_location(self.output_path());
+        compilation_configuration.set_fast_draft(self.fast_draft);
+
+        if let Some(parallelization) = self.parallelization {
+            compilation_configuration.set_parallelization(parallelization);
+        }
+
+        if let Some(theme) = &self.theme {
+            compilation_configuration.set_theme(theme.clone());
+        }
+
+        compilation_configuration.apply_environment_variable_overrides();
+        compilation_configuration.apply_variable_overrides(&self.variables);
+
+        compilation_configuration.tags_mut().extend(self.tags.iter().cloned());
+
+        compilation_configuration
+    }
+
+    pub fn assembler_configuration(&self) -> AssemblerConfiguration {
+
+        let mut assembler_configuration = AssemblerConfiguration::from(&self.dossier_configuration);
+
+        if let Some(parallelization) = self.parallelization {
+            assembler_configuration.set_parallelization(parallelization);
+        }
+
+        if let Some(theme) = &self.theme {
+            assembler_configuration.set_theme(theme.clone());
+        }
+
+        if let Some(text_width) = self.text_width {
+            assembler_configuration.set_text_width(text_width);
+        }
+
+        assembler_configuration
+    }
+
+    pub fn compilation_configuration_overlay(&self) -> CompilationConfigurationOverLay {
+
+        let mut compilation_configuration_overlay = CompilationConfigurationOverLay::default();
+
+        compilation_configuration_overlay.set_assembler_configuration(self.assembler_configuration());
+        compilation_configuration_overlay.set_compile_only_documents(self.compile_only_documents.clone());
+
+        compilation_configuration_overlay
+    }
+
+    /// `Codex` of output format, extended with user-defined modifiers of dossier configuration
+    pub fn codex(&self) -> Result<Codex, BuildError> {
+
+        let mut codex = Codex::from(&self.format);
+
+        codex.insert_dossier_configuration_modifiers(self.dossier_configuration.modifiers())?;
+
+        codex.validate_ordering()?;
+
+        Ok(codex)
+    }
+
+    pub fn dump_configuration(&self) -> DumpConfiguration {
+        DumpConfiguration::new(self.output_path(), self.force_dump)
+    }
+
+    /// Final output file path
+    pub fn output_path(&self) -> PathBuf {
+
+        let output_location = self.output_location.clone().unwrap_or(self.input_location.clone());
+
+        if file_utility::is_directory_like(&output_location) {
+            return output_location.join(file_utility::build_output_file_name(self.dossier_configuration.name(), Some(&self.format.get_extension())))
+        }
+
+        output_location
+    }
+
+    /// Load and compile dossier without dump it
+    pub fn build_artifact(&self) -> Result<(Artifact, BuildReport), BuildError> {
+
+        let mut report = BuildReport::new(self.dossier_configuration.name().clone(), self.format.clone());
+
+        let codex = self.codex()?;
+
+        let now = Instant::now();
+
+        let mut dossier = Dossier::load_dossier_from_dossier_configuration(&self.dossier_configuration, &codex, &self.load_configuration(), LoadConfigurationOverLay::default())?;
+
+        report.set_load_time(now.elapsed());
+        report.set_loaded_documents(dossier.documents().len());
+
+        self.check_dossier(&dossier, &mut report);
+
+        let compilation_configuration = self.compilation_configuration();
+
+        compilation_configuration.check_variables()?;
+
+        let now = Instant::now();
+
+        let artifact = if self.format.is_package() {
+
+            Artifact::new_binary(dossier.compile_package(&self.format, &codex, &compilation_configuration, self.compilation_configuration_overlay())?)
+
+        } else {
+
+            Artifact::from(dossier.compile(&self.format, &codex, &compilation_configuration, self.compilation_configuration_overlay())?.content())
+        };
+
+        report.set_compilation_time(now.elapsed());
+
+        Ok((artifact, report))
+    }
+
+    /// Load, compile and dump dossier
+    pub fn build(&self) -> Result<BuildReport, BuildError> {
+
+        let (mut artifact, mut report) = self.build_artifact()?;
+
+        let now = Instant::now();
+
+        let dump_configuration = self.dump_configuration();
+
+        artifact.dump(&dump_configuration)?;
+
+        report.set_dump_time(now.elapsed());
+        report.written_files_mut().push(dump_configuration.output_path().clone());
+
+        log::info!("dossier '{}' built in {} ms", self.dossier_configuration.name(), report.total_time().as_millis());
+
+        Ok(report)
+    }
+
+    /// Collect non-blocking issues of loaded dossier
+    fn check_dossier(&self, dossier: &Dossier, report: &mut BuildReport) {
+
+        for document in dossier.documents() {
+            if document.content().preamble().is_empty() && document.content().chapters().is_empty() {
+                report.add_warning(format!("document '{}' is empty", document.name()));
+            }
+        }
+
+        if let Some(compile_only_documents) = &self.compile_only_documents {
+
+            if !self.fast_draft {
+                report.add_warning(String::from("compile only documents is ignored because fast draft is disabled"));
+            }
+
+            for document_name in compile_only_documents {
+                if !dossier.documents().iter().any(|document| document.name().eq(document_name)) {
+                    report.add_warning(format!("document '{}' to compile not found in dossier", document_name));
+                }
+            }
+        }
+    }
+}
+
+impl TryFrom<&PathBuf> for DossierBuilder {
+    type Error = BuildError;
+
+    /// Load dossier configuration from dossier directory or from dossier configuration file
+    fn try_from(path_buf: &PathBuf) -> Result<Self, Self::Error> {
+
+        let dossier_configuration = DossierConfiguration::try_from(path_buf)?;
+
+        let mut input_location = path_buf.clone();
+
+        if input_location.is_file() {
+            if let Some(parent) = path_buf.parent() {
+                input_location = parent.to_path_buf();
+            }
+        }
+
+        Ok(Self::new(dossier_configuration, input_location))
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+    use crate::{dossier::dossier_configuration::DossierConfiguration, theme::Theme};
+    use super::DossierBuilder;
+
+
+    #[test]
+    fn build_artifact() {
+
+        let dossier_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("test-resources").join("nmd-test-dossier-1");
+
+        let mut dossier_configuration = DossierConfiguration::try_from(&dossier_path).unwrap();
+
+        dossier_configuration.set_raw_documents_paths(vec![String::from("./d1.nmd"), String::from("./d3.nmd")]);
+
+        let builder = DossierBuilder::new(dossier_configuration, dossier_path.clone())
+                                    .with_theme(Theme::Dark)
+                                    .with_parallelization(false);
+
+        assert_eq!(builder.output_path(), dossier_path.join("new-dossier.html"));
+
+        let (artifact, report) = builder.build_artifact().unwrap();
+
+        assert!(artifact.content().contains("<html>"));
+        assert_eq!(report.loaded_documents(), 2);
+        assert!(report.written_files().is_empty());
+    }
+}
@@ -0,0 +1,80 @@
+use std::{fmt::Display, path::PathBuf, time::Duration};
+use getset::{CopyGetters, Getters, MutGetters, Setters};
+use serde::Serialize;
+use crate::output_format::OutputFormat;
+
+
+/// Summary of a `DossierBuilder` build
+#[derive(Debug, Clone, Default, Getters, CopyGetters, MutGetters, Setters, Serialize)]
+pub struct BuildReport {
+
+    #[getset(get = "pub", set = "pub")]
+    dossier_name: String,
+
+    #[getset(get = "pub", set = "pub")]
+    #[serde(skip)]
+    format: OutputFormat,
+
+    #[getset(get_copy = "pub", set = "pub")]
+    loaded_documents: usize,
+
+    #[getset(get_copy = "pub", set = "pub")]
+    load_time: Duration,
+
+    #[getset(get_copy = "pub", set = "pub")]
+    compilation_time: Duration,
+
+    #[getset(get_copy = "pub", set = "pub")]
+    dump_time: Duration,
+
+    #[getset(get = "pub", get_mut = "pub", set = "pub")]
+    warnings: Vec<String>,
+
+    #[getset(get = "pub", get_mut = "pub", set = "pub")]
+    written_files: Vec<PathBuf>,
+}
+
+impl BuildReport {
+
+    pub fn new(dossier_name: String, format: OutputFormat) -> Self {
+        Self {
+            dossier_name,
+            format,
+            ..Default::default()
+        }
+    }
+
+    pub fn total_time(&self) -> Duration {
+        self.load_time + self.compilation_time + self.dump_time
+    }
+
+    pub fn add_warning(&mut self, warning: String) {
+
+        log::warn!("{}", warning);
+
+        self.warnings.push(warning)
+    }
+}
+
+impl Display for BuildReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "dossier '{}' built as {} in {} ms (load: {} ms, compilation: {} ms, dump: {} ms)",
+            self.dossier_name,
+            self.format.get_extension(),
+            self.total_time().as_millis(),
+            self.load_time.as_millis(),
+            self.compilation_time.as_millis(),
+            self.dump_time.as_millis(),
+        )?;
+
+        for warning in &self.warnings {
+            writeln!(f, "warning: {}", warning)?;
+        }
+
+        for file in &self.written_files {
+            writeln!(f, "written: {}", file.to_string_lossy())?;
+        }
+
+        Ok(())
+    }
+}
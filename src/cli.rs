@@ -0,0 +1,963 @@
+//! `nmd` command line interface. It wraps load, compilation and dump of NMD dossiers and files
+
+
+use std::collections::HashSet;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+use clap::{Args, Parser, Subcommand};
+use log::LevelFilter;
+use notify::{EventKind, RecursiveMode, Watcher};
+use nmd_core::artifact::Artifact;
+use nmd_core::assembler::assembler_configuration::AssemblerConfiguration;
+use nmd_core::assembler::AssemblerError;
+use nmd_core::builder::{BuildError, DossierBuilder};
+use nmd_core::codex::Codex;
+use nmd_core::codex::modifier::constants::MAX_HEADING_LEVEL;
+use nmd_core::compilation::compilable::Compilable;
+use nmd_core::compilation::compilation_configuration::compilation_configuration_overlay::CompilationConfigurationOverLay;
+use nmd_core::compilation::compilation_configuration::{CompilableResourceType, CompilationConfiguration};
+use nmd_core::compilation::compilation_error::CompilationError;
+use nmd_core::constants::{DOSSIER_CONFIGURATION_FILE_NAMES, DOSSIER_CONFIGURATION_YAML_FILE_NAME, NMD_EXTENSION};
+use nmd_core::dossier::document::Document;
+use nmd_core::dossier::document::chapter::heading::Heading;
+use nmd_core::dossier::dossier_configuration::DossierConfiguration;
+use nmd_core::dossier::dossier_configuration::dossier_configuration_validation;
+use nmd_core::dossier;
+use nmd_core::dumpable::{DumpConfiguration, DumpError, Dumpable};
+use nmd_core::formatter::{Formatter, FormatterError};
+use nmd_core::linter::{Linter, LinterError};
+use nmd_core::language_server::LanguageServer;
+use nmd_core::importer::ImportError;
+use nmd_core::importer::markdown_importer::MarkdownImporter;
+use nmd_core::load::{LoadConfiguration, LoadConfigurationOverLay, LoadError};
+use nmd_core::output_format::OutputFormat;
+use nmd_core::resource::ResourceError;
+use nmd_core::table_of_contents::TableOfContents;
+use nmd_core::theme::Theme;
+use nmd_core::utility::file_utility;
+use simple_logger::SimpleLogger;
+use thiserror::Error;
+
+
+const SAMPLE_DOCUMENT_FILE_NAME: &str = "welcome.nmd";
+
+const SAMPLE_DOCUMENT_CONTENT: &str = r#"# Welcome
+
+Welcome in **NMD**!
+
+This dossier was generated by `nmd init`: add your documents in `nmd.yml` and compile them using `nmd compile`.
+
+## Assets
+
+Put images in `assets/images` and custom styles in `assets/styles`.
+"#;
+
+
+#[derive(Error, Debug)]
+pub enum CliError {
+
+    #[error(transparent)]
+    LoadError(#[from] LoadError),
+
+    #[error(transparent)]
+    CompilationError(#[from] CompilationError),
+
+    #[error(transparent)]
+    AssemblerError(#[from] AssemblerError),
+
+    #[error(transparent)]
+    DumpError(#[from] DumpError),
+
+    #[error(transparent)]
+    BuildError(#[from] BuildError),
+
+    #[error(transparent)]
+    ResourceError(#[from] ResourceError),
+
+    #[error(transparent)]
+    ImportError(#[from] ImportError),
+
+    #[error(transparent)]
+    FormatterError(#[from] FormatterError),
+
+    #[error(transparent)]
+    LinterError(#[from] LinterError),
+
+    #[error(transparent)]
+    WatcherError(#[from] notify::Error),
+
+    #[error(transparent)]
+    IoError(#[from] io::Error),
+
+    #[error("schema cannot be serialized: {0}")]
+    SchemaError(#[from] serde_json::Error),
+
+    #[error("logger cannot be initialized: {0}")]
+    LoggerError(String),
+
+    #[error("dossier already exists in {0}, use --force to overwrite its configuration")]
+    DossierAlreadyExists(String),
+
+    #[error("{0} already exists, use --force to overwrite it")]
+    FileAlreadyExists(String),
+
+    #[error("{0} files need formatting")]
+    FilesNotFormatted(usize),
+
+    #[error("{0} files cannot be formatted")]
+    FormattingFailed(usize),
+
+    #[error("{0} lint errors found")]
+    LintErrors(usize),
+
+    #[error("invalid dossier configuration: {0} errors found")]
+    InvalidDossierConfiguration(usize),
+}
+
+
+#[derive(Debug, Parser)]
+#[command(name = "nmd", version, about = "Compile NMD dossiers and documents")]
+pub struct NmdCli {
+
+    /// Log verbosity (off, error, warn, info, debug, trace)
+    #[arg(short, long, global = true, default_value = "warn")]
+    verbose: LevelFilter,
+
+    #[command(subcommand)]
+    command: NmdCliCommand,
+}
+
+#[derive(Debug, Subcommand)]
+enum NmdCliCommand {
+
+    /// Compile a dossier or a single .nmd file
+    Compile(CompileArgs),
+
+    /// Load and compile a dossier or a single .nmd file without writing any output
+    Check(CheckArgs),
+
+    /// Compile a dossier or a single .nmd file every time its sources change
+    Watch(WatchArgs),
+
+    /// Create a new dossier
+    Init(InitArgs),
+
+    /// Print JSON Schema of dossier configuration, useful for editors autocompletion
+    Schema(SchemaArgs),
+
+    /// Convert a Markdown file in a .nmd file, or a directory of Markdown files in a dossier
+    Import(ImportArgs),
+
+    /// Format .nmd files in place
+    Format(FormatArgs),
+
+    /// Report issues of dossier or .nmd file (rules can be configured in dossier configuration)
+    Lint(LintArgs),
+
+    /// Start language server (Language Server Protocol over standard input and output)
+    Lsp,
+}
+
+#[derive(Debug, Clone, Args)]
+struct CompileArgs {
+
+    /// Dossier directory, dossier configuration file or .nmd file
+    #[arg(short, long, default_value = ".")]
+    input: PathBuf,
+
+    /// Output file or directory (default: input directory)
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+
+    /// Output format
+    #[arg(short, long, default_value = "html")]
+    format: OutputFormat,
+
+    /// Theme which overrides the dossier one
+    #[arg(short, long)]
+    theme: Option<Theme>,
+
+    /// Skip time consuming operations (e.g. image embedding)
+    #[arg(long)]
+    fast_draft: bool,
+
+    /// Compile only these documents of dossier (fast draft only)
+    #[arg(short, long, num_args = 1.., requires = "fast_draft")]
+    documents: Vec<String>,
+
+    /// Disable parallel load and compilation
+    #[arg(long)]
+    no_parallelization: bool,
+
+    /// Create output parent directories if they do not exist
+    #[arg(long)]
+    force: bool,
+
+    /// Override a variable (e.g. `--var audience=developers`), it can be repeated
+    #[arg(long = "var", value_name = "KEY=VALUE", value_parser = parse_variable)]
+    variables: Vec<(String, String)>,
+
+    /// Add a build tag used by conditional content, it can be repeated
+    #[arg(long = "tag")]
+    tags: Vec<String>,
+
+    /// Build profile of dossier configuration (e.g. `release`)
+    #[arg(long)]
+    profile: Option<String>,
+
+    /// Maximum line width of plain text outputs (text and man)
+    #[arg(long)]
+    text_width: Option<usize>,
+}
+
+/// Parse `KEY=VALUE` variable override
+fn parse_variable(raw: &str) -> Result<(String, String), String> {
+
+    let (key, value) = raw.split_once('=').ok_or(format!("invalid variable '{}': expected KEY=VALUE", raw))?;
+
+    if key.trim().is_empty() {
+        return Err(format!("invalid variable '{}': empty key", raw))
+    }
+
+    Ok((key.trim().to_string(), value.to_string()))
+}
+
+#[derive(Debug, Clone, Args)]
+struct CheckArgs {
+
+    /// Dossier directory, dossier configuration file or .nmd file
+    #[arg(short, long, default_value = ".")]
+    input: PathBuf,
+
+    /// Disable parallel load and compilation
+    #[arg(long)]
+    no_parallelization: bool,
+}
+
+#[derive(Debug, Clone, Args)]
+struct WatchArgs {
+
+    #[command(flatten)]
+    compile: CompileArgs,
+
+    /// Time without changes after which sources are compiled again (milliseconds)
+    #[arg(long, default_value_t = 1000)]
+    min_elapsed_time_between_events: u64,
+}
+
+#[derive(Debug, Clone, Args)]
+struct SchemaArgs {
+
+    /// Output file (default: standard output)
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Args)]
+struct InitArgs {
+
+    /// Dossier directory, it is created if it does not exist
+    #[arg(default_value = ".")]
+    path: PathBuf,
+
+    /// Dossier name (default: directory name)
+    #[arg(short, long)]
+    name: Option<String>,
+
+    /// Overwrite an existing dossier configuration
+    #[arg(long)]
+    force: bool,
+}
+
+#[derive(Debug, Clone, Args)]
+struct ImportArgs {
+
+    /// Markdown file or directory
+    input: PathBuf,
+
+    /// Output .nmd file or dossier directory (default: input with .nmd extension or input directory)
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+
+    /// Overwrite existing .nmd file or dossier configuration
+    #[arg(long)]
+    force: bool,
+}
+
+#[derive(Debug, Clone, Args)]
+struct FormatArgs {
+
+    /// .nmd files or directories (searched recursively)
+    #[arg(default_value = ".")]
+    paths: Vec<PathBuf>,
+
+    /// Do not write files, report files which need formatting
+    #[arg(long)]
+    check: bool,
+}
+
+#[derive(Debug, Clone, Args)]
+struct LintArgs {
+
+    /// Dossier directory, dossier configuration file or .nmd file
+    #[arg(default_value = ".")]
+    input: PathBuf,
+}
+
+
+impl NmdCli {
+
+    /// Parse command line arguments
+    pub fn new() -> Self {
+        Self::parse()
+    }
+
+    pub fn serve(self) -> Result<(), CliError> {
+
+        // standard output is used by language server protocol, so logs are disabled
+        let level = match self.command {
+            NmdCliCommand::Lsp => LevelFilter::Off,
+            _ => self.verbose,
+        };
+
+        SimpleLogger::new()
+            .with_level(level)
+            .init()
+            .map_err(|err| CliError::LoggerError(err.to_string()))?;
+
+        match self.command {
+            NmdCliCommand::Compile(args) => {
+
+                let output = compile(&args)?;
+
+                println!("{}", compilation_message(output.as_ref()));
+
+                Ok(())
+            },
+            NmdCliCommand::Check(args) => check(&args),
+            NmdCliCommand::Watch(args) => watch(&args),
+            NmdCliCommand::Init(args) => {
+
+                init(&args)?;
+
+                println!("dossier created in {}", args.path.to_string_lossy());
+
+                Ok(())
+            },
+            NmdCliCommand::Schema(args) => schema(&args),
+            NmdCliCommand::Format(args) => format(&args),
+            NmdCliCommand::Lint(args) => lint(&args),
+            NmdCliCommand::Lsp => Ok(LanguageServer::new().run(&mut io::stdin().lock(), &mut io::stdout().lock())?),
+            NmdCliCommand::Import(args) => {
+
+                let output = import(&args)?;
+
+                println!("imported in {}", output.to_string_lossy());
+
+                Ok(())
+            },
+        }
+    }
+}
+
+
+/// Return `true` if input is a single NMD file, `false` if it must be considered a dossier
+fn is_single_file(input: &Path) -> bool {
+    input.is_file() && input.extension().map(|ext| ext.to_string_lossy().eq(NMD_EXTENSION)).unwrap_or(false)
+}
+
+/// Directory which contains input (dossier root or file parent)
+fn input_directory(input: &Path) -> PathBuf {
+    if input.is_dir() {
+        return input.to_path_buf()
+    }
+
+    input.parent().map(|parent| parent.to_path_buf()).unwrap_or(PathBuf::from("."))
+}
+
+/// Build final output path, if `output` is a directory (or it is not provided) `default_file_name` is appended
+fn output_path(output: Option<&PathBuf>, input: &Path, default_file_name: &str) -> PathBuf {
+
+    let output = output.cloned().unwrap_or(input_directory(input));
+
+    if file_utility::is_directory_like(&output) {
+        return output.join(default_file_name)
+    }
+
+    output
+}
+
+/// Build `DossierBuilder` using compile arguments
+fn dossier_builder(args: &CompileArgs) -> Result<DossierBuilder, CliError> {
+
+    let mut builder = DossierBuilder::try_from(&args.input)?
+                                .with_format(args.format.clone())
+                                .with_fast_draft(args.fast_draft)
+                                .with_force_dump(args.force);
+
+    if let Some(output) = &args.output {
+        builder = builder.with_output_location(output.clone());
+    }
+
+    if let Some(theme) = &args.theme {
+        builder = builder.with_theme(theme.clone());
+    }
+
+    if args.no_parallelization {
+        builder = builder.with_parallelization(false);
+    }
+
+    if !args.documents.is_empty() {
+        builder = builder.with_compile_only_documents(args.documents.iter().cloned().collect::<HashSet<String>>());
+    }
+
+    for (key, value) in &args.variables {
+        builder = builder.with_variable(key.clone(), value.clone());
+    }
+
+    for tag in &args.tags {
+        builder = builder.with_tag(tag.clone());
+    }
+
+    if let Some(profile) = &args.profile {
+        builder = builder.with_profile(profile)?;
+    }
+
+    if let Some(text_width) = args.text_width {
+        builder = builder.with_text_width(text_width);
+    }
+
+    Ok(builder)
+}
+
+/// Load and compile single file, returning artifact and its output path
+fn build_file(args: &CompileArgs) -> Result<(Artifact, PathBuf), CliError> {
+
+    let codex = Codex::from(&args.format);
+
+    let theme = args.theme.clone().unwrap_or_default();
+
+    let mut compilation_configuration = CompilationConfiguration::default();
+    compilation_configuration.set_input_location(input_directory(&args.input));
+    compilation_configuration.set_fast_draft(args.fast_draft);
+    compilation_configuration.set_parallelization(!args.no_parallelization);
+    compilation_configuration.set_theme(theme.clone());
+    compilation_configuration.set_resource_type(CompilableResourceType::File);
+    compilation_configuration.apply_variable_overrides(&args.variables.iter().cloned().collect());
+    compilation_configuration.set_tags(args.tags.iter().cloned().collect());
+
+    let mut load_configuration = LoadConfiguration::default();
+    load_configuration.set_input_location(input_directory(&args.input));
+    load_configuration.set_parallelization(!args.no_parallelization);
+    load_configuration.set_tags(compilation_configuration.tags().clone());
+    load_configuration.set_variables(compilation_configuration.variables().clone());
+
+    let mut document = Document::load_document_from_path(&args.input, &codex, &load_configuration, LoadConfigurationOverLay::default())?;
+
+    let mut assembler_configuration = AssemblerConfiguration::new(theme, false, !args.no_parallelization);
+
+    if let Some(text_width) = args.text_width {
+        assembler_configuration.set_text_width(text_width);
+    }
+
+    let mut compilation_configuration_overlay = CompilationConfigurationOverLay::default();
+    compilation_configuration_overlay.set_assembler_configuration(assembler_configuration.clone());
+
+    let outcome = document.compile(&args.format, &codex, &compilation_configuration, compilation_configuration_overlay)?;
+
+    let output = output_path(
+        args.output.as_ref(),
+        &args.input,
+        &file_utility::build_output_file_name(
+            &args.input.file_stem().unwrap_or_default().to_string_lossy(),
+            Some(&args.format.get_extension())
+        )
+    );
+
+    if args.format.is_package() {
+
+        // single file is packaged as a dossier with only one document
+        let mut dossier_configuration = DossierConfiguration::default();
+        dossier_configuration.set_name(document.metadata().title().clone().unwrap_or(document.name().clone()));
+
+        let headings: Vec<Heading> = document.content().chapters().iter().map(|chapter| chapter.header().heading().clone()).collect();
+
+        let table_of_contents = TableOfContents::new(String::from("Table of contents"), false, false, MAX_HEADING_LEVEL as usize, headings);
+
+        let package = codex.assembler().assemble_dossier_package(&vec![outcome], None, None, None, &table_of_contents, &dossier_configuration, &assembler_configuration)?;
+
+        return Ok((Artifact::new_binary(package), output))
+    }
+
+    let content = codex.assembler().assemble_document_standalone(document.name(), document.metadata(), &outcome, None, None, &assembler_configuration)?;
+
+    Ok((Artifact::from(content), output))
+}
+
+/// Compile and dump input, returning output path (`None` if no file is written)
+fn compile(args: &CompileArgs) -> Result<Option<PathBuf>, CliError> {
+
+    let now = Instant::now();
+
+    if !is_single_file(&args.input) {
+
+        let report = dossier_builder(args)?.build()?;
+
+        log::info!("{}", report);
+
+        return Ok(report.written_files().first().cloned())
+    }
+
+    let (mut artifact, output) = build_file(args)?;
+
+    artifact.dump(&DumpConfiguration::new(output.clone(), args.force))?;
+
+    log::info!("{:?} compiled in {} ms", args.input, now.elapsed().as_millis());
+
+    Ok(Some(output))
+}
+
+fn check(args: &CheckArgs) -> Result<(), CliError> {
+
+    let compile_args = CompileArgs {
+        input: args.input.clone(),
+        output: None,
+        format: OutputFormat::default(),
+        theme: None,
+        fast_draft: false,
+        documents: Vec::new(),
+        no_parallelization: args.no_parallelization,
+        force: false,
+        variables: Vec::new(),
+        tags: Vec::new(),
+        profile: None,
+        text_width: None,
+    };
+
+    if is_single_file(&compile_args.input) {
+        build_file(&compile_args)?;
+    } else {
+
+        let issues = dossier_configuration_validation::validate_dossier_configuration(&compile_args.input)?;
+
+        for issue in issues.iter() {
+            eprintln!("{}: {}", args.input.to_string_lossy(), issue);
+        }
+
+        let errors = issues.iter().filter(|issue| issue.is_error()).count();
+
+        if errors > 0 {
+            return Err(CliError::InvalidDossierConfiguration(errors))
+        }
+
+        dossier_builder(&compile_args)?.build_artifact()?;
+    }
+
+    println!("{} is valid", args.input.to_string_lossy());
+
+    Ok(())
+}
+
+fn schema(args: &SchemaArgs) -> Result<(), CliError> {
+
+    let schema = serde_json::to_string_pretty(&DossierConfiguration::json_schema())?;
+
+    match &args.output {
+        Some(output) => std::fs::write(output, schema)?,
+        None => println!("{}", schema),
+    }
+
+    Ok(())
+}
+
+/// Message printed after a compilation
+fn compilation_message(output: Option<&PathBuf>) -> String {
+    match output {
+        Some(output) => format!("compiled in {}", output.to_string_lossy()),
+        None => String::from("no files written"),
+    }
+}
+
+/// Paths changed by a watcher event, `None` if event is not a change of sources (e.g. output changes)
+fn changed_paths(event: notify::Result<notify::Event>, output: Option<&Path>) -> Option<Vec<PathBuf>> {
+
+    let event = match event {
+        Ok(event) => event,
+        Err(err) => {
+            log::error!("watch error: {}", err);
+            return None
+        }
+    };
+
+    if !matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)) {
+        return None
+    }
+
+    // output changes must not trigger a new compilation (filter is skipped if output has no file name, e.g. `.`)
+    if let Some(output_file_name) = output.and_then(|output| output.file_name()) {
+        if event.paths.iter().all(|path| path.ends_with(output_file_name)) {
+            return None
+        }
+    }
+
+    Some(event.paths)
+}
+
+fn watch(args: &WatchArgs) -> Result<(), CliError> {
+
+    let min_elapsed_time = Duration::from_millis(args.min_elapsed_time_between_events);
+
+    let mut output = compile(&args.compile)?;
+
+    println!("{}", compilation_message(output.as_ref()));
+
+    let (tx, rx) = mpsc::channel();
+
+    let mut watcher = notify::recommended_watcher(tx)?;
+
+    let watched_directory = input_directory(&args.compile.input);
+
+    watcher.watch(&watched_directory, RecursiveMode::Recursive)?;
+
+    println!("watching {}...", watched_directory.to_string_lossy());
+
+    while let Ok(event) = rx.recv() {
+
+        let Some(paths) = changed_paths(event, output.as_deref()) else {
+            continue
+        };
+
+        log::info!("change detected in {:?}", paths);
+
+        // compile once no other change is detected for `min_elapsed_time`, so the last change is never lost
+        while let Ok(event) = rx.recv_timeout(min_elapsed_time) {
+            if let Some(paths) = changed_paths(event, output.as_deref()) {
+                log::debug!("change detected in {:?}, compilation is postponed", paths);
+            }
+        }
+
+        match compile(&args.compile) {
+            Ok(o) => {
+                println!("{}", compilation_message(o.as_ref()));
+                output = o;
+            },
+            Err(err) => log::error!("compilation failed: {}", err),
+        }
+    }
+
+    Ok(())
+}
+
+/// Create dossier directory tree, its configuration and a sample document
+fn init(args: &InitArgs) -> Result<(), CliError> {
+
+    let root = &args.path;
+
+    let already_exists = DOSSIER_CONFIGURATION_FILE_NAMES.iter().any(|file_name| root.join(file_name).exists());
+
+    if already_exists && !args.force {
+        return Err(CliError::DossierAlreadyExists(root.to_string_lossy().to_string()))
+    }
+
+    std::fs::create_dir_all(root.join(dossier::ASSETS_DIR).join(dossier::IMAGES_DIR))?;
+    std::fs::create_dir_all(root.join(dossier::ASSETS_DIR).join(dossier::STYLES_DIR))?;
+
+    let sample_document = root.join(SAMPLE_DOCUMENT_FILE_NAME);
+
+    if !sample_document.exists() {
+        file_utility::create_file_with_content(&sample_document, SAMPLE_DOCUMENT_CONTENT)?;
+    }
+
+    let name = args.name.clone().unwrap_or_else(|| {
+        std::fs::canonicalize(root).ok()
+            .and_then(|path| path.file_name().map(|name| name.to_string_lossy().to_string()))
+            .unwrap_or(DossierConfiguration::default().name().clone())
+    });
+
+    let mut configuration = DossierConfiguration::default();
+    configuration.set_name(name);
+    configuration.set_raw_documents_paths(vec![format!("./{}", SAMPLE_DOCUMENT_FILE_NAME)]);
+
+    configuration.dump_as_yaml(root.join(DOSSIER_CONFIGURATION_YAML_FILE_NAME))?;
+
+    Ok(())
+}
+
+/// Import a Markdown file or a directory of Markdown files, output path is returned
+fn import(args: &ImportArgs) -> Result<PathBuf, CliError> {
+
+    let importer = MarkdownImporter::new();
+
+    if args.input.is_dir() {
+
+        let output = args.output.clone().unwrap_or(args.input.clone());
+
+        let already_exists = DOSSIER_CONFIGURATION_FILE_NAMES.iter().any(|file_name| output.join(file_name).exists());
+
+        if already_exists && !args.force {
+            return Err(CliError::DossierAlreadyExists(output.to_string_lossy().to_string()))
+        }
+
+        let documents = importer.import_directory(&args.input, &output)?;
+
+        log::info!("{} documents imported", documents.len());
+
+        return Ok(output)
+    }
+
+    let output = match &args.output {
+        Some(output) if output.is_dir() => output.join(args.input.with_extension(NMD_EXTENSION).file_name().unwrap_or_default()),
+        Some(output) => output.clone(),
+        None => args.input.with_extension(NMD_EXTENSION),
+    };
+
+    if output.exists() && !args.force {
+        return Err(CliError::FileAlreadyExists(output.to_string_lossy().to_string()))
+    }
+
+    file_utility::create_file_with_content(&output, &importer.import_file(&args.input)?)?;
+
+    Ok(output)
+}
+
+/// Format files in place, in check mode files which need formatting are reported and an error is returned
+fn format(args: &FormatArgs) -> Result<(), CliError> {
+
+    let formatter = Formatter::default();
+
+    let mut not_formatted: usize = 0;
+    let mut failed: usize = 0;
+
+    for file in args.paths.iter().flat_map(|path| Formatter::nmd_files(path)) {
+
+        let outcome = if args.check { formatter.check_file(&file) } else { formatter.format_file(&file) };
+
+        match outcome {
+            Ok(true) if args.check => {
+                println!("{} needs formatting", file.to_string_lossy());
+                not_formatted += 1;
+            },
+            Ok(true) => println!("{} formatted", file.to_string_lossy()),
+            Ok(false) => (),
+            Err(err) => {
+                log::error!("{} cannot be formatted: {}", file.to_string_lossy(), err);
+                failed += 1;
+            }
+        }
+    }
+
+    if failed > 0 {
+        return Err(CliError::FormattingFailed(failed))
+    }
+
+    if not_formatted > 0 {
+        return Err(CliError::FilesNotFormatted(not_formatted))
+    }
+
+    Ok(())
+}
+
+/// Lint dossier or single file, an error is returned if an issue with error severity is found
+fn lint(args: &LintArgs) -> Result<(), CliError> {
+
+    let diagnostics = if is_single_file(&args.input) {
+
+        let directory = input_directory(&args.input);
+
+        // lint configuration of dossier which contains file is used, if it exists
+        let has_dossier_configuration = DOSSIER_CONFIGURATION_FILE_NAMES.iter().any(|file_name| directory.join(file_name).is_file());
+
+        let linter = match has_dossier_configuration {
+            true => Linter::from_dossier_configuration(&DossierConfiguration::try_from(&directory)?)?,
+            false => Linter::default(),
+        };
+
+        linter.lint_file(&args.input)?
+
+    } else {
+
+        let dossier_configuration = DossierConfiguration::try_from(&args.input)?;
+
+        Linter::from_dossier_configuration(&dossier_configuration)?.lint_dossier(&dossier_configuration)?
+    };
+
+    for diagnostic in diagnostics.iter() {
+        println!("{}", diagnostic);
+    }
+
+    let errors = diagnostics.iter().filter(|diagnostic| diagnostic.is_error()).count();
+
+    if errors > 0 {
+        return Err(CliError::LintErrors(errors))
+    }
+
+    Ok(())
+}
+
+
+#[cfg(test)]
+mod test {
+    use std::{io::{Cursor, Read}, path::PathBuf};
+    use crate::test_utility::TempDir;
+    use super::*;
+
+    #[test]
+    fn package_single_file_with_front_matter() {
+
+        let dir = TempDir::new("package-single-file");
+
+        let input = dir.join("a.nmd");
+
+        std::fs::write(&input, "---\ntitle: My Title\n---\n\n# Intro\n\nSome text\n").unwrap();
+
+        let read = |format: OutputFormat, name: &str| {
+
+            let (artifact, _) = build_file(&CompileArgs {
+                input: input.clone(),
+                output: None,
+                format,
+                theme: None,
+                fast_draft: false,
+                documents: Vec::new(),
+                no_parallelization: true,
+                force: false,
+                variables: Vec::new(),
+                tags: Vec::new(),
+                profile: None,
+                text_width: None,
+            }).unwrap();
+
+            let mut archive = zip::ZipArchive::new(Cursor::new(artifact.binary_content().clone().unwrap())).unwrap();
+
+            let mut content = String::new();
+            archive.by_name(name).unwrap().read_to_string(&mut content).unwrap();
+            content
+        };
+
+        let document = read(OutputFormat::Epub, "OEBPS/text/document-1.xhtml");
+        assert_eq!(document.matches(r#"class="document-title-block""#).count(), 1);
+
+        let document = read(OutputFormat::Docx, "word/document.xml");
+        assert_eq!(document.matches(r#"w:val="Title""#).count(), 1);
+    }
+
+    #[test]
+    fn single_file_snippets_from_file_directory() {
+
+        let dir = TempDir::new("single-file-snippets");
+
+        std::fs::create_dir_all(dir.join("src")).unwrap();
+        std::fs::write(dir.join("src").join("main.rs"), "fn main() {}\n").unwrap();
+
+        let input = dir.join("a.nmd");
+
+        std::fs::write(&input, "# Code\n\n@code(src/main.rs)\n").unwrap();
+
+        let built = build_file(&CompileArgs {
+            input,
+            output: None,
+            format: OutputFormat::Html,
+            theme: None,
+            fast_draft: false,
+            documents: Vec::new(),
+            no_parallelization: true,
+            force: false,
+            variables: Vec::new(),
+            tags: Vec::new(),
+            profile: None,
+            text_width: None,
+        });
+
+        assert!(built.unwrap().0.content().contains("fn main()"));
+    }
+
+    #[test]
+    fn lint_file_with_invalid_dossier_configuration() {
+
+        let dir = TempDir::new("lint-invalid-configuration");
+
+        let input = dir.join("a.nmd");
+
+        std::fs::write(&input, "# Title\n\nText\n").unwrap();
+
+        let without_configuration = lint(&LintArgs { input: input.clone() });
+
+        std::fs::write(dir.join(DOSSIER_CONFIGURATION_YAML_FILE_NAME), "lint: [\n").unwrap();
+
+        let with_invalid_configuration = lint(&LintArgs { input: input.clone() });
+
+        assert!(without_configuration.is_ok());
+        assert!(with_invalid_configuration.is_err());
+    }
+
+    #[test]
+    fn init_and_check_dossier() {
+
+        let root = TempDir::new("dossier-init");
+
+        let dossier_path = root.join("dossier");
+
+        init(&InitArgs {
+            path: dossier_path.clone(),
+            name: Some(String::from("init-test")),
+            force: false,
+        }).unwrap();
+
+        assert!(dossier_path.join(DOSSIER_CONFIGURATION_YAML_FILE_NAME).exists());
+        assert!(dossier_path.join(dossier::ASSETS_DIR).join(dossier::IMAGES_DIR).is_dir());
+
+        assert!(init(&InitArgs {
+            path: dossier_path.clone(),
+            name: None,
+            force: false,
+        }).is_err());
+
+        check(&CheckArgs {
+            input: dossier_path.clone(),
+            no_parallelization: true,
+        }).unwrap();
+    }
+
+    #[test]
+    fn import_and_check_dossier() {
+
+        let root = TempDir::new("dossier-import");
+        std::fs::write(root.join("guide.md"), "# Guide\n\n| Step | Done |\n|---|---|\n| 1 | [x] |\n\nSee [^1].\n\n[^1]: A note\n").unwrap();
+
+        let args = ImportArgs {
+            input: root.clone(),
+            output: None,
+            force: false,
+        };
+
+        import(&args).unwrap();
+
+        assert!(root.join("guide.nmd").is_file());
+        assert!(import(&args).is_err());
+
+        check(&CheckArgs {
+            input: root.clone(),
+            no_parallelization: true,
+        }).unwrap();
+    }
+
+    #[test]
+    fn watch_source_changes_only() {
+
+        let output = PathBuf::from("dossier").join("dossier.html");
+
+        let event = |path: PathBuf| Ok(notify::Event::new(EventKind::Modify(notify::event::ModifyKind::Any)).add_path(path));
+
+        assert_eq!(changed_paths(event(PathBuf::from("dossier").join("d1.nmd")), Some(&output)), Some(vec![PathBuf::from("dossier").join("d1.nmd")]));
+        assert_eq!(changed_paths(event(output.clone()), Some(&output)), None);
+        assert_eq!(changed_paths(Ok(notify::Event::new(EventKind::Access(notify::event::AccessKind::Any))), Some(&output)), None);
+
+        // without output file name (or without output) every source change is kept
+        assert_eq!(changed_paths(event(PathBuf::from("dossier").join("d1.nmd")), Some(Path::new("."))), Some(vec![PathBuf::from("dossier").join("d1.nmd")]));
+        assert_eq!(changed_paths(event(PathBuf::from("dossier").join("d1.nmd")), None), Some(vec![PathBuf::from("dossier").join("d1.nmd")]));
+
+        assert_eq!(compilation_message(None), "no files written");
+    }
+}
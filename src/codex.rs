@@ -10,9 +10,12 @@ use std::sync::Arc;
 use getset::{Getters, Setters};
 use indexmap::IndexMap;
 use modifier::base_modifier::BaseModifier;
-use modifier::{Modifier, ModifierIdentifier};
+use modifier::{Modifier, ModifierIdentifier, ModifiersBucket};
+use thiserror::Error;
+use self::modifier::standard_heading_modifier::StandardHeading;
 use self::modifier::standard_paragraph_modifier::StandardParagraphModifier;
 use self::modifier::standard_text_modifier::StandardTextModifier;
+use crate::assembler::{docx_assembler::DocxAssembler, epub_assembler::EpubAssembler, man_assembler::ManAssembler, text_assembler::TextAssembler};
 use crate::assembler::html_assembler::HtmlAssembler;
 use crate::assembler::Assembler;
 use crate::compilable_text::compilable_text_part::CompilableTextPart;
@@ -21,30 +24,75 @@ use crate::compilation::compilation_rule::replacement_rule::replacement_rule_par
 use crate::compilation::compilation_rule::replacement_rule::replacement_rule_part::fixed_replacement_rule_part::FixedReplacementRuleReplacerPart;
 use crate::compilation::compilation_rule::replacement_rule::replacement_rule_part::single_capture_group_replacement_rule_part::SingleCaptureGroupReplacementRuleReplacerPart;
 use crate::compilation::compilation_rule::replacement_rule::ReplacementRule;
+use crate::dossier::document::chapter::heading::heading_loading_rule::base_heading_loading_rule::BaseHeadingLoadingRule;
+use crate::dossier::document::chapter::heading::heading_loading_rule::HeadingLoadingRule;
 use crate::dossier::document::chapter::paragraph::paragraph_loading_rule::block_quote_paragraph_loading_rule::BlockQuoteParagraphLoadingRule;
+use crate::dossier::document::chapter::paragraph::paragraph_loading_rule::code_snippet_paragraph_loading_rule::CodeSnippetParagraphLoadingRule;
 use crate::dossier::document::chapter::paragraph::paragraph_loading_rule::common_paragraph_loading_rule::CommonParagraphLoadingRule;
+use crate::dossier::document::chapter::paragraph::paragraph_loading_rule::conditional_paragraph_loading_rule::ConditionalParagraphLoadingRule;
 use crate::dossier::document::chapter::paragraph::paragraph_loading_rule::focus_block_paragraph_loading_rule::FocusBlockParagraphLoadingRule;
 use crate::dossier::document::chapter::paragraph::paragraph_loading_rule::image_paragraph_loading_rule::ImageParagraphLoadingRule;
+use crate::dossier::document::chapter::paragraph::paragraph_loading_rule::include_paragraph_loading_rule::IncludeParagraphLoadingRule;
 use crate::dossier::document::chapter::paragraph::paragraph_loading_rule::list_paragraph_loading_rule::ListParagraphLoadingRule;
 use crate::dossier::document::chapter::paragraph::paragraph_loading_rule::metadata_wrapper_paragraph_loading_rule::MetadataWrapperParagraphLoadingRule;
 use crate::dossier::document::chapter::paragraph::paragraph_loading_rule::replacement_rule_paragraph_loading_rule::ReplacementRuleParagraphLoadingRule;
 use crate::dossier::document::chapter::paragraph::paragraph_loading_rule::table_paragraph_loading_rule::TableParagraphLoadingRule;
+use crate::dossier::document::chapter::paragraph::paragraph_loading_rule::external_table_paragraph_loading_rule::ExternalTableParagraphLoadingRule;
 use crate::dossier::document::chapter::paragraph::paragraph_loading_rule::{MultiParagraphLoadingRule, ParagraphLoadingRule};
+use crate::dossier::dossier_configuration::dossier_configuration_modifier::{DossierConfigurationModifier, DossierConfigurationModifierKind};
 use crate::output_format::OutputFormat;
+use crate::resource::ResourceError;
 use crate::resource::resource_reference::ResourceReference;
 use crate::utility::text_utility;
 use super::compilation::compilation_rule::constants::ESCAPE_HTML;
+use super::compilation::compilation_rule::conditional_rule::ConditionalRule;
 use super::compilation::compilation_rule::html_cite_rule::HtmlCiteRule;
+use super::compilation::compilation_rule::html_glossary_term_rule::HtmlGlossaryTermRule;
+use super::compilation::compilation_rule::html_index_term_rule::HtmlIndexTermRule;
 use super::compilation::compilation_rule::html_greek_letter_rule::HtmlGreekLettersRule;
 use super::compilation::compilation_rule::reference_rule::ReferenceRule;
 use super::compilation::compilation_rule::CompilationRule;
 
 
-pub type TextModifierOrderedMap = IndexMap<ModifierIdentifier, (Box<dyn Modifier>, Box<dyn CompilationRule>)>;
-pub type ParagraphModifierOrderedMap = IndexMap<ModifierIdentifier, (Box<dyn Modifier>, Box<dyn ParagraphLoadingRule>)>;
+pub type TextModifierEntry = (Box<dyn Modifier>, Box<dyn CompilationRule>);
+pub type ParagraphModifierEntry = (Box<dyn Modifier>, Box<dyn ParagraphLoadingRule>);
+pub type TextModifierOrderedMap = IndexMap<ModifierIdentifier, TextModifierEntry>;
+pub type HeadingModifierEntry = (Box<dyn Modifier>, Box<dyn HeadingLoadingRule>);
+pub type ParagraphModifierOrderedMap = IndexMap<ModifierIdentifier, ParagraphModifierEntry>;
+pub type HeadingModifierOrderedMap = IndexMap<ModifierIdentifier, HeadingModifierEntry>;
 pub type FallbackParagraph = (ModifierIdentifier, Box<dyn MultiParagraphLoadingRule>);
 
 
+#[derive(Error, Debug)]
+pub enum CodexError {
+
+    #[error("modifier '{0}' not found")]
+    ModifierNotFound(ModifierIdentifier),
+
+    #[error("modifier '{0}' already exists")]
+    DuplicatedModifier(ModifierIdentifier),
+
+    #[error("modifier '{0}' cannot be both before and after other modifiers")]
+    AmbiguousPosition(ModifierIdentifier),
+
+    #[error("modifier '{0}' must precede its incompatible modifier '{1}'")]
+    InvalidOrdering(ModifierIdentifier, ModifierIdentifier),
+
+    #[error(transparent)]
+    ResourceError(#[from] ResourceError),
+}
+
+
+/// Position of a modifier in `Codex`, relative to other modifiers
+#[derive(Debug, Clone, PartialEq)]
+pub enum ModifierPosition {
+    First,
+    Last,
+    Before(ModifierIdentifier),
+    After(ModifierIdentifier),
+}
+
+
 /// Ordered collection of rules
 /// A **rule** is defined as the actual text transformation
 #[derive(Debug, Getters, Setters)]
@@ -56,6 +104,9 @@ pub struct Codex {
     #[getset(get = "pub", set = "pub")]
     paragraph_modifiers: ParagraphModifierOrderedMap,
 
+    #[getset(get = "pub", set = "pub")]
+    heading_modifiers: HeadingModifierOrderedMap,
+
     #[getset(get = "pub", set = "pub")]
     fallback_paragraph: Option<FallbackParagraph>,
     
@@ -67,18 +118,64 @@ impl Codex {
 
     pub fn from(format: &OutputFormat) -> Self {
         match format {
-            OutputFormat::Html => Self::of_html()
+            OutputFormat::Html => Self::of_html(),
+            OutputFormat::Epub => Self::of_epub(),
+            OutputFormat::Docx => Self::of_docx(),
+            OutputFormat::Text => Self::of_text(),
+            OutputFormat::Man => Self::of_man(),
         }
     }
 
+    /// HTML codex whose assembler packages output as EPUB 3
+    pub fn of_epub() -> Self {
+
+        let mut codex = Self::of_html();
+
+        codex.set_assembler(Box::new(EpubAssembler::new()));
+
+        codex
+    }
+
+    /// HTML codex whose assembler converts output to Office Open XML (DOCX)
+    pub fn of_docx() -> Self {
+
+        let mut codex = Self::of_html();
+
+        codex.set_assembler(Box::new(DocxAssembler::new()));
+
+        codex
+    }
+
+    /// HTML codex whose assembler converts output to wrapped plain text (styling is stripped)
+    pub fn of_text() -> Self {
+
+        let mut codex = Self::of_html();
+
+        codex.set_assembler(Box::new(TextAssembler::new()));
+
+        codex
+    }
+
+    /// HTML codex whose assembler converts output to roff (man page)
+    pub fn of_man() -> Self {
+
+        let mut codex = Self::of_html();
+
+        codex.set_assembler(Box::new(ManAssembler::new()));
+
+        codex
+    }
+
     /// Create a new `Codex`
     pub fn new(text_modifiers: TextModifierOrderedMap, paragraph_modifiers: ParagraphModifierOrderedMap,
+                heading_modifiers: HeadingModifierOrderedMap,
                 fallback_paragraph_modifier: Option<FallbackParagraph>,
                 assembler: Box<dyn Assembler>,) -> Self {
 
         Self {
             text_modifiers,
             paragraph_modifiers,
+            heading_modifiers,
             fallback_paragraph: fallback_paragraph_modifier,
             assembler
         }
@@ -89,6 +186,7 @@ impl Codex {
 
         self.text_modifiers.retain(|id, _| identifiers.contains(id));
         self.paragraph_modifiers.retain(|id, _| identifiers.contains(id));
+        self.heading_modifiers.retain(|id, _| identifiers.contains(id));
     }
 
 
@@ -98,9 +196,169 @@ impl Codex {
 
             self.text_modifiers.shift_remove(id);
             self.paragraph_modifiers.shift_remove(id);
+            self.heading_modifiers.shift_remove(id);
         });
     }
 
+    /// Customize this codex (e.g. standard codex of an output format or a codex with dossier modifiers).
+    /// Ordering of resulting codex is validated
+    pub fn derive<F>(mut self, customize: F) -> Result<Self, CodexError>
+    where F: FnOnce(&mut Self) -> Result<(), CodexError> {
+
+        customize(&mut self)?;
+
+        self.validate_ordering()?;
+
+        Ok(self)
+    }
+
+    /// Identifiers of text modifiers, in application order
+    pub fn text_modifier_identifiers(&self) -> Vec<&ModifierIdentifier> {
+        self.text_modifiers.keys().collect()
+    }
+
+    /// Identifiers of paragraph modifiers, in application order
+    pub fn paragraph_modifier_identifiers(&self) -> Vec<&ModifierIdentifier> {
+        self.paragraph_modifiers.keys().collect()
+    }
+
+    /// Identifiers of heading modifiers, in application order
+    pub fn heading_modifier_identifiers(&self) -> Vec<&ModifierIdentifier> {
+        self.heading_modifiers.keys().collect()
+    }
+
+    fn index_of_position<V>(map: &IndexMap<ModifierIdentifier, V>, position: &ModifierPosition) -> Result<usize, CodexError> {
+        match position {
+            ModifierPosition::First => Ok(0),
+            ModifierPosition::Last => Ok(map.len()),
+            ModifierPosition::Before(identifier) => map.get_index_of(identifier).ok_or(CodexError::ModifierNotFound(identifier.clone())),
+            ModifierPosition::After(identifier) => map.get_index_of(identifier).map(|index| index + 1).ok_or(CodexError::ModifierNotFound(identifier.clone())),
+        }
+    }
+
+    fn insert_in_position<V>(map: &mut IndexMap<ModifierIdentifier, V>, position: &ModifierPosition, identifier: ModifierIdentifier, value: V) -> Result<(), CodexError> {
+
+        if map.contains_key(&identifier) {
+            return Err(CodexError::DuplicatedModifier(identifier))
+        }
+
+        let index = Self::index_of_position(map, position)?;
+
+        map.shift_insert(index, identifier, value);
+
+        Ok(())
+    }
+
+    fn replace_in_position<V>(map: &mut IndexMap<ModifierIdentifier, V>, identifier: &ModifierIdentifier, value: V) -> Result<V, CodexError> {
+
+        let current = map.get_mut(identifier).ok_or(CodexError::ModifierNotFound(identifier.clone()))?;
+
+        Ok(std::mem::replace(current, value))
+    }
+
+    /// Insert a text modifier and its rule in `position`
+    pub fn insert_text_modifier(&mut self, position: &ModifierPosition, identifier: ModifierIdentifier, modifier: Box<dyn Modifier>, rule: Box<dyn CompilationRule>) -> Result<(), CodexError> {
+        Self::insert_in_position(&mut self.text_modifiers, position, identifier, (modifier, rule))
+    }
+
+    /// Insert a paragraph modifier and its loading rule in `position`
+    pub fn insert_paragraph_modifier(&mut self, position: &ModifierPosition, identifier: ModifierIdentifier, modifier: Box<dyn Modifier>, loading_rule: Box<dyn ParagraphLoadingRule>) -> Result<(), CodexError> {
+        Self::insert_in_position(&mut self.paragraph_modifiers, position, identifier, (modifier, loading_rule))
+    }
+
+    /// Insert a heading modifier and its loading rule in `position`
+    pub fn insert_heading_modifier(&mut self, position: &ModifierPosition, identifier: ModifierIdentifier, modifier: Box<dyn Modifier>, loading_rule: Box<dyn HeadingLoadingRule>) -> Result<(), CodexError> {
+        Self::insert_in_position(&mut self.heading_modifiers, position, identifier, (modifier, loading_rule))
+    }
+
+    /// Replace a text modifier and its rule keeping position, previous ones are returned
+    pub fn replace_text_modifier(&mut self, identifier: &ModifierIdentifier, modifier: Box<dyn Modifier>, rule: Box<dyn CompilationRule>) -> Result<TextModifierEntry, CodexError> {
+        Self::replace_in_position(&mut self.text_modifiers, identifier, (modifier, rule))
+    }
+
+    /// Replace only the rule of a text modifier, previous one is returned
+    pub fn replace_text_rule(&mut self, identifier: &ModifierIdentifier, rule: Box<dyn CompilationRule>) -> Result<Box<dyn CompilationRule>, CodexError> {
+
+        let (_, current) = self.text_modifiers.get_mut(identifier).ok_or(CodexError::ModifierNotFound(identifier.clone()))?;
+
+        Ok(std::mem::replace(current, rule))
+    }
+
+    /// Replace a paragraph modifier and its loading rule keeping position, previous ones are returned
+    pub fn replace_paragraph_modifier(&mut self, identifier: &ModifierIdentifier, modifier: Box<dyn Modifier>, loading_rule: Box<dyn ParagraphLoadingRule>) -> Result<ParagraphModifierEntry, CodexError> {
+        Self::replace_in_position(&mut self.paragraph_modifiers, identifier, (modifier, loading_rule))
+    }
+
+    /// Replace only the loading rule of a paragraph modifier, previous one is returned
+    pub fn replace_paragraph_loading_rule(&mut self, identifier: &ModifierIdentifier, loading_rule: Box<dyn ParagraphLoadingRule>) -> Result<Box<dyn ParagraphLoadingRule>, CodexError> {
+
+        let (_, current) = self.paragraph_modifiers.get_mut(identifier).ok_or(CodexError::ModifierNotFound(identifier.clone()))?;
+
+        Ok(std::mem::replace(current, loading_rule))
+    }
+
+    /// Replace a heading modifier and its loading rule keeping position, previous ones are returned
+    pub fn replace_heading_modifier(&mut self, identifier: &ModifierIdentifier, modifier: Box<dyn Modifier>, loading_rule: Box<dyn HeadingLoadingRule>) -> Result<HeadingModifierEntry, CodexError> {
+        Self::replace_in_position(&mut self.heading_modifiers, identifier, (modifier, loading_rule))
+    }
+
+    /// A modifier must precede the modifiers it declares as incompatible, otherwise they would be already applied
+    /// to its content
+    fn validate_map_ordering<V>(map: &IndexMap<ModifierIdentifier, (Box<dyn Modifier>, V)>) -> Result<(), CodexError> {
+
+        for (index, (identifier, (modifier, _))) in map.iter().enumerate() {
+
+            if let ModifiersBucket::List(incompatible_modifiers) = modifier.incompatible_modifiers() {
+
+                for incompatible_modifier in incompatible_modifiers {
+
+                    if let Some(incompatible_index) = map.get_index_of(incompatible_modifier) {
+
+                        if incompatible_index < index {
+                            return Err(CodexError::InvalidOrdering(identifier.clone(), incompatible_modifier.clone()))
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Check compatibility ordering of text, paragraph and heading modifiers
+    pub fn validate_ordering(&self) -> Result<(), CodexError> {
+
+        Self::validate_map_ordering(&self.text_modifiers)?;
+        Self::validate_map_ordering(&self.paragraph_modifiers)?;
+        Self::validate_map_ordering(&self.heading_modifiers)
+    }
+
+    /// Insert user-defined modifiers (e.g. declared in dossier configuration), compiled as `ReplacementRule`
+    pub fn insert_dossier_configuration_modifiers(&mut self, modifiers: &[DossierConfigurationModifier]) -> Result<(), CodexError> {
+
+        for modifier in modifiers {
+
+            let position = match (modifier.before(), modifier.after()) {
+                (Some(_), Some(_)) => return Err(CodexError::AmbiguousPosition(modifier.identifier().clone())),
+                (Some(before), None) => ModifierPosition::Before(before.clone()),
+                (None, Some(after)) => ModifierPosition::After(after.clone()),
+                (None, None) => ModifierPosition::Last,
+            };
+
+            let base_modifier = Box::new(modifier.build_modifier()?) as Box<dyn Modifier>;
+            let replacement_rule = modifier.build_replacement_rule()?;
+
+            match modifier.kind() {
+                DossierConfigurationModifierKind::Text => self.insert_text_modifier(&position, modifier.identifier().clone(), base_modifier, Box::new(replacement_rule))?,
+                DossierConfigurationModifierKind::Paragraph => self.insert_paragraph_modifier(&position, modifier.identifier().clone(), base_modifier, Box::new(ReplacementRuleParagraphLoadingRule::new(replacement_rule)))?,
+            }
+
+            log::debug!("user-defined modifier '{}' inserted in codex", modifier.identifier());
+        }
+
+        Ok(())
+    }
+
     /// Standard HTML `Codex`
     pub fn of_html() -> Self {
 
@@ -147,6 +405,20 @@ impl Codex {
                     ))
                 )
             ),
+            (
+                StandardTextModifier::Conditional.identifier().clone(),
+                (
+                    Box::new(Into::<BaseModifier>::into(StandardTextModifier::Conditional)),
+                    Box::new(ConditionalRule::new())
+                )
+            ),
+            (
+                StandardTextModifier::IndexTerm.identifier().clone(),
+                (
+                    Box::new(Into::<BaseModifier>::into(StandardTextModifier::IndexTerm)),
+                    Box::new(HtmlIndexTermRule::new())
+                )
+            ),
             (
                 StandardTextModifier::GreekLetter.identifier().clone(),
                 (
@@ -182,7 +454,7 @@ impl Codex {
                             if let Some(raw_id) = captures.get(2) {
                                 id_attr = format!(
                                     r#"id="{}""#,
-                                    ResourceReference::of_internal_from_without_sharp(raw_id.as_str(), cco.document_name().as_ref())?.build(),
+                                    ResourceReference::of_internal_from_without_sharp(raw_id.as_str(), cco.document_name().as_ref())?.build_without_internal_sharp(),
                                 );
 
                                 identifier_class = String::from("identifier");
@@ -217,7 +489,7 @@ impl Codex {
                                 if let Some(raw_id) = captures.get(2) {
                                     id_attr = format!(
                                         r#"id="{}""#,
-                                        ResourceReference::of_internal_from_without_sharp(raw_id.as_str(), cco.document_name().as_ref())?.build(),
+                                        ResourceReference::of_internal_from_without_sharp(raw_id.as_str(), cco.document_name().as_ref())?.build_without_internal_sharp(),
                                     );
     
                                     identifier_class = String::from("identifier");
@@ -253,7 +525,7 @@ impl Codex {
                                 if let Some(raw_id) = captures.get(2) {
                                     id_attr = format!(
                                         r#"id="{}""#,
-                                        ResourceReference::of_internal_from_without_sharp(raw_id.as_str(), cco.document_name().as_ref())?.build(),
+                                        ResourceReference::of_internal_from_without_sharp(raw_id.as_str(), cco.document_name().as_ref())?.build_without_internal_sharp(),
                                     );
     
                                     identifier_class = String::from("identifier");
@@ -289,7 +561,7 @@ impl Codex {
                                 if let Some(raw_id) = captures.get(2) {
                                     id_attr = format!(
                                         r#"id="{}""#,
-                                        ResourceReference::of_internal_from_without_sharp(raw_id.as_str(), cco.document_name().as_ref())?.build(),
+                                        ResourceReference::of_internal_from_without_sharp(raw_id.as_str(), cco.document_name().as_ref())?.build_without_internal_sharp(),
                                     );
     
                                     identifier_class = String::from("identifier");
@@ -339,7 +611,7 @@ impl Codex {
                                 Ok(CompilableText::from(vec![
                                     CompilableTextPart::new_fixed(format!(
                                         r#"<span class="identifier" id="{}">"#,
-                                        ResourceReference::of_internal_from_without_sharp(captures.get(2).unwrap().as_str(), cco.document_name().as_ref())?.build(),
+                                        ResourceReference::of_internal_from_without_sharp(captures.get(2).unwrap().as_str(), cco.document_name().as_ref())?.build_without_internal_sharp(),
                                     ))
                                 ]))
                             }))),
@@ -567,9 +839,23 @@ impl Codex {
                     Box::new(HtmlCiteRule::new())
                 )
             ),
+            (
+                StandardTextModifier::GlossaryTerm.identifier().clone(),
+                (
+                    Box::new(Into::<BaseModifier>::into(StandardTextModifier::GlossaryTerm)),
+                    Box::new(HtmlGlossaryTermRule::new())
+                )
+            ),
         ]);
 
         let paragraph_rules: ParagraphModifierOrderedMap = ParagraphModifierOrderedMap::from([
+            (
+                StandardParagraphModifier::ConditionalBlock.identifier().clone(),
+                (
+                    Box::new(Into::<BaseModifier>::into(StandardParagraphModifier::ConditionalBlock)) as Box<dyn Modifier>,
+                    Box::new(ConditionalParagraphLoadingRule::new()) as Box<dyn ParagraphLoadingRule>
+                )
+            ),
             (
                 StandardParagraphModifier::CodeBlock.identifier().clone(),
                 (
@@ -579,9 +865,12 @@ impl Codex {
                             StandardParagraphModifier::CodeBlock.modifier_pattern().clone(),
                             vec![
                                 Arc::new(ClosureReplacementRuleReplacerPart::new(Arc::new(|captures, compilable, _, _, _| {
-    
+
+                                    // language and content groups of matched fence length
+                                    let lang_group = (1..captures.len()).step_by(2).find(|group| captures.get(group + 1).is_some()).unwrap();
+
                                     let mut lang_class = String::from("language-markup");
-                                    if let Some(lang) = captures.get(1) {
+                                    if let Some(lang) = captures.get(lang_group) {
                                         lang_class = format!("language-{}", lang.as_str());
                                     }
     
@@ -590,7 +879,7 @@ impl Codex {
                                             r#"<pre{}><code class="{} code-block">{}</code></pre>"#,
                                             text_utility::html_nuid_tag_or_nothing(compilable.nuid().as_ref()),
                                             lang_class,
-                                            text_utility::replace(captures.get(2).unwrap().as_str(), &ESCAPE_HTML),
+                                            text_utility::replace(captures.get(lang_group + 1).unwrap().as_str(), &ESCAPE_HTML),
                                         )
                                     )
                                     ]))
@@ -600,6 +889,13 @@ impl Codex {
                     )) as Box<dyn ParagraphLoadingRule>
                 ) as (Box<dyn Modifier>, Box<dyn ParagraphLoadingRule>)
             ),
+            (
+                StandardParagraphModifier::CodeSnippet.identifier().clone(),
+                (
+                    Box::new(Into::<BaseModifier>::into(StandardParagraphModifier::CodeSnippet)) as Box<dyn Modifier>,
+                    Box::new(CodeSnippetParagraphLoadingRule::new())
+                )
+            ),
             (
                 StandardParagraphModifier::MathBlock.identifier().clone(),
                 (
@@ -661,6 +957,13 @@ impl Codex {
                     ))
                 )
             ),
+            (
+                StandardParagraphModifier::ExternalTable.identifier().clone(),
+                (
+                    Box::new(Into::<BaseModifier>::into(StandardParagraphModifier::ExternalTable)) as Box<dyn Modifier>,
+                    Box::new(ExternalTableParagraphLoadingRule::new())
+                )
+            ),
             (
                     StandardParagraphModifier::Table.identifier(),
                     (
@@ -876,11 +1179,29 @@ impl Codex {
                     ))
                 )
             ),
+            (
+                StandardParagraphModifier::Include.identifier().clone(),
+                (
+                    Box::new(Into::<BaseModifier>::into(StandardParagraphModifier::Include)) as Box<dyn Modifier>,
+                    Box::new(IncludeParagraphLoadingRule::new())
+                )
+            ),
         ]);
 
+        let heading_rules: HeadingModifierOrderedMap = StandardHeading::ordered().into_iter().map(|heading| {
+            (
+                heading.identifier(),
+                (
+                    Box::new(Into::<BaseModifier>::into(heading.clone())) as Box<dyn Modifier>,
+                    Box::new(BaseHeadingLoadingRule::from(heading)) as Box<dyn HeadingLoadingRule>
+                )
+            )
+        }).collect();
+
         Self::new(
             text_rules,
             paragraph_rules,
+            heading_rules,
             Some(
                 (
                     StandardParagraphModifier::CommonParagraph.identifier().clone(),
@@ -897,6 +1218,10 @@ mod test {
 
     use indexmap::IndexMap;
     use modifier::base_modifier::BaseModifier;
+    use crate::compilation::compilable::Compilable;
+    use crate::compilation::compilation_configuration::{compilation_configuration_overlay::CompilationConfigurationOverLay, CompilationConfiguration};
+    use crate::dossier::document::Document;
+    use crate::load::{LoadConfiguration, LoadConfigurationOverLay};
     use super::*;
 
 
@@ -972,6 +1297,7 @@ mod test {
                     ) as (Box<dyn Modifier>, Box<dyn ParagraphLoadingRule>)
                 ),
             ]),
+            IndexMap::new(),
             None,
             Box::new(HtmlAssembler::new())
         );
@@ -981,4 +1307,103 @@ mod test {
 
         assert_eq!(ids.join(""), "abeci");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn insert_dossier_configuration_modifiers() {
+
+        let mut keyboard = DossierConfigurationModifier::new(String::from("keyboard"), DossierConfigurationModifierKind::Text, String::from(r"\[\[kbd:(.+?)\]\]"), String::from("<kbd>$1</kbd>"));
+        keyboard.set_after(Some(StandardTextModifier::InlineCode.identifier()));
+
+        let mut note = DossierConfigurationModifier::new(String::from("note"), DossierConfigurationModifierKind::Paragraph, String::from(r"(?m)^!!!(.*)$"), String::from(r#"<aside>${1}</aside>"#));
+        note.set_before(Some(StandardParagraphModifier::Table.identifier()));
+
+        let mut codex = Codex::of_html();
+        codex.insert_dossier_configuration_modifiers(&[keyboard, note]).unwrap();
+
+        assert_eq!(codex.text_modifiers().get_index_of("keyboard"), Some(1));
+        assert_eq!(codex.paragraph_modifiers().get_index_of("note"), codex.paragraph_modifiers().get_index_of(&StandardParagraphModifier::Table.identifier()).map(|i| i - 1));
+
+        let mut document = Document::load_document_from_str("test", "!!! press [[kbd:Ctrl]]", &codex, &LoadConfiguration::default(), LoadConfigurationOverLay::default()).unwrap();
+
+        let outcome = document.compile(&OutputFormat::Html, &codex, &CompilationConfiguration::default(), CompilationConfigurationOverLay::default()).unwrap();
+
+        assert!(outcome.content().contains("<aside> press <kbd>Ctrl</kbd></aside>"));
+
+        let unknown = DossierConfigurationModifier::new(String::from("unknown"), DossierConfigurationModifierKind::Text, String::from("x"), String::from("y"));
+        let mut unknown_position = unknown.clone();
+        unknown_position.set_before(Some(String::from("missing")));
+
+        assert!(codex.insert_dossier_configuration_modifiers(&[unknown_position]).is_err());
+        assert!(codex.insert_dossier_configuration_modifiers(&[unknown.clone(), unknown]).is_err());
+    }
+
+    #[test]
+    fn compose_codex() {
+
+        let highlight = StandardTextModifier::Highlight.identifier();
+        let bold = StandardTextModifier::BoldStarVersion.identifier();
+        let link = StandardTextModifier::Link.identifier();
+
+        let codex = Codex::of_html().derive(|codex| {
+
+            let link_position = codex.text_modifier_identifiers().iter().position(|id| **id == link);
+
+            codex.replace_text_rule(&link, Box::new(ReplacementRule::new(StandardTextModifier::Link.modifier_pattern(), vec![
+                Arc::new(FixedReplacementRuleReplacerPart::new(String::from("link")))
+            ])))?;
+
+            assert_eq!(codex.text_modifier_identifiers().iter().position(|id| **id == link), link_position);
+
+            codex.insert_text_modifier(
+                &ModifierPosition::Before(bold.clone()),
+                String::from("custom"),
+                Box::new(BaseModifier::new(String::from("x"), regex::Regex::new("x").unwrap(), ModifiersBucket::List(vec![bold.clone()]))),
+                Box::new(ReplacementRule::new(String::from("x"), vec![]))
+            )
+        }).unwrap();
+
+        let identifiers = codex.text_modifier_identifiers();
+        let custom_index = identifiers.iter().position(|id| *id == "custom").unwrap();
+
+        assert_eq!(identifiers[custom_index + 1], &bold);
+
+        // previous customizations are kept
+        let codex = codex.derive(|codex| {
+            codex.remove(HashSet::from([highlight.clone()]));
+            Ok(())
+        }).unwrap();
+
+        assert!(codex.text_modifier_identifiers().iter().any(|id| *id == "custom"));
+        assert!(!codex.text_modifier_identifiers().contains(&&highlight));
+
+        // custom modifier is incompatible with highlight, but it is after it
+        let result = Codex::of_html().derive(|codex| {
+            codex.insert_text_modifier(
+                &ModifierPosition::Last,
+                String::from("custom"),
+                Box::new(BaseModifier::new(String::from("x"), regex::Regex::new("x").unwrap(), ModifiersBucket::List(vec![highlight]))),
+                Box::new(ReplacementRule::new(String::from("x"), vec![]))
+            )
+        });
+
+        assert!(matches!(result, Err(CodexError::InvalidOrdering(_, _))));
+    }
+
+    #[test]
+    fn identifier_ids_without_sharp() {
+
+        let codex = Codex::of_html();
+
+        let mut cco = CompilationConfigurationOverLay::default();
+        cco.set_document_name(Some(String::from("d1.nmd")));
+
+        let mut compilable = CompilableText::from("[target]#my-id and @[title]#mark(note)");
+
+        compilable.compile(&OutputFormat::Html, &codex, &CompilationConfiguration::default(), cco).unwrap();
+
+        // ids used to be "#d1-nmd-my-id", which `href="#d1-nmd-my-id"` links never matched
+        assert!(compilable.content().contains(r#"<span class="identifier" id="d1-nmd-my-id">target</span>"#));
+        assert!(compilable.content().contains(r#"id="d1-nmd-mark""#));
+        assert!(!compilable.content().contains(r##"id="#"##));
+    }
+}
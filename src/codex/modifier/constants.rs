@@ -14,6 +14,9 @@ pub const MULTI_LINES_CONTENT_EXCLUDING_HEADINGS_PATTERN: &str = r"(?m)^([^#\n][
 
 pub const MAX_HEADING_LEVEL: u32 = 6;
 
+/// Code block fences have from 3 to this number of backticks
+pub const MAX_CODE_BLOCK_FENCE_LENGTH: usize = 10;
+
 
 pub const STYLE_PATTERN: &str = r"([^{}]*(?:\.(?:\w+|\d+)\s*|(?:\w+\s*:\s*[^;{}]+\s*;?))*)";
 pub const ABRIDGED_STYLE_PATTERN: &str = r"((#?[\w\d\-]+)?;(#?[\w\d\-]+)?;?([\w\d\-]+)?)";
@@ -3,7 +3,7 @@ use std::collections::HashMap;
 use once_cell::sync::Lazy;
 use regex::Regex;
 
-use super::{base_modifier::BaseModifier, constants::{build_strict_reserved_line_pattern, IDENTIFIER_PATTERN, MULTI_LINES_CONTENT_PATTERN, MULTI_LINES_CONTENT_EXCLUDING_HEADINGS_PATTERN, NEW_LINE_PATTERN, STYLE_PATTERN}, ModifierIdentifier, ModifierPattern, ModifiersBucket};
+use super::{base_modifier::BaseModifier, constants::{build_strict_reserved_line_pattern, IDENTIFIER_PATTERN, MAX_CODE_BLOCK_FENCE_LENGTH, MULTI_LINES_CONTENT_PATTERN, MULTI_LINES_CONTENT_EXCLUDING_HEADINGS_PATTERN, NEW_LINE_PATTERN, STYLE_PATTERN}, ModifierIdentifier, ModifierPattern, ModifiersBucket};
 
 
 pub const PARAGRAPH_SEPARATOR_START: &str = r"(?m:^[ \t]*\r?\n)+";
@@ -27,10 +27,12 @@ pub enum StandardParagraphModifier {
     List,
     ListItem,
     Table,
+    ExternalTable,
     Image,
     AbridgedImage,
     MultiImage,
     CodeBlock,
+    CodeSnippet,
     CommentBlock,
     ExtendedBlockQuote,
     ExtendedBlockQuoteLine,
@@ -46,6 +48,8 @@ pub enum StandardParagraphModifier {
     Todo,
     AbridgedTodo,
     MultilineTodo,
+    Include,
+    ConditionalBlock,
 }
 
 impl StandardParagraphModifier {
@@ -53,10 +57,13 @@ impl StandardParagraphModifier {
 
         //! they must have the compatibility order
         vec![
+            Self::ConditionalBlock,
             Self::CodeBlock,
+            Self::CodeSnippet,
             Self::MathBlock,
             Self::EmbeddedParagraphStyle,
             Self::ParagraphIdentifier,
+            Self::ExternalTable,
             Self::Table,
             Self::ExtendedBlockQuote,
             Self::FocusBlock,
@@ -72,14 +79,17 @@ impl StandardParagraphModifier {
             Self::LineBreakStar,
             Self::LineBreakPlus,
             Self::CommentBlock,
+            Self::Include,
         ]
     }
 
     pub fn identifier(&self) -> ModifierIdentifier {
         match self {
             Self::Image => String::from("image"),
+            Self::ConditionalBlock => String::from("conditional-block"),
             Self::CommonParagraph => String::from("common-paragraph"),
             Self::CodeBlock => String::from("code-block"),
+            Self::CodeSnippet => String::from("code-snippet"),
             Self::MathBlock => String::from("math-block"),
             Self::ListItem => String::from("list-item"),
             Self::List => String::from("list"),
@@ -98,7 +108,9 @@ impl StandardParagraphModifier {
             Self::AbridgedImage => String::from(r"abridged-image"),
             Self::MultiImage => String::from("multi-image"),
             Self::Table => String::from("table"),
+            Self::ExternalTable => String::from("external-table"),
             Self::CommentBlock => String::from("comment-block"),
+            Self::Include => String::from("include"),
         }
     }
 
@@ -110,10 +122,16 @@ impl StandardParagraphModifier {
             Self::MultiImage => String::from(r"!!(?::([\w-]+):)?\[\[(?s:(.*?))\]\]"),
             Self::CommonParagraph => String::from(r"([\s\S]+)"),
             Self::CommentBlock => format!(r"<!--(?s:(.*?))-->"),
-            Self::CodeBlock => format!(r"{}{}{}{}", build_strict_reserved_line_pattern(r"```[ \t]*(\w+)?"), NEW_LINE_PATTERN, MULTI_LINES_CONTENT_PATTERN, build_strict_reserved_line_pattern("```")),
+            // regex has no back references, so each fence length has its own alternative (and its own language and content groups)
+            Self::CodeBlock => format!("(?:{})", (3..=MAX_CODE_BLOCK_FENCE_LENGTH).map(|length| {
+                let fence = "`".repeat(length);
+
+                format!(r"{}{}{}{}", build_strict_reserved_line_pattern(&format!(r"{}[ \t]*(\w+)?", fence)), NEW_LINE_PATTERN, MULTI_LINES_CONTENT_PATTERN, build_strict_reserved_line_pattern(&fence))
+            }).collect::<Vec<String>>().join("|")),
+            Self::CodeSnippet => build_strict_reserved_line_pattern(r"@code\(([^)#]+?)(?:#([^)]+))?\)(?:\{(\w+)\})?"),
             Self::MathBlock => format!(r"{}{}{}", build_strict_reserved_line_pattern(r"\$\$"), MULTI_LINES_CONTENT_PATTERN, build_strict_reserved_line_pattern(r"\$\$")),
             Self::FocusBlock => format!(r"{}{}{}{}", build_strict_reserved_line_pattern(r":::[ \t]*(\w+)?"), NEW_LINE_PATTERN, MULTI_LINES_CONTENT_EXCLUDING_HEADINGS_PATTERN, build_strict_reserved_line_pattern(":::")),
-            Self::ListItem => format!(r#"(?m:^([\t ]*)(-\[\]|-\[ \]|-\[x\]|-\[X\]|-|->|\||\*|\+|--|\d[\.)]?|[a-zA-Z]{{1,8}}[\.)]|&[^;]+;) (.*){}?)"#, NEW_LINE_PATTERN),
+            Self::ListItem => format!(r#"(?m:^([\t ]*)(-\[\]|-\[ \]|-\[x\]|-\[X\]|-|->|\||\*|\+|--|\d+[\.)]|\d|[a-zA-Z]{{1,8}}[\.)]|&[^;]+;) (.*){}?)"#, NEW_LINE_PATTERN),
             Self::List => format!(r#"((?:{}+)+)"#, Self::ListItem.modifier_pattern()),
             Self::ExtendedBlockQuoteLine => String::from(r"(?m:^> (.*))"),
             Self::ExtendedBlockQuote => format!(r"(?m)(^[ \t]*>.*(?:\r?\n>.*)*)"),
@@ -126,7 +144,11 @@ impl StandardParagraphModifier {
             Self::Todo => build_strict_reserved_line_pattern(r"(?i:TODO):\s(?:(.*?))"),
             Self::AbridgedTodo => build_strict_reserved_line_pattern(r"(?i:TODO)"),
             Self::MultilineTodo => format!("{}{}", build_strict_reserved_line_pattern(r"(?i:TODO):"), r"(?s:(.*?)):(?i:TODO)"),
-            Self::Table => format!(r"(\|(.*)\|{}?)+(?:\|(.*)\|)(?:{}?(?:\[(.*)\])?(?:{})?(?:\{{\{{{}\}}\}})?)?", NEW_LINE_PATTERN, NEW_LINE_PATTERN, IDENTIFIER_PATTERN, STYLE_PATTERN),
+            Self::Include => build_strict_reserved_line_pattern(r"@include\(([^)#]+?)(?:#([^)]+))?\)(?:\{([+-]?\d+)\})?"),
+            // lone directives are matched too, so that unmatched ones are reported instead of being loaded as text
+            Self::ConditionalBlock => format!(r"(?:{}{}{}(?:{}{}?{})?{}|{})", build_strict_reserved_line_pattern(r"@if\(([^)]+)\)"), NEW_LINE_PATTERN, MULTI_LINES_CONTENT_PATTERN, build_strict_reserved_line_pattern("@else"), NEW_LINE_PATTERN, MULTI_LINES_CONTENT_PATTERN, build_strict_reserved_line_pattern("@endif"), build_strict_reserved_line_pattern(r"@(?:if\([^)]+\)|else|endif)")),
+            Self::ExternalTable => format!(r"{}(?:{}[ \t]*((?:\[.*\]|#[\w-]+|\{{\{{).*))?", build_strict_reserved_line_pattern(r"@table\(([^)]+)\)(?:\{([^}]*)\})?"), NEW_LINE_PATTERN),
+            Self::Table => format!(r"(\|(.*)\|[ \t]*\\?{}?)+(?:\|(.*)\|)(?:{}?(?:\[(.*)\])?(?:{})?(?:\{{\{{{}\}}\}})?)?", NEW_LINE_PATTERN, NEW_LINE_PATTERN, IDENTIFIER_PATTERN, STYLE_PATTERN),
         }
     }
 
@@ -137,8 +159,11 @@ impl StandardParagraphModifier {
             Self::AbridgedImage => ModifiersBucket::All,
             Self::MultiImage => ModifiersBucket::All,
             Self::CodeBlock => ModifiersBucket::All,
+            Self::CodeSnippet => ModifiersBucket::All,
+            Self::ExternalTable => ModifiersBucket::All,
             Self::MathBlock => ModifiersBucket::All,
             Self::CommentBlock => ModifiersBucket::All,
+            Self::ConditionalBlock => ModifiersBucket::All,
 
             _ => ModifiersBucket::None
         }
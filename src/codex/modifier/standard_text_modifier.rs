@@ -46,6 +46,9 @@ pub enum StandardTextModifier {
     Escape,
     Reference,
     Cite,
+    GlossaryTerm,
+    IndexTerm,
+    Conditional,
 }
 
 impl StandardTextModifier {
@@ -57,6 +60,8 @@ impl StandardTextModifier {
             Self::InlineCode,
             Self::InlineMath,
             Self::Comment,
+            Self::Conditional,
+            Self::IndexTerm,
             Self::GreekLetter,
             Self::Todo,
             Self::Bookmark,
@@ -80,6 +85,7 @@ impl StandardTextModifier {
             Self::Escape,
             Self::Reference,
             Self::Cite,
+            Self::GlossaryTerm,
         ]
     }
 
@@ -111,6 +117,9 @@ impl StandardTextModifier {
             Self::Escape => String::from("escape"),
             Self::Reference => String::from("reference"),
             Self::Cite => String::from("cite"),
+            Self::GlossaryTerm => String::from("glossary-term"),
+            Self::IndexTerm => String::from("index-term"),
+            Self::Conditional => String::from("conditional"),
         }
     }
     
@@ -142,6 +151,9 @@ impl StandardTextModifier {
             Self::Escape => String::from(r"\\([\*\+\\~%\^\$@=\[\]!<>\{\}\(\)#-_\|\?&]+)"),
             Self::Reference => String::from(r"&([\w-]+)&"),
             Self::Cite => String::from(r"\^\[([\w_]+)\]"),
+            Self::GlossaryTerm => String::from(r"\?\[([\w-]+)\]"),
+            Self::IndexTerm => String::from(r"@index\[([^\]!|]+)(?:!([^\]|]+))?(?:\|([^\]]+))?\]"),
+            Self::Conditional => String::from(r"@if\(([^)]+)\)\[((?:[^\[\]]|\[[^\[\]]*\])*)\]"),
         }
     }
 
@@ -155,6 +167,8 @@ impl StandardTextModifier {
             Self::Escape => ModifiersBucket::All,
             Self::Reference => ModifiersBucket::All,
             Self::Cite => ModifiersBucket::All,
+            Self::GlossaryTerm => ModifiersBucket::All,
+            Self::IndexTerm => ModifiersBucket::All,
             _ => ModifiersBucket::None
         }
     }
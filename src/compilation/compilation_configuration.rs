@@ -1,9 +1,9 @@
 pub mod list_bullet_configuration_record;
 pub mod compilation_configuration_overlay;
 
-use std::{collections::HashMap, path::PathBuf};
+use std::{collections::{HashMap, HashSet}, path::PathBuf};
 use getset::{CopyGetters, Getters, MutGetters, Setters};
-use crate::{bibliography::Bibliography, resource::text_reference::TextReferenceMap, theme::Theme};
+use crate::{bibliography::Bibliography, glossary::Glossary, dossier::dossier_configuration::DossierConfiguration, resource::{text_reference::TextReferenceMap, variable::{self, VariableMap}, ResourceError}, theme::Theme};
 use self::list_bullet_configuration_record::ListBulletConfigurationRecord;
 
 
@@ -61,6 +61,9 @@ pub struct CompilationConfiguration {
     #[getset(get = "pub", set = "pub")]
     bibliography: Option<Bibliography>,
 
+    #[getset(get = "pub", set = "pub")]
+    glossary: Option<Glossary>,
+
     #[getset(get = "pub", set = "pub")]
     theme: Theme,
 
@@ -73,8 +76,18 @@ pub struct CompilationConfiguration {
     #[getset(get_copy = "pub", set = "pub")]
     strict_cite_check: bool,
 
+    #[getset(get_copy = "pub", set = "pub")]
+    strict_glossary_check: bool,
+
     #[getset(get_copy = "pub", set = "pub")]
     strict_reference_check: bool,
+
+    #[getset(get = "pub", get_mut = "pub", set = "pub")]
+    variables: VariableMap,
+
+    /// Build tags used by conditional content
+    #[getset(get = "pub", get_mut = "pub", set = "pub")]
+    tags: HashSet<String>,
 }
 
 impl CompilationConfiguration {
@@ -100,12 +113,50 @@ impl CompilationConfiguration {
             references,
             fast_draft,
             bibliography,
+            glossary: None,
             theme,
             resource_type,
             strict_cite_check,
+            strict_glossary_check: true,
             strict_greek_letters_check,
             strict_reference_check,
+            variables: HashMap::new(),
+            tags: HashSet::new(),
+        }
+    }
+
+    /// Value of user variable `key` for a document, if `key` is not a variable references are used
+    pub fn resolve_variable(&self, key: &str, document_name: Option<&String>) -> Option<&String> {
+        variable::resolve_variable(&self.variables, &self.references, key, document_name)
+    }
+
+    /// Override variables values (e.g. from CLI), unknown variables are added as string variables
+    pub fn apply_variable_overrides(&mut self, overrides: &HashMap<String, String>) {
+        for (key, value) in overrides {
+            self.variables.entry(key.clone()).or_default().override_value(value.clone());
+        }
+    }
+
+    /// Override variables values using environment variables (e.g. `NMD_VAR_AUDIENCE`)
+    pub fn apply_environment_variable_overrides(&mut self) {
+
+        let overrides: HashMap<String, String> = self.variables.keys()
+                                                    .filter_map(|key| std::env::var(variable::environment_variable_name(key)).ok().map(|value| (key.clone(), value)))
+                                                    .collect();
+
+        self.apply_variable_overrides(&overrides);
+    }
+
+    /// Check that variables values match their types
+    pub fn check_variables(&self) -> Result<(), ResourceError> {
+
+        for (key, variable) in &self.variables {
+            if let Some(value) = variable.invalid_values().first() {
+                return Err(ResourceError::InvalidResourceVerbose(format!("value '{}' of variable '{}' is not a valid {:?}", value, key, variable.variable_type())))
+            }
         }
+
+        Ok(())
     }
 }
 
@@ -125,11 +176,42 @@ impl Default for CompilationConfiguration {
             references: HashMap::new(),
             fast_draft: false,
             bibliography: None,
+            glossary: None,
             theme: Theme::default(),
             resource_type: CompilableResourceType::default(),
             strict_cite_check: true,
+            strict_glossary_check: true,
             strict_greek_letters_check: true,
             strict_reference_check: true,
+            variables: HashMap::new(),
+            tags: HashSet::new(),
         }
     }
-}
\ No newline at end of file
+}
+
+impl From<&DossierConfiguration> for CompilationConfiguration {
+    fn from(dossier_configuration: &DossierConfiguration) -> Self {
+        Self {
+            embed_local_image: dossier_configuration.compilation().embed_local_image(),
+            embed_remote_image: dossier_configuration.compilation().embed_remote_image(),
+            compress_embed_image: dossier_configuration.compilation().compress_embed_image(),
+            strict_image_src_check: dossier_configuration.compilation().strict_image_src_check(),
+            parallelization: dossier_configuration.compilation().parallelization(),
+            list_bullets_configuration: dossier_configuration.style().list_bullets_configuration().clone(),
+            strict_list_check: dossier_configuration.compilation().strict_list_check(),
+            references: dossier_configuration.references().clone(),
+            bibliography: Some(Bibliography::from(dossier_configuration.bibliography())),
+            glossary: Some(Glossary::from(dossier_configuration.glossary())),
+            theme: dossier_configuration.style().theme().clone(),
+            resource_type: CompilableResourceType::Dossier,
+            strict_greek_letters_check: dossier_configuration.compilation().strict_greek_letters_check(),
+            strict_cite_check: dossier_configuration.compilation().strict_cite_check(),
+            strict_glossary_check: dossier_configuration.compilation().strict_glossary_check(),
+            strict_reference_check: dossier_configuration.compilation().strict_reference_check(),
+            variables: dossier_configuration.variables().iter().map(|(key, variable)| (key.clone(), variable.build_variable())).collect(),
+            tags: dossier_configuration.tags().iter().cloned().collect(),
+
+            ..Default::default()
+        }
+    }
+}
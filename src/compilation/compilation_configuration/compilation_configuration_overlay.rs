@@ -1,6 +1,6 @@
 use std::collections::HashSet;
 use getset::{Getters, Setters};
-use crate::{assembler::assembler_configuration::AssemblerConfiguration, codex::modifier::ModifiersBucket};
+use crate::{assembler::assembler_configuration::AssemblerConfiguration, codex::modifier::ModifiersBucket, resource::text_reference::TextReferenceMap};
 
 
 
@@ -23,5 +23,9 @@ pub struct CompilationConfigurationOverLay {
     excluded_modifiers: ModifiersBucket,
 
     #[getset(get = "pub", set = "pub")]
-    assembler_configuration: AssemblerConfiguration
+    assembler_configuration: AssemblerConfiguration,
+
+    /// Values computed during compilation (e.g. `chapter_count`)
+    #[getset(get = "pub", set = "pub")]
+    computed_variables: TextReferenceMap,
 }
\ No newline at end of file
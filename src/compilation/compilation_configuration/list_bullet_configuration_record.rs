@@ -1,11 +1,12 @@
 use getset::{CopyGetters, Getters, Setters};
 use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
 
 pub const CHECKBOX: &str = ":checkbox:";
 pub const CHECKBOX_CHECKED: &str = ":checkbox-checked:";
 
 
-#[derive(Debug, Clone, Deserialize, Serialize, Getters, CopyGetters, Setters)]
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, Getters, CopyGetters, Setters)]
 pub struct ListBulletConfigurationRecord {
 
     #[getset(get = "pub", set = "pub")]
@@ -2,6 +2,9 @@ pub mod replacement_rule;
 pub mod html_greek_letter_rule;
 pub mod reference_rule;
 pub mod html_cite_rule;
+pub mod html_glossary_term_rule;
+pub mod html_index_term_rule;
+pub mod conditional_rule;
 pub mod constants;
 
 
@@ -0,0 +1,102 @@
+use std::fmt::Debug;
+use std::str::FromStr;
+use regex::Regex;
+use crate::compilable_text::CompilableText;
+use crate::resource::condition::Condition;
+use crate::{codex::modifier::standard_text_modifier::StandardTextModifier, compilation::compilation_configuration::{compilation_configuration_overlay::CompilationConfigurationOverLay, CompilationConfiguration}, output_format::OutputFormat};
+use super::CompilationRule;
+use crate::compilation::compilation_error::CompilationError;
+
+
+/// Inline conditional content, i.e. `@if(condition)[content]`.
+/// Content is kept (and compiled) only if condition is true
+pub struct ConditionalRule {
+    search_pattern: String,
+    search_pattern_regex: Regex,
+}
+
+impl ConditionalRule {
+    pub fn new() -> Self {
+        Self {
+            search_pattern: StandardTextModifier::Conditional.modifier_pattern(),
+            search_pattern_regex: StandardTextModifier::Conditional.modifier_pattern_regex().clone(),
+        }
+    }
+}
+
+impl Default for ConditionalRule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Debug for ConditionalRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ConditionalRule").field("searching_pattern", &self.search_pattern).finish()
+    }
+}
+
+impl CompilationRule for ConditionalRule {
+
+    fn search_pattern(&self) -> &String {
+        &self.search_pattern
+    }
+
+    fn standard_compile(&self, compilable: &CompilableText, _format: &OutputFormat, compilation_configuration: &CompilationConfiguration, compilation_configuration_overlay: CompilationConfigurationOverLay) -> Result<CompilableText, CompilationError> {
+
+        let mut compiled_parts = Vec::new();
+
+        let compilable_content = compilable.compilable_content();
+
+        for captures in self.search_pattern_regex.captures_iter(&compilable_content) {
+
+            let condition = Condition::from_str(captures.get(1).unwrap().as_str())?;
+
+            let satisfied = condition.evaluate(compilation_configuration.tags(), |key| {
+                compilation_configuration.resolve_variable(key, compilation_configuration_overlay.document_name().as_ref())
+            });
+
+            if satisfied {
+
+                let content = captures.get(2).unwrap();
+
+                compiled_parts.append(&mut compilable.parts_slice(content.start(), content.end())?);
+            }
+        }
+
+        Ok(CompilableText::new(compiled_parts))
+    }
+    
+    fn search_pattern_regex(&self) -> &Regex {
+        &self.search_pattern_regex
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use crate::{codex::modifier::ModifiersBucket, compilable_text::{compilable_text_part::CompilableTextPart, CompilableText}, compilation::{compilation_configuration::{compilation_configuration_overlay::CompilationConfigurationOverLay, CompilationConfiguration}, compilation_rule::CompilationRule}, output_format::OutputFormat};
+
+    use super::ConditionalRule;
+
+
+    #[test]
+    fn keep_content_only_if_tag_is_set() {
+
+        let compilable = CompilableText::from(vec![
+            CompilableTextPart::new_compilable(String::from("@if(internal)[secret]"), ModifiersBucket::None),
+        ]);
+
+        let mut compilation_configuration = CompilationConfiguration::default();
+
+        let output = ConditionalRule::new().compile(&compilable, &OutputFormat::Html, &compilation_configuration, CompilationConfigurationOverLay::default()).unwrap();
+
+        assert_eq!(output.content(), "");
+
+        compilation_configuration.tags_mut().insert(String::from("internal"));
+
+        let output = ConditionalRule::new().compile(&compilable, &OutputFormat::Html, &compilation_configuration, CompilationConfigurationOverLay::default()).unwrap();
+
+        assert_eq!(output.content(), "secret");
+    }
+}
@@ -0,0 +1,119 @@
+use std::fmt::Debug;
+use regex::Regex;
+use crate::{codex::modifier::standard_text_modifier::StandardTextModifier, compilable_text::{compilable_text_part::{CompilableTextPart, CompilableTextPartType}, CompilableText}, compilation::compilation_configuration::{compilation_configuration_overlay::CompilationConfigurationOverLay, CompilationConfiguration}, output_format::OutputFormat};
+use super::CompilationRule;
+use crate::compilation::compilation_error::CompilationError;
+
+
+/// Compile glossary term uses, first uses are expanded after document compilation (see `Glossary::expand_first_uses`)
+pub struct HtmlGlossaryTermRule {
+    search_pattern: String,
+    search_pattern_regex: Regex,
+}
+
+impl HtmlGlossaryTermRule {
+    pub fn new() -> Self {
+        Self {
+            search_pattern: StandardTextModifier::GlossaryTerm.modifier_pattern(),
+            search_pattern_regex: StandardTextModifier::GlossaryTerm.modifier_pattern_regex().clone(),
+        }
+    }
+}
+
+impl Default for HtmlGlossaryTermRule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Debug for HtmlGlossaryTermRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GlossaryTermRule").field("searching_pattern", &self.search_pattern).finish()
+    }
+}
+
+impl CompilationRule for HtmlGlossaryTermRule {
+
+    fn search_pattern(&self) -> &String {
+        &self.search_pattern
+    }
+
+    fn standard_compile(&self, compilable: &CompilableText, _format: &OutputFormat, compilation_configuration: &CompilationConfiguration, _compilation_configuration_overlay: CompilationConfigurationOverLay) -> Result<CompilableText, CompilationError> {
+
+        let mut compiled_parts = Vec::new();
+
+        for matc in self.search_pattern_regex.captures_iter(&compilable.compilable_content()) {
+
+            let key = matc.get(1).unwrap().as_str();
+
+            // without glossary `?[...]` is plain text
+            let Some(glossary) = compilation_configuration.glossary().as_ref().filter(|glossary| !glossary.entries().is_empty()) else {
+
+                compiled_parts.push(CompilableTextPart::new(matc.get(0).unwrap().as_str().to_string(), CompilableTextPartType::Fixed));
+
+                continue;
+            };
+
+            if let Some(Ok(term_use)) = glossary.compile_use(key) {
+
+                compiled_parts.push(CompilableTextPart::new(term_use, CompilableTextPartType::Fixed));
+
+                continue;
+            }
+
+            log::error!("glossary entry with key: '{}' ('{}') not found: no replacement will be applied", key, matc.get(0).unwrap().as_str());
+
+            if compilation_configuration.strict_glossary_check() {
+                return Err(CompilationError::ElaborationErrorVerbose(format!("glossary entry with key: '{}' ('{}') not found: no replacement will be applied", key, matc.get(0).unwrap().as_str())))
+            }
+
+            compiled_parts.push(CompilableTextPart::new(key.to_string(), CompilableTextPartType::Fixed));
+        }
+
+        Ok(CompilableText::new(compiled_parts))
+    }
+
+    fn search_pattern_regex(&self) -> &Regex {
+        &self.search_pattern_regex
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+    use crate::{codex::Codex, compilable_text::CompilableText, compilation::{compilable::Compilable, compilation_configuration::{compilation_configuration_overlay::CompilationConfigurationOverLay, CompilationConfiguration}}, glossary::{glossary_entry::GlossaryEntry, Glossary}, output_format::OutputFormat};
+
+
+    #[test]
+    fn compile_term_use() {
+
+        let codex = Codex::of_html();
+
+        let mut compilation_configuration = CompilationConfiguration::default();
+
+        compilation_configuration.set_glossary(Some(Glossary::new(String::from("Glossary"), BTreeMap::from([
+            (String::from("api"), GlossaryEntry::new(Some(String::from("API")), None, Some(String::from("interface")))),
+        ]), false)));
+
+        let mut compilable = CompilableText::from("an ?[api] call");
+
+        compilable.compile(&OutputFormat::Html, &codex, &compilation_configuration, CompilationConfigurationOverLay::default()).unwrap();
+
+        assert_eq!(compilable.content(), r#"an <abbr class="glossary-term" data-glossary-key="api" title="interface">API</abbr> call"#);
+
+        let mut compilable = CompilableText::from("an ?[unknown] call");
+
+        assert!(compilable.compile(&OutputFormat::Html, &codex, &compilation_configuration, CompilationConfigurationOverLay::default()).is_err());
+    }
+
+    #[test]
+    fn keep_text_without_glossary() {
+
+        let mut compilable = CompilableText::from("what ?[word] means");
+
+        compilable.compile(&OutputFormat::Html, &Codex::of_html(), &CompilationConfiguration::default(), CompilationConfigurationOverLay::default()).unwrap();
+
+        assert_eq!(compilable.content(), "what ?[word] means");
+    }
+}
@@ -5,6 +5,60 @@ use super::CompilationRule;
 use crate::compilation::compilation_error::CompilationError;
 
 
+/// Greek letters keys (e.g. `a` in `%a%`) and their names
+pub const GREEK_LETTERS: [(&str, &str); 48] = [
+    ("a", r"alpha"),
+    ("b", r"beta"),
+    ("g", r"gamma"),
+    ("d", r"delta"),
+    ("e", r"epsilon"),
+    ("z", r"zeta"),
+    ("n", r"eta"),
+    ("th", r"theta"),
+    ("i", r"iota"),
+    ("k", r"kappa"),
+    ("l", r"lambda"),
+    ("m", r"mu"),
+    ("nu", r"nu"),
+    ("x", r"xi"),
+    ("o", r"omicron"),
+    ("p", r"pi"),
+    ("r", r"rho"),
+    ("s", r"sigma"),
+    ("t", r"tau"),
+    ("u", r"upsilon"),
+    ("phi", r"phi"),
+    ("chi", r"chi"),
+    ("psi", r"psi"),
+    ("w", r"omega"),
+
+    ("A", r"Alpha"),
+    ("B", r"Beta"),
+    ("G", r"Gamma"),
+    ("D", r"Delta"),
+    ("E", r"Epsilon"),
+    ("Z", r"Zeta"),
+    ("N", r"Eta"),
+    ("Th", r"Theta"),
+    ("I", r"Iota"),
+    ("K", r"Kappa"),
+    ("L", r"Lambda"),
+    ("M", r"Mu"),
+    ("Nu", r"Nu"),
+    ("X", r"Xi"),
+    ("O", r"Omicron"),
+    ("P", r"Pi"),
+    ("R", r"Rho"),
+    ("S", r"Sigma"),
+    ("T", r"Tau"),
+    ("U", r"Upsilon"),
+    ("Phi", r"Phi"),
+    ("Chi", r"Chi"),
+    ("Psi", r"Psi"),
+    ("W", r"Omega"),
+];
+
+
 pub struct HtmlGreekLettersRule {
     search_pattern: String,
     search_pattern_regex: Regex,
@@ -16,57 +70,7 @@ impl HtmlGreekLettersRule {
         Self {
             search_pattern: StandardTextModifier::GreekLetter.modifier_pattern(),
             search_pattern_regex: StandardTextModifier::GreekLetter.modifier_pattern_regex().clone(),
-            greek_letters_map: HashMap::from([
-                ("a", r"alpha"),
-                ("b", r"beta"),
-                ("g", r"gamma"),
-                ("d", r"delta"),
-                ("e", r"epsilon"),
-                ("z", r"zeta"),
-                ("n", r"eta"),
-                ("th", r"theta"),
-                ("i", r"iota"),
-                ("k", r"kappa"),
-                ("l", r"lambda"),
-                ("m", r"mu"),
-                ("nu", r"nu"),
-                ("x", r"xi"),
-                ("o", r"omicron"),
-                ("p", r"pi"),
-                ("r", r"rho"),
-                ("s", r"sigma"),
-                ("t", r"tau"),
-                ("u", r"upsilon"),
-                ("phi", r"phi"),
-                ("chi", r"chi"),
-                ("psi", r"psi"),
-                ("w", r"omega"),
-
-                ("A", r"Alpha"),
-                ("B", r"Beta"),
-                ("G", r"Gamma"),
-                ("D", r"Delta"),
-                ("E", r"Epsilon"),
-                ("Z", r"Zeta"),
-                ("N", r"Eta"),
-                ("Th", r"Theta"),
-                ("I", r"Iota"),
-                ("K", r"Kappa"),
-                ("L", r"Lambda"),
-                ("M", r"Mu"),
-                ("Nu", r"Nu"),
-                ("X", r"Xi"),
-                ("O", r"Omicron"),
-                ("P", r"Pi"),
-                ("R", r"Rho"),
-                ("S", r"Sigma"),
-                ("T", r"Tau"),
-                ("U", r"Upsilon"),
-                ("Phi", r"Phi"),
-                ("Chi", r"Chi"),
-                ("Psi", r"Psi"),
-                ("W", r"Omega"),
-            ])
+            greek_letters_map: HashMap::from(GREEK_LETTERS)
         }
     }
 
@@ -0,0 +1,66 @@
+use std::fmt::Debug;
+use regex::Regex;
+use crate::{codex::modifier::standard_text_modifier::StandardTextModifier, compilable_text::{compilable_text_part::{CompilableTextPart, CompilableTextPartType}, CompilableText}, compilation::compilation_configuration::{compilation_configuration_overlay::CompilationConfigurationOverLay, CompilationConfiguration}, output_format::OutputFormat, utility::html_utility::escape_xml};
+use super::CompilationRule;
+use crate::compilation::compilation_error::CompilationError;
+
+
+/// Compile index terms as empty anchors, their ids are assigned after document compilation (see `Index::assign_occurrence_ids`)
+pub struct HtmlIndexTermRule {
+    search_pattern: String,
+    search_pattern_regex: Regex,
+}
+
+impl HtmlIndexTermRule {
+    pub fn new() -> Self {
+        Self {
+            search_pattern: StandardTextModifier::IndexTerm.modifier_pattern(),
+            search_pattern_regex: StandardTextModifier::IndexTerm.modifier_pattern_regex().clone(),
+        }
+    }
+}
+
+impl Default for HtmlIndexTermRule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Debug for HtmlIndexTermRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IndexTermRule").field("searching_pattern", &self.search_pattern).finish()
+    }
+}
+
+impl CompilationRule for HtmlIndexTermRule {
+
+    fn search_pattern(&self) -> &String {
+        &self.search_pattern
+    }
+
+    fn standard_compile(&self, compilable: &CompilableText, _format: &OutputFormat, _compilation_configuration: &CompilationConfiguration, _compilation_configuration_overlay: CompilationConfigurationOverLay) -> Result<CompilableText, CompilationError> {
+
+        let mut compiled_parts = Vec::new();
+
+        for matc in self.search_pattern_regex.captures_iter(&compilable.compilable_content()) {
+
+            let mut attributes = format!(r#" data-index-term="{}""#, escape_xml(matc.get(1).unwrap().as_str().trim()));
+
+            if let Some(sub_entry) = matc.get(2) {
+                attributes.push_str(&format!(r#" data-index-sub="{}""#, escape_xml(sub_entry.as_str().trim())));
+            }
+
+            if let Some(see_also) = matc.get(3) {
+                attributes.push_str(&format!(r#" data-index-see="{}""#, escape_xml(see_also.as_str().trim())));
+            }
+
+            compiled_parts.push(CompilableTextPart::new(format!(r#"<span class="index-term"{}></span>"#, attributes), CompilableTextPartType::Fixed));
+        }
+
+        Ok(CompilableText::new(compiled_parts))
+    }
+
+    fn search_pattern_regex(&self) -> &Regex {
+        &self.search_pattern_regex
+    }
+}
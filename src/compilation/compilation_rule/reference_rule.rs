@@ -2,6 +2,7 @@ use std::fmt::Debug;
 use regex::Regex;
 use crate::compilable_text::compilable_text_part::{CompilableTextPart, CompilableTextPartType};
 use crate::compilable_text::CompilableText;
+use crate::resource::variable::{self, ComputedVariable};
 use crate::{codex::modifier::standard_text_modifier::StandardTextModifier, compilation::compilation_configuration::{compilation_configuration_overlay::CompilationConfigurationOverLay, CompilationConfiguration}, output_format::OutputFormat};
 use super::CompilationRule;
 use crate::compilation::compilation_error::CompilationError;
@@ -19,6 +20,22 @@ impl ReferenceRule {
             search_pattern_regex: StandardTextModifier::Reference.modifier_pattern_regex().clone(),
         }
     }
+
+    /// Resolve key using (in order) user variables, references and computed values
+    fn resolve(key: &str, compilation_configuration: &CompilationConfiguration, compilation_configuration_overlay: &CompilationConfigurationOverLay) -> Option<String> {
+
+        if let Some(value) = compilation_configuration.resolve_variable(key, compilation_configuration_overlay.document_name().as_ref()) {
+            return Some(value.clone())
+        }
+
+        match ComputedVariable::from_key(key)? {
+            ComputedVariable::BuildDate => Some(variable::build_date()),
+            ComputedVariable::Version => Some(variable::version()),
+            ComputedVariable::DossierName => compilation_configuration_overlay.dossier_name().clone(),
+            ComputedVariable::DocumentName => compilation_configuration_overlay.document_name().clone(),
+            ComputedVariable::ChapterCount | ComputedVariable::WordCount => compilation_configuration_overlay.computed_variables().get(key).cloned(),
+        }
+    }
 }
 
 impl Debug for ReferenceRule {
@@ -33,7 +50,7 @@ impl CompilationRule for ReferenceRule {
         &self.search_pattern
     }
 
-    fn standard_compile(&self, compilable: &CompilableText, _format: &OutputFormat, compilation_configuration: &CompilationConfiguration, _compilation_configuration_overlay: CompilationConfigurationOverLay) -> Result<CompilableText, CompilationError> {
+    fn standard_compile(&self, compilable: &CompilableText, _format: &OutputFormat, compilation_configuration: &CompilationConfiguration, compilation_configuration_overlay: CompilationConfigurationOverLay) -> Result<CompilableText, CompilationError> {
 
         let mut compiled_parts = Vec::new();
 
@@ -41,10 +58,10 @@ impl CompilationRule for ReferenceRule {
 
             let reference_key = matc.get(1).unwrap().as_str();
 
-            if let Some(reference) = compilation_configuration.references().get(reference_key) {
+            if let Some(reference) = Self::resolve(reference_key, compilation_configuration, &compilation_configuration_overlay) {
 
                 let reference_part = CompilableTextPart::new(
-                    reference.clone(),
+                    reference,
                     CompilableTextPartType::Fixed
                 );
 
@@ -68,3 +85,39 @@ impl CompilationRule for ReferenceRule {
         &self.search_pattern_regex
     }
 }
+
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+    use crate::{codex::modifier::ModifiersBucket, compilable_text::{compilable_text_part::CompilableTextPart, CompilableText}, compilation::{compilation_configuration::{compilation_configuration_overlay::CompilationConfigurationOverLay, CompilationConfiguration}, compilation_rule::CompilationRule}, output_format::OutputFormat, resource::variable::{Variable, VariableType}};
+
+    use super::ReferenceRule;
+
+
+    fn compile(content: &str, compilation_configuration: &CompilationConfiguration, compilation_configuration_overlay: CompilationConfigurationOverLay) -> String {
+
+        let compilable = CompilableText::from(vec![
+            CompilableTextPart::new_compilable(String::from(content), ModifiersBucket::None),
+        ]);
+
+        ReferenceRule::new().compile(&compilable, &OutputFormat::Html, compilation_configuration, compilation_configuration_overlay).unwrap().content()
+    }
+
+    #[test]
+    fn variables_and_computed_values() {
+
+        let mut compilation_configuration = CompilationConfiguration::default();
+        compilation_configuration.variables_mut().insert(String::from("audience"), Variable::new(VariableType::String, Some(String::from("developers")), HashMap::from([(String::from("guide.nmd"), String::from("users"))])));
+
+        let mut compilation_configuration_overlay = CompilationConfigurationOverLay::default();
+        compilation_configuration_overlay.set_document_name(Some(String::from("guide.nmd")));
+        compilation_configuration_overlay.set_computed_variables(HashMap::from([(String::from("chapter_count"), String::from("3"))]));
+
+        assert_eq!(compile("&audience&&document_name&&chapter_count&", &compilation_configuration, compilation_configuration_overlay.clone()), "usersguide.nmd3");
+
+        compilation_configuration.apply_variable_overrides(&HashMap::from([(String::from("audience"), String::from("testers"))]));
+
+        assert_eq!(compile("&audience&", &compilation_configuration, compilation_configuration_overlay), "testers");
+    }
+}
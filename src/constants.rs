@@ -2,5 +2,9 @@ pub const VERSION: Option<&str> = option_env!("CARGO_PKG_VERSION");
 
 pub const DOSSIER_CONFIGURATION_YAML_FILE_NAME: &str = "nmd.yml";
 pub const DOSSIER_CONFIGURATION_JSON_FILE_NAME: &str = "nmd.json";
+pub const DOSSIER_CONFIGURATION_TOML_FILE_NAME: &str = "nmd.toml";
+
+/// Dossier configuration file names, in lookup order
+pub const DOSSIER_CONFIGURATION_FILE_NAMES: [&str; 3] = [DOSSIER_CONFIGURATION_YAML_FILE_NAME, DOSSIER_CONFIGURATION_JSON_FILE_NAME, DOSSIER_CONFIGURATION_TOML_FILE_NAME];
 
 pub const NMD_EXTENSION: &str = "nmd";
\ No newline at end of file
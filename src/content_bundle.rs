@@ -27,6 +27,95 @@ impl ContentBundle {
         }
     }
 
+    /// Number of words in raw content (headings included)
+    pub fn word_count(&self) -> usize {
+
+        let paragraphs_words = self.preamble.iter()
+                                .chain(self.chapters.iter().flat_map(|chapter| chapter.paragraphs().iter()))
+                                .map(|paragraph| paragraph.raw_content().split_whitespace().count())
+                                .sum::<usize>();
+
+        let headings_words = self.chapters.iter()
+                                .map(|chapter| chapter.header().heading().title().split_whitespace().count())
+                                .sum::<usize>();
+
+        paragraphs_words + headings_words
+    }
+}
+
+impl ContentBundle {
+
+    /// Make heading levels of sorted blocks explicit (minor, major and same levels are resolved
+    /// based on previous heading)
+    pub fn make_heading_levels_explicit(blocks: &mut [LoadBlock]) {
+
+        let mut last_heading_level: u32 = 0;
+
+        for block in blocks.iter_mut() {
+
+            let LoadBlockContent::ChapterHeader(header) = block.content_mut() else {
+                continue;
+            };
+
+            let level = match header.heading().level() {
+                HeadingLevel::Minor => {
+                    
+                    let l;
+                    if last_heading_level < 1 {
+                        log::warn!("minor heading found, but last heading has level {}, so it is set as 1", last_heading_level);
+                        
+                        l = HeadingLevel::Explicit(1)
+                    
+                    } else {
+
+                        l = HeadingLevel::Explicit(last_heading_level - 1);
+                    }
+                    
+                    l
+                },
+                HeadingLevel::Major => {
+                    let l;
+                    if last_heading_level < 1 {
+                        log::warn!("major heading found, but last heading has level {}, so it is set as 1", last_heading_level);
+                        
+                        l = HeadingLevel::Explicit(1)
+                    
+                    } else {
+
+                        l = HeadingLevel::Explicit(last_heading_level + 1);
+                    }
+                    
+                    l
+                },
+                HeadingLevel::Same => {
+                    let l;
+                    if last_heading_level < 1 {
+                        log::warn!("same heading found, but last heading has level {}, so it is set as 1", last_heading_level);
+                        
+                        l = HeadingLevel::Explicit(1)
+                    
+                    } else {
+
+                        l = HeadingLevel::Explicit(last_heading_level);
+                    }
+                    
+                    l
+                },
+                HeadingLevel::Explicit(l) => HeadingLevel::Explicit(*l)
+            };
+
+            if let HeadingLevel::Explicit(l) = &level {
+            
+                last_heading_level = *l;
+            
+            } else {
+
+                unreachable!("heading level must be made 'explicit' now");
+            }
+
+            header.heading_mut().set_level(level);
+        }
+    }
 }
 
 impl From<Vec<LoadBlock>> for ContentBundle {
@@ -42,10 +131,11 @@ impl From<Vec<LoadBlock>> for ContentBundle {
             blocks.par_sort_by(|a, b| a.start().cmp(&b.start()));
         }
 
+        Self::make_heading_levels_explicit(&mut blocks);
+
         let mut preamble: Vec<Box<dyn Paragraph>> = Vec::new();
         let mut current_chapter: Option<Chapter> = None;
         let mut chapters: Vec<Chapter> = Vec::new();
-        let mut last_heading_level: u32 = 0;
 
         for block in blocks {
 
@@ -62,7 +152,7 @@ impl From<Vec<LoadBlock>> for ContentBundle {
                     }
 
                 },
-                LoadBlockContent::ChapterHeader(mut header) => {
+                LoadBlockContent::ChapterHeader(header) => {
 
                     if let Some(cc) = current_chapter.take() {
                         chapters.push(cc);
@@ -70,64 +160,6 @@ impl From<Vec<LoadBlock>> for ContentBundle {
 
                     assert!(current_chapter.is_none());
 
-                    let level = match header.heading().level() {
-                        HeadingLevel::Minor => {
-                            
-                            let l;
-                            if last_heading_level < 1 {
-                                log::warn!("minor heading found, but last heading has level {}, so it is set as 1", last_heading_level);
-                                
-                                l = HeadingLevel::Explicit(1)
-                            
-                            } else {
-
-                                l = HeadingLevel::Explicit(last_heading_level - 1);
-                            }
-                            
-                            l
-                        },
-                        HeadingLevel::Major => {
-                            let l;
-                            if last_heading_level < 1 {
-                                log::warn!("major heading found, but last heading has level {}, so it is set as 1", last_heading_level);
-                                
-                                l = HeadingLevel::Explicit(1)
-                            
-                            } else {
-
-                                l = HeadingLevel::Explicit(last_heading_level + 1);
-                            }
-                            
-                            l
-                        },
-                        HeadingLevel::Same => {
-                            let l;
-                            if last_heading_level < 1 {
-                                log::warn!("same heading found, but last heading has level {}, so it is set as 1", last_heading_level);
-                                
-                                l = HeadingLevel::Explicit(1)
-                            
-                            } else {
-
-                                l = HeadingLevel::Explicit(last_heading_level);
-                            }
-                            
-                            l
-                        },
-                        HeadingLevel::Explicit(l) => HeadingLevel::Explicit(*l)
-                    };
-
-                    if let HeadingLevel::Explicit(l) = &level {
-                    
-                        last_heading_level = *l;
-                    
-                    } else {
-
-                        unreachable!("heading level must be made 'explicit' now");
-                    }
-
-                    header.heading_mut().set_level(level);
-
                     current_chapter = Some(Chapter::new(header, Vec::new()));
                 },
             }
@@ -221,7 +253,7 @@ impl Compilable for ContentBundle {
 mod test {
     use std::sync::Arc;
 
-    use crate::{assembler::html_assembler::HtmlAssembler, codex::{modifier::{base_modifier::BaseModifier, standard_text_modifier::StandardTextModifier, Modifier, ModifiersBucket}, Codex, ParagraphModifierOrderedMap, TextModifierOrderedMap}, compilable_text::{compilable_text_part::CompilableTextPart, CompilableText}, compilation::{compilable::Compilable, compilation_configuration::{compilation_configuration_overlay::CompilationConfigurationOverLay, CompilationConfiguration}, compilation_rule::{replacement_rule::{replacement_rule_part::{closure_replacement_rule_part::ClosureReplacementRuleReplacerPart, fixed_replacement_rule_part::FixedReplacementRuleReplacerPart}, ReplacementRule}, CompilationRule}}, output_format::OutputFormat};
+    use crate::{assembler::html_assembler::HtmlAssembler, codex::{modifier::{base_modifier::BaseModifier, standard_text_modifier::StandardTextModifier, Modifier, ModifiersBucket}, Codex, HeadingModifierOrderedMap, ParagraphModifierOrderedMap, TextModifierOrderedMap}, compilable_text::{compilable_text_part::CompilableTextPart, CompilableText}, compilation::{compilable::Compilable, compilation_configuration::{compilation_configuration_overlay::CompilationConfigurationOverLay, CompilationConfiguration}, compilation_rule::{replacement_rule::{replacement_rule_part::{closure_replacement_rule_part::ClosureReplacementRuleReplacerPart, fixed_replacement_rule_part::FixedReplacementRuleReplacerPart}, ReplacementRule}, CompilationRule}}, output_format::OutputFormat};
 
 
     #[test]
@@ -275,6 +307,7 @@ mod test {
                 )
             ]),
             ParagraphModifierOrderedMap::new(),
+            HeadingModifierOrderedMap::new(),
             None,
             Box::new(HtmlAssembler::new())
         );
@@ -3,15 +3,16 @@ pub mod dossier_configuration;
 
 
 use std::{collections::HashSet, path::PathBuf, time::Instant};
-use document::chapter::heading::Heading;
+use document::chapter::heading::{Heading, HeadingLevel};
 use document::Document;
 use getset::{Getters, MutGetters, Setters};
 use rayon::iter::{IndexedParallelIterator, IntoParallelRefIterator, IntoParallelRefMutIterator, ParallelIterator};
 use thiserror::Error;
-use crate::{codex::Codex, compilation::{compilable::Compilable, compilation_configuration::{compilation_configuration_overlay::CompilationConfigurationOverLay, CompilationConfiguration}, compilation_error::CompilationError, compilation_outcome::CompilationOutcome}, load::{LoadConfiguration, LoadConfigurationOverLay, LoadError}, output_format::OutputFormat, resource::ResourceError};
-
-use self::dossier_configuration::DossierConfiguration;
-use super::{bibliography::Bibliography, table_of_contents::TableOfContents};
+use crate::{codex::Codex, compilation::{compilable::Compilable, compilation_configuration::{compilation_configuration_overlay::CompilationConfigurationOverLay, CompilationConfiguration}, compilation_error::CompilationError, compilation_outcome::CompilationOutcome}, load::{LoadConfiguration, LoadConfigurationOverLay, LoadError}, output_format::OutputFormat, resource::{resource_reference::ResourceReference, ResourceError}};
+use crate::codex::modifier::constants::MAX_HEADING_LEVEL;
+use self::dossier_configuration::{dossier_configuration_documents::DossierConfigurationDocument, DossierConfiguration};
+use super::{bibliography::Bibliography, generated_list::{GeneratedList, GeneratedListKind}, glossary::Glossary, index::Index, table_of_contents::TableOfContents};
+use self::dossier_configuration::dossier_configuration_generated_list::GeneratedListPosition;
 use serde::Serialize;
 
 
@@ -71,32 +72,37 @@ impl Dossier {
 
     /// Load dossier from its filesystem path considering only a subset of documents
     pub fn load_dossier_from_path_buf_only_documents(path_buf: &PathBuf, only_documents: &HashSet<String>, codex: &Codex, configuration: &LoadConfiguration, configuration_overlay: LoadConfigurationOverLay) -> Result<Dossier, LoadError> {
-        let mut dossier_configuration = DossierConfiguration::try_from(path_buf)?;
-
-        let d: Vec<String> = dossier_configuration.raw_documents_paths().iter()
-                                                    .filter(|item| {
-
-                                                        let file_name = PathBuf::from(*item).file_name().unwrap().to_string_lossy().to_string();
-
-                                                        only_documents.contains(file_name.as_str())
-                                                    })
-                                                    .map(|item| item.clone())
-                                                    .collect();
-
-        dossier_configuration.set_raw_documents_paths(d);
+        let dossier_configuration = DossierConfiguration::try_from(path_buf)?;
 
         let mut configuration_overlay = configuration_overlay.clone();
 
         configuration_overlay.set_dossier_name(Some(dossier_configuration.name().clone()));
 
-        Self::load_dossier_from_dossier_configuration(&dossier_configuration, codex, configuration, configuration_overlay)
+        Self::inner_load_dossier_from_dossier_configuration(&dossier_configuration, Some(only_documents), codex, configuration, configuration_overlay)
     }
 
     /// Load dossier from its dossier configuration
     pub fn load_dossier_from_dossier_configuration(dossier_configuration: &DossierConfiguration, codex: &Codex, configuration: &LoadConfiguration, configuration_overlay: LoadConfigurationOverLay) -> Result<Dossier, LoadError> {
+        Self::inner_load_dossier_from_dossier_configuration(dossier_configuration, None, codex, configuration, configuration_overlay)
+    }
+
+    fn inner_load_dossier_from_dossier_configuration(dossier_configuration: &DossierConfiguration, only_documents: Option<&HashSet<String>>, codex: &Codex, configuration: &LoadConfiguration, configuration_overlay: LoadConfigurationOverLay) -> Result<Dossier, LoadError> {
+
+        // documents paths paired with their sub-dossier name
+        let documents_paths: Vec<(PathBuf, Option<String>)> = dossier_configuration.resolve_documents()?.into_iter()
+                                                                .flat_map(|document| match document {
+                                                                    DossierConfigurationDocument::Document(path) => vec![(path, None)],
+                                                                    DossierConfigurationDocument::SubDossier { name, documents } => documents.into_iter().map(|path| (path, Some(name.clone()))).collect(),
+                                                                })
+                                                                .filter(|(path, _)| {
+                                                                    only_documents.map(|only_documents| {
+                                                                        path.file_name().is_some_and(|file_name| only_documents.contains(file_name.to_string_lossy().as_ref()))
+                                                                    }).unwrap_or(true)
+                                                                })
+                                                                .collect();
 
         // TODO: are really mandatory?
-        if dossier_configuration.documents_paths().is_empty() {
+        if documents_paths.is_empty() {
             return Err(LoadError::ResourceError(ResourceError::InvalidResourceVerbose("there are no documents".to_string())))
         }
 
@@ -109,9 +115,11 @@ impl Dossier {
 
             let mut documents_res: Vec<Result<Document, LoadError>> = Vec::new();
 
-            dossier_configuration.documents_paths().par_iter()
-            .map(|document_path| {
-                Document::load_document_from_path(&PathBuf::from(document_path), codex, configuration, configuration_overlay.clone())
+            documents_paths.par_iter()
+            .map(|(document_path, sub_dossier)| {
+                let document = Document::load_document_from_path(document_path, codex, configuration, configuration_overlay.clone())?;
+
+                Ok(Self::place_document_in_sub_dossier(document, sub_dossier))
             }).collect_into_vec(&mut documents_res);
             
             let error = documents_res.par_iter().find_any(|result| result.is_err());
@@ -130,21 +138,90 @@ impl Dossier {
 
             let mut documents: Vec<Document> = Vec::new();
 
-            for document_path in dossier_configuration.documents_paths() {
+            for (document_path, sub_dossier) in documents_paths.iter() {
     
-                let document = Document::load_document_from_path(&PathBuf::from(document_path), codex, configuration, configuration_overlay.clone())?;
+                let document = Document::load_document_from_path(document_path, codex, configuration, configuration_overlay.clone())?;
     
-                documents.push(document)
+                documents.push(Self::place_document_in_sub_dossier(document, sub_dossier))
             }
 
             return Ok(Dossier::new(dossier_configuration.clone(), documents))
         }
     }
+
+    /// Documents of a sub-dossier are nested under its title, so their headings are shifted down by one level
+    fn place_document_in_sub_dossier(mut document: Document, sub_dossier: &Option<String>) -> Document {
+
+        if sub_dossier.is_none() {
+            return document
+        }
+
+        for chapter in document.content_mut().chapters_mut() {
+
+            let heading = chapter.header_mut().heading_mut();
+
+            if let HeadingLevel::Explicit(level) = heading.level() {
+                heading.set_level(HeadingLevel::Explicit((level + 1).min(MAX_HEADING_LEVEL)));
+            }
+        }
+
+        document.set_sub_dossier(sub_dossier.clone());
+
+        document
+    }
+
+    /// Group consecutive documents outcomes of the same sub-dossier in a single section
+    fn assemble_sub_dossiers(&self, documents_outcomes: Vec<(Option<String>, CompilationOutcome)>, codex: &Codex, compilation_configuration_overlay: &CompilationConfigurationOverLay) -> Result<Vec<CompilationOutcome>, CompilationError> {
+
+        let mut outcomes: Vec<CompilationOutcome> = Vec::new();
+
+        let mut group: Option<(String, Vec<CompilationOutcome>)> = None;
+
+        for (sub_dossier, outcome) in documents_outcomes.into_iter().map(|(sub_dossier, outcome)| (sub_dossier, Some(outcome))).chain(std::iter::once((None, None))) {
+
+            let same_group = match (&group, &sub_dossier) {
+                (Some((name, _)), Some(sub_dossier)) => name.eq(sub_dossier),
+                _ => false,
+            };
+
+            if !same_group {
+                if let Some((name, group_outcomes)) = group.take() {
+
+                    let id = ResourceReference::of_internal_from_without_sharp(&name, Some(self.name()))?;
+
+                    outcomes.push(CompilationOutcome::from(codex.assembler().assemble_dossier_section(&name, &id.build_without_internal_sharp(), &group_outcomes, compilation_configuration_overlay.assembler_configuration())?));
+                }
+            }
+
+            let Some(outcome) = outcome else {
+                break;
+            };
+
+            match sub_dossier {
+                Some(sub_dossier) => group.get_or_insert_with(|| (sub_dossier, Vec::new())).1.push(outcome),
+                None => outcomes.push(outcome),
+            }
+        }
+
+        Ok(outcomes)
+    }
 }
 
 
-impl Compilable for Dossier {
-    fn standard_compile(&mut self, format: &OutputFormat, codex: &Codex, compilation_configuration: &CompilationConfiguration, mut compilation_configuration_overlay: CompilationConfigurationOverLay) -> Result<CompilationOutcome, CompilationError> {
+impl Dossier {
+
+    /// Compile dossier as a package of files (e.g. EPUB), see `Assembler::assemble_dossier_package`
+    pub fn compile_package(&mut self, format: &OutputFormat, codex: &Codex, compilation_configuration: &CompilationConfiguration, compilation_configuration_overlay: CompilationConfigurationOverLay) -> Result<Vec<u8>, CompilationError> {
+
+        let (documents_outcomes, compiled_toc, compiled_bib, compiled_index, table_of_contents) = self.compile_components(format, codex, compilation_configuration, compilation_configuration_overlay.clone())?;
+
+        Ok(codex.assembler().assemble_dossier_package(&documents_outcomes, compiled_toc.as_ref(), compiled_bib.as_ref(), compiled_index.as_ref(), &table_of_contents, &self.configuration, compilation_configuration_overlay.assembler_configuration())?)
+    }
+
+    /// Compile documents (grouped by sub-dossier), table of contents, glossary, bibliography and index (if they must be included in output).
+    /// Glossary is returned as last document, table of contents is returned anyway
+    #[allow(clippy::type_complexity)]
+    fn compile_components(&mut self, format: &OutputFormat, codex: &Codex, compilation_configuration: &CompilationConfiguration, mut compilation_configuration_overlay: CompilationConfigurationOverLay) -> Result<(Vec<CompilationOutcome>, Option<CompilationOutcome>, Option<CompilationOutcome>, Option<CompilationOutcome>, TableOfContents), CompilationError> {
     
         log::info!("compile dossier {} with ({} documents, parallelization: {})", self.name(), self.documents().len(), compilation_configuration.parallelization());
 
@@ -152,13 +229,34 @@ impl Compilable for Dossier {
 
         let fast_draft = compilation_configuration.fast_draft();
 
-        let mut documents_outcomes: Vec<CompilationOutcome> = Vec::new();
+        let generated_lists: Vec<(GeneratedListKind, GeneratedListPosition, GeneratedList)> = [
+                (GeneratedListKind::Figures, self.configuration().list_of_figures()),
+                (GeneratedListKind::Tables, self.configuration().list_of_tables()),
+                (GeneratedListKind::Todos, self.configuration().list_of_todos()),
+            ].into_iter()
+            .filter(|(_, configuration)| configuration.include_in_output())
+            .map(|(kind, configuration)| (kind, configuration.position(), GeneratedList::from_configuration(kind, configuration)))
+            .collect();
+
+        let generated_lists_kinds: Vec<GeneratedListKind> = generated_lists.iter().map(|(kind, _, _)| *kind).collect();
+
+        // listed items need an id to be linked
+        let assign_generated_lists_ids = |document: &Document, mut outcome: CompilationOutcome| -> Result<CompilationOutcome, CompilationError> {
+
+            if !generated_lists_kinds.is_empty() {
+                *outcome.content_mut() = GeneratedList::assign_ids(outcome.content(), document.name(), &generated_lists_kinds)?;
+            }
+
+            Ok(outcome)
+        };
+
+        let mut documents_outcomes: Vec<(Option<String>, CompilationOutcome)> = Vec::new();
 
         if compilation_configuration.parallelization() {
 
             let compile_only_documents = compilation_configuration_overlay.compile_only_documents();
 
-            let documents_results: Vec<Result<CompilationOutcome, CompilationError>> = self.documents_mut().par_iter_mut()
+            let documents_results: Vec<Result<(Option<String>, CompilationOutcome), CompilationError>> = self.documents_mut().par_iter_mut()
                 .filter(|document| {
                     if fast_draft {
     
@@ -184,7 +282,7 @@ impl Compilable for Dossier {
 
                     log::info!("document '{}' compiled in {} ms", document.name(), now.elapsed().as_millis());
 
-                    res
+                    res.and_then(|outcome| assign_generated_lists_ids(document, outcome)).map(|outcome| (document.sub_dossier().clone(), outcome))
                 })
                 .collect();
 
@@ -230,9 +328,11 @@ impl Compilable for Dossier {
 
                 let outcome = document.compile(format, codex, compilation_configuration, compilation_configuration_overlay.clone())?;
 
+                let outcome = assign_generated_lists_ids(document, outcome)?;
+
                 log::info!("document '{}' compiled in {} ms", document.name(), now.elapsed().as_millis());
 
-                documents_outcomes.push(outcome);
+                documents_outcomes.push((document.sub_dossier().clone(), outcome));
 
             }
         }
@@ -240,25 +340,42 @@ impl Compilable for Dossier {
         let mut compiled_toc: Option<CompilationOutcome> = None;
         let mut compiled_bib: Option<CompilationOutcome> = None;
 
-        if self.configuration().table_of_contents_configuration().include_in_output() {
+        let mut headings: Vec<Heading> = Vec::new();
 
-            log::info!("dossier table of contents will be included in output");
+        let mut last_sub_dossier: Option<&String> = None;
+
+        for document in self.documents() {
 
-            let mut headings: Vec<Heading> = Vec::new();
+            // sub-dossier title heading
+            if let Some(sub_dossier) = document.sub_dossier() {
+                if last_sub_dossier != Some(sub_dossier) {
 
-            for document in self.documents() {
-                for chapter in document.content().chapters() {
-                    headings.push(chapter.header().heading().clone());
+                    let mut heading = Heading::new(HeadingLevel::Explicit(1), sub_dossier.clone());
+
+                    heading.set_resource_reference(Some(ResourceReference::of_internal_from_without_sharp(sub_dossier, Some(self.name()))?));
+
+                    headings.push(heading);
                 }
             }
 
-            let mut table_of_contents = TableOfContents::new(
-                self.configuration().table_of_contents_configuration().title().clone(),
-                self.configuration().table_of_contents_configuration().page_numbers(),
-                self.configuration().table_of_contents_configuration().plain(),
-                self.configuration().table_of_contents_configuration().maximum_heading_level(),
-                headings
-            );
+            last_sub_dossier = document.sub_dossier().as_ref();
+
+            for chapter in document.content().chapters() {
+                headings.push(chapter.header().heading().clone());
+            }
+        }
+
+        let mut table_of_contents = TableOfContents::new(
+            self.configuration().table_of_contents_configuration().title().clone(),
+            self.configuration().table_of_contents_configuration().page_numbers(),
+            self.configuration().table_of_contents_configuration().plain(),
+            self.configuration().table_of_contents_configuration().maximum_heading_level(),
+            headings
+        );
+
+        if self.configuration().table_of_contents_configuration().include_in_output() {
+
+            log::info!("dossier table of contents will be included in output");
 
             compiled_toc = Some(table_of_contents.compile(format, codex, compilation_configuration, compilation_configuration_overlay.clone())?);
         }
@@ -272,7 +389,61 @@ impl Compilable for Dossier {
             compiled_bib = Some(bibliography.compile(format, codex, compilation_configuration, compilation_configuration_overlay.clone())?);
         }
 
-        Ok(CompilationOutcome::from(codex.assembler().assemble_dossier(&documents_outcomes, compiled_toc.as_ref(), compiled_bib.as_ref(), &self.configuration, compilation_configuration_overlay.assembler_configuration())?))
+        let mut compiled_index: Option<CompilationOutcome> = None;
+
+        if self.configuration().index().include_in_output() {
+
+            let mut index = Index::from(self.configuration().index());
+
+            for (_, outcome) in documents_outcomes.iter() {
+                index.add_occurrences(outcome.content());
+            }
+
+            log::info!("dossier index will be included in output ({} entries)", index.entries().len());
+
+            compiled_index = Some(index.compile(format, codex, compilation_configuration, compilation_configuration_overlay.clone())?);
+        }
+
+        let mut compiled_generated_lists: Vec<(GeneratedListPosition, CompilationOutcome)> = Vec::new();
+
+        for (kind, position, mut generated_list) in generated_lists {
+
+            for (_, outcome) in documents_outcomes.iter() {
+                generated_list.add_items(outcome.content());
+            }
+
+            log::info!("dossier {} will be included in output ({} items)", kind.css_class(), generated_list.items().len());
+
+            compiled_generated_lists.push((position, generated_list.compile(format, codex, compilation_configuration, compilation_configuration_overlay.clone())?));
+        }
+
+        let mut documents_outcomes = self.assemble_sub_dossiers(documents_outcomes, codex, &compilation_configuration_overlay)?;
+
+        // generated lists precede or follow documents
+        let (start_lists, end_lists): (Vec<_>, Vec<_>) = compiled_generated_lists.into_iter().partition(|(position, _)| *position == GeneratedListPosition::Start);
+
+        documents_outcomes.splice(0..0, start_lists.into_iter().map(|(_, outcome)| outcome));
+        documents_outcomes.extend(end_lists.into_iter().map(|(_, outcome)| outcome));
+
+        // glossary follows documents
+        if self.configuration().glossary().include_in_output() {
+
+            let mut glossary = Glossary::from(self.configuration().glossary());
+
+            documents_outcomes.push(glossary.compile(format, codex, compilation_configuration, compilation_configuration_overlay.clone())?);
+        }
+
+        Ok((documents_outcomes, compiled_toc, compiled_bib, compiled_index, table_of_contents))
+    }
+}
+
+
+impl Compilable for Dossier {
+    fn standard_compile(&mut self, format: &OutputFormat, codex: &Codex, compilation_configuration: &CompilationConfiguration, compilation_configuration_overlay: CompilationConfigurationOverLay) -> Result<CompilationOutcome, CompilationError> {
+
+        let (documents_outcomes, compiled_toc, compiled_bib, compiled_index, _) = self.compile_components(format, codex, compilation_configuration, compilation_configuration_overlay.clone())?;
+
+        Ok(CompilationOutcome::from(codex.assembler().assemble_dossier(&documents_outcomes, compiled_toc.as_ref(), compiled_bib.as_ref(), compiled_index.as_ref(), &self.configuration, compilation_configuration_overlay.assembler_configuration())?))
     }
 } 
 
@@ -1,10 +1,12 @@
 pub mod chapter;
+pub mod document_metadata;
 
 
 use std::path::PathBuf;
 use std::time::Instant;
 
 pub use chapter::Chapter;
+use document_metadata::DocumentMetadata;
 use getset::{Getters, MutGetters, Setters};
 use rayon::slice::ParallelSliceMut;
 use serde::Serialize;
@@ -16,6 +18,8 @@ use crate::compilation::compilation_error::CompilationError;
 use crate::compilation::compilable::Compilable;
 use crate::compilation::compilation_outcome::CompilationOutcome;
 use crate::content_bundle::ContentBundle;
+use crate::index::Index;
+use crate::resource::variable::ComputedVariable;
 use crate::load::{LoadConfiguration, LoadConfigurationOverLay, LoadError};
 use crate::load_block::LoadBlock;
 use crate::output_format::OutputFormat;
@@ -43,7 +47,14 @@ pub struct Document {
     name: String,
 
     #[getset(get = "pub", get_mut = "pub", set = "pub")]
-    content: ContentBundle
+    content: ContentBundle,
+
+    #[getset(get = "pub", get_mut = "pub", set = "pub")]
+    metadata: DocumentMetadata,
+
+    /// Name of sub-dossier which contains document (if any)
+    #[getset(get = "pub", set = "pub")]
+    sub_dossier: Option<String>,
 }
 
 
@@ -54,6 +65,8 @@ impl Document {
         Self {
             name,
             content,
+            metadata: DocumentMetadata::default(),
+            sub_dossier: None,
         }
     }
 
@@ -65,11 +78,24 @@ impl Document {
 
         configuration_overlay.set_document_name(Some(document_name.to_string()));
         
-        let mut blocks: Vec<LoadBlock> = LoadBlock::load_from_str(content, codex, configuration, configuration_overlay.clone())?;
+        let (metadata, body_start) = DocumentMetadata::load_front_matter(content)?;
+        
+        let mut blocks: Vec<LoadBlock> = LoadBlock::load_from_str(&content[body_start..], codex, configuration, configuration_overlay.clone())
+                                            .map_err(|err| match err {
+
+                                                // lines are counted from body start
+                                                LoadError::IncludeError(path, Some(line), err) => LoadError::IncludeError(path, Some(line + content[..body_start].matches('\n').count()), err),
+                                                LoadError::LineError(line, err) => LoadError::LineError(line + content[..body_start].matches('\n').count(), err),
+                                                err => err,
+                                            })?;
 
         blocks.par_sort_by(|a, b| a.start().cmp(&b.start()));
 
-        let document = Self::create_document_by_blocks(document_name, blocks)?;
+        let mut document = Self::create_document_by_blocks(document_name, blocks)?;
+
+        if let Some(metadata) = metadata {
+            document.set_metadata(metadata);
+        }
 
         log::info!("document '{}' loaded in {} ms (preamble: {}, chapters: {})", document_name, now.elapsed().as_millis(), document.content().preamble().is_empty(), document.content().chapters().len());
 
@@ -93,7 +119,11 @@ impl Document {
 
         let document_name = resource.name();
 
-        match Self::load_document_from_str(document_name, &content, codex, configuration, configuration_overlay.clone()) {
+        let mut configuration_overlay = configuration_overlay.clone();
+
+        configuration_overlay.set_document_path(Some(path_buf.clone()));
+
+        match Self::load_document_from_str(document_name, &content, codex, configuration, configuration_overlay) {
             Ok(document) => {
                 return Ok(document)
             },
@@ -121,7 +151,31 @@ impl Compilable for Document {
 
         compilation_configuration_overlay.set_document_name(Some(self.name().clone()));
 
-        self.content.compile(format, codex, compilation_configuration, compilation_configuration_overlay.clone())
+        let mut computed_variables = compilation_configuration_overlay.computed_variables().clone();
+        computed_variables.insert(ComputedVariable::ChapterCount.key(), self.content.chapters().len().to_string());
+        computed_variables.insert(ComputedVariable::WordCount.key(), self.content.word_count().to_string());
+        compilation_configuration_overlay.set_computed_variables(computed_variables);
+
+        let mut outcome = match self.metadata.compilation() {
+            Some(overrides) => {
+                let mut compilation_configuration = compilation_configuration.clone();
+
+                overrides.apply(&mut compilation_configuration);
+
+                self.content.compile(format, codex, &compilation_configuration, compilation_configuration_overlay.clone())?
+            },
+            None => self.content.compile(format, codex, compilation_configuration, compilation_configuration_overlay.clone())?
+        };
+
+        // acronyms are expanded on their first use in each document
+        if let Some(glossary) = compilation_configuration.glossary() {
+            *outcome.content_mut() = glossary.expand_first_uses(outcome.content());
+        }
+
+        // index terms need ids to be linked from index
+        *outcome.content_mut() = Index::assign_occurrence_ids(outcome.content(), self.name())?;
+
+        Ok(CompilationOutcome::from(codex.assembler().assemble_document(&self.metadata, &outcome, compilation_configuration_overlay.assembler_configuration())?))
     }
 }
 
@@ -1,7 +1,7 @@
 use getset::{Getters, MutGetters, Setters};
 use serde::Serialize;
-use crate::{codex::{modifier::{base_modifier::BaseModifier, standard_heading_modifier::StandardHeading, Modifier}, Codex}, load::{LoadConfiguration, LoadError}, load_block::{LoadBlock, LoadBlockContent}};
-use super::{chapter_tag::ChapterTag, heading::{Heading, HeadingLevel}};
+use crate::{codex::Codex, load::{LoadConfiguration, LoadError}, load_block::{LoadBlock, LoadBlockContent}};
+use super::{chapter_tag::ChapterTag, heading::Heading};
 
 
 
@@ -27,33 +27,32 @@ impl ChapterHeader {
         }
     }
 
-    /// Load headings and chapter tags from `&str`
+    /// Load headings and chapter tags from `&str` using heading modifiers of `Codex` (in order).
+    /// Matches which overlap an already found header are discarded
     pub fn load(content: &str, codex: &Codex, configuration: &LoadConfiguration) -> Result<Vec<LoadBlock>, LoadError> {
 
         let mut headers: Vec<LoadBlock> = Vec::new();
 
-        for heading in StandardHeading::ordered() {     // TODO: include `StandardHeading::ordered()` in `Codex`
+        for (modifier, loading_rule) in codex.heading_modifiers().values() {
 
-            let heading_modifier = Into::<BaseModifier>::into(heading);
-
-            for m in heading_modifier.modifier_pattern_regex().find_iter(content) {
-
-                let matched_str = m.as_str().to_string();
+            for m in modifier.modifier_pattern_regex().find_iter(content) {
 
                 let m_start = m.start();
                 let m_end = m.end();
 
-                log::debug!("header found (between {} and {}): {:?}", m_start, m_end, &matched_str);
+                if headers.iter().any(|h| m_start < h.end() && h.start() < m_end) {
+                    continue;
+                }
 
-                if let Some((heading, tags)) = Self::parse_chapter_heading_and_tags_from_str(&matched_str, codex, configuration)? {
+                log::debug!("header found (between {} and {}): {:?}", m_start, m_end, m.as_str());
 
-                    headers.push(LoadBlock::new(
-                        m_start,
-                        m_end,
-                        LoadBlockContent::ChapterHeader(ChapterHeader::new(heading, tags))
-                    ));
-                }
+                let header = loading_rule.load(m.as_str(), codex, configuration)?;
 
+                headers.push(LoadBlock::new(
+                    m_start,
+                    m_end,
+                    LoadBlockContent::ChapterHeader(header)
+                ));
             };
         }
 
@@ -61,101 +60,4 @@ impl ChapterHeader {
 
         Ok(headers)
     }
-
-    /// Load the chapter heading and metadata from `&str`. This method returns a tuple with optional heading and a chapter tags vector.
-    fn parse_chapter_heading_and_tags_from_str(content: &str, _codex: &Codex, _configuration: &LoadConfiguration) -> Result<Option<(Heading, Vec<ChapterTag>)>, LoadError> {
-
-        log::debug!("parse headings and chapter tags from:\n{}", content);
-
-        for heading in StandardHeading::ordered() {         // TODO: insert in codex
-
-            let heading_modifier = Into::<BaseModifier>::into(heading.clone());
-
-            if !heading_modifier.modifier_pattern_regex().is_match(content) {
-                continue
-            }
-
-            if let Some(capture) = heading_modifier.modifier_pattern_regex().captures(content) {
-
-                match heading {
-
-                    StandardHeading::MinorHeading => {
-    
-                        let level = HeadingLevel::Minor;
-
-                        let title = capture.get(1).unwrap();    
-    
-                        let tags = ChapterTag::load_chapter_tags_from_str(&content[title.end()..])?;
-                    
-                        return Ok(Some((
-                            Heading::new(level, title.as_str().to_string()),
-                            tags
-                        )))
-                    },
-    
-                    StandardHeading::MajorHeading => {
-                        
-                        let level = HeadingLevel::Major;
-    
-                        let title = capture.get(1).unwrap();
-       
-    
-                        let tags = ChapterTag::load_chapter_tags_from_str(&content[title.end()..])?;
-                    
-                        return Ok(Some((
-                            Heading::new(level, title.as_str().to_string()),
-                            tags
-                        )))
-                    },
-    
-                    StandardHeading::SameHeading => {
-    
-                        
-                        let level = HeadingLevel::Same;
-                        
-                        let title = capture.get(1).unwrap();
-    
-    
-                        let tags = ChapterTag::load_chapter_tags_from_str(&content[title.end()..])?;
-                    
-                        return Ok(Some((
-                            Heading::new(level, title.as_str().to_string()),
-                            tags
-                        )))
-                    },
-    
-                    StandardHeading::HeadingGeneralExtendedVersion(_) => {
-                        let level: u32 = content.chars().take_while(|&c| c == '#').count() as u32;
-    
-                        let title = capture.get(1).unwrap();    
-    
-                        let tags = ChapterTag::load_chapter_tags_from_str(&content[title.end()..])?;
-                    
-                        return Ok(Some((
-                            Heading::new(HeadingLevel::Explicit(level), title.as_str().to_string()),
-                            tags
-                        )))
-                    },
-    
-                    StandardHeading::HeadingGeneralCompactVersion(_) => {
-                        let matched = heading_modifier.modifier_pattern_regex().captures(content).unwrap();
-    
-                        let level = HeadingLevel::Explicit(matched.get(1).unwrap().as_str().parse().unwrap());
-                        let title = capture.get(2).unwrap();    
-    
-                        let tags = ChapterTag::load_chapter_tags_from_str(&content[title.end()..])?;
-                    
-                        return Ok(Some((
-                            Heading::new(level, title.as_str().to_string()),
-                            tags
-                        )))
-                    },
-                }   
-            }
-
-        }
-        
-        Ok(None)
-    }
-
 }
\ No newline at end of file
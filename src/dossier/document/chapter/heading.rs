@@ -1,3 +1,6 @@
+pub mod heading_loading_rule;
+
+
 use getset::{Getters, Setters};
 use serde::Serialize;
 use crate::{codex::{modifier::ModifiersBucket, Codex}, compilable_text::{compilable_text_part::{CompilableTextPart, CompilableTextPartType}, CompilableText}, compilation::{compilable::Compilable, compilation_configuration::{compilation_configuration_overlay::CompilationConfigurationOverLay, CompilationConfiguration}, compilation_error::CompilationError, compilation_outcome::CompilationOutcome}, output_format::OutputFormat, resource::resource_reference::ResourceReference, utility::nmd_unique_identifier::NmdUniqueIdentifier};
@@ -11,6 +14,19 @@ pub enum HeadingLevel {
     Same
 }
 
+impl HeadingLevel {
+
+    /// Explicit level, relative levels are resolved using level of previous heading (level 1 is used if there is no previous heading)
+    pub fn resolve(&self, last_heading_level: Option<u32>) -> u32 {
+        match self {
+            Self::Explicit(level) => *level,
+            Self::Minor => last_heading_level.map(|level| level.saturating_sub(1).max(1)).unwrap_or(1),
+            Self::Major => last_heading_level.map(|level| level + 1).unwrap_or(1),
+            Self::Same => last_heading_level.unwrap_or(1),
+        }
+    }
+}
+
 
 #[derive(Debug, Getters, Setters, Clone, Serialize)]
 pub struct Heading {
@@ -58,7 +74,7 @@ impl Compilable for Heading {
         compiled_title.compile(format, codex, compilation_configuration, compilation_configuration_overlay.clone())?;
 
         let res = match format {
-            OutputFormat::Html => {
+            OutputFormat::Html | OutputFormat::Epub | OutputFormat::Docx | OutputFormat::Text | OutputFormat::Man => {
 
                 let nuid_attr: String;
 
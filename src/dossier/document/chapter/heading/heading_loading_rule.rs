@@ -0,0 +1,12 @@
+pub mod base_heading_loading_rule;
+
+
+use std::fmt::Debug;
+use crate::{codex::Codex, dossier::document::chapter::chapter_header::ChapterHeader, load::{LoadConfiguration, LoadError}};
+
+
+/// Rule to load a `ChapterHeader` from a string matched by a heading modifier
+pub trait HeadingLoadingRule: Debug + Send + Sync {
+
+    fn load(&self, raw_content: &str, codex: &Codex, configuration: &LoadConfiguration) -> Result<ChapterHeader, LoadError>;
+}
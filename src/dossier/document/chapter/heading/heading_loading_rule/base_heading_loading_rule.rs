@@ -0,0 +1,125 @@
+use regex::Regex;
+use super::HeadingLoadingRule;
+use crate::{codex::{modifier::standard_heading_modifier::StandardHeading, Codex}, dossier::document::chapter::{chapter_header::ChapterHeader, chapter_tag::ChapterTag, heading::{Heading, HeadingLevel}}, load::{LoadConfiguration, LoadError}};
+
+
+/// Where heading level comes from
+#[derive(Debug, Clone)]
+pub enum HeadingLevelSource {
+
+    /// Same level for every match
+    Fixed(HeadingLevel),
+
+    /// Explicit level parsed from a capture group
+    CaptureGroup(usize),
+}
+
+
+/// Heading loading rule based on a regex: title is a capture group, level is fixed or captured.
+/// Chapter tags are the lines starting with `@` after title
+#[derive(Debug)]
+pub struct BaseHeadingLoadingRule {
+    search_pattern_regex: Regex,
+    level: HeadingLevelSource,
+    title_capture_group: usize,
+}
+
+impl BaseHeadingLoadingRule {
+
+    pub fn new(search_pattern_regex: Regex, level: HeadingLevelSource, title_capture_group: usize) -> Self {
+        Self {
+            search_pattern_regex,
+            level,
+            title_capture_group,
+        }
+    }
+}
+
+impl HeadingLoadingRule for BaseHeadingLoadingRule {
+    fn load(&self, raw_content: &str, _codex: &Codex, _configuration: &LoadConfiguration) -> Result<ChapterHeader, LoadError> {
+
+        log::debug!("parse headings and chapter tags from:\n{}", raw_content);
+
+        let captures = self.search_pattern_regex.captures(raw_content).ok_or(LoadError::ElaborationError(format!("invalid heading: {}", raw_content)))?;
+
+        let title = captures.get(self.title_capture_group).ok_or(LoadError::ElaborationError(format!("heading title not found in: {}", raw_content)))?;
+
+        let level = match &self.level {
+            HeadingLevelSource::Fixed(level) => level.clone(),
+            HeadingLevelSource::CaptureGroup(group) => {
+
+                let raw_level = captures.get(*group).ok_or(LoadError::ElaborationError(format!("heading level not found in: {}", raw_content)))?;
+
+                HeadingLevel::Explicit(raw_level.as_str().parse().map_err(|_| LoadError::ElaborationError(format!("invalid heading level: {}", raw_level.as_str())))?)
+            },
+        };
+
+        let after_title = &raw_content[title.end()..];
+
+        let tags = match after_title.find("\n@") {
+            Some(tags_start) => ChapterTag::load_chapter_tags_from_str(&after_title[tags_start..])?,
+            None => Vec::new(),
+        };
+
+        Ok(ChapterHeader::new(Heading::new(level, title.as_str().to_string()), tags))
+    }
+}
+
+impl From<StandardHeading> for BaseHeadingLoadingRule {
+    fn from(heading: StandardHeading) -> Self {
+
+        let regex = Regex::new(&heading.modifier_pattern()).unwrap();
+
+        match heading {
+            StandardHeading::MinorHeading => Self::new(regex, HeadingLevelSource::Fixed(HeadingLevel::Minor), 1),
+            StandardHeading::MajorHeading => Self::new(regex, HeadingLevelSource::Fixed(HeadingLevel::Major), 1),
+            StandardHeading::SameHeading => Self::new(regex, HeadingLevelSource::Fixed(HeadingLevel::Same), 1),
+            StandardHeading::HeadingGeneralExtendedVersion(level) => Self::new(regex, HeadingLevelSource::Fixed(HeadingLevel::Explicit(level)), 1),
+            StandardHeading::HeadingGeneralCompactVersion(_) => Self::new(regex, HeadingLevelSource::CaptureGroup(1), 2),
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashSet;
+    use regex::Regex;
+    use crate::{codex::{modifier::{base_modifier::BaseModifier, constants::CHAPTER_TAGS_PATTERN, standard_heading_modifier::StandardHeading, ModifiersBucket}, Codex, ModifierPosition}, dossier::document::{chapter::heading::HeadingLevel, Document}, load::{LoadConfiguration, LoadConfigurationOverLay}};
+    use super::{BaseHeadingLoadingRule, HeadingLevelSource};
+
+
+    #[test]
+    fn setext_heading() {
+
+        let pattern = format!(r"(?m:^([^\n]+)\r?\n=+[ \t]*$){}", CHAPTER_TAGS_PATTERN);
+        let regex = Regex::new(&pattern).unwrap();
+
+        let mut codex = Codex::of_html().derive(|codex| {
+            codex.insert_heading_modifier(
+                &ModifierPosition::First,
+                String::from("setext-heading-1"),
+                Box::new(BaseModifier::new(pattern.clone(), regex.clone(), ModifiersBucket::None)),
+                Box::new(BaseHeadingLoadingRule::new(regex.clone(), HeadingLevelSource::Fixed(HeadingLevel::Explicit(1)), 1))
+            )
+        }).unwrap();
+
+        let content = "Title\n=====\n@author Alice\n\ntext\n\n## Sub\n\ntext";
+
+        let document = Document::load_document_from_str("test", content, &codex, &LoadConfiguration::default(), LoadConfigurationOverLay::default()).unwrap();
+
+        let chapters = document.content().chapters();
+
+        assert_eq!(chapters.len(), 2);
+        assert_eq!(chapters[0].header().heading().title(), "Title");
+        assert_eq!(chapters[0].header().tags().len(), 1);
+        assert!(matches!(chapters[1].header().heading().level(), HeadingLevel::Explicit(2)));
+
+        // standard headings are removed like other modifiers
+        codex.remove(HashSet::from([StandardHeading::HeadingGeneralExtendedVersion(2).identifier()]));
+
+        let document = Document::load_document_from_str("test", content, &codex, &LoadConfiguration::default(), LoadConfigurationOverLay::default()).unwrap();
+
+        assert_eq!(document.content().chapters().len(), 1);
+    }
+}
@@ -49,7 +49,7 @@ impl Compilable for ExtendedBlockQuoteParagraph {
     fn standard_compile(&mut self, format: &OutputFormat, codex: &Codex, compilation_configuration: &CompilationConfiguration, compilation_configuration_overlay: CompilationConfigurationOverLay) -> Result<CompilationOutcome, CompilationError> {
         
         match format {
-            OutputFormat::Html => self.html_standard_compile(codex, compilation_configuration, compilation_configuration_overlay),
+            OutputFormat::Html | OutputFormat::Epub | OutputFormat::Docx | OutputFormat::Text | OutputFormat::Man => self.html_standard_compile(codex, compilation_configuration, compilation_configuration_overlay),
         }
     }
 }
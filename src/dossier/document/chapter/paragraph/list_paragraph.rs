@@ -131,7 +131,7 @@ impl Compilable for ListParagraph {
     fn standard_compile(&mut self, format: &OutputFormat, codex: &Codex, compilation_configuration: &CompilationConfiguration, compilation_configuration_overlay: CompilationConfigurationOverLay) -> Result<CompilationOutcome, CompilationError> {
         
         match format {
-            OutputFormat::Html => self.html_standard_compile(codex, compilation_configuration, compilation_configuration_overlay.clone()),
+            OutputFormat::Html | OutputFormat::Epub | OutputFormat::Docx | OutputFormat::Text | OutputFormat::Man => self.html_standard_compile(codex, compilation_configuration, compilation_configuration_overlay.clone()),
         }
     }
 }
@@ -201,4 +201,30 @@ mod test {
         // )
 
     }
+
+    #[test]
+    fn multi_digit_numbered_items() {
+
+        let nmd_text = "9. nine\n10. ten\n11) eleven\n1 one";
+
+        let codex = Codex::of_html();
+
+        let mut paragraph = ListParagraphLoadingRule::new().load(nmd_text, &codex, &LoadConfiguration::default(), LoadConfigurationOverLay::default()).unwrap();
+
+        let outcome = paragraph.compile(&OutputFormat::Html, &codex, &CompilationConfiguration::default(), CompilationConfigurationOverLay::default()).unwrap();
+
+        let compiled_content = outcome.content();
+
+        // `10.` and `11)` used to be read as text of previous item
+        assert_eq!(Regex::new("<li").unwrap().find_iter(compiled_content).count(), 4);
+        assert!(compiled_content.contains(">10.<"));
+        assert!(compiled_content.contains(">11)<"));
+        assert!(!compiled_content.contains("nine 10."));
+
+        // a bare number is a bullet only if it is a single digit
+        let list_item = Regex::new(&StandardParagraphModifier::ListItem.modifier_pattern()).unwrap();
+
+        assert!(list_item.is_match("1 one"));
+        assert!(!list_item.is_match("10 ten"));
+    }
 }
\ No newline at end of file
@@ -1,14 +1,18 @@
 pub mod replacement_rule_paragraph_loading_rule;
 pub mod table_paragraph_loading_rule;
+pub mod external_table_paragraph_loading_rule;
 pub mod image_paragraph_loading_rule;
 pub mod list_paragraph_loading_rule;
 pub mod block_quote_paragraph_loading_rule;
 pub mod focus_block_paragraph_loading_rule;
 pub mod metadata_wrapper_paragraph_loading_rule;
 pub mod common_paragraph_loading_rule;
+pub mod include_paragraph_loading_rule;
+pub mod code_snippet_paragraph_loading_rule;
+pub mod conditional_paragraph_loading_rule;
 
 
-use crate::{codex::Codex, dossier::document::chapter::paragraph::Paragraph, load::{LoadConfiguration, LoadConfigurationOverLay, LoadError}};
+use crate::{codex::Codex, dossier::document::chapter::paragraph::Paragraph, load::{LoadConfiguration, LoadConfigurationOverLay, LoadError}, load_block::{LoadBlock, LoadBlockContent}};
 use std::fmt::Debug;
 
 
@@ -16,6 +20,13 @@ pub trait ParagraphLoadingRule: Debug + Send + Sync {
 
     fn load(&self, raw_content: &str, codex: &Codex, configuration: &LoadConfiguration, configuration_overlay: LoadConfigurationOverLay) -> Result<Box<dyn Paragraph>, LoadError>;
 
+    /// Load raw content as a sequence of blocks (paragraphs and chapter headers), in order.
+    /// Block positions are replaced by caller, block sources are kept.
+    /// Lines reported in errors are relative to raw content.
+    /// By default, raw content is loaded as a single paragraph.
+    fn load_blocks(&self, raw_content: &str, codex: &Codex, configuration: &LoadConfiguration, configuration_overlay: LoadConfigurationOverLay) -> Result<Vec<LoadBlock>, LoadError> {
+        Ok(vec![LoadBlock::new(0, raw_content.len(), LoadBlockContent::Paragraph(self.load(raw_content, codex, configuration, configuration_overlay)?))])
+    }
 }
 
 pub trait MultiParagraphLoadingRule: Debug + Send + Sync {
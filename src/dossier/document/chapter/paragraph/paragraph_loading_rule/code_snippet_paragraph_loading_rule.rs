@@ -0,0 +1,307 @@
+use std::fs;
+use std::path::Path;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use super::ParagraphLoadingRule;
+use crate::{codex::{modifier::{constants::MAX_CODE_BLOCK_FENCE_LENGTH, standard_paragraph_modifier::StandardParagraphModifier}, Codex}, dossier::document::chapter::paragraph::Paragraph, load::{LoadConfiguration, LoadConfigurationOverLay, LoadError}, resource::ResourceError, utility::file_utility};
+
+
+static CODE_SNIPPET_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(&StandardParagraphModifier::CodeSnippet.modifier_pattern()).unwrap());
+static LINE_RANGE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"^L?(\d+)(?:-L?(\d+))?$").unwrap());
+static REGION_START_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"#region\b\s*([\w-]*)").unwrap());
+static REGION_END_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"#endregion\b").unwrap());
+static BACKTICKS_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"`+").unwrap());
+
+
+/// Code snippet, i.e. `@code(path)`, `@code(path#L10-L20)` or `@code(path#region){lang}`.
+/// File content is loaded as a code block using `CodeBlock` loading rule of `Codex`
+#[derive(Debug, Default)]
+pub struct CodeSnippetParagraphLoadingRule {
+}
+
+impl CodeSnippetParagraphLoadingRule {
+
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    /// Infer language (Prism name) from file extension
+    pub fn language_from_extension(path: &Path) -> Option<String> {
+
+        let extension = path.extension()?.to_string_lossy().to_lowercase();
+
+        let language = match extension.as_str() {
+            "rs" => "rust",
+            "py" => "python",
+            "js" | "mjs" | "cjs" => "javascript",
+            "ts" => "typescript",
+            "kt" | "kts" => "kotlin",
+            "c" | "h" => "c",
+            "cpp" | "cc" | "cxx" | "hpp" => "cpp",
+            "cs" => "csharp",
+            "rb" => "ruby",
+            "sh" | "bash" => "bash",
+            "yml" | "yaml" => "yaml",
+            "md" => "markdown",
+            "htm" | "html" | "xml" | "svg" => "markup",
+            "java" | "php" | "css" | "go" | "json" | "toml" | "sql" | "swift" | "scala" => extension.as_str(),
+            _ => return None,
+        };
+
+        Some(language.to_string())
+    }
+
+    /// Select lines between `from` and `to` (1-based, inclusive)
+    fn select_lines(content: &str, from: usize, to: usize) -> Option<String> {
+
+        let lines: Vec<&str> = content.lines().collect();
+
+        if from == 0 || from > to || to > lines.len() {
+            return None
+        }
+
+        Some(lines[(from - 1)..to].join("\n"))
+    }
+
+    /// Select lines between `#region name` and its `#endregion` (markers excluded, nested markers removed)
+    fn select_region(content: &str, name: &str) -> Option<String> {
+
+        let mut selected: Vec<&str> = Vec::new();
+        let mut depth: usize = 0;
+
+        for line in content.lines() {
+
+            if depth == 0 {
+
+                if let Some(c) = REGION_START_REGEX.captures(line) {
+                    if c.get(1).unwrap().as_str().eq(name) {
+                        depth = 1;
+                    }
+                }
+
+                continue;
+            }
+
+            if REGION_START_REGEX.is_match(line) {
+                depth += 1;
+                continue;
+            }
+
+            if REGION_END_REGEX.is_match(line) {
+                depth -= 1;
+
+                if depth == 0 {
+                    return Some(selected.join("\n"))
+                }
+
+                continue;
+            }
+
+            selected.push(line);
+        }
+
+        None
+    }
+
+    /// Remove common indentation, i.e. the longest whitespace prefix shared by all not blank lines (tabs and spaces are different)
+    fn dedent(snippet: &str) -> String {
+
+        let indentation = snippet.lines()
+                            .filter(|line| !line.trim().is_empty())
+                            .map(|line| &line[..line.len() - line.trim_start().len()])
+                            .reduce(|common, indentation| {
+
+                                let length: usize = common.chars().zip(indentation.chars())
+                                                        .take_while(|(a, b)| a == b)
+                                                        .map(|(c, _)| c.len_utf8())
+                                                        .sum();
+
+                                &common[..length]
+                            })
+                            .unwrap_or("");
+
+        snippet.lines()
+            .map(|line| line.strip_prefix(indentation).unwrap_or(line.trim_start()))
+            .collect::<Vec<&str>>()
+            .join("\n")
+    }
+
+    /// Parse a line number of a line range selector
+    fn parse_line_number(line: &str) -> Result<usize, LoadError> {
+        line.parse().map_err(|_| LoadError::ElaborationError(format!("invalid line number in code snippet selector: {}", line)))
+    }
+
+    /// Code block fence longer than every backtick run of snippet, so that snippet cannot close it
+    fn fence(snippet: &str) -> Result<String, LoadError> {
+
+        let length = BACKTICKS_REGEX.find_iter(snippet).map(|m| m.len() + 1).max().unwrap_or(0).max(3);
+
+        if length > MAX_CODE_BLOCK_FENCE_LENGTH {
+            return Err(LoadError::ElaborationError(format!("code snippet contains more than {} consecutive backticks", MAX_CODE_BLOCK_FENCE_LENGTH - 1)))
+        }
+
+        Ok("`".repeat(length))
+    }
+
+    /// Load snippet from file, `selector` can be a line range or a region name.
+    /// Invalid line numbers are reported as `ElaborationError`, missing file or selection as `ResourceError`
+    fn load_snippet(path: &Path, selector: Option<&str>) -> Result<String, LoadError> {
+
+        let content = fs::read_to_string(path).map_err(|_| ResourceError::ResourceNotFound(path.to_string_lossy().to_string()))?;
+
+        let snippet = match selector {
+            None => Some(content),
+            Some(selector) => {
+                if let Some(c) = LINE_RANGE_REGEX.captures(selector) {
+
+                    let from: usize = Self::parse_line_number(c.get(1).unwrap().as_str())?;
+                    let to: usize = c.get(2).map(|to| Self::parse_line_number(to.as_str())).transpose()?.unwrap_or(from);
+
+                    Self::select_lines(&content, from, to)
+
+                } else {
+
+                    Self::select_region(&content, selector)
+                }
+            }
+        };
+
+        match snippet {
+            Some(snippet) => Ok(Self::dedent(snippet.trim_end())),
+            None => Err(ResourceError::ResourceNotFound(format!("{}#{}", path.to_string_lossy(), selector.unwrap_or_default())).into()),
+        }
+    }
+}
+
+impl ParagraphLoadingRule for CodeSnippetParagraphLoadingRule {
+    fn load(&self, raw_content: &str, codex: &Codex, configuration: &LoadConfiguration, configuration_overlay: LoadConfigurationOverLay) -> Result<Box<dyn Paragraph>, LoadError> {
+
+        let captures = CODE_SNIPPET_REGEX.captures(raw_content).ok_or(LoadError::ElaborationError(format!("invalid code snippet: {}", raw_content.trim())))?;
+
+        let path = file_utility::resolve_dossier_path(captures.get(1).unwrap().as_str(), configuration.input_location());
+
+        let selector = captures.get(2).map(|s| s.as_str());
+
+        let language = captures.get(3).map(|l| l.as_str().to_string()).or(Self::language_from_extension(&path));
+
+        let snippet = match Self::load_snippet(&path, selector) {
+            Ok(snippet) => snippet,
+            Err(LoadError::ResourceError(err)) => {
+                if configuration.strict_code_snippet_check() {
+                    return Err(LoadError::ResourceError(err))
+                }
+
+                log::warn!("code snippet cannot be loaded: {}", err);
+
+                format!("{}", err)
+            }
+            Err(err) => return Err(err),
+        };
+
+        let (_, code_block_loading_rule) = codex.paragraph_modifiers().get(&StandardParagraphModifier::CodeBlock.identifier())
+                                                .ok_or(LoadError::ElaborationError(String::from("code block loading rule not found in codex")))?;
+
+        let fence = Self::fence(&snippet)?;
+
+        let raw_code_block = format!("{}{}\n{}\n{}", fence, language.unwrap_or_default(), snippet, fence);
+
+        code_block_loading_rule.load(&raw_code_block, codex, configuration, configuration_overlay)
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+    use crate::{codex::Codex, compilation::compilation_configuration::{compilation_configuration_overlay::CompilationConfigurationOverLay, CompilationConfiguration}, load::{LoadConfiguration, LoadConfigurationOverLay, LoadError}, output_format::OutputFormat};
+    use super::{CodeSnippetParagraphLoadingRule, ParagraphLoadingRule};
+
+
+    const SOURCE: &str = concat!(
+        "fn main() {\n",
+        "    // #region greet\n",
+        "    println!(\"hello\");\n",
+        "    // #endregion\n",
+        "}\n",
+    );
+
+    #[test]
+    fn select_lines_and_region() {
+
+        assert_eq!(CodeSnippetParagraphLoadingRule::select_lines(SOURCE, 1, 1).unwrap(), "fn main() {");
+        assert!(CodeSnippetParagraphLoadingRule::select_lines(SOURCE, 4, 10).is_none());
+
+        let region = CodeSnippetParagraphLoadingRule::select_region(SOURCE, "greet").unwrap();
+
+        assert_eq!(CodeSnippetParagraphLoadingRule::dedent(&region), "println!(\"hello\");");
+        assert!(CodeSnippetParagraphLoadingRule::select_region(SOURCE, "missing").is_none());
+    }
+
+    #[test]
+    fn dedent_mixed_whitespaces() {
+
+        assert_eq!(CodeSnippetParagraphLoadingRule::dedent("   a\n \u{3000}b\n\n    c"), "  a\n\u{3000}b\n\n   c");
+        assert_eq!(CodeSnippetParagraphLoadingRule::dedent("\ta\n    b"), "\ta\n    b");
+        assert_eq!(CodeSnippetParagraphLoadingRule::dedent("\t\ta\n\tb"), "\ta\nb");
+    }
+
+    #[test]
+    fn load_snippet() {
+
+        let mut configuration = LoadConfiguration::default();
+        configuration.set_input_location(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("test-resources").join("nmd-test-code-snippet"));
+
+        let rule = CodeSnippetParagraphLoadingRule::new();
+        let codex = Codex::of_html();
+
+        let paragraph = rule.load("@code(main.rs#L1)", &codex, &configuration, LoadConfigurationOverLay::default()).unwrap();
+
+        assert_eq!(paragraph.raw_content(), "```rust\nfn main() {\n```");
+
+        let paragraph = rule.load("@code(main.rs#greet)", &codex, &configuration, LoadConfigurationOverLay::default()).unwrap();
+
+        assert_eq!(paragraph.raw_content(), "```rust\nprintln!(\"hello\");\n```");
+
+        assert!(rule.load("@code(missing.rs)", &codex, &configuration, LoadConfigurationOverLay::default()).is_err());
+
+        configuration.set_strict_code_snippet_check(false);
+
+        assert!(rule.load("@code(main.rs#missing)", &codex, &configuration, LoadConfigurationOverLay::default()).is_ok());
+
+        // invalid line numbers are errors even if check is not strict
+        assert!(matches!(
+            rule.load("@code(main.rs#L99999999999999999999)", &codex, &configuration, LoadConfigurationOverLay::default()),
+            Err(LoadError::ElaborationError(_))
+        ));
+    }
+
+    #[test]
+    fn load_snippet_from_relative_dossier_root() {
+
+        // tests run in crate directory, so dossier root is relative like `nmd compile -i docs`
+        let mut configuration = LoadConfiguration::default();
+        configuration.set_input_location(PathBuf::from("test-resources").join("nmd-test-code-snippet"));
+
+        let paragraph = CodeSnippetParagraphLoadingRule::new().load("@code(main.rs#L1)", &Codex::of_html(), &configuration, LoadConfigurationOverLay::default()).unwrap();
+
+        assert_eq!(paragraph.raw_content(), "```rust\nfn main() {\n```");
+    }
+
+    #[test]
+    fn fence_longer_than_snippet_backticks() {
+
+        let mut configuration = LoadConfiguration::default();
+        configuration.set_input_location(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("test-resources").join("nmd-test-code-snippet"));
+
+        let codex = Codex::of_html();
+
+        let mut paragraph = CodeSnippetParagraphLoadingRule::new().load("@code(fenced.md)", &codex, &configuration, LoadConfigurationOverLay::default()).unwrap();
+
+        assert!(paragraph.raw_content().starts_with("````markdown\n# Example"));
+
+        let outcome = paragraph.compile(&OutputFormat::Html, &codex, &CompilationConfiguration::default(), CompilationConfigurationOverLay::default()).unwrap();
+
+        assert_eq!(outcome.content().matches("<pre").count(), 1);
+        assert!(outcome.content().contains("```rust\nlet a = 1;\n```\n</code></pre>"));
+    }
+}
@@ -0,0 +1,162 @@
+use std::str::FromStr;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use super::ParagraphLoadingRule;
+use crate::{codex::{modifier::standard_paragraph_modifier::StandardParagraphModifier, Codex}, dossier::document::chapter::paragraph::Paragraph, load::{LoadConfiguration, LoadConfigurationOverLay, LoadError}, load_block::LoadBlock, resource::condition::Condition};
+
+
+static CONDITIONAL_BLOCK_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(&StandardParagraphModifier::ConditionalBlock.modifier_pattern()).unwrap());
+
+
+/// Conditional block, i.e. `@if(condition)` ... `@else` ... `@endif` (`@else` is optional).
+/// Selected content is loaded as blocks (paragraphs and chapter headers), so discarded chapters
+/// are not part of document at all. Nested blocks are not supported
+#[derive(Debug, Default)]
+pub struct ConditionalParagraphLoadingRule {
+}
+
+impl ConditionalParagraphLoadingRule {
+
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    fn inner_load(&self, raw_content: &str, codex: &Codex, configuration: &LoadConfiguration, configuration_overlay: LoadConfigurationOverLay) -> Result<Vec<LoadBlock>, LoadError> {
+
+        let captures = CONDITIONAL_BLOCK_REGEX.captures(raw_content).ok_or(LoadError::ElaborationError(format!("invalid conditional block: {}", raw_content.trim())))?;
+
+        let Some(condition) = captures.get(1) else {
+            return Err(LoadError::ElaborationError(format!("unmatched conditional directive: {}", raw_content.trim())))
+        };
+
+        // nested blocks would be closed by the first `@endif`
+        for branch in [captures.get(2), captures.get(3)].into_iter().flatten() {
+            if let Some(directive) = CONDITIONAL_BLOCK_REGEX.find(branch.as_str()) {
+                return Err(LoadError::ElaborationError(format!("nested or unmatched conditional directive: {}", directive.as_str().trim())))
+            }
+        }
+
+        let condition = Condition::from_str(condition.as_str())?;
+
+        let satisfied = condition.evaluate(configuration.tags(), |key| {
+            configuration.resolve_variable(key, configuration_overlay.document_name().as_ref())
+        });
+
+        let content = match satisfied {
+            true => captures.get(2),
+            false => captures.get(3),
+        };
+
+        let Some(content) = content else {
+            return Ok(Vec::new())
+        };
+
+        // lines of errors are made relative to raw content
+        let mut blocks = LoadBlock::load_from_str(content.as_str(), codex, configuration, configuration_overlay)
+                                    .map_err(|err| match err {
+                                        LoadError::IncludeError(path, Some(line), err) => LoadError::IncludeError(path, Some(raw_content[..content.start()].matches('\n').count() + line), err),
+                                        LoadError::LineError(line, err) => LoadError::LineError(raw_content[..content.start()].matches('\n').count() + line, err),
+                                        err => err,
+                                    })?;
+
+        blocks.sort_by_key(|block| block.start());
+
+        Ok(blocks)
+    }
+}
+
+impl ParagraphLoadingRule for ConditionalParagraphLoadingRule {
+
+    /// Conditional block can generate more blocks, so it cannot be loaded as a single paragraph
+    fn load(&self, raw_content: &str, _codex: &Codex, _configuration: &LoadConfiguration, _configuration_overlay: LoadConfigurationOverLay) -> Result<Box<dyn Paragraph>, LoadError> {
+        Err(LoadError::ElaborationError(format!("conditional block must be loaded as blocks: {}", raw_content.trim())))
+    }
+
+    fn load_blocks(&self, raw_content: &str, codex: &Codex, configuration: &LoadConfiguration, configuration_overlay: LoadConfigurationOverLay) -> Result<Vec<LoadBlock>, LoadError> {
+        self.inner_load(raw_content, codex, configuration, configuration_overlay)
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use crate::{codex::Codex, dossier::document::Document, load::{LoadConfiguration, LoadConfigurationOverLay}};
+
+
+    #[test]
+    fn load_selected_branch() {
+
+        let content = concat!(
+            "# Intro\n\n",
+            "common\n\n",
+            "@if(internal)\n",
+            "# Internal notes\n\n",
+            "internal only\n",
+            "@else\n",
+            "public\n",
+            "@endif\n",
+        );
+
+        let codex = Codex::of_html();
+
+        let mut configuration = LoadConfiguration::default();
+        configuration.set_parallelization(false);
+
+        let document = Document::load_document_from_str("test", content, &codex, &configuration, LoadConfigurationOverLay::default()).unwrap();
+
+        assert_eq!(document.content().chapters().len(), 1);
+        assert_eq!(document.content().chapters()[0].paragraphs().len(), 2);
+
+        configuration.set_tags([String::from("internal")].into());
+
+        let document = Document::load_document_from_str("test", content, &codex, &configuration, LoadConfigurationOverLay::default()).unwrap();
+
+        assert_eq!(document.content().chapters().len(), 2);
+        assert_eq!(document.content().chapters()[1].header().heading().title(), "Internal notes");
+    }
+
+    #[test]
+    fn resolve_references_as_inline_conditions() {
+
+        let content = concat!(
+            "# Intro\n\n",
+            "@if(edition=pro)\n",
+            "pro only\n",
+            "@endif\n",
+        );
+
+        let codex = Codex::of_html();
+
+        let mut configuration = LoadConfiguration::default();
+        configuration.set_parallelization(false);
+        configuration.set_references([(String::from("edition"), String::from("pro"))].into());
+
+        let document = Document::load_document_from_str("test", content, &codex, &configuration, LoadConfigurationOverLay::default()).unwrap();
+
+        assert_eq!(document.content().chapters()[0].paragraphs().len(), 1);
+    }
+
+    #[test]
+    fn reject_nested_and_unmatched_directives() {
+
+        let codex = Codex::of_html();
+
+        let mut configuration = LoadConfiguration::default();
+        configuration.set_parallelization(false);
+        configuration.set_tags([String::from("a")].into());
+
+        let contents = [
+            "@if(a)\n@if(b)\ninner\n@endif\nafter-inner\n@endif\n",
+            "@if(a)\nfirst\n@else\nsecond\n@else\nthird\n@endif\n",
+            "text\n@endif\n",
+            "@if(a)\nnever closed\n",
+        ];
+
+        for content in contents {
+
+            let error = Document::load_document_from_str("test", content, &codex, &configuration, LoadConfigurationOverLay::default()).unwrap_err();
+
+            assert!(error.to_string().contains("conditional directive"), "{}", content);
+        }
+    }
+}
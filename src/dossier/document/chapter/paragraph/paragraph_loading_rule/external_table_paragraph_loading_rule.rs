@@ -0,0 +1,474 @@
+use std::cmp::Ordering;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use super::{table_paragraph_loading_rule::TableParagraphLoadingRule, ParagraphLoadingRule};
+use crate::{codex::{modifier::{standard_paragraph_modifier::StandardParagraphModifier, ModifiersBucket}, Codex}, compilable_text::{compilable_text_part::CompilableTextPart, CompilableText}, compilation::compilation_rule::{constants::ESCAPE_HTML, replacement_rule::{replacement_rule_part::{fixed_replacement_rule_part::FixedReplacementRuleReplacerPart, single_capture_group_replacement_rule_part::SingleCaptureGroupReplacementRuleReplacerPart}, ReplacementRule}}, content_bundle::ContentBundle, dossier::document::chapter::paragraph::{replacement_rule_paragraph::ReplacementRuleParagraph, table_paragraph::{TableParagraph, TableParagraphContent}, Paragraph}, load::{LoadConfiguration, LoadConfigurationOverLay, LoadError}, load_block::LoadBlock, resource::{table::{Table, TableCell, TableCellAlignment}, ResourceError}, utility::file_utility};
+
+
+static EXTERNAL_TABLE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(&StandardParagraphModifier::ExternalTable.modifier_pattern()).unwrap());
+static FILTER_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"^(.+?)(!=|>=|<=|=|>|<)(.*)$").unwrap());
+
+/// Cell values are plain text by default: they are escaped and no modifier is applied
+static PLAIN_CELL_RULE: Lazy<ReplacementRule> = Lazy::new(|| ReplacementRule::new(String::from(r"(?s)^(.*)$"), vec![
+    Arc::new(FixedReplacementRuleReplacerPart::new(String::from(r#"<p class="paragraph">"#))),
+    Arc::new(SingleCaptureGroupReplacementRuleReplacerPart::new(1, ESCAPE_HTML.clone(), ModifiersBucket::All)),
+    Arc::new(FixedReplacementRuleReplacerPart::new(String::from("</p>"))),
+]));
+
+const OPTIONS_SEPARATOR: char = ';';
+const LIST_SEPARATOR: char = ',';
+const DESCENDING_SORT_PREFIX: char = '-';
+
+
+#[derive(Debug, Clone, PartialEq)]
+enum FilterOperator {
+    Equal,
+    NotEqual,
+    Greater,
+    GreaterOrEqual,
+    Less,
+    LessOrEqual,
+}
+
+impl FilterOperator {
+
+    fn from_str(operator: &str) -> Option<Self> {
+        match operator {
+            "=" => Some(Self::Equal),
+            "!=" => Some(Self::NotEqual),
+            ">" => Some(Self::Greater),
+            ">=" => Some(Self::GreaterOrEqual),
+            "<" => Some(Self::Less),
+            "<=" => Some(Self::LessOrEqual),
+            _ => None,
+        }
+    }
+
+    fn accept(&self, ordering: Ordering) -> bool {
+        match self {
+            Self::Equal => ordering == Ordering::Equal,
+            Self::NotEqual => ordering != Ordering::Equal,
+            Self::Greater => ordering == Ordering::Greater,
+            Self::GreaterOrEqual => ordering != Ordering::Less,
+            Self::Less => ordering == Ordering::Less,
+            Self::LessOrEqual => ordering != Ordering::Greater,
+        }
+    }
+}
+
+
+/// Options of an external table, i.e. `{columns=name,total;sort=-total;filter=region=EU;header=true;footer=false;markup=false}`
+#[derive(Debug, Clone)]
+struct ExternalTableOptions {
+    columns: Option<Vec<String>>,
+    sort: Option<(String, bool)>,
+    filters: Vec<(String, FilterOperator, String)>,
+    header: bool,
+    footer: bool,
+
+    /// Load cell values as NMD, otherwise they are plain text
+    markup: bool,
+}
+
+impl Default for ExternalTableOptions {
+    fn default() -> Self {
+        Self {
+            columns: None,
+            sort: None,
+            filters: Vec::new(),
+            header: true,
+            footer: false,
+            markup: false,
+        }
+    }
+}
+
+impl ExternalTableOptions {
+
+    fn parse_bool(key: &str, value: &str) -> Result<bool, LoadError> {
+        value.trim().parse().map_err(|_| LoadError::ElaborationError(format!("invalid external table option '{}': '{}' is not a boolean", key, value)))
+    }
+
+    fn parse(raw_options: &str) -> Result<Self, LoadError> {
+
+        let mut options = Self::default();
+
+        for option in raw_options.split(OPTIONS_SEPARATOR).map(|o| o.trim()).filter(|o| !o.is_empty()) {
+
+            let (key, value) = option.split_once('=').ok_or(LoadError::ElaborationError(format!("invalid external table option: '{}'", option)))?;
+
+            let key = key.trim();
+
+            match key {
+                "columns" => options.columns = Some(value.split(LIST_SEPARATOR).map(|c| c.trim().to_string()).collect()),
+                "sort" => {
+                    let value = value.trim();
+
+                    options.sort = match value.strip_prefix(DESCENDING_SORT_PREFIX) {
+                        Some(column) => Some((column.to_string(), true)),
+                        None => Some((value.to_string(), false)),
+                    };
+                },
+                "filter" => {
+                    let captures = FILTER_REGEX.captures(value).ok_or(LoadError::ElaborationError(format!("invalid external table filter: '{}'", value)))?;
+
+                    options.filters.push((
+                        captures.get(1).unwrap().as_str().trim().to_string(),
+                        FilterOperator::from_str(captures.get(2).unwrap().as_str()).unwrap(),
+                        captures.get(3).unwrap().as_str().trim().to_string(),
+                    ));
+                },
+                "header" => options.header = Self::parse_bool(key, value)?,
+                "footer" => options.footer = Self::parse_bool(key, value)?,
+                "markup" => options.markup = Self::parse_bool(key, value)?,
+                _ => return Err(LoadError::ElaborationError(format!("unknown external table option: '{}'", key))),
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+
+/// Raw data of an external table: column names (if any) and rows
+type ExternalTableData = (Option<Vec<String>>, Vec<Vec<String>>);
+
+
+/// External table, i.e. a table loaded from a CSV, TSV or JSON file:
+///
+/// ```text
+/// @table(data/sales.csv){columns=name,total;sort=-total;filter=total>=10}
+/// [Caption]#table-id{{style}}
+/// ```
+#[derive(Debug, Default)]
+pub struct ExternalTableParagraphLoadingRule {
+}
+
+impl ExternalTableParagraphLoadingRule {
+
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    fn load_delimited(content: &str, delimiter: u8, has_header: bool) -> Result<ExternalTableData, ResourceError> {
+
+        let mut reader = csv::ReaderBuilder::new()
+                                .delimiter(delimiter)
+                                .has_headers(false)
+                                .flexible(true)
+                                .from_reader(content.as_bytes());
+
+        let mut rows: Vec<Vec<String>> = Vec::new();
+
+        for record in reader.records() {
+            let record = record.map_err(|err| ResourceError::ElaborationError(err.to_string()))?;
+
+            rows.push(record.iter().map(|value| value.trim().to_string()).collect());
+        }
+
+        if has_header && !rows.is_empty() {
+            let header = rows.remove(0);
+
+            return Ok((Some(header), rows))
+        }
+
+        Ok((None, rows))
+    }
+
+    fn json_value_to_string(value: &serde_json::Value) -> String {
+        match value {
+            serde_json::Value::Null => String::new(),
+            serde_json::Value::String(s) => s.clone(),
+            value => value.to_string(),
+        }
+    }
+
+    /// JSON must be an array of objects (keys are column names) or an array of arrays
+    fn load_json(content: &str, has_header: bool) -> Result<ExternalTableData, ResourceError> {
+
+        let value: serde_json::Value = serde_json::from_str(content).map_err(|err| ResourceError::ElaborationError(err.to_string()))?;
+
+        let items = value.as_array().ok_or(ResourceError::ElaborationError(String::from("JSON table must be an array")))?;
+
+        if items.iter().all(|item| item.is_object()) {
+
+            let mut header: Vec<String> = Vec::new();
+
+            for item in items {
+                for key in item.as_object().unwrap().keys() {
+                    if !header.contains(key) {
+                        header.push(key.clone());
+                    }
+                }
+            }
+
+            let rows = items.iter().map(|item| {
+                let item = item.as_object().unwrap();
+
+                header.iter().map(|key| item.get(key).map(Self::json_value_to_string).unwrap_or_default()).collect()
+            }).collect();
+
+            return Ok((Some(header), rows))
+        }
+
+        if items.iter().all(|item| item.is_array()) {
+
+            let mut rows: Vec<Vec<String>> = items.iter().map(|item| item.as_array().unwrap().iter().map(Self::json_value_to_string).collect()).collect();
+
+            if has_header && !rows.is_empty() {
+                let header = rows.remove(0);
+
+                return Ok((Some(header), rows))
+            }
+
+            return Ok((None, rows))
+        }
+
+        Err(ResourceError::ElaborationError(String::from("JSON table must be an array of objects or an array of arrays")))
+    }
+
+    fn load_data(path: &Path, has_header: bool) -> Result<ExternalTableData, ResourceError> {
+
+        let content = fs::read_to_string(path).map_err(|_| ResourceError::ResourceNotFound(path.to_string_lossy().to_string()))?;
+
+        let extension = path.extension().map(|e| e.to_string_lossy().to_lowercase()).unwrap_or_default();
+
+        match extension.as_str() {
+            "csv" => Self::load_delimited(&content, b',', has_header),
+            "tsv" | "tab" => Self::load_delimited(&content, b'\t', has_header),
+            "json" => Self::load_json(&content, has_header),
+            _ => Err(ResourceError::InvalidResourceVerbose(format!("unsupported table format: {}", path.to_string_lossy()))),
+        }
+    }
+
+    /// Find column by name or by 1-based index
+    fn column_index(column: &str, header: &Option<Vec<String>>) -> Result<usize, LoadError> {
+
+        if let Some(header) = header {
+            if let Some(index) = header.iter().position(|name| name.eq(column)) {
+                return Ok(index)
+            }
+        }
+
+        match column.parse::<usize>() {
+            Ok(index) if index > 0 => Ok(index - 1),
+            _ => Err(LoadError::ElaborationError(format!("external table column '{}' not found", column))),
+        }
+    }
+
+    /// Compare values numerically if both are numbers, otherwise lexicographically
+    fn compare_values(a: &str, b: &str) -> Ordering {
+        match (a.parse::<f64>(), b.parse::<f64>()) {
+            (Ok(a), Ok(b)) => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+            _ => a.cmp(b),
+        }
+    }
+
+    fn cell_value(row: &[String], index: usize) -> &str {
+        row.get(index).map(|value| value.as_str()).unwrap_or_default()
+    }
+
+    /// Apply filters, sort and column selection. Footer row (if any) is excluded by rows to elaborate
+    fn elaborate_rows(options: &ExternalTableOptions, header: &Option<Vec<String>>, mut rows: Vec<Vec<String>>) -> Result<(Vec<usize>, Vec<Vec<String>>), LoadError> {
+
+        for (column, operator, value) in &options.filters {
+
+            let index = Self::column_index(column, header)?;
+
+            rows.retain(|row| operator.accept(Self::compare_values(Self::cell_value(row, index), value)));
+        }
+
+        if let Some((column, descending)) = &options.sort {
+
+            let index = Self::column_index(column, header)?;
+
+            rows.sort_by(|a, b| {
+                let ordering = Self::compare_values(Self::cell_value(a, index), Self::cell_value(b, index));
+
+                if *descending { ordering.reverse() } else { ordering }
+            });
+        }
+
+        let columns: Vec<usize> = match &options.columns {
+            Some(columns) => columns.iter().map(|c| Self::column_index(c, header)).collect::<Result<Vec<usize>, LoadError>>()?,
+            None => {
+                let columns_n = rows.iter().map(|row| row.len()).chain(header.iter().map(|h| h.len())).max().unwrap_or(0);
+
+                (0..columns_n).collect()
+            },
+        };
+
+        Ok((columns, rows))
+    }
+
+    /// Paragraph which compiles `value` as it is
+    fn plain_cell_paragraph(value: &str) -> Box<dyn Paragraph> {
+
+        let compilable_text = CompilableText::from(CompilableTextPart::new_compilable(value.to_string(), ModifiersBucket::None));
+
+        Box::new(ReplacementRuleParagraph::new(value.to_string(), compilable_text, PLAIN_CELL_RULE.clone()))
+    }
+
+    fn build_row(row: &[String], columns: &[usize], markup: bool, codex: &Codex, configuration: &LoadConfiguration, configuration_overlay: &LoadConfigurationOverLay) -> Result<Vec<TableCell<ContentBundle>>, LoadError> {
+
+        let mut cells: Vec<TableCell<ContentBundle>> = Vec::new();
+
+        for index in columns {
+
+            let value = Self::cell_value(row, *index);
+
+            if value.is_empty() {
+                cells.push(TableCell::None);
+                continue;
+            }
+
+            let content = match markup {
+                true => ContentBundle::from(LoadBlock::load_from_str(value, codex, configuration, configuration_overlay.clone())?),
+                false => ContentBundle::new(vec![Self::plain_cell_paragraph(value)], Vec::new()),
+            };
+
+            cells.push(TableCell::new_content_cell(content, TableCellAlignment::default()));
+        }
+
+        Ok(cells)
+    }
+
+    fn load_table(&self, raw_content: &str, codex: &Codex, configuration: &LoadConfiguration, configuration_overlay: LoadConfigurationOverLay) -> Result<TableParagraph, LoadError> {
+
+        let captures = EXTERNAL_TABLE_REGEX.captures(raw_content).ok_or(LoadError::ElaborationError(format!("invalid external table: {}", raw_content.trim())))?;
+
+        let path = file_utility::resolve_dossier_path(captures.get(1).unwrap().as_str(), configuration.input_location());
+
+        let options = ExternalTableOptions::parse(captures.get(2).map(|o| o.as_str()).unwrap_or_default())?;
+
+        let (caption, id, styles, classes) = match captures.get(3) {
+            Some(metadata) => TableParagraphLoadingRule::new().extract_table_metadata(metadata.as_str().trim()),
+            None => (None, None, None, None),
+        };
+
+        let (header, mut rows) = Self::load_data(&path, options.header)?;
+
+        let footer = if options.footer { rows.pop() } else { None };
+
+        let (columns, rows) = Self::elaborate_rows(&options, &header, rows)?;
+
+        let mut table: TableParagraphContent = Table::new_empty();
+
+        let render_header = options.header && header.is_some();
+
+        if let Some(header) = header.as_ref().filter(|_| render_header) {
+            table.append_to_body(Self::build_row(header, &columns, options.markup, codex, configuration, &configuration_overlay)?);
+        }
+
+        for row in &rows {
+            table.append_to_body(Self::build_row(row, &columns, options.markup, codex, configuration, &configuration_overlay)?);
+        }
+
+        if let Some(footer) = &footer {
+            table.append_to_body(Self::build_row(footer, &columns, options.markup, codex, configuration, &configuration_overlay)?);
+        }
+
+        if render_header {
+            table.shift_first_body_row_to_header();
+        }
+
+        if footer.is_some() {
+            table.shift_last_body_row_to_footer();
+        }
+
+        Ok(TableParagraph::new(raw_content.to_string(), table, id, styles, classes, caption))
+    }
+}
+
+impl ParagraphLoadingRule for ExternalTableParagraphLoadingRule {
+    fn load(&self, raw_content: &str, codex: &Codex, configuration: &LoadConfiguration, configuration_overlay: LoadConfigurationOverLay) -> Result<Box<dyn Paragraph>, LoadError> {
+        Ok(Box::new(self.load_table(raw_content, codex, configuration, configuration_overlay)?))
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+    use crate::{codex::Codex, compilation::{compilable::Compilable, compilation_configuration::{compilation_configuration_overlay::CompilationConfigurationOverLay, CompilationConfiguration}}, content_bundle::ContentBundle, dossier::document::chapter::paragraph::table_paragraph::TableParagraph, load::{LoadConfiguration, LoadConfigurationOverLay}, output_format::OutputFormat, resource::table::TableCell};
+    use super::ExternalTableParagraphLoadingRule;
+
+
+    fn load(raw_content: &str) -> TableParagraph {
+
+        let mut configuration = LoadConfiguration::default();
+        configuration.set_input_location(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("test-resources").join("nmd-test-external-table"));
+
+        ExternalTableParagraphLoadingRule::new().load_table(raw_content, &Codex::of_html(), &configuration, LoadConfigurationOverLay::default()).unwrap()
+    }
+
+    fn first_column(rows: &[Vec<TableCell<ContentBundle>>]) -> Vec<String> {
+        rows.iter().map(|row| match &row[0] {
+            TableCell::ContentCell { content, .. } => content.preamble()[0].raw_content().trim().to_string(),
+            _ => String::new(),
+        }).collect()
+    }
+
+    #[test]
+    fn load_csv_with_options() {
+
+        let paragraph = load("@table(sales.csv){columns=name,total;sort=-total;filter=total>=10;footer=true}\n[Sales]#sales");
+
+        let table = paragraph.content();
+
+        assert_eq!(table.header().as_ref().unwrap().len(), 2);
+        assert_eq!(first_column(table.body()), vec!["Gamma", "Alpha"]);
+        assert!(table.footer().is_some());
+        assert_eq!(paragraph.raw_caption().as_ref().unwrap(), "Sales");
+        assert_eq!(paragraph.raw_id().as_ref().unwrap(), "sales");
+    }
+
+    #[test]
+    fn load_from_relative_dossier_root() {
+
+        // tests run in crate directory, so dossier root is relative like `nmd compile -i docs`
+        let mut configuration = LoadConfiguration::default();
+        configuration.set_input_location(PathBuf::from("test-resources").join("nmd-test-external-table"));
+
+        let paragraph = ExternalTableParagraphLoadingRule::new().load_table("@table(sales.csv)", &Codex::of_html(), &configuration, LoadConfigurationOverLay::default()).unwrap();
+
+        assert_eq!(paragraph.content().body().len(), 4);
+    }
+
+    #[test]
+    fn load_json() {
+
+        let paragraph = load("@table(sales.json){sort=name}");
+
+        let table = paragraph.content();
+
+        assert_eq!(table.header().as_ref().unwrap().len(), 3);
+        assert_eq!(first_column(table.body()), vec!["Alpha", "Beta"]);
+    }
+
+    #[test]
+    fn plain_text_cells() {
+
+        let codex = Codex::of_html();
+
+        let mut cco = CompilationConfigurationOverLay::default();
+        cco.set_document_name(Some(String::from("d1.nmd")));
+
+        let mut paragraph = load("@table(markup.csv)");
+
+        let outcome = paragraph.compile(&OutputFormat::Html, &codex, &CompilationConfiguration::default(), cco.clone()).unwrap();
+
+        assert!(outcome.content().contains(r#"<p class="paragraph">*</p>"#));
+        assert!(outcome.content().contains(r#"<p class="paragraph">_x_ and &x&</p>"#));
+        assert!(outcome.content().contains(r#"<p class="paragraph"># title</p>"#));
+        assert!(outcome.content().contains(r#"<p class="paragraph">a `code`</p>"#));
+
+        // with markup, `&x&` is a reference
+        let mut paragraph = load("@table(markup.csv){markup=true}");
+
+        assert!(paragraph.compile(&OutputFormat::Html, &codex, &CompilationConfiguration::default(), cco).is_err());
+    }
+}
@@ -0,0 +1,337 @@
+use std::fs;
+use std::path::PathBuf;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use super::ParagraphLoadingRule;
+use crate::{codex::{modifier::{constants::MAX_HEADING_LEVEL, standard_paragraph_modifier::StandardParagraphModifier}, Codex}, content_bundle::ContentBundle, dossier::document::chapter::{heading::HeadingLevel, paragraph::Paragraph}, load::{LoadConfiguration, LoadConfigurationOverLay, LoadError}, load_block::{LoadBlock, LoadBlockContent, LoadBlockSource}, resource::ResourceError};
+
+
+static INCLUDE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(&StandardParagraphModifier::Include.modifier_pattern()).unwrap());
+
+
+/// Include directive, i.e. `@include(path)`, `@include(path#chapter)` or `@include(path#chapter){+1}`.
+/// Included content is inlined at load time as blocks (paragraphs and chapter headers),
+/// so its chapters are part of including document.
+#[derive(Debug, Default)]
+pub struct IncludeParagraphLoadingRule {
+}
+
+impl IncludeParagraphLoadingRule {
+
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    /// Resolve path of included file. Relative paths are relative to including file directory (if any),
+    /// otherwise to input location
+    fn resolve_path(raw_path: &str, configuration: &LoadConfiguration, configuration_overlay: &LoadConfigurationOverLay) -> PathBuf {
+
+        let path = PathBuf::from(raw_path.trim());
+
+        if path.is_absolute() {
+            return path
+        }
+
+        if let Some(document_path) = configuration_overlay.document_path() {
+            if let Some(parent) = document_path.parent() {
+                return parent.join(path)
+            }
+        }
+
+        configuration.input_location().join(path)
+    }
+
+    /// Shift explicit heading level, result is kept between 1 and `MAX_HEADING_LEVEL`
+    fn shift_level(level: u32, shift: i32) -> u32 {
+        (level as i64 + shift as i64).clamp(1, MAX_HEADING_LEVEL as i64) as u32
+    }
+
+    fn is_selected_chapter(title: &str, chapter: &str) -> bool {
+
+        let title = title.trim().to_lowercase();
+        let chapter = chapter.trim().to_lowercase();
+
+        title.eq(&chapter) || title.replace(' ', "-").eq(&chapter)
+    }
+
+    fn inner_load(&self, raw_content: &str, codex: &Codex, configuration: &LoadConfiguration, configuration_overlay: LoadConfigurationOverLay) -> Result<Vec<LoadBlock>, LoadError> {
+
+        let captures = INCLUDE_REGEX.captures(raw_content).ok_or(LoadError::ElaborationError(format!("invalid include directive: {}", raw_content.trim())))?;
+
+        let raw_path = captures.get(1).unwrap().as_str();
+        let chapter = captures.get(2).map(|c| c.as_str().to_string());
+        let shift: i32 = match captures.get(3) {
+            Some(shift) => shift.as_str().parse().map_err(|_| LoadError::ElaborationError(format!("invalid heading shift: {}", shift.as_str())))?,
+            None => 0,
+        };
+
+        let path = Self::resolve_path(raw_path, configuration, &configuration_overlay);
+
+        if configuration_overlay.include_stack().len() >= configuration.max_include_depth() {
+            return Err(LoadError::IncludeDepthExceeded(configuration.max_include_depth()))
+        }
+
+        let canonical_path = fs::canonicalize(&path).map_err(|_| LoadError::IncludeError(
+                                    path.to_string_lossy().to_string(),
+                                    None,
+                                    Box::new(LoadError::ResourceError(ResourceError::InvalidResourceVerbose(format!("{} not exists", path.to_string_lossy()))))
+                                ))?;
+
+        let mut including_chain: Vec<PathBuf> = configuration_overlay.include_stack().clone();
+
+        if let Some(document_path) = configuration_overlay.document_path() {
+            including_chain.push(fs::canonicalize(document_path).unwrap_or(document_path.clone()));
+        }
+
+        if including_chain.contains(&canonical_path) {
+
+            let mut cycle: Vec<String> = including_chain.iter().map(|p| p.to_string_lossy().to_string()).collect();
+            cycle.push(canonical_path.to_string_lossy().to_string());
+
+            return Err(LoadError::IncludeCycle(cycle.join(" -> ")))
+        }
+
+        log::debug!("include '{}' (chapter: {:?}, shift: {})", canonical_path.to_string_lossy(), chapter, shift);
+
+        let content = fs::read_to_string(&canonical_path)?;
+
+        let mut included_configuration_overlay = configuration_overlay.clone();
+        included_configuration_overlay.set_include_stack(including_chain);
+        included_configuration_overlay.set_document_path(Some(canonical_path.clone()));
+
+        let mut blocks = LoadBlock::load_from_str(&content, codex, configuration, included_configuration_overlay)
+                                    .map_err(|err| LoadError::IncludeError(path.to_string_lossy().to_string(), None, Box::new(err)))?;
+
+        blocks.sort_by_key(|block| block.start());
+
+        ContentBundle::make_heading_levels_explicit(&mut blocks);
+
+        // blocks of nested included files keep their own source
+        for block in blocks.iter_mut() {
+            if block.source().is_none() {
+                block.set_source(Some(LoadBlockSource::new(canonical_path.clone(), block.start(), block.end())));
+            }
+        }
+
+        let mut included: Vec<LoadBlock> = Vec::new();
+
+        if let Some(chapter) = &chapter {
+
+            let mut selected_level: Option<u32> = None;
+
+            for mut block in blocks {
+
+                if let LoadBlockContent::ChapterHeader(header) = block.content_mut() {
+
+                    let level = match header.heading().level() {
+                        HeadingLevel::Explicit(l) => *l,
+                        _ => unreachable!("heading level must be explicit"),
+                    };
+
+                    if let Some(selected_level) = selected_level {
+
+                        // next chapter of same or upper level closes selected chapter
+                        if level <= selected_level {
+                            break;
+                        }
+
+                    } else if Self::is_selected_chapter(header.heading().title(), chapter) {
+
+                        selected_level = Some(level);
+
+                    } else {
+                        continue;
+                    }
+
+                    header.heading_mut().set_level(HeadingLevel::Explicit(Self::shift_level(level, shift)));
+
+                } else if selected_level.is_none() {
+                    continue;
+                }
+
+                included.push(block);
+            }
+
+            if selected_level.is_none() {
+                return Err(LoadError::IncludeError(path.to_string_lossy().to_string(), None, Box::new(LoadError::ElaborationError(format!("chapter '{}' not found", chapter)))))
+            }
+
+        } else {
+
+            for mut block in blocks {
+
+                if let LoadBlockContent::ChapterHeader(header) = block.content_mut() {
+                    if let HeadingLevel::Explicit(level) = header.heading().level() {
+                        let level = Self::shift_level(*level, shift);
+
+                        header.heading_mut().set_level(HeadingLevel::Explicit(level));
+                    }
+                }
+
+                included.push(block);
+            }
+        }
+
+        Ok(included)
+    }
+}
+
+impl ParagraphLoadingRule for IncludeParagraphLoadingRule {
+
+    /// Include directive generates more blocks, so it cannot be loaded as a single paragraph
+    fn load(&self, raw_content: &str, _codex: &Codex, _configuration: &LoadConfiguration, _configuration_overlay: LoadConfigurationOverLay) -> Result<Box<dyn Paragraph>, LoadError> {
+        Err(LoadError::ElaborationError(format!("include directive must be loaded as blocks: {}", raw_content.trim())))
+    }
+
+    fn load_blocks(&self, raw_content: &str, codex: &Codex, configuration: &LoadConfiguration, configuration_overlay: LoadConfigurationOverLay) -> Result<Vec<LoadBlock>, LoadError> {
+        self.inner_load(raw_content, codex, configuration, configuration_overlay)
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+    use crate::{codex::Codex, dossier::document::{chapter::heading::HeadingLevel, Document}, load::{LoadConfiguration, LoadConfigurationOverLay, LoadError}, load_block::{LoadBlock, LoadBlockContent}};
+
+
+    fn include_dir() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("test-resources").join("nmd-test-include")
+    }
+
+    fn load(file_name: &str) -> Result<Document, LoadError> {
+
+        let mut configuration = LoadConfiguration::default();
+        configuration.set_input_location(include_dir());
+        configuration.set_parallelization(false);
+
+        Document::load_document_from_path(&include_dir().join(file_name), &Codex::of_html(), &configuration, LoadConfigurationOverLay::default())
+    }
+
+    #[test]
+    fn include_whole_file_and_chapter() {
+
+        let document = load("main.nmd").unwrap();
+
+        let titles: Vec<(String, u32)> = document.content().chapters().iter().map(|c| {
+            match c.header().heading().level() {
+                HeadingLevel::Explicit(l) => (c.header().heading().title().clone(), *l),
+                _ => panic!("heading level must be explicit"),
+            }
+        }).collect();
+
+        assert_eq!(titles, vec![
+            (String::from("Main"), 1),
+            (String::from("Part A"), 2),
+            (String::from("Part A detail"), 3),
+            (String::from("Part B"), 2),
+            (String::from("Main end"), 1),
+        ]);
+
+        // part preamble is included in "Main" chapter
+        assert_eq!(document.content().chapters()[0].paragraphs().len(), 2);
+    }
+
+    #[test]
+    fn include_cycle() {
+
+        let error = load("cycle-a.nmd").unwrap_err();
+
+        assert!(error.to_string().contains("include cycle detected"));
+        assert!(error.to_string().contains("cycle-b.nmd' (included at line 3)"));
+    }
+
+    #[test]
+    fn include_missing_chapter() {
+
+        let mut configuration = LoadConfiguration::default();
+        configuration.set_input_location(include_dir());
+        configuration.set_parallelization(false);
+
+        let error = Document::load_document_from_str("test", "# Title\n\ntext\n\n@include(./parts.nmd#missing)\n", &Codex::of_html(), &configuration, LoadConfigurationOverLay::default()).unwrap_err();
+
+        let LoadError::IncludeError(path, line, error) = error else {
+            panic!("include error expected, found: {}", error)
+        };
+
+        assert!(path.ends_with("parts.nmd"));
+        assert_eq!(line, Some(5));
+        assert!(error.to_string().contains("chapter 'missing' not found"));
+    }
+
+    #[test]
+    fn include_errors_count_front_matter_lines() {
+
+        let mut configuration = LoadConfiguration::default();
+        configuration.set_input_location(include_dir());
+        configuration.set_parallelization(false);
+
+        let content = "---\ntitle: Test\nauthor: Me\n---\n\n# Title\n\ntext\n\n@include(./parts.nmd#nope)\n\n@include(./missing.nmd)\n";
+
+        let error = Document::load_document_from_str("test", content, &Codex::of_html(), &configuration, LoadConfigurationOverLay::default()).unwrap_err();
+
+        let LoadError::IncludeError(_, line, _) = error else {
+            panic!("include error expected, found: {}", error)
+        };
+
+        assert_eq!(line, Some(10));
+
+        let content = "---\ntitle: Test\n---\n\n@include(./missing.nmd)\n";
+
+        let error = Document::load_document_from_str("test", content, &Codex::of_html(), &configuration, LoadConfigurationOverLay::default()).unwrap_err();
+
+        let LoadError::IncludeError(path, line, error) = error else {
+            panic!("include error expected, found: {}", error)
+        };
+
+        assert!(path.ends_with("missing.nmd"));
+        assert_eq!(line, Some(5));
+        assert!(error.to_string().contains("not exists"));
+    }
+
+    #[test]
+    fn keep_included_blocks_source() {
+
+        let mut configuration = LoadConfiguration::default();
+        configuration.set_input_location(include_dir());
+        configuration.set_parallelization(false);
+
+        let mut blocks = LoadBlock::load_from_str("text\n\n@include(./parts.nmd#part-b)\n", &Codex::of_html(), &configuration, LoadConfigurationOverLay::default()).unwrap();
+
+        blocks.sort_by_key(|block| block.start());
+
+        assert!(blocks[0].source().is_none());
+
+        let header = blocks.iter().find(|block| matches!(block.content(), LoadBlockContent::ChapterHeader(_))).unwrap();
+        let source = header.source().as_ref().unwrap();
+
+        let parts = std::fs::read_to_string(include_dir().join("parts.nmd")).unwrap();
+
+        assert!(source.path().ends_with("parts.nmd"));
+        assert_eq!(parts[source.start()..source.end()].trim(), "# Part B");
+    }
+
+    #[test]
+    fn include_errors_report_line_in_included_file() {
+
+        let mut configuration = LoadConfiguration::default();
+        configuration.set_input_location(include_dir());
+        configuration.set_parallelization(false);
+
+        let error = Document::load_document_from_str("test", "# Title\n\n@include(./broken.nmd)\n", &Codex::of_html(), &configuration, LoadConfigurationOverLay::default()).unwrap_err();
+
+        let LoadError::IncludeError(path, line, error) = error else {
+            panic!("include error expected, found: {}", error)
+        };
+
+        assert!(path.ends_with("broken.nmd"));
+        assert_eq!(line, Some(3));
+
+        let LoadError::LineError(line, error) = *error else {
+            panic!("line error expected, found: {}", error)
+        };
+
+        assert_eq!(line, 5);
+        assert!(error.to_string().contains("unmatched conditional directive"));
+    }
+}
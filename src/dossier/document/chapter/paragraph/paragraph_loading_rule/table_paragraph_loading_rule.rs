@@ -6,9 +6,19 @@ use crate::{codex::{modifier::constants::{IDENTIFIER_PATTERN, STYLE_PATTERN}, Co
 
 
 /// (caption, id, styles, classes)
-type TableMetadata = (Option<String>, Option<String>, Option<String>, Option<String>);
+pub(crate) type TableMetadata = (Option<String>, Option<String>, Option<String>, Option<String>);
 
 static EXTRACT_TABLE_METADATA_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(&format!(r"(?:\[(.*)\])?(?:{})?(?:\{{\{{{}\}}\}})?", IDENTIFIER_PATTERN, STYLE_PATTERN)).unwrap());
+static COLUMN_WIDTH_RATIO_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\s*\((\d+)\)$").unwrap());
+
+/// Cell which is merged with the cell on its left
+const COLUMN_SPAN_MARKER: &str = "<";
+
+/// Cell which is merged with the cell above
+const ROW_SPAN_MARKER: &str = "^";
+
+/// Line ending which continues row cells in the next line
+const ROW_CONTINUATION_MARKER: char = '\\';
 
 
 #[derive(Debug)]
@@ -51,12 +61,20 @@ impl TableParagraphLoadingRule {
         Some(row)
     }
 
-    fn extract_table_alignments_from_row(row: &Vec<String>) -> Option<Vec<TableCellAlignment>> {
+    /// Extract alignments and optional width ratios (e.g. `:---: (2)`) from a delimiter row
+    fn extract_table_alignments_from_row(row: &Vec<String>) -> Option<(Vec<TableCellAlignment>, Vec<Option<u32>>)> {
 
         let mut alignments = vec![TableCellAlignment::default(); row.len()];
+        let mut widths: Vec<Option<u32>> = vec![None; row.len()];
 
         for (index, cell) in row.iter().enumerate() {
-            let cell = cell.trim();
+            let mut cell = cell.trim();
+
+            if let Some(c) = COLUMN_WIDTH_RATIO_REGEX.captures(cell) {
+                widths[index] = c.get(1).unwrap().as_str().parse().ok();
+
+                cell = &cell[..c.get(0).unwrap().start()];
+            }
 
             if cell.starts_with(":-") && cell.ends_with("-:") {
                 alignments[index] = TableCellAlignment::Center;
@@ -81,7 +99,73 @@ impl TableParagraphLoadingRule {
             return None;
         }
 
-        Some(alignments)
+        Some((alignments, widths))
+    }
+
+    fn is_span_marker(cell: &str) -> bool {
+        let cell = cell.trim();
+
+        cell.eq(COLUMN_SPAN_MARKER) || cell.eq(ROW_SPAN_MARKER)
+    }
+
+    /// Merge cells marked by span markers into their origin cell (i.e. the first not spanned cell on the left or above).
+    /// Origin cells are extended only if the marker is adjacent to their area.
+    /// `section_starts` are the rows after header and footer delimiters, row spans cannot cross them
+    fn apply_spans<T>(rows: &mut [Vec<TableCell<T>>], raw_rows: &[Vec<String>], section_starts: &[usize]) -> Result<(), LoadError> {
+
+        // position of origin cell of each cell
+        let mut origins: Vec<Vec<(usize, usize)>> = Vec::new();
+
+        for (r, raw_row) in raw_rows.iter().enumerate() {
+
+            origins.push(Vec::new());
+
+            for (c, raw_cell) in raw_row.iter().enumerate() {
+
+                let raw_cell = raw_cell.trim();
+
+                if raw_cell.eq(ROW_SPAN_MARKER) && section_starts.contains(&r) {
+                    return Err(LoadError::ElaborationError(format!("row span marker in row {} crosses a table delimiter", r + 1)))
+                }
+
+                let origin = if raw_cell.eq(COLUMN_SPAN_MARKER) && c > 0 {
+                    Some(origins[r][c - 1])
+                } else if raw_cell.eq(ROW_SPAN_MARKER) && r > 0 && c < origins[r - 1].len() {
+                    Some(origins[r - 1][c])
+                } else {
+                    None
+                };
+
+                match origin {
+                    Some((origin_r, origin_c)) => {
+
+                        if let TableCell::ContentCell { column_span, row_span, .. } = &mut rows[origin_r][origin_c] {
+
+                            if origin_r == r && origin_c + *column_span == c {
+                                *column_span += 1;
+                            }
+
+                            if origin_r + *row_span == r && origin_c == c {
+                                *row_span += 1;
+                            }
+                        }
+
+                        origins[r].push((origin_r, origin_c));
+                    },
+                    None => {
+
+                        // marker without origin is an empty cell
+                        if Self::is_span_marker(raw_cell) {
+                            rows[r][c] = TableCell::None;
+                        }
+
+                        origins[r].push((r, c));
+                    },
+                }
+            }
+        }
+
+        Ok(())
     }
 
     fn build_row(row: &Vec<String>, alignments: &Vec<TableCellAlignment>, codex: &Codex, configuration: &LoadConfiguration, configuration_overlay: LoadConfigurationOverLay) -> Result<Vec<TableCell<ContentBundle>>, LoadError> {
@@ -90,9 +174,14 @@ impl TableParagraphLoadingRule {
 
         for (index, cell) in row.iter().enumerate() {
 
-            let mut cell = String::from(cell);
+            // surrounding spaces would be read as indentation (e.g. ` 1 ` is a list item)
+            let mut cell = String::from(cell.trim());
+
+            if Self::is_span_marker(&cell) {
 
-            if cell.is_empty() {
+                cells.push(TableCell::Spanned);
+
+            } else if cell.trim().is_empty() {
 
                 cells.push(TableCell::None);
 
@@ -121,14 +210,14 @@ impl TableParagraphLoadingRule {
 
                 let inner_blocks = LoadBlock::load_from_str(&cell, codex, configuration, configuration_overlay.clone())?;
 
-                cells.push(TableCell::ContentCell { content: ContentBundle::from(inner_blocks), alignment: align});
+                cells.push(TableCell::new_content_cell(ContentBundle::from(inner_blocks), align));
             }
         }
 
         Ok(cells)
     }
 
-    fn extract_table_metadata(&self, s: &str) -> TableMetadata {
+    pub(crate) fn extract_table_metadata(&self, s: &str) -> TableMetadata {
 
         let captures = EXTRACT_TABLE_METADATA_REGEX.captures(s);
 
@@ -167,19 +256,22 @@ impl ParagraphLoadingRule for TableParagraphLoadingRule {
         let mut table: TableParagraphContent = Table::new_empty();
 
         let lines = raw_content.trim().lines();
-        let lines_n = lines.clone().count();
 
-        let mut there_is_header: bool = false;
-        let mut there_is_footer: bool = false;
         let mut max_row_len: usize = 0;
         let mut alignments: Option<Vec<TableCellAlignment>> = None;
+        let mut header_delimiter_found: bool = false;
+        let mut footer_delimiter_position: Option<usize> = None;
+
+        // (raw cells, alignments)
+        let mut raw_rows: Vec<(Vec<String>, Vec<TableCellAlignment>)> = Vec::new();
+        let mut continued_row: Option<Vec<String>> = None;
 
         let mut id: Option<String> = None;
         let mut caption: Option<String> = None;
         let mut styles: Option<String> = None;
         let mut classes: Option<String> = None;
 
-        for (index, line) in lines.enumerate() {
+        for line in lines {
 
             // check if there are metadata
             let trim_line = line.trim_start();
@@ -194,7 +286,33 @@ impl ParagraphLoadingRule for TableParagraphLoadingRule {
                 continue;
             }
     
-            let row = row.unwrap();
+            let mut row = row.unwrap();
+
+            let continues = line.trim_end().ends_with(ROW_CONTINUATION_MARKER);
+
+            // merge continuation lines cell by cell
+            if let Some(mut previous) = continued_row.take() {
+
+                while previous.len() < row.len() {
+                    previous.push(String::new());
+                }
+
+                // cells built so far are kept as they are, so blank continuation lines separate paragraphs
+                for (index, cell) in row.iter().enumerate() {
+                    previous[index] = format!("{}\n{}", previous[index], cell.trim());
+                }
+
+                row = previous;
+
+            } else if continues {
+
+                row = row.iter().map(|cell| cell.trim().to_string()).collect();
+            }
+
+            if continues {
+                continued_row = Some(row);
+                continue;
+            }
 
             max_row_len = max_row_len.max(row.len());
 
@@ -202,14 +320,17 @@ impl ParagraphLoadingRule for TableParagraphLoadingRule {
                 alignments = Some(vec![TableCellAlignment::default(); max_row_len])
             }
 
-            if let Some(mut aligns) = Self::extract_table_alignments_from_row(&row) {
+            if let Some((mut aligns, widths)) = Self::extract_table_alignments_from_row(&row) {
+
+                if raw_rows.len() == 1 && !header_delimiter_found {
+                    header_delimiter_found = true;
 
-                if table.body().len() == 1 {
-                    there_is_header = true;
+                } else if !raw_rows.is_empty() {
+                    footer_delimiter_position = Some(raw_rows.len());
                 }
 
-                if index == lines_n - 2 {
-                    there_is_footer = true;
+                if widths.iter().any(|width| width.is_some()) {
+                    table.set_column_width_ratios(Some(widths.iter().map(|width| width.unwrap_or(1)).collect()));
                 }
 
                 while aligns.len() < max_row_len {
@@ -221,17 +342,34 @@ impl ParagraphLoadingRule for TableParagraphLoadingRule {
                 continue;
             }
 
-            let row = Self::build_row(&row, alignments.as_ref().unwrap(), codex, configuration, configuration_overlay.clone())?;
+            raw_rows.push((row, alignments.clone().unwrap()));
+        }
+
+        if let Some(row) = continued_row {
+            raw_rows.push((row, alignments.clone().unwrap_or_default()));
+        }
+
+        for (row, alignments) in &raw_rows {
+
+            let row = Self::build_row(row, alignments, codex, configuration, configuration_overlay.clone())?;
 
             table.append_to_body(row);
         }
 
-        if there_is_header {
+        let raw_cells: Vec<Vec<String>> = raw_rows.into_iter().map(|(row, _)| row).collect();
+
+        // footer is the last row, after a delimiter which is not the header one
+        let footer_position = footer_delimiter_position.filter(|position| *position == raw_cells.len() - 1);
+
+        let section_starts: Vec<usize> = [header_delimiter_found.then_some(1), footer_position].into_iter().flatten().collect();
+
+        Self::apply_spans(table.body_mut(), &raw_cells, &section_starts)?;
+
+        if header_delimiter_found {
             table.shift_first_body_row_to_header();
         }
 
-        // check if there is footer
-        if there_is_footer {
+        if footer_position.is_some() {
             table.shift_last_body_row_to_footer();
         }
 
@@ -244,7 +382,7 @@ impl ParagraphLoadingRule for TableParagraphLoadingRule {
 #[cfg(test)]
 mod test {
     
-    use crate::{codex::Codex, load::{LoadConfiguration, LoadConfigurationOverLay}, load_block::{LoadBlock, LoadBlockContent}};
+    use crate::{codex::Codex, compilation::compilation_configuration::{compilation_configuration_overlay::CompilationConfigurationOverLay, CompilationConfiguration}, load::{LoadConfiguration, LoadConfigurationOverLay}, load_block::{LoadBlock, LoadBlockContent}, output_format::OutputFormat};
 
 
     #[test]
@@ -296,5 +434,68 @@ mod test {
         }
     }
 
-}
+    #[test]
+    fn padded_cells() {
+        let nmd_text = "| Step | Item |\n|---|---|\n| 1 | a |\n";
+
+        let codex = Codex::of_html();
+
+        let mut paragraphs = LoadBlock::load_from_str(nmd_text, &codex, &LoadConfiguration::default(), LoadConfigurationOverLay::default()).unwrap();
+
+        let LoadBlockContent::Paragraph(p) = paragraphs[0].content_mut() else {
+            panic!("table expected")
+        };
+
+        let mut cco = CompilationConfigurationOverLay::default();
+        cco.set_document_name(Some(String::from("d1.nmd")));
+
+        let outcome = p.compile(&OutputFormat::Html, &codex, &CompilationConfiguration::default(), cco).unwrap();
+
+        // padding spaces used to be read as indentation, so cells were compiled as list items
+        assert!(!outcome.content().contains("<li"));
+        assert!(outcome.content().contains(r#"<p class="paragraph">1</p>"#));
+        assert!(outcome.content().contains(r#"<p class="paragraph">a</p>"#));
+    }
 
+    #[test]
+    fn multi_paragraph_cell() {
+        let nmd_text = "| A | B |\n|---|---|\n| para one | b | \\\n|  | | \\\n| para two | |\n";
+
+        let codex = Codex::of_html();
+
+        let mut paragraphs = LoadBlock::load_from_str(nmd_text, &codex, &LoadConfiguration::default(), LoadConfigurationOverLay::default()).unwrap();
+
+        let LoadBlockContent::Paragraph(p) = paragraphs[0].content_mut() else {
+            panic!("table expected")
+        };
+
+        let mut cco = CompilationConfigurationOverLay::default();
+        cco.set_document_name(Some(String::from("d1.nmd")));
+
+        let outcome = p.compile(&OutputFormat::Html, &codex, &CompilationConfiguration::default(), cco).unwrap();
+
+        assert!(outcome.content().contains(r#"<p class="paragraph">para one</p><p class="paragraph">para two</p>"#), "{}", outcome.content());
+        assert!(outcome.content().contains(r#"<p class="paragraph">b</p>"#));
+    }
+
+    #[test]
+    fn row_span_across_delimiters() {
+
+        let codex = Codex::of_html();
+
+        let contents = [
+            "| A | B |\n|---|---|\n| ^ | b |\n",
+            "| A | B |\n|---|---|\n| a | b |\n|---|---|\n| ^ | f |\n",
+        ];
+
+        for content in contents {
+
+            let error = LoadBlock::load_from_str(content, &codex, &LoadConfiguration::default(), LoadConfigurationOverLay::default()).unwrap_err();
+
+            assert!(error.to_string().contains("crosses a table delimiter"), "{}", content);
+        }
+
+        // row spans inside body are allowed
+        assert!(LoadBlock::load_from_str("| A | B |\n|---|---|\n| a | b |\n| ^ | c |\n", &codex, &LoadConfiguration::default(), LoadConfigurationOverLay::default()).is_ok());
+    }
+}
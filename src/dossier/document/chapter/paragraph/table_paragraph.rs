@@ -54,9 +54,9 @@ impl TableParagraph {
         }
     }
 
-    fn load_html_row(html_row: &mut HtmlTableRow, cells: &Vec<TableCell<String>>, _codex: &Codex, _compilation_configuration: &CompilationConfiguration) -> Result<(), CompilationError> {
+    fn load_html_row(html_row: &mut HtmlTableRow, cells: &[TableCell<String>], column_widths: Option<&[f64]>, _codex: &Codex, _compilation_configuration: &CompilationConfiguration) -> Result<(), CompilationError> {
 
-        for cell in cells {
+        for (column, cell) in cells.iter().enumerate() {
             match cell {
                 TableCell::None => {
 
@@ -69,7 +69,7 @@ impl TableParagraph {
                     );                       
 
                 },
-                TableCell::ContentCell { content, alignment } => {
+                TableCell::ContentCell { content, alignment, column_span, row_span } => {
 
                     let align_class = match alignment {
                         TableCellAlignment::Left => String::from("table-left-cell"),
@@ -77,14 +77,34 @@ impl TableParagraph {
                         TableCellAlignment::Right => String::from("table-right-cell"),
                     };
 
+                    let mut attributes: Vec<(String, String)> = vec![
+                        (String::from("class"), format!("table-cell {}", align_class))
+                    ];
+
+                    if *column_span > 1 {
+                        attributes.push((String::from("colspan"), column_span.to_string()));
+                    }
+
+                    if *row_span > 1 {
+                        attributes.push((String::from("rowspan"), row_span.to_string()));
+                    }
+
+                    if let Some(column_widths) = column_widths {
+
+                        let width: f64 = column_widths.iter().skip(column).take(*column_span).sum();
+
+                        if width > 0.0 {
+                            attributes.push((String::from("style"), format!("width: {:.2}%", width)));
+                        }
+                    }
+
                     html_row.add_cell(
                         HtmlTableCell::new(build_html::TableCellType::Data)
-                                    .with_attributes(vec![
-                                        ("class", format!("table-cell {}", align_class).as_str())
-                                    ])
+                                    .with_attributes(attributes)
                                     .with_raw(content)
                     );       
                 },
+                TableCell::Spanned => (),       // already rendered by spanning cell
             }
         }
 
@@ -117,13 +137,16 @@ impl TableParagraph {
 
         let mut html_table = build_html::Table::new().with_attributes(html_table_attrs);
 
+        let column_widths = self.content.column_width_percentages();
+
         let compile_cells_fn = |cells: &mut Vec<TableCell<ContentBundle>>| -> Result<Vec<TableCell<String>>, CompilationError> {
             let mut result: Vec<TableCell<String>> = Vec::new();
 
             for cell in cells.iter_mut() {
                 match cell {
                     TableCell::None => result.push(TableCell::None),
-                    TableCell::ContentCell { content, alignment } => {
+                    TableCell::Spanned => result.push(TableCell::Spanned),
+                    TableCell::ContentCell { content, alignment, column_span, row_span } => {
 
                         let compiled_content = String::from(content.compile(
                             &OutputFormat::Html,
@@ -132,7 +155,7 @@ impl TableParagraph {
                             compilation_configuration_overlay.clone()
                         )?.content());
 
-                        let cell = TableCell::ContentCell { content: compiled_content, alignment: alignment.clone() };
+                        let cell = TableCell::ContentCell { content: compiled_content, alignment: alignment.clone(), column_span: *column_span, row_span: *row_span };
 
                         result.push(cell);
                     },
@@ -156,7 +179,7 @@ impl TableParagraph {
 
             let new_header_cells = compile_cells_fn(header_cells)?;
             
-            Self::load_html_row(&mut html_table_header, &new_header_cells, codex, &compilation_configuration).unwrap();
+            Self::load_html_row(&mut html_table_header, &new_header_cells, column_widths.as_deref(), codex, &compilation_configuration).unwrap();
 
             html_table.add_custom_header_row(html_table_header);
         }
@@ -175,7 +198,7 @@ impl TableParagraph {
 
             let compiled_row = compile_cells_fn(row)?;
 
-            Self::load_html_row(&mut html_body_row, &compiled_row, codex, &compilation_configuration).unwrap();
+            Self::load_html_row(&mut html_body_row, &compiled_row, column_widths.as_deref(), codex, &compilation_configuration).unwrap();
 
             html_table.add_custom_body_row(html_body_row);
         }
@@ -194,7 +217,7 @@ impl TableParagraph {
 
             let new_footer_cells = compile_cells_fn(footer_cells)?;
 
-            Self::load_html_row(&mut html_table_footer, &new_footer_cells, codex, &compilation_configuration).unwrap();
+            Self::load_html_row(&mut html_table_footer, &new_footer_cells, column_widths.as_deref(), codex, &compilation_configuration).unwrap();
 
             html_table.add_custom_footer_row(html_table_footer);
         }
@@ -226,7 +249,7 @@ impl Compilable for TableParagraph {
     fn standard_compile(&mut self, format: &OutputFormat, codex: &Codex, compilation_configuration: &CompilationConfiguration, compilation_configuration_overlay: CompilationConfigurationOverLay) -> Result<CompilationOutcome, CompilationError> {
         
         match format {
-            OutputFormat::Html => self.html_standard_compile(codex, compilation_configuration, compilation_configuration_overlay.clone()),
+            OutputFormat::Html | OutputFormat::Epub | OutputFormat::Docx | OutputFormat::Text | OutputFormat::Man => self.html_standard_compile(codex, compilation_configuration, compilation_configuration_overlay.clone()),
         }
     }
 }
@@ -318,4 +341,34 @@ mod test {
 
         assert!(outcome.contains("<img"))
     }
+
+    #[test]
+    fn spans_multi_line_cells_and_widths() {
+
+        let nmd_text = concat!(
+            "|h1|h2|h3|\n",
+            "|--- (1)|--- (1)|--- (2)|\n",
+            "|a|<|b|\n",
+            "|c|d|^|\n",
+            "|- item 1|e|f|\\\n",
+            "|- item 2|||\n",
+        );
+
+        let compilation_configuration = CompilationConfiguration::default();
+        let mut compilation_configuration_overlay = CompilationConfigurationOverLay::default();
+        let codex = Codex::of_html();
+
+        compilation_configuration_overlay.set_document_name(Some("test".to_string()));
+
+        let mut paragraph = load_table(nmd_text, &codex);
+        
+        let outcome = paragraph.compile(&OutputFormat::Html, &codex, &compilation_configuration, compilation_configuration_overlay).unwrap();
+        
+        let outcome = outcome.content();
+
+        assert!(outcome.contains(r#"colspan="2" style="width: 50.00%""#));
+        assert!(outcome.contains(r#"rowspan="2" style="width: 50.00%""#));
+        assert_eq!(outcome.matches("<li").count(), 2);
+        assert!(!outcome.contains("<tfoot"));
+    }
 }
\ No newline at end of file
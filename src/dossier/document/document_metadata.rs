@@ -0,0 +1,251 @@
+use getset::{Getters, Setters};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Deserializer, Serialize};
+use crate::{compilation::compilation_configuration::CompilationConfiguration, load::LoadError};
+
+
+static FRONT_MATTER_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\A---[ \t]*\r?\n((?s:.*?))\r?\n---[ \t]*(?:\r?\n|\z)").unwrap());
+
+
+/// Accept both a single string and a list of strings
+pub(crate) fn one_or_many<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(String),
+        Many(Vec<String>),
+    }
+
+    Ok(match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(value) => vec![value],
+        OneOrMany::Many(values) => values,
+    })
+}
+
+/// Schema of values deserialized by `one_or_many`, i.e. a string or a list of strings
+pub(crate) fn one_or_many_schema(generator: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+
+    #[allow(dead_code)]
+    #[derive(JsonSchema)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(String),
+        Many(Vec<String>),
+    }
+
+    OneOrMany::json_schema(generator)
+}
+
+
+/// Per-document overrides of `CompilationConfiguration`
+#[derive(Debug, Clone, Default, Getters, Setters, Serialize, Deserialize)]
+pub struct DocumentMetadataCompilation {
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get = "pub", set = "pub")]
+    embed_local_image: Option<bool>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get = "pub", set = "pub")]
+    embed_remote_image: Option<bool>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get = "pub", set = "pub")]
+    compress_embed_image: Option<bool>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get = "pub", set = "pub")]
+    strict_image_src_check: Option<bool>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get = "pub", set = "pub")]
+    strict_list_check: Option<bool>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get = "pub", set = "pub")]
+    strict_focus_block_check: Option<bool>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get = "pub", set = "pub")]
+    strict_greek_letters_check: Option<bool>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get = "pub", set = "pub")]
+    strict_cite_check: Option<bool>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get = "pub", set = "pub")]
+    strict_glossary_check: Option<bool>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get = "pub", set = "pub")]
+    strict_reference_check: Option<bool>,
+}
+
+impl DocumentMetadataCompilation {
+
+    /// Apply overrides to a compilation configuration
+    pub fn apply(&self, compilation_configuration: &mut CompilationConfiguration) {
+
+        if let Some(v) = self.embed_local_image {
+            compilation_configuration.set_embed_local_image(v);
+        }
+
+        if let Some(v) = self.embed_remote_image {
+            compilation_configuration.set_embed_remote_image(v);
+        }
+
+        if let Some(v) = self.compress_embed_image {
+            compilation_configuration.set_compress_embed_image(v);
+        }
+
+        if let Some(v) = self.strict_image_src_check {
+            compilation_configuration.set_strict_image_src_check(v);
+        }
+
+        if let Some(v) = self.strict_list_check {
+            compilation_configuration.set_strict_list_check(v);
+        }
+
+        if let Some(v) = self.strict_focus_block_check {
+            compilation_configuration.set_strict_focus_block_check(v);
+        }
+
+        if let Some(v) = self.strict_greek_letters_check {
+            compilation_configuration.set_strict_greek_letters_check(v);
+        }
+
+        if let Some(v) = self.strict_cite_check {
+            compilation_configuration.set_strict_cite_check(v);
+        }
+
+        if let Some(v) = self.strict_glossary_check {
+            compilation_configuration.set_strict_glossary_check(v);
+        }
+
+        if let Some(v) = self.strict_reference_check {
+            compilation_configuration.set_strict_reference_check(v);
+        }
+    }
+}
+
+
+/// Document metadata, loaded from YAML front matter at the top of a document:
+///
+/// ```yaml
+/// ---
+/// title: My document
+/// authors:
+///   - Alice
+/// date: 2024-05-01
+/// tags: [draft]
+/// language: en
+/// compilation:
+///   strict_reference_check: false
+/// ---
+/// ```
+#[derive(Debug, Clone, Default, Getters, Setters, Serialize, Deserialize)]
+pub struct DocumentMetadata {
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get = "pub", set = "pub")]
+    title: Option<String>,
+
+    #[serde(default, alias = "author", deserialize_with = "one_or_many", skip_serializing_if = "Vec::is_empty")]
+    #[getset(get = "pub", set = "pub")]
+    authors: Vec<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get = "pub", set = "pub")]
+    date: Option<String>,
+
+    #[serde(default, deserialize_with = "one_or_many", skip_serializing_if = "Vec::is_empty")]
+    #[getset(get = "pub", set = "pub")]
+    tags: Vec<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get = "pub", set = "pub")]
+    language: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get = "pub", set = "pub")]
+    compilation: Option<DocumentMetadataCompilation>,
+}
+
+impl DocumentMetadata {
+
+    /// `true` if title, authors or date are present, so a title block can be built
+    pub fn has_title_block(&self) -> bool {
+        self.title.is_some() || !self.authors.is_empty() || self.date.is_some()
+    }
+
+    /// Load front matter from document content. It returns metadata (if there is a front matter) and
+    /// the position in which document body starts.
+    ///
+    /// Front matter must start at the first line and it must be a YAML mapping, otherwise it is considered a normal content
+    /// (e.g. a line break)
+    pub fn load_front_matter(content: &str) -> Result<(Option<Self>, usize), LoadError> {
+
+        if let Some(captures) = FRONT_MATTER_REGEX.captures(content) {
+
+            let raw_front_matter = captures.get(1).unwrap().as_str();
+
+            let is_mapping = matches!(serde_yaml::from_str::<serde_yaml::Value>(raw_front_matter), Ok(serde_yaml::Value::Mapping(_)));
+
+            if is_mapping {
+
+                let metadata: Self = serde_yaml::from_str(raw_front_matter).map_err(|err| LoadError::ElaborationError(format!("invalid front matter: {}", err)))?;
+
+                return Ok((Some(metadata), captures.get(0).unwrap().end()))
+            }
+        }
+
+        Ok((None, 0))
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::DocumentMetadata;
+
+
+    #[test]
+    fn load_front_matter() {
+
+        let content = concat!(
+            "---\n",
+            "title: Title\n",
+            "author: Alice\n",
+            "tags: [a, b]\n",
+            "compilation:\n",
+            "  strict_reference_check: false\n",
+            "---\n",
+            "body\n",
+        );
+
+        let (metadata, offset) = DocumentMetadata::load_front_matter(content).unwrap();
+
+        let metadata = metadata.unwrap();
+
+        assert_eq!(metadata.title().as_ref().unwrap(), "Title");
+        assert_eq!(metadata.authors(), &vec![String::from("Alice")]);
+        assert_eq!(metadata.tags().len(), 2);
+        assert_eq!(metadata.compilation().as_ref().unwrap().strict_reference_check(), &Some(false));
+        assert_eq!(&content[offset..], "body\n");
+    }
+
+    #[test]
+    fn line_break_is_not_front_matter() {
+
+        let content = "---\n\nparagraph\n\n---\n";
+
+        let (metadata, offset) = DocumentMetadata::load_front_matter(content).unwrap();
+
+        assert!(metadata.is_none());
+        assert_eq!(offset, 0);
+    }
+}
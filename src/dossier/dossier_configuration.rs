@@ -4,19 +4,40 @@ pub mod dossier_configuration_path_reference;
 pub mod dossier_configuration_path_reference_manager;
 pub mod dossier_configuration_table_of_contents;
 pub mod dossier_configuration_bibliography;
+pub mod dossier_configuration_glossary;
+pub mod dossier_configuration_index;
+pub mod dossier_configuration_generated_list;
+pub mod dossier_configuration_modifier;
+pub mod dossier_configuration_variable;
+pub mod dossier_configuration_profile;
+pub mod dossier_configuration_extension;
+pub mod dossier_configuration_documents;
+pub mod dossier_configuration_validation;
+pub mod dossier_configuration_metadata;
+pub mod dossier_configuration_lint;
 
 use std::collections::HashMap;
 use std::io;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use dossier_configuration_bibliography::DossierConfigurationBibliography;
+use dossier_configuration_glossary::DossierConfigurationGlossary;
+use dossier_configuration_index::DossierConfigurationIndex;
+use dossier_configuration_generated_list::DossierConfigurationGeneratedList;
+use dossier_configuration_documents::{DocumentsOrder, DossierConfigurationDocument, EXCLUSION_PREFIX};
+use dossier_configuration_lint::DossierConfigurationLint;
+use dossier_configuration_metadata::DossierConfigurationMetadata;
+use dossier_configuration_modifier::DossierConfigurationModifier;
+use dossier_configuration_profile::DossierConfigurationProfile;
+use dossier_configuration_variable::DossierConfigurationVariable;
 use dossier_configuration_table_of_contents::DossierConfigurationTableOfContents;
 use getset::{Getters, Setters};
-use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
 use log;
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 
-use crate::constants::{DOSSIER_CONFIGURATION_JSON_FILE_NAME, DOSSIER_CONFIGURATION_YAML_FILE_NAME, NMD_EXTENSION};
+use crate::constants::{DOSSIER_CONFIGURATION_FILE_NAMES, NMD_EXTENSION};
 use crate::resource::text_reference::TextReferenceMap;
 use crate::resource::Resource;
 use crate::resource::{disk_resource::DiskResource, ResourceError};
@@ -28,18 +49,28 @@ use self::{dossier_configuration_compilation::DossierConfigurationCompilation, d
 
 
 
-#[derive(Debug, Clone, Deserialize, Serialize, Getters, Setters)]
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, Getters, Setters)]
 pub struct DossierConfiguration {
     #[serde(default = "default_name")]
     #[getset(get = "pub", set = "pub")]
     name: String,
 
+    /// Publication metadata (authors, language, ...)
+    #[serde(default)]
+    #[getset(get = "pub", set = "pub")]
+    metadata: DossierConfigurationMetadata,
+
     #[serde(rename(serialize = "toc", deserialize = "toc"), default = "default_toc")]
     table_of_contents_configuration: DossierConfigurationTableOfContents,
 
     #[serde(rename = "documents")]
     raw_documents_paths: Vec<DossierConfigurationRawPathReference>,
 
+    /// Order of documents found using glob patterns or directories
+    #[serde(default)]
+    #[getset(get = "pub", set = "pub")]
+    documents_order: DocumentsOrder,
+
     #[serde(default = "default_style")]
     style: DossierConfigurationStyle,
 
@@ -49,8 +80,58 @@ pub struct DossierConfiguration {
     #[serde(default = "default_bibliography")]
     bibliography: DossierConfigurationBibliography,
 
+    /// Glossary entries, referenced in documents using `?[key]`
+    #[serde(default)]
+    #[getset(get = "pub", set = "pub")]
+    glossary: DossierConfigurationGlossary,
+
+    /// Back-of-book index of terms marked using `@index[term]`
+    #[serde(default)]
+    #[getset(get = "pub", set = "pub")]
+    index: DossierConfigurationIndex,
+
+    /// List of captioned images
+    #[serde(default)]
+    #[getset(get = "pub", set = "pub")]
+    list_of_figures: DossierConfigurationGeneratedList,
+
+    /// List of captioned tables
+    #[serde(default)]
+    #[getset(get = "pub", set = "pub")]
+    list_of_tables: DossierConfigurationGeneratedList,
+
+    /// List of todos and bookmarks
+    #[serde(default)]
+    #[getset(get = "pub", set = "pub")]
+    list_of_todos: DossierConfigurationGeneratedList,
+
     #[serde(default = "default_compilation")]
     compilation: DossierConfigurationCompilation,
+
+    /// User-defined text and paragraph modifiers
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[getset(get = "pub", set = "pub")]
+    modifiers: Vec<DossierConfigurationModifier>,
+
+    /// Typed variables, usable in documents like references (e.g. `&audience&`)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    #[getset(get = "pub", set = "pub")]
+    variables: HashMap<String, DossierConfigurationVariable>,
+
+    /// Build tags used by conditional content
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[getset(get = "pub", set = "pub")]
+    tags: Vec<String>,
+
+    /// Named build profiles (e.g. `draft`, `release`)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    #[getset(get = "pub", set = "pub")]
+    profiles: HashMap<String, DossierConfigurationProfile>,
+
+    /// Severity of lint rules (see `nmd lint`)
+    #[serde(default)]
+    #[getset(get = "pub", set = "pub")]
+    lint: DossierConfigurationLint,
 }
 
 fn default_name() -> String {
@@ -87,12 +168,24 @@ impl DossierConfiguration {
         
         Self {
             name,
+            metadata: DossierConfigurationMetadata::default(),
             table_of_contents_configuration: toc,
             raw_documents_paths,
+            documents_order: DocumentsOrder::default(),
             style,
             references,
             compilation,
-            bibliography
+            bibliography,
+            glossary: DossierConfigurationGlossary::default(),
+            index: DossierConfigurationIndex::default(),
+            list_of_figures: DossierConfigurationGeneratedList::default(),
+            list_of_tables: DossierConfigurationGeneratedList::default(),
+            list_of_todos: DossierConfigurationGeneratedList::default(),
+            modifiers: Vec::new(),
+            variables: HashMap::new(),
+            tags: Vec::new(),
+            profiles: HashMap::new(),
+            lint: DossierConfigurationLint::default(),
         }
     }
 
@@ -100,6 +193,7 @@ impl DossierConfiguration {
         &self.raw_documents_paths
     }
 
+    /// Documents paths as written in configuration, only the dossier root is applied (see `resolved_documents_paths`)
     pub fn documents_paths(&self) -> Vec<DossierConfigurationPathReference> {
 
         let dcrfm = DOSSIER_CONFIGURATION_RAW_REFERENCE_MANAGER.lock().unwrap();
@@ -118,6 +212,21 @@ impl DossierConfiguration {
         }
     }
 
+    /// Flat list of documents paths (see `resolve_documents`)
+    pub fn resolved_documents_paths(&self) -> Result<Vec<DossierConfigurationPathReference>, ResourceError> {
+
+        Ok(self.resolve_documents()?.iter()
+                .flat_map(|document| document.paths())
+                .map(|path| path.to_string_lossy().to_string())
+                .collect())
+    }
+
+    /// Resolve documents expanding glob patterns and directories, applying exclusions (`!` prefix)
+    /// and loading sub-dossiers
+    pub fn resolve_documents(&self) -> Result<Vec<DossierConfigurationDocument>, ResourceError> {
+        dossier_configuration_documents::resolve_documents(&self.raw_documents_paths, &self.documents_order)
+    }
+
     pub fn set_raw_documents_paths(&mut self, documents: Vec<String>) -> () {
         self.raw_documents_paths = documents
     }
@@ -146,6 +255,10 @@ impl DossierConfiguration {
         &self.table_of_contents_configuration
     }
 
+    pub fn root_path(&self) -> Option<PathBuf> {
+        DOSSIER_CONFIGURATION_RAW_REFERENCE_MANAGER.lock().unwrap().root_path().cloned()
+    }
+
     pub fn set_root_path(&mut self, root_path: PathBuf) {
         DOSSIER_CONFIGURATION_RAW_REFERENCE_MANAGER.lock().unwrap().set_root_path(root_path);
     }
@@ -163,8 +276,40 @@ impl DossierConfiguration {
         Ok(())
     }
 
+    /// Apply a named profile: its tags are added, documents and compilation flags are overridden
+    pub fn apply_profile(&mut self, profile_name: &str) -> Result<(), ResourceError> {
+
+        let profile = self.profiles.get(profile_name).cloned()
+                            .ok_or(ResourceError::ResourceNotFound(format!("profile '{}'", profile_name)))?;
+
+        for tag in profile.tags() {
+            if !self.tags.contains(tag) {
+                self.tags.push(tag.clone());
+            }
+        }
+
+        if let Some(documents) = profile.documents() {
+            self.raw_documents_paths = documents.clone();
+        }
+
+        // exclusions are added as patterns, so documents found through globs or directories are excluded too
+        for excluded in profile.exclude_documents() {
+            self.raw_documents_paths.push(format!("{}{}", EXCLUSION_PREFIX, excluded.trim().trim_start_matches(EXCLUSION_PREFIX)));
+        }
+
+        for (key, value) in profile.variables() {
+            self.variables.entry(key.clone()).or_default().set_default(Some(value.clone()));
+        }
+
+        profile.compilation().apply(&mut self.compilation);
+
+        Ok(())
+    }
+
     pub fn with_files_in_dir(mut self, dir_path: &PathBuf) -> Result<Self, io::Error> {
-        let files = file_utility::all_files_in_dir(dir_path, &vec![NMD_EXTENSION.to_string()])?;
+        let mut files = file_utility::all_files_in_dir(dir_path, &vec![NMD_EXTENSION.to_string()])?;
+
+        self.documents_order.sort(&mut files);
 
         self.raw_documents_paths = files.iter().map(|f| f.to_string_lossy().to_string()).collect();
 
@@ -176,41 +321,66 @@ impl Default for DossierConfiguration {
     fn default() -> Self {
         Self {
             name: String::from("New Dossier"),
+            metadata: DossierConfigurationMetadata::default(),
             raw_documents_paths: vec![],
+            documents_order: DocumentsOrder::default(),
             style: DossierConfigurationStyle::default(),
             references: HashMap::new(),
             compilation: DossierConfigurationCompilation::default(),
             table_of_contents_configuration: DossierConfigurationTableOfContents::default(),
-            bibliography: DossierConfigurationBibliography::default()
+            bibliography: DossierConfigurationBibliography::default(),
+            glossary: DossierConfigurationGlossary::default(),
+            index: DossierConfigurationIndex::default(),
+            list_of_figures: DossierConfigurationGeneratedList::default(),
+            list_of_tables: DossierConfigurationGeneratedList::default(),
+            list_of_todos: DossierConfigurationGeneratedList::default(),
+            modifiers: Vec::new(),
+            variables: HashMap::new(),
+            tags: Vec::new(),
+            profiles: HashMap::new(),
+            lint: DossierConfigurationLint::default(),
         }
     }
 }
 
 
 impl DossierConfiguration {
-    fn try_from_as_yaml(content: String) -> Result<Self, ResourceError> {
 
-        log::info!("try to load dossier configuration from yaml content...");
-        
-        match serde_yaml::from_str(&content) {
-            Ok(config) => {
-                log::info!("dossier configuration loaded from yaml");
-                return Ok(config)
-            },
-            Err(e) => return Err(ResourceError::InvalidResourceVerbose(e.to_string()))
-        }
+    /// JSON Schema of dossier configuration, it can be used by editors to provide autocompletion
+    pub fn json_schema() -> serde_json::Value {
+
+        let mut schema = serde_json::to_value(schemars::schema_for!(DossierConfiguration)).unwrap();
+
+        // `extend` is resolved before deserialization
+        schema["properties"][dossier_configuration_extension::EXTEND_KEY] = serde_json::json!({
+            "description": "Base configuration file (or list of files) to inherit, relative to this file",
+            "anyOf": [
+                { "type": "string" },
+                { "type": "array", "items": { "type": "string" } }
+            ]
+        });
+
+        schema
     }
 
-    fn try_from_as_json(content: String) -> Result<Self, ResourceError> {
+    /// Load dossier configuration file (YAML, JSON or TOML), resolving its `extend` chain
+    fn try_from_file(path_buf: &Path) -> Result<Self, ResourceError> {
+
+        log::info!("try to load dossier configuration from {}...", path_buf.to_string_lossy());
+
+        let value = dossier_configuration_extension::load_extended_configuration(path_buf)?;
 
-        log::info!("try to load dossier configuration from json content...");
+        match serde_json::from_value::<Self>(value) {
+            Ok(mut config) => {
 
-        match serde_json::from_str(&content) {
-            Ok(config) => {
-                log::info!("dossier configuration loaded from json");
-                return Ok(config)
+                if let Some(base_dir) = path_buf.parent() {
+                    config.glossary.load_file(base_dir)?;
+                }
+
+                log::info!("dossier configuration loaded from {}", path_buf.to_string_lossy());
+                Ok(config)
             },
-            Err(e) => return Err(ResourceError::InvalidResourceVerbose(e.to_string()))
+            Err(e) => Err(ResourceError::InvalidResourceVerbose(format!("invalid dossier configuration {}: {}", path_buf.to_string_lossy(), e)))
         }
     }
 
@@ -227,26 +397,13 @@ impl TryFrom<&PathBuf> for DossierConfiguration {
         if path_buf.is_file() {
             if let Some(file_name) = path_buf.file_name() {
 
-                let file_content = file_utility::read_file_content(path_buf)?;
+                if DOSSIER_CONFIGURATION_FILE_NAMES.contains(&file_name.to_string_lossy().as_ref()) {
 
-                if file_name.to_string_lossy().eq(DOSSIER_CONFIGURATION_YAML_FILE_NAME) {
+                    log::info!("{} found", file_name.to_string_lossy());
 
-                    log::info!("{} found", DOSSIER_CONFIGURATION_YAML_FILE_NAME);
+                    let mut config = Self::try_from_file(path_buf)?;
 
-                    let mut config = Self::try_from_as_yaml(file_content)?;
-
-                    config.set_root_path(path_buf.clone());
-
-                    return Ok(config)
-                }
-
-                if file_name.to_string_lossy().eq(DOSSIER_CONFIGURATION_JSON_FILE_NAME) {
-
-                    log::info!("{} found", DOSSIER_CONFIGURATION_JSON_FILE_NAME);
-
-                    let mut config = Self::try_from_as_json(file_content)?;
-
-                    config.set_root_path(path_buf.clone());
+                    config.set_root_path(path_buf.parent().map(|parent| parent.to_path_buf()).unwrap_or_default());
 
                     return Ok(config)
                 }
@@ -255,30 +412,18 @@ impl TryFrom<&PathBuf> for DossierConfiguration {
 
         if path_buf.is_dir() {
 
-            let yaml_path_buf = path_buf.join(DOSSIER_CONFIGURATION_YAML_FILE_NAME);
-
-            if yaml_path_buf.exists() {
-
-                let file_content = file_utility::read_file_content(&yaml_path_buf)?;
-
-                let mut config = Self::try_from_as_yaml(file_content)?;
-
-                config.set_root_path(path_buf.clone());
+            for file_name in DOSSIER_CONFIGURATION_FILE_NAMES {
 
-                return Ok(config)
-            }
+                let config_path_buf = path_buf.join(file_name);
 
-            let json_path_buf = path_buf.join(DOSSIER_CONFIGURATION_JSON_FILE_NAME);
+                if config_path_buf.exists() {
 
-            if json_path_buf.exists() {
-                
-                let file_content = file_utility::read_file_content(&json_path_buf)?;
-                
-                let mut config = Self::try_from_as_json(file_content)?;
+                    let mut config = Self::try_from_file(&config_path_buf)?;
 
-                config.set_root_path(path_buf.clone());
+                    config.set_root_path(path_buf.clone());
 
-                return Ok(config)
+                    return Ok(config)
+                }
             }
         }
 
@@ -290,6 +435,7 @@ impl TryFrom<&PathBuf> for DossierConfiguration {
 #[cfg(test)]
 mod test {
     use std::path::PathBuf;
+    use crate::utility::test_utility::TempDir;
     use super::*;
 
 
@@ -303,4 +449,145 @@ mod test {
 
         assert_eq!(configuration.documents_paths()[0], nmd_dossier_path.join("d1.nmd").to_string_lossy().to_string())
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn apply_profile() {
+
+        let yaml = concat!(
+            "documents: [intro.nmd, internal.nmd, guide.nmd]\n",
+            "tags: [draft]\n",
+            "profiles:\n",
+            "  customer:\n",
+            "    tags: [customer]\n",
+            "    exclude_documents: [internal.nmd]\n",
+            "    variables:\n",
+            "      audience: customers\n",
+            "    compilation:\n",
+            "      strict_reference_check: false\n",
+        );
+
+        let mut configuration: DossierConfiguration = serde_yaml::from_str(yaml).unwrap();
+
+        configuration.apply_profile("customer").unwrap();
+
+        assert_eq!(configuration.tags(), &vec![String::from("draft"), String::from("customer")]);
+        assert_eq!(configuration.raw_documents_paths(), &vec![String::from("intro.nmd"), String::from("internal.nmd"), String::from("guide.nmd"), String::from("!internal.nmd")]);
+        assert_eq!(configuration.variables().get("audience").unwrap().default().as_ref().unwrap().to_string(), "customers");
+        assert!(!configuration.compilation().strict_reference_check());
+        assert!(configuration.apply_profile("missing").is_err());
+    }
+
+    #[test]
+    fn apply_profile_exclusions_to_globs() {
+
+        let dossier_dir = TempDir::new("profile-exclusions");
+
+        let chapters_dir = dossier_dir.join("chapters");
+
+        std::fs::create_dir_all(&chapters_dir).unwrap();
+        std::fs::write(chapters_dir.join("intro.nmd"), "# Intro\n").unwrap();
+        std::fs::write(chapters_dir.join("internal.nmd"), "# Internal\n").unwrap();
+        std::fs::write(dossier_dir.join("nmd.yml"), concat!(
+            "name: profile exclusions\n",
+            "documents: [./chapters/*.nmd]\n",
+            "profiles:\n",
+            "  customer:\n",
+            "    exclude_documents: [./chapters/internal.nmd]\n",
+        )).unwrap();
+
+        let mut configuration = DossierConfiguration::try_from(dossier_dir.path()).unwrap();
+
+        configuration.apply_profile("customer").unwrap();
+
+        let documents = configuration.resolve_documents();
+
+        assert_eq!(documents.unwrap(), vec![DossierConfigurationDocument::Document(chapters_dir.join("intro.nmd"))]);
+    }
+
+    #[test]
+    fn extend_base_configuration() {
+
+        let dossier_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("test-resources").join("nmd-test-extend").join("dossier");
+
+        let configuration = DossierConfiguration::try_from(&dossier_dir).unwrap();
+
+        assert_eq!(configuration.name(), "extended");
+        assert_eq!(configuration.references().get("company").unwrap(), "ACME");
+        assert_eq!(configuration.raw_documents_paths().len(), 1);
+    }
+
+    #[test]
+    fn resolve_documents() {
+
+        let dossier_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("test-resources").join("nmd-test-documents");
+
+        let configuration = DossierConfiguration::try_from(&dossier_dir).unwrap();
+
+        let documents = configuration.resolve_documents().unwrap();
+
+        let chapters_dir = dossier_dir.join("chapters");
+
+        assert_eq!(documents, vec![
+            DossierConfigurationDocument::Document(chapters_dir.join("1-intro.nmd")),
+            DossierConfigurationDocument::Document(chapters_dir.join("2-body.nmd")),
+            DossierConfigurationDocument::Document(chapters_dir.join("10-end.nmd")),
+            DossierConfigurationDocument::SubDossier {
+                name: String::from("Appendix"),
+                documents: vec![dossier_dir.join("appendix").join("a.nmd")],
+            },
+        ]);
+    }
+
+    #[test]
+    fn resolve_documents_from_current_directory() {
+
+        // like default `.` input, tests run in crate directory
+        let dossier_dir = PathBuf::from(".").join("test-resources").join("nmd-test-documents");
+
+        let configuration = DossierConfiguration::try_from(&dossier_dir).unwrap();
+
+        let documents = configuration.resolve_documents().unwrap();
+
+        let names: Vec<String> = documents.iter().filter_map(|document| match document {
+            DossierConfigurationDocument::Document(path) => Some(path.file_name().unwrap().to_string_lossy().to_string()),
+            _ => None,
+        }).collect();
+
+        assert_eq!(names, vec!["1-intro.nmd", "2-body.nmd", "10-end.nmd"]);
+    }
+
+    #[test]
+    fn resolve_sub_dossier_documents() {
+
+        let dossier_dir = TempDir::new("sub-dossier-documents");
+
+        let sub_dir = dossier_dir.join("sub");
+
+        std::fs::create_dir_all(&sub_dir).unwrap();
+
+        for file_name in ["a.nmd", "b.nmd", "draft.nmd"] {
+            std::fs::write(sub_dir.join(file_name), "# Title\n").unwrap();
+        }
+
+        std::fs::write(sub_dir.join("nmd.yml"), "name: Sub\ndocuments:\n  - ./*.nmd\n").unwrap();
+        std::fs::write(dossier_dir.join("nmd.yml"), "name: main\ndocuments:\n  - ./sub/b.nmd\n  - ./sub\n  - '!./sub/draft.nmd'\n").unwrap();
+
+        let documents = DossierConfiguration::try_from(dossier_dir.path()).unwrap().resolve_documents();
+
+        // sub-dossier which includes its parent dossier
+        std::fs::write(sub_dir.join("nmd.yml"), "name: Sub\ndocuments:\n  - ./..\n").unwrap();
+
+        let cycle = DossierConfiguration::try_from(dossier_dir.path()).unwrap().resolve_documents();
+
+        // excluded and already listed documents are skipped
+        assert_eq!(documents.unwrap(), vec![
+            DossierConfigurationDocument::Document(sub_dir.join("b.nmd")),
+            DossierConfigurationDocument::SubDossier {
+                name: String::from("Sub"),
+                documents: vec![sub_dir.join("a.nmd")],
+            },
+        ]);
+
+        assert!(cycle.is_err());
+    }
+}
@@ -2,10 +2,11 @@ use std::collections::BTreeMap;
 
 use getset::{CopyGetters, Getters, Setters};
 use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
 
 use crate::bibliography::bibliography_record::BibliographyRecord;
 
-#[derive(Debug, Clone, Getters, CopyGetters, Setters, Deserialize, Serialize)]
+#[derive(Debug, Clone, Getters, CopyGetters, Setters, Deserialize, Serialize, JsonSchema)]
 pub struct DossierConfigurationBibliography {
 
     #[getset(get = "pub", set = "pub")]
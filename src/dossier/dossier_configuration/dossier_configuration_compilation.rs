@@ -1,5 +1,6 @@
 use getset::{CopyGetters, Setters};
 use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
 
 
 fn yes() -> bool {
@@ -11,7 +12,7 @@ fn no() -> bool {
 }
 
 
-#[derive(Debug, Clone, Deserialize, Serialize, CopyGetters, Setters)]
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, CopyGetters, Setters)]
 pub struct DossierConfigurationCompilation {
 
     #[serde(default = "yes")]
@@ -50,6 +51,10 @@ pub struct DossierConfigurationCompilation {
     #[getset(get_copy = "pub", set = "pub")]
     strict_cite_check: bool,
 
+    #[serde(default = "yes")]
+    #[getset(get_copy = "pub", set = "pub")]
+    strict_glossary_check: bool,
+
     #[serde(default = "yes")]
     #[getset(get_copy = "pub", set = "pub")]
     strict_reference_check: bool,
@@ -57,6 +62,10 @@ pub struct DossierConfigurationCompilation {
     #[serde(default = "yes")]
     #[getset(get_copy = "pub", set = "pub")]
     strict_paragraph_loading_rules_check: bool,
+
+    #[serde(default = "yes")]
+    #[getset(get_copy = "pub", set = "pub")]
+    strict_code_snippet_check: bool,
 }
 
 impl Default for DossierConfigurationCompilation {
@@ -70,9 +79,11 @@ impl Default for DossierConfigurationCompilation {
             use_remote_addons: false,
             strict_list_check: false,
             strict_cite_check: true,
+            strict_glossary_check: true,
             strict_greek_letters_check: true,
             strict_reference_check: true,
             strict_paragraph_loading_rules_check: true,
+            strict_code_snippet_check: true,
         }
     }
 }
\ No newline at end of file
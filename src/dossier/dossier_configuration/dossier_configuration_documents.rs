@@ -0,0 +1,289 @@
+use std::{cmp::Ordering, path::{Component, Path, PathBuf}};
+use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+use crate::{constants::{DOSSIER_CONFIGURATION_FILE_NAMES, NMD_EXTENSION}, resource::ResourceError, utility::file_utility};
+use super::{dossier_configuration_path_reference::DossierConfigurationRawPathReference, dossier_configuration_path_reference_manager::DOSSIER_CONFIGURATION_RAW_REFERENCE_MANAGER, DossierConfiguration};
+
+
+/// Prefix of documents exclusion patterns (e.g. `!./chapters/draft-*.nmd`)
+pub const EXCLUSION_PREFIX: char = '!';
+
+/// Characters which make a document path a glob pattern
+const GLOB_CHARS: [char; 3] = ['*', '?', '['];
+
+
+/// Order of documents found using glob patterns or directories (documents listed one by one keep their position)
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum DocumentsOrder {
+
+    /// Numbers are compared by value, e.g. `2-intro.nmd` before `10-end.nmd`
+    #[default] Natural,
+
+    /// Files with numeric prefix first (ordered by number), then others by name
+    NumericPrefix,
+
+    /// Plain lexicographic order
+    Name,
+}
+
+impl DocumentsOrder {
+
+    fn compare_names(&self, a: &str, b: &str) -> Ordering {
+        match self {
+            Self::Natural => file_utility::natural_cmp(a, b),
+            Self::NumericPrefix => match (file_utility::numeric_prefix(a), file_utility::numeric_prefix(b)) {
+                (Some(a_number), Some(b_number)) => a_number.cmp(&b_number).then_with(|| a.cmp(b)),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => a.cmp(b),
+            },
+            Self::Name => a.cmp(b),
+        }
+    }
+
+    /// Compare paths component by component
+    pub fn compare(&self, a: &Path, b: &Path) -> Ordering {
+
+        let a_components: Vec<String> = a.components().map(|c| c.as_os_str().to_string_lossy().to_string()).collect();
+        let b_components: Vec<String> = b.components().map(|c| c.as_os_str().to_string_lossy().to_string()).collect();
+
+        for (a_component, b_component) in a_components.iter().zip(b_components.iter()) {
+
+            let ordering = self.compare_names(a_component, b_component);
+
+            if ordering != Ordering::Equal {
+                return ordering
+            }
+        }
+
+        a_components.len().cmp(&b_components.len())
+    }
+
+    pub fn sort(&self, paths: &mut [PathBuf]) {
+        paths.sort_by(|a, b| self.compare(a, b));
+    }
+}
+
+
+/// Resolved entry of dossier documents
+#[derive(Debug, Clone, PartialEq)]
+pub enum DossierConfigurationDocument {
+    Document(PathBuf),
+
+    /// Documents of a nested dossier (i.e. a directory with its own dossier configuration),
+    /// they are grouped in a section named as sub-dossier
+    SubDossier { name: String, documents: Vec<PathBuf> },
+}
+
+impl DossierConfigurationDocument {
+
+    pub fn paths(&self) -> Vec<&PathBuf> {
+        match self {
+            Self::Document(path) => vec![path],
+            Self::SubDossier { name: _, documents } => documents.iter().collect(),
+        }
+    }
+}
+
+
+fn is_glob_pattern(path: &str) -> bool {
+    path.contains(GLOB_CHARS)
+}
+
+fn is_dossier_directory(path: &Path) -> bool {
+    path.is_dir() && DOSSIER_CONFIGURATION_FILE_NAMES.iter().any(|file_name| path.join(file_name).is_file())
+}
+
+fn is_dossier_configuration_file(path: &Path) -> bool {
+    path.is_file() && path.file_name().is_some_and(|file_name| DOSSIER_CONFIGURATION_FILE_NAMES.contains(&file_name.to_string_lossy().as_ref()))
+}
+
+/// Load documents of sub-dossier in `directory`, `ancestors` are canonical directories of dossiers which (transitively) include it
+fn load_sub_dossier(directory: &Path, ancestors: &mut Vec<PathBuf>) -> Result<(String, Vec<PathBuf>), ResourceError> {
+
+    let canonical_directory = directory.canonicalize().map_err(|err| ResourceError::InvalidResourceVerbose(format!("sub-dossier {} cannot be read: {}", directory.to_string_lossy(), err)))?;
+
+    if ancestors.contains(&canonical_directory) {
+        return Err(ResourceError::InvalidResourceVerbose(format!("sub-dossier {} includes itself (directly or by its sub-dossiers)", directory.to_string_lossy())))
+    }
+
+    // sub-dossier configuration changes root path, so it must be restored
+    let root_path = DOSSIER_CONFIGURATION_RAW_REFERENCE_MANAGER.lock().unwrap().root_path().cloned();
+
+    ancestors.push(canonical_directory);
+
+    let sub_dossier = DossierConfiguration::try_from(&directory.to_path_buf()).and_then(|configuration| {
+
+        let documents = resolve_documents_of(&configuration.raw_documents_paths, &configuration.documents_order, ancestors)?;
+
+        Ok((configuration, documents))
+    });
+
+    ancestors.pop();
+
+    if let Some(root_path) = root_path {
+        DOSSIER_CONFIGURATION_RAW_REFERENCE_MANAGER.lock().unwrap().set_root_path(root_path);
+    }
+
+    let (sub_dossier_configuration, documents) = sub_dossier?;
+
+    log::info!("sub-dossier '{}' found in {}", sub_dossier_configuration.name(), directory.to_string_lossy());
+
+    Ok((
+        sub_dossier_configuration.name().clone(),
+        documents.iter().flat_map(|document| document.paths()).cloned().collect(),
+    ))
+}
+
+/// Resolve raw documents paths: glob patterns and directories are expanded (using `order`),
+/// exclusion patterns are applied, duplicated documents are removed and sub-dossiers are loaded
+pub fn resolve_documents(raw_documents_paths: &[DossierConfigurationRawPathReference], order: &DocumentsOrder) -> Result<Vec<DossierConfigurationDocument>, ResourceError> {
+
+    let root_path = DOSSIER_CONFIGURATION_RAW_REFERENCE_MANAGER.lock().unwrap().root_path().cloned();
+
+    let mut ancestors: Vec<PathBuf> = root_path.and_then(|root_path| root_path.canonicalize().ok()).into_iter().collect();
+
+    resolve_documents_of(raw_documents_paths, order, &mut ancestors)
+}
+
+/// Remove `.` components, so patterns (e.g. `./chapters/*.nmd`) and found paths (e.g. `chapters/a.nmd`) can be compared
+fn without_current_dir(path: &Path) -> PathBuf {
+    path.components().filter(|component| !matches!(component, Component::CurDir)).collect()
+}
+
+fn resolve_documents_of(raw_documents_paths: &[DossierConfigurationRawPathReference], order: &DocumentsOrder, ancestors: &mut Vec<PathBuf>) -> Result<Vec<DossierConfigurationDocument>, ResourceError> {
+
+    let (raw_exclusions, raw_inclusions): (Vec<&String>, Vec<&String>) = raw_documents_paths.iter().partition(|raw| raw.trim_start().starts_with(EXCLUSION_PREFIX));
+
+    let (exclusions, inclusions): (Vec<String>, Vec<String>) = {
+
+        let manager = DOSSIER_CONFIGURATION_RAW_REFERENCE_MANAGER.lock().unwrap();
+
+        let root_path = manager.root_path().cloned().unwrap_or(PathBuf::from("."));
+
+        let parse = |raw: &str| manager.parse_raw_reference(&raw.trim().to_string(), Some(root_path.clone()));
+
+        (
+            raw_exclusions.iter().map(|raw| parse(raw.trim_start().trim_start_matches(EXCLUSION_PREFIX))).collect(),
+            raw_inclusions.iter().map(|raw| parse(raw)).collect(),
+        )
+    };
+
+    let exclusions = exclusions.iter()
+                        .map(|exclusion| glob::Pattern::new(&without_current_dir(Path::new(exclusion)).to_string_lossy()).map_err(|err| ResourceError::InvalidResourceVerbose(format!("invalid exclusion pattern '{}': {}", exclusion, err))))
+                        .collect::<Result<Vec<_>, _>>()?;
+
+    let is_excluded = |path: &Path| exclusions.iter().any(|exclusion| exclusion.matches_path(&without_current_dir(path)));
+
+    let mut documents: Vec<DossierConfigurationDocument> = Vec::new();
+
+    // canonical paths of found documents (the same document can be reached by different paths)
+    let mut found: Vec<PathBuf> = Vec::new();
+
+    // documents which are not excluded and not already found
+    let mut new_documents = |paths: Vec<PathBuf>| -> Vec<PathBuf> {
+        paths.into_iter().filter(|path| {
+
+            let canonical_path = path.canonicalize().unwrap_or(path.clone());
+
+            if is_excluded(path) || found.contains(&canonical_path) {
+                return false
+            }
+
+            found.push(canonical_path);
+
+            true
+
+        }).collect()
+    };
+
+    for inclusion in inclusions {
+
+        if is_glob_pattern(&inclusion) {
+
+            let mut paths: Vec<PathBuf> = glob::glob(&inclusion)
+                                            .map_err(|err| ResourceError::InvalidResourceVerbose(format!("invalid documents pattern '{}': {}", inclusion, err)))?
+                                            .filter_map(Result::ok)
+                                            .filter(|path| path.is_file())
+                                            .collect();
+
+            if paths.is_empty() {
+                log::warn!("no documents match '{}'", inclusion);
+            }
+
+            order.sort(&mut paths);
+
+            documents.extend(new_documents(paths).into_iter().map(DossierConfigurationDocument::Document));
+
+            continue;
+        }
+
+        let path = PathBuf::from(&inclusion);
+
+        let sub_dossier_directory = if is_dossier_directory(&path) {
+            Some(path.clone())
+        } else if is_dossier_configuration_file(&path) {
+            Some(path.parent().unwrap_or(Path::new(".")).to_path_buf())
+        } else {
+            None
+        };
+
+        if let Some(directory) = sub_dossier_directory {
+
+            let (name, sub_documents) = load_sub_dossier(&directory, ancestors)?;
+
+            let sub_documents = new_documents(sub_documents);
+
+            if sub_documents.is_empty() {
+                log::warn!("sub-dossier '{}' has no documents", name);
+            } else {
+                documents.push(DossierConfigurationDocument::SubDossier { name, documents: sub_documents });
+            }
+
+        } else if path.is_dir() {
+
+            let mut paths = file_utility::all_files_in_dir(&path, &vec![NMD_EXTENSION.to_string()])?;
+
+            order.sort(&mut paths);
+
+            documents.extend(new_documents(paths).into_iter().map(DossierConfigurationDocument::Document));
+
+        } else {
+
+            documents.extend(new_documents(vec![path]).into_iter().map(DossierConfigurationDocument::Document));
+        }
+    }
+
+    Ok(documents)
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+    use super::DocumentsOrder;
+
+
+    #[test]
+    fn documents_orders() {
+
+        let mut paths: Vec<PathBuf> = vec!["c/10-end.nmd", "c/2-intro.nmd", "c/appendix.nmd", "b/1-a.nmd"].into_iter().map(PathBuf::from).collect();
+
+        DocumentsOrder::Natural.sort(&mut paths);
+        assert_eq!(paths, vec![PathBuf::from("b/1-a.nmd"), PathBuf::from("c/2-intro.nmd"), PathBuf::from("c/10-end.nmd"), PathBuf::from("c/appendix.nmd")]);
+
+        // case is used only for names which are equal ignoring case
+        let mut cased_paths: Vec<PathBuf> = vec!["b-end.nmd", "B-intro.nmd", "a.nmd", "A.nmd"].into_iter().map(PathBuf::from).collect();
+
+        DocumentsOrder::Natural.sort(&mut cased_paths);
+        assert_eq!(cased_paths, vec![PathBuf::from("A.nmd"), PathBuf::from("a.nmd"), PathBuf::from("b-end.nmd"), PathBuf::from("B-intro.nmd")]);
+
+        DocumentsOrder::Name.sort(&mut paths);
+        assert_eq!(paths[1], PathBuf::from("c/10-end.nmd"));
+
+        let mut paths: Vec<PathBuf> = vec!["appendix.nmd", "10-end.nmd", "2-intro.nmd"].into_iter().map(PathBuf::from).collect();
+
+        DocumentsOrder::NumericPrefix.sort(&mut paths);
+        assert_eq!(paths, vec![PathBuf::from("2-intro.nmd"), PathBuf::from("10-end.nmd"), PathBuf::from("appendix.nmd")]);
+    }
+}
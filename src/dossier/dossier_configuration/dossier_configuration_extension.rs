@@ -0,0 +1,298 @@
+//! Dossier configuration inheritance: a configuration file can `extend` one or more base files
+//! (YAML, JSON or TOML), which are merged before deserialization.
+//!
+//! Merge rules:
+//!
+//! - maps are merged recursively, values of extending file override base values
+//! - lists (and other values) of extending file replace base ones
+//! - a key with `+` suffix (e.g. `modifiers+`) appends its list to the base one
+//!
+//! Relative paths (i.e. `./...`) of base files are relative to base file directory. Only values of path keys
+//! (`documents`, `style.styles`, `glossary.file` and documents of profiles) are paths, other values (e.g. references)
+//! are kept as they are.
+
+use std::path::{Path, PathBuf};
+use serde_json::{Map, Value};
+use crate::resource::ResourceError;
+use crate::utility::file_utility;
+use super::dossier_configuration_documents::EXCLUSION_PREFIX;
+use super::dossier_configuration_path_reference_manager::PATH_START_FOR_RELATIVE;
+
+
+/// Key which contains base file path (or a list of paths)
+pub const EXTEND_KEY: &str = "extend";
+
+/// Suffix of keys whose lists are appended to the base ones
+pub const APPEND_SUFFIX: char = '+';
+
+/// Keys whose values are paths, as key sequences from configuration root (`*` matches any key)
+const PATH_KEYS: [&[&str]; 5] = [
+    &["documents"],
+    &["style", "styles"],
+    &["glossary", "file"],
+    &["profiles", "*", "documents"],
+    &["profiles", "*", "exclude_documents"],
+];
+
+
+/// Parse configuration file as generic value, format is inferred from extension (YAML is the default)
+pub fn parse_configuration_file(path: &Path) -> Result<Value, ResourceError> {
+
+    let content = file_utility::read_file_content(&path.to_path_buf())?;
+
+    let extension = path.extension().map(|ext| ext.to_string_lossy().to_lowercase()).unwrap_or_default();
+
+    let invalid = |err: String| ResourceError::InvalidResourceVerbose(format!("invalid dossier configuration {}: {}", path.to_string_lossy(), err));
+
+    match extension.as_str() {
+        "json" => serde_json::from_str(&content).map_err(|err| invalid(err.to_string())),
+        "toml" => {
+            let value: toml::Value = toml::from_str(&content).map_err(|err| invalid(err.to_string()))?;
+
+            serde_json::to_value(value).map_err(|err| invalid(err.to_string()))
+        },
+        _ => {
+            let value: serde_yaml::Value = serde_yaml::from_str(&content).map_err(|err| invalid(err.to_string()))?;
+
+            serde_json::to_value(value).map_err(|err| invalid(err.to_string()))
+        },
+    }
+}
+
+/// Load configuration file resolving its `extend` chain. Base paths are relative to extending file
+pub fn load_extended_configuration(path: &Path) -> Result<Value, ResourceError> {
+    inner_load_extended_configuration(path, &mut Vec::new())
+}
+
+fn inner_load_extended_configuration(path: &Path, stack: &mut Vec<PathBuf>) -> Result<Value, ResourceError> {
+
+    let canonical_path = std::fs::canonicalize(path).map_err(|_| ResourceError::ResourceNotFound(path.to_string_lossy().to_string()))?;
+
+    if stack.contains(&canonical_path) {
+
+        let mut cycle: Vec<String> = stack.iter().map(|p| p.to_string_lossy().to_string()).collect();
+        cycle.push(canonical_path.to_string_lossy().to_string());
+
+        return Err(ResourceError::InvalidResourceVerbose(format!("dossier configuration extension cycle: {}", cycle.join(" -> "))))
+    }
+
+    let mut value = parse_configuration_file(&canonical_path)?;
+
+    let root = canonical_path.parent().map(|parent| parent.to_path_buf()).unwrap_or_default();
+
+    // paths of base files must not be resolved using dossier root
+    if !stack.is_empty() {
+        value = rebase_relative_paths(value, &root);
+    }
+
+    let bases: Vec<String> = match value.as_object_mut().and_then(|map| map.remove(EXTEND_KEY)) {
+        None => Vec::new(),
+        Some(Value::String(base)) => vec![base],
+        Some(Value::Array(bases)) => bases.into_iter().map(|base| match base {
+            Value::String(base) => Ok(base),
+            other => Err(ResourceError::InvalidResourceVerbose(format!("invalid '{}' value: {}", EXTEND_KEY, other))),
+        }).collect::<Result<_, _>>()?,
+        Some(other) => return Err(ResourceError::InvalidResourceVerbose(format!("invalid '{}' value: {}", EXTEND_KEY, other))),
+    };
+
+    if bases.is_empty() {
+        return Ok(resolve_append_keys(value))
+    }
+
+    stack.push(canonical_path);
+
+    let mut merged = Value::Object(Map::new());
+
+    for base in bases {
+
+        log::info!("dossier configuration extends {}", base);
+
+        let base_path = root.join(base.trim());
+
+        let base_value = inner_load_extended_configuration(&base_path, stack)?;
+
+        merged = merge(merged, base_value);
+    }
+
+    stack.pop();
+
+    Ok(merge(merged, value))
+}
+
+/// Make relative paths of path keys of `value` absolute using `directory`
+fn rebase_relative_paths(value: Value, directory: &Path) -> Value {
+    rebase_path_keys(value, &PATH_KEYS, directory)
+}
+
+fn rebase_path_keys(value: Value, keys: &[&[&str]], directory: &Path) -> Value {
+
+    if keys.iter().any(|keys| keys.is_empty()) {
+        return rebase_paths(value, directory)
+    }
+
+    match value {
+        Value::Object(map) => Value::Object(map.into_iter().map(|(key, value)| {
+
+            // appended lists (e.g. `documents+`) are paths too
+            let name = key.strip_suffix(APPEND_SUFFIX).unwrap_or(&key);
+
+            let nested_keys: Vec<&[&str]> = keys.iter().filter(|keys| keys[0] == "*" || keys[0] == name).map(|keys| &keys[1..]).collect();
+
+            let value = match nested_keys.is_empty() {
+                true => value,
+                false => rebase_path_keys(value, &nested_keys, directory),
+            };
+
+            (key, value)
+        }).collect()),
+        other => other,
+    }
+}
+
+/// Make relative paths (also of exclusion patterns) of `value` absolute using `directory`
+fn rebase_paths(value: Value, directory: &Path) -> Value {
+
+    match value {
+        Value::String(raw) => {
+
+            let (prefix, path) = match raw.strip_prefix(EXCLUSION_PREFIX) {
+                Some(path) => (EXCLUSION_PREFIX.to_string(), path),
+                None => (String::new(), raw.as_str()),
+            };
+
+            match path.strip_prefix(PATH_START_FOR_RELATIVE) {
+                Some(path) => Value::String(format!("{}{}", prefix, directory.join(path).to_string_lossy())),
+                None => Value::String(raw),
+            }
+        },
+        Value::Array(values) => Value::Array(values.into_iter().map(|value| rebase_paths(value, directory)).collect()),
+        other => other,
+    }
+}
+
+/// Apply keys with `+` suffix of `value` (also in nested maps), so they are never left in configuration
+fn resolve_append_keys(value: Value) -> Value {
+    match value {
+        Value::Object(_) => merge(Value::Object(Map::new()), value),
+        other => other,
+    }
+}
+
+/// Merge `overlay` into `base` (see module documentation for rules)
+pub fn merge(base: Value, overlay: Value) -> Value {
+
+    match (base, overlay) {
+        (Value::Object(mut base), Value::Object(overlay)) => {
+
+            for (key, value) in overlay {
+
+                let value = resolve_append_keys(value);
+
+                if let Some(key) = key.strip_suffix(APPEND_SUFFIX) {
+
+                    let appended = match (base.remove(key), value) {
+                        (Some(Value::Array(mut base_list)), Value::Array(list)) => {
+                            base_list.extend(list);
+                            Value::Array(base_list)
+                        },
+                        (_, value) => value,
+                    };
+
+                    base.insert(key.to_string(), appended);
+
+                    continue;
+                }
+
+                let merged = match base.remove(&key) {
+                    Some(base_value) => merge(base_value, value),
+                    None => value,
+                };
+
+                base.insert(key, merged);
+            }
+
+            Value::Object(base)
+        },
+        (_, overlay) => overlay,
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+    use serde_json::json;
+    use crate::utility::test_utility::TempDir;
+    use super::{load_extended_configuration, merge};
+
+
+    #[test]
+    fn merge_rules() {
+
+        let base = json!({
+            "style": { "theme": "light", "list_bullets_configuration": [1, 2] },
+            "references": { "company": "ACME" },
+            "modifiers": ["a"],
+        });
+
+        let overlay = json!({
+            "style": { "theme": "dark" },
+            "references": { "product": "NMD" },
+            "modifiers+": ["b"],
+        });
+
+        assert_eq!(merge(base, overlay), json!({
+            "style": { "theme": "dark", "list_bullets_configuration": [1, 2] },
+            "references": { "company": "ACME", "product": "NMD" },
+            "modifiers": ["a", "b"],
+        }));
+    }
+
+    #[test]
+    fn extend_toml_base() {
+
+        let dossier_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("test-resources").join("nmd-test-extend").join("dossier");
+
+        let value = load_extended_configuration(&dossier_dir.join("nmd.yml")).unwrap();
+
+        assert_eq!(value["name"], "extended");
+        assert_eq!(value["references"]["company"], "ACME");
+        assert_eq!(value["references"]["product"], "NMD");
+        assert!(value.get("extend").is_none());
+    }
+
+    #[test]
+    fn extend_base_with_relative_paths() {
+
+        let root = TempDir::new("extend-paths");
+
+        std::fs::create_dir_all(root.join("shared")).unwrap();
+        std::fs::create_dir_all(root.join("dossier")).unwrap();
+
+        std::fs::write(root.join("shared").join("base.yml"), "documents:\n  - ./disclaimer.nmd\n  - '!./draft-*.nmd'\nglossary:\n  file: ./glossary.yml\nreferences:\n  bin: ./bin\n").unwrap();
+        std::fs::write(root.join("dossier").join("nmd.yml"), "extend: ../shared/base.yml\nname: paths\ndocuments+:\n  - ./d1.nmd\nstyle:\n  list_bullets_configuration+: []\n").unwrap();
+        std::fs::write(root.join("dossier").join("base.yml"), "name: base\nmodifiers: []\nmodifiers+: []\n").unwrap();
+
+        let value = load_extended_configuration(&root.join("dossier").join("nmd.yml"));
+        let base = load_extended_configuration(&root.join("dossier").join("base.yml"));
+
+        let shared_dir = root.join("shared").canonicalize().unwrap();
+
+        let value = value.unwrap();
+
+        assert_eq!(value["documents"], json!([
+            shared_dir.join("disclaimer.nmd").to_string_lossy(),
+            format!("!{}", shared_dir.join("draft-*.nmd").to_string_lossy()),
+            "./d1.nmd",
+        ]));
+
+        assert_eq!(value["glossary"]["file"], json!(shared_dir.join("glossary.yml").to_string_lossy()));
+
+        // only values of path keys are paths
+        assert_eq!(value["references"]["bin"], "./bin");
+
+        // append keys of new subtrees and of files without base are resolved
+        assert_eq!(value["style"], json!({ "list_bullets_configuration": [] }));
+        assert_eq!(base.unwrap(), json!({ "name": "base", "modifiers": [] }));
+    }
+}
@@ -0,0 +1,47 @@
+use getset::{CopyGetters, Getters, Setters};
+use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+
+
+/// Position of a generated section in dossier
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum GeneratedListPosition {
+
+    /// Before documents (after table of contents)
+    #[default]
+    Start,
+
+    /// After documents (before glossary and bibliography)
+    End,
+}
+
+
+/// Generated list of dossier items (e.g. list of figures)
+#[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema, Getters, CopyGetters, Setters)]
+pub struct DossierConfigurationGeneratedList {
+
+    /// Title of section, default title of list is used if it is missing (e.g. `List of figures`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get = "pub", set = "pub")]
+    title: Option<String>,
+
+    #[serde(default)]
+    #[getset(get_copy = "pub", set = "pub")]
+    position: GeneratedListPosition,
+
+    #[serde(default)]
+    #[getset(get_copy = "pub", set = "pub")]
+    include_in_output: bool,
+}
+
+impl DossierConfigurationGeneratedList {
+
+    pub fn new(title: Option<String>, position: GeneratedListPosition, include_in_output: bool) -> Self {
+        Self {
+            title,
+            position,
+            include_in_output,
+        }
+    }
+}
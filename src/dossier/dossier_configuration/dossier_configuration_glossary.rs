@@ -0,0 +1,70 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use getset::{CopyGetters, Getters, Setters};
+use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+
+use crate::glossary::glossary_entry::GlossaryEntry;
+use crate::resource::ResourceError;
+
+#[derive(Debug, Clone, Getters, CopyGetters, Setters, Deserialize, Serialize, JsonSchema)]
+pub struct DossierConfigurationGlossary {
+
+    #[serde(default = "default_title")]
+    #[getset(get = "pub", set = "pub")]
+    title: String,
+
+    #[serde(default)]
+    #[getset(get = "pub", set = "pub")]
+    entries: BTreeMap<String, GlossaryEntry>,
+
+    /// YAML (or JSON) file of glossary entries, relative to dossier configuration file.
+    /// Its entries are added to `entries` when configuration is loaded (`entries` have precedence)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get = "pub", set = "pub")]
+    file: Option<String>,
+
+    #[serde(default)]
+    #[getset(get_copy = "pub", set = "pub")]
+    include_in_output: bool,
+}
+
+fn default_title() -> String {
+    String::from("Glossary")
+}
+
+impl DossierConfigurationGlossary {
+
+    /// Add entries of glossary file (if any), `base_dir` is the directory of dossier configuration file
+    pub fn load_file(&mut self, base_dir: &Path) -> Result<(), ResourceError> {
+
+        if let Some(file) = &self.file {
+
+            let path = base_dir.join(file);
+
+            let content = fs::read_to_string(&path).map_err(|_| ResourceError::ReadError(path.to_string_lossy().to_string()))?;
+
+            let entries: BTreeMap<String, GlossaryEntry> = serde_yaml::from_str(&content)
+                    .map_err(|err| ResourceError::InvalidResourceVerbose(format!("invalid glossary file {}: {}", path.to_string_lossy(), err)))?;
+
+            for (key, entry) in entries {
+                self.entries.entry(key).or_insert(entry);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for DossierConfigurationGlossary {
+    fn default() -> Self {
+        Self {
+            title: default_title(),
+            entries: Default::default(),
+            file: None,
+            include_in_output: false
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use getset::{CopyGetters, Getters, Setters};
+use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, Getters, CopyGetters, Setters)]
+pub struct DossierConfigurationIndex {
+
+    #[serde(default = "default_title")]
+    #[getset(get = "pub", set = "pub")]
+    title: String,
+
+    /// Text before related terms of an entry
+    #[serde(default = "default_see_also_label")]
+    #[getset(get = "pub", set = "pub")]
+    see_also_label: String,
+
+    #[serde(default)]
+    #[getset(get_copy = "pub", set = "pub")]
+    include_in_output: bool,
+}
+
+fn default_title() -> String {
+    String::from("Index")
+}
+
+fn default_see_also_label() -> String {
+    String::from("see also")
+}
+
+impl Default for DossierConfigurationIndex {
+    fn default() -> Self {
+        Self {
+            title: default_title(),
+            see_also_label: default_see_also_label(),
+            include_in_output: false,
+        }
+    }
+}
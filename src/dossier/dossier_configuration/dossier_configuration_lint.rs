@@ -0,0 +1,43 @@
+use std::collections::BTreeMap;
+
+use getset::{CopyGetters, Getters, Setters};
+use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+
+use crate::linter::{LintRule, LintSeverity};
+
+
+fn default_max_paragraph_words() -> usize {
+    300
+}
+
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, Getters, CopyGetters, Setters)]
+pub struct DossierConfigurationLint {
+
+    /// Severity of rules, rules which are not listed use their default severity
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    #[getset(get = "pub", set = "pub")]
+    rules: BTreeMap<LintRule, LintSeverity>,
+
+    /// Number of words over which a paragraph is reported by `long-paragraph` rule
+    #[serde(default = "default_max_paragraph_words")]
+    #[getset(get_copy = "pub", set = "pub")]
+    max_paragraph_words: usize,
+}
+
+impl DossierConfigurationLint {
+
+    pub fn severity_of(&self, rule: &LintRule) -> LintSeverity {
+        self.rules.get(rule).copied().unwrap_or_else(|| rule.default_severity())
+    }
+}
+
+impl Default for DossierConfigurationLint {
+    fn default() -> Self {
+        Self {
+            rules: BTreeMap::new(),
+            max_paragraph_words: default_max_paragraph_words(),
+        }
+    }
+}
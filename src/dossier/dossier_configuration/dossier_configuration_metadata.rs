@@ -0,0 +1,39 @@
+use getset::{Getters, Setters};
+use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+use crate::dossier::document::document_metadata::{one_or_many, one_or_many_schema};
+
+
+/// Publication metadata of dossier (e.g. used by EPUB package), dossier name is its title
+#[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema, Getters, Setters)]
+pub struct DossierConfigurationMetadata {
+
+    /// Author or list of authors
+    #[serde(default, skip_serializing_if = "Vec::is_empty", deserialize_with = "one_or_many")]
+    #[schemars(schema_with = "one_or_many_schema")]
+    #[getset(get = "pub", set = "pub")]
+    authors: Vec<String>,
+
+    /// Language tag (e.g. `en`, `it-IT`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get = "pub", set = "pub")]
+    language: Option<String>,
+
+    /// Unique identifier of publication (e.g. ISBN or URN)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get = "pub", set = "pub")]
+    identifier: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get = "pub", set = "pub")]
+    description: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get = "pub", set = "pub")]
+    publisher: Option<String>,
+
+    /// Publication date (e.g. `2024-05-01`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get = "pub", set = "pub")]
+    date: Option<String>,
+}
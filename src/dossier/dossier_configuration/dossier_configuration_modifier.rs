@@ -0,0 +1,192 @@
+use std::sync::Arc;
+use getset::{Getters, Setters};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+use crate::{codex::modifier::{base_modifier::BaseModifier, ModifierIdentifier, ModifiersBucket}, compilation::compilation_rule::replacement_rule::{replacement_rule_part::{fixed_replacement_rule_part::FixedReplacementRuleReplacerPart, single_capture_group_replacement_rule_part::SingleCaptureGroupReplacementRuleReplacerPart}, ReplacementRule, ReplacementRuleParts}, dossier::document::document_metadata::{one_or_many, one_or_many_schema}, resource::ResourceError};
+
+
+/// Placeholders of replacement template, i.e. `$$` (escaped `$`), `$1` or `${1}`
+static REPLACEMENT_PLACEHOLDER_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\$(?:(\$)|(\d+)|\{(\d+)\})").unwrap());
+
+/// Value of `incompatible_modifiers` which makes a modifier incompatible with all others
+const ALL_MODIFIERS: &str = "all";
+
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum DossierConfigurationModifierKind {
+    #[default] Text,
+    Paragraph,
+}
+
+
+/// User-defined modifier, e.g.:
+///
+/// ```yaml
+/// modifiers:
+///   - identifier: keyboard
+///     kind: text
+///     pattern: '\[\[kbd:(.+?)\]\]'
+///     before: bold-star-version
+///     incompatible_modifiers: all
+///     replacement: '<kbd>$1</kbd>'
+/// ```
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, Getters, Setters)]
+pub struct DossierConfigurationModifier {
+
+    #[getset(get = "pub", set = "pub")]
+    identifier: ModifierIdentifier,
+
+    #[serde(default)]
+    #[getset(get = "pub", set = "pub")]
+    kind: DossierConfigurationModifierKind,
+
+    #[getset(get = "pub", set = "pub")]
+    pattern: String,
+
+    /// Insert modifier before this one (if it is not present, modifier is appended)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get = "pub", set = "pub")]
+    before: Option<ModifierIdentifier>,
+
+    /// Insert modifier after this one (if it is not present, modifier is appended)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get = "pub", set = "pub")]
+    after: Option<ModifierIdentifier>,
+
+    /// List of modifiers identifiers or `all`
+    #[serde(default, skip_serializing_if = "Vec::is_empty", deserialize_with = "one_or_many")]
+    #[schemars(schema_with = "one_or_many_schema")]
+    #[getset(get = "pub", set = "pub")]
+    incompatible_modifiers: Vec<ModifierIdentifier>,
+
+    /// Replacement template, `$n` or `${n}` are replaced by n-th capture group, `$$` by `$`
+    #[getset(get = "pub", set = "pub")]
+    replacement: String,
+}
+
+impl DossierConfigurationModifier {
+
+    pub fn new(identifier: ModifierIdentifier, kind: DossierConfigurationModifierKind, pattern: String, replacement: String) -> Self {
+        Self {
+            identifier,
+            kind,
+            pattern,
+            before: None,
+            after: None,
+            incompatible_modifiers: Vec::new(),
+            replacement,
+        }
+    }
+
+    pub fn incompatible_modifiers_bucket(&self) -> ModifiersBucket {
+
+        if self.incompatible_modifiers.iter().any(|m| m.eq(ALL_MODIFIERS)) {
+            return ModifiersBucket::All
+        }
+
+        if self.incompatible_modifiers.is_empty() {
+            return ModifiersBucket::None
+        }
+
+        ModifiersBucket::List(self.incompatible_modifiers.clone())
+    }
+
+    fn pattern_regex(&self) -> Result<Regex, ResourceError> {
+        Regex::new(&self.pattern).map_err(|err| ResourceError::InvalidResourceVerbose(format!("invalid pattern of modifier '{}': {}", self.identifier, err)))
+    }
+
+    pub fn build_modifier(&self) -> Result<BaseModifier, ResourceError> {
+        Ok(BaseModifier::new(self.pattern.clone(), self.pattern_regex()?, self.incompatible_modifiers_bucket()))
+    }
+
+    /// Split replacement template in fixed parts and capture group parts
+    pub fn build_replacement_parts(&self) -> Result<ReplacementRuleParts, ResourceError> {
+
+        let captures_len = self.pattern_regex()?.captures_len();
+
+        let mut parts: ReplacementRuleParts = Vec::new();
+        let mut fixed = String::new();
+        let mut last_match_end: usize = 0;
+
+        for captures in REPLACEMENT_PLACEHOLDER_REGEX.captures_iter(&self.replacement) {
+
+            let placeholder = captures.get(0).unwrap();
+
+            fixed.push_str(&self.replacement[last_match_end..placeholder.start()]);
+            last_match_end = placeholder.end();
+
+            if captures.get(1).is_some() {
+                fixed.push('$');
+                continue;
+            }
+
+            let raw_group = captures.get(2).or(captures.get(3)).unwrap().as_str();
+
+            let group: usize = raw_group.parse().map_err(|_| ResourceError::InvalidResourceVerbose(format!("invalid capture group {} in replacement of modifier '{}'", raw_group, self.identifier)))?;
+
+            if group >= captures_len {
+                return Err(ResourceError::InvalidResourceVerbose(format!("capture group {} not found in pattern of modifier '{}'", group, self.identifier)))
+            }
+
+            if !fixed.is_empty() {
+                parts.push(Arc::new(FixedReplacementRuleReplacerPart::new(std::mem::take(&mut fixed))));
+            }
+
+            parts.push(Arc::new(SingleCaptureGroupReplacementRuleReplacerPart::new(group, Vec::new(), self.incompatible_modifiers_bucket())));
+        }
+
+        fixed.push_str(&self.replacement[last_match_end..]);
+
+        if !fixed.is_empty() {
+            parts.push(Arc::new(FixedReplacementRuleReplacerPart::new(fixed)));
+        }
+
+        Ok(parts)
+    }
+
+    pub fn build_replacement_rule(&self) -> Result<ReplacementRule, ResourceError> {
+        Ok(ReplacementRule::new(self.pattern.clone(), self.build_replacement_parts()?))
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::{DossierConfigurationModifier, DossierConfigurationModifierKind};
+    use crate::codex::modifier::ModifiersBucket;
+
+
+    #[test]
+    fn deserialize_and_build() {
+
+        let yaml = concat!(
+            "identifier: keyboard\n",
+            "pattern: '\\[\\[kbd:(.+?)\\]\\]'\n",
+            "after: inline-code\n",
+            "incompatible_modifiers: all\n",
+            "replacement: '<kbd>$1</kbd> $$'\n",
+        );
+
+        let modifier: DossierConfigurationModifier = serde_yaml::from_str(yaml).unwrap();
+
+        assert_eq!(modifier.kind(), &DossierConfigurationModifierKind::Text);
+        assert_eq!(modifier.after().as_ref().unwrap(), "inline-code");
+        assert_eq!(modifier.incompatible_modifiers_bucket(), ModifiersBucket::All);
+        assert_eq!(modifier.build_replacement_parts().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn missing_capture_group() {
+
+        let modifier = DossierConfigurationModifier::new(String::from("x"), DossierConfigurationModifierKind::Paragraph, String::from("x(.*)"), String::from("${2}"));
+
+        assert!(modifier.build_replacement_parts().is_err());
+
+        let modifier = DossierConfigurationModifier::new(String::from("x"), DossierConfigurationModifierKind::Paragraph, String::from("x(.*)"), String::from("$99999999999999999999"));
+
+        assert!(modifier.build_replacement_parts().is_err());
+    }
+}
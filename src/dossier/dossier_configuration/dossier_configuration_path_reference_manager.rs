@@ -33,6 +33,10 @@ impl DossierConfigurationRawReferenceManager {
         }
     }
 
+    pub fn root_path(&self) -> Option<&PathBuf> {
+        self.root_path.as_ref()
+    }
+
     pub fn set_root_path(&mut self, root_path: PathBuf) {
 
         self.not_unix_like_os = false;
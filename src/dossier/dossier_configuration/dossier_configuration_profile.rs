@@ -0,0 +1,150 @@
+use std::collections::HashMap;
+use getset::{Getters, Setters};
+use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+use super::{dossier_configuration_compilation::DossierConfigurationCompilation, dossier_configuration_variable::DossierConfigurationVariableValue};
+
+
+/// Compilation flags overridden by a profile, missing flags keep dossier values
+#[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema)]
+pub struct DossierConfigurationProfileCompilation {
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    embed_local_image: Option<bool>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    embed_remote_image: Option<bool>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    compress_embed_image: Option<bool>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    strict_image_src_check: Option<bool>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    parallelization: Option<bool>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    use_remote_addons: Option<bool>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    strict_list_check: Option<bool>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    strict_greek_letters_check: Option<bool>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    strict_cite_check: Option<bool>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    strict_glossary_check: Option<bool>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    strict_reference_check: Option<bool>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    strict_paragraph_loading_rules_check: Option<bool>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    strict_code_snippet_check: Option<bool>,
+}
+
+impl DossierConfigurationProfileCompilation {
+
+    /// Apply overrides to dossier compilation configuration
+    pub fn apply(&self, compilation: &mut DossierConfigurationCompilation) {
+
+        if let Some(v) = self.embed_local_image {
+            compilation.set_embed_local_image(v);
+        }
+
+        if let Some(v) = self.embed_remote_image {
+            compilation.set_embed_remote_image(v);
+        }
+
+        if let Some(v) = self.compress_embed_image {
+            compilation.set_compress_embed_image(v);
+        }
+
+        if let Some(v) = self.strict_image_src_check {
+            compilation.set_strict_image_src_check(v);
+        }
+
+        if let Some(v) = self.parallelization {
+            compilation.set_parallelization(v);
+        }
+
+        if let Some(v) = self.use_remote_addons {
+            compilation.set_use_remote_addons(v);
+        }
+
+        if let Some(v) = self.strict_list_check {
+            compilation.set_strict_list_check(v);
+        }
+
+        if let Some(v) = self.strict_greek_letters_check {
+            compilation.set_strict_greek_letters_check(v);
+        }
+
+        if let Some(v) = self.strict_cite_check {
+            compilation.set_strict_cite_check(v);
+        }
+
+        if let Some(v) = self.strict_glossary_check {
+            compilation.set_strict_glossary_check(v);
+        }
+
+        if let Some(v) = self.strict_reference_check {
+            compilation.set_strict_reference_check(v);
+        }
+
+        if let Some(v) = self.strict_paragraph_loading_rules_check {
+            compilation.set_strict_paragraph_loading_rules_check(v);
+        }
+
+        if let Some(v) = self.strict_code_snippet_check {
+            compilation.set_strict_code_snippet_check(v);
+        }
+    }
+}
+
+
+/// Named build profile, e.g.:
+///
+/// ```yaml
+/// profiles:
+///   customer:
+///     tags: [customer]
+///     exclude_documents: [internal-notes.nmd]
+///     variables:
+///       audience: customers
+///     compilation:
+///       strict_reference_check: false
+/// ```
+#[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema, Getters, Setters)]
+pub struct DossierConfigurationProfile {
+
+    /// Build tags added by profile
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[getset(get = "pub", set = "pub")]
+    tags: Vec<String>,
+
+    /// Documents of profile, they replace dossier documents
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get = "pub", set = "pub")]
+    documents: Option<Vec<String>>,
+
+    /// Documents removed from dossier documents, they can be glob patterns
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[getset(get = "pub", set = "pub")]
+    exclude_documents: Vec<String>,
+
+    /// Default values of variables
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    #[getset(get = "pub", set = "pub")]
+    variables: HashMap<String, DossierConfigurationVariableValue>,
+
+    #[serde(default)]
+    #[getset(get = "pub", set = "pub")]
+    compilation: DossierConfigurationProfileCompilation,
+}
@@ -1,8 +1,9 @@
 use std::path::PathBuf;
 
-use getset::{Getters, Setters};
+use getset::{CopyGetters, Getters, Setters};
 use serde::{Deserialize, Serialize};
-use crate::{compilation::compilation_configuration::list_bullet_configuration_record::{self, ListBulletConfigurationRecord}, theme::Theme};
+use schemars::JsonSchema;
+use crate::{assembler::assembler_configuration::DEFAULT_TEXT_WIDTH, compilation::compilation_configuration::list_bullet_configuration_record::{self, ListBulletConfigurationRecord}, theme::Theme};
 
 use super::{dossier_configuration_path_reference::{DossierConfigurationPathReference, DossierConfigurationRawPathReference}, dossier_configuration_path_reference_manager::DOSSIER_CONFIGURATION_RAW_REFERENCE_MANAGER};
 
@@ -10,7 +11,7 @@ const STYLE_PREFIX: &str = "./assets/styles";
 
 
 #[allow(dead_code)]
-#[derive(Debug, Clone, Deserialize, Serialize, Getters, Setters)]
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, Getters, CopyGetters, Setters)]
 pub struct DossierConfigurationStyle {
 
     #[serde(default)]
@@ -23,13 +24,22 @@ pub struct DossierConfigurationStyle {
 
     #[serde(default = "default_list_bullets")]
     #[getset(get = "pub", set = "pub")]
-    list_bullets_configuration: Vec<ListBulletConfigurationRecord> 
+    list_bullets_configuration: Vec<ListBulletConfigurationRecord>,
+
+    /// Maximum line width (in characters) of plain text outputs
+    #[serde(default = "default_text_width")]
+    #[getset(get_copy = "pub", set = "pub")]
+    text_width: usize,
 }
 
 fn default_list_bullets() -> Vec<ListBulletConfigurationRecord> {
     list_bullet_configuration_record::default_bullets_configuration()
 }
 
+fn default_text_width() -> usize {
+    DEFAULT_TEXT_WIDTH
+}
+
 impl DossierConfigurationStyle {
 
     pub fn styles_references(&self) -> Vec<DossierConfigurationPathReference> {
@@ -47,7 +57,8 @@ impl Default for DossierConfigurationStyle {
         Self {
             theme: Default::default(),
             styles: Default::default(),
-            list_bullets_configuration: default_list_bullets()
+            list_bullets_configuration: default_list_bullets(),
+            text_width: default_text_width(),
         }
     }
 }
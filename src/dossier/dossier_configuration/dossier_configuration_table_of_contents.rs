@@ -1,8 +1,9 @@
 use getset::{CopyGetters, Getters, Setters};
 use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
 
 
-#[derive(Debug, Clone, Deserialize, Serialize, Getters, CopyGetters, Setters)]
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, Getters, CopyGetters, Setters)]
 pub struct DossierConfigurationTableOfContents {
 
     #[getset(get = "pub", set = "pub")]
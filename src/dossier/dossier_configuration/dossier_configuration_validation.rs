@@ -0,0 +1,381 @@
+//! Semantic validation of dossier configuration files. Unlike plain deserialization (which silently
+//! uses defaults for misspelled keys), validation reports every issue found:
+//!
+//! - unknown keys (checked against dossier configuration JSON Schema)
+//! - invalid themes and wrong value types
+//! - missing documents and glob patterns which match nothing
+//! - unreadable local styles
+//!
+//! Each issue has the line of the configuration file which causes it (if it is found).
+
+use std::{fmt::Display, fs::File, path::{Path, PathBuf}};
+use getset::{CopyGetters, Getters};
+use serde_json::{Map, Value};
+use crate::{constants::DOSSIER_CONFIGURATION_FILE_NAMES, resource::{remote_resource::RemoteResource, ResourceError}, theme::Theme, utility::{file_utility, text_utility}};
+use super::{dossier_configuration_documents::{self, EXCLUSION_PREFIX}, dossier_configuration_extension::{self, APPEND_SUFFIX, EXTEND_KEY}, DossierConfiguration};
+
+
+/// Maximum edit distance to suggest a known key in place of an unknown one
+const MAX_SUGGESTION_DISTANCE: usize = 2;
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValidationSeverity {
+    Error,
+    Warning,
+}
+
+impl Display for ValidationSeverity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Error => f.write_str("error"),
+            Self::Warning => f.write_str("warning"),
+        }
+    }
+}
+
+
+#[derive(Debug, Clone, Getters, CopyGetters)]
+pub struct ValidationIssue {
+
+    #[getset(get_copy = "pub")]
+    severity: ValidationSeverity,
+
+    /// Dotted path of key which causes issue (e.g. `style.theme`)
+    #[getset(get = "pub")]
+    key: String,
+
+    #[getset(get = "pub")]
+    message: String,
+
+    /// Line (starting from 1) of configuration file, `None` if it is not found (e.g. key is in a base file)
+    #[getset(get_copy = "pub")]
+    line: Option<usize>,
+}
+
+impl ValidationIssue {
+
+    pub fn new(severity: ValidationSeverity, key: String, message: String, line: Option<usize>) -> Self {
+        Self {
+            severity,
+            key,
+            message,
+            line,
+        }
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == ValidationSeverity::Error
+    }
+}
+
+impl Display for ValidationIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+
+        if let Some(line) = self.line {
+            write!(f, "line {}: ", line)?;
+        }
+
+        write!(f, "{}: {}", self.severity, self.message)?;
+
+        if !self.key.is_empty() {
+            write!(f, " (`{}`)", self.key)?;
+        }
+
+        Ok(())
+    }
+}
+
+
+/// Validate dossier configuration of `path` (dossier directory or configuration file).
+///
+/// `Err` is returned only if configuration file cannot be found or parsed
+pub fn validate_dossier_configuration(path: &Path) -> Result<Vec<ValidationIssue>, ResourceError> {
+
+    let configuration_path = find_configuration_file(path)?;
+
+    let content = file_utility::read_file_content(&configuration_path)?;
+
+    let mut value = dossier_configuration_extension::load_extended_configuration(&configuration_path)?;
+
+    let root_path = configuration_path.parent().map(|parent| parent.to_path_buf()).unwrap_or_default();
+
+    let mut issues: Vec<ValidationIssue> = Vec::new();
+
+    let issue = |severity: ValidationSeverity, path: &[String], message: String| {
+        ValidationIssue::new(severity, path.join("."), message, find_key_line(&content, path))
+    };
+
+    let schema = DossierConfiguration::json_schema();
+
+    let definitions = schema.get("definitions").cloned().unwrap_or(Value::Null);
+
+    for (path, suggestion) in unknown_keys(&value, &schema, &definitions, &mut Vec::new()) {
+
+        let message = match suggestion {
+            Some(suggestion) => format!("unknown key '{}', did you mean '{}'?", path.last().unwrap(), suggestion),
+            None => format!("unknown key '{}'", path.last().unwrap()),
+        };
+
+        issues.push(issue(ValidationSeverity::Warning, &path, message));
+    }
+
+    // invalid theme is reported and removed, so the other checks can be done anyway
+    if let Some(theme) = value.get_mut("style").and_then(|style| style.as_object_mut()).and_then(|style| style.remove("theme")) {
+
+        if serde_json::from_value::<Theme>(theme.clone()).is_err() {
+
+            let path = vec![String::from("style"), String::from("theme")];
+
+            issues.push(issue(ValidationSeverity::Error, &path, format!("invalid theme {}, expected one of: Light, Dark, Scientific, Vintage, HighContrast, None", theme)));
+
+        } else {
+
+            value["style"]["theme"] = theme;
+        }
+    }
+
+    let mut configuration: DossierConfiguration = match serde_path_to_error::deserialize(value) {
+        Ok(configuration) => configuration,
+        Err(err) => {
+
+            let path: Vec<String> = err.path().iter().map(|segment| segment.to_string()).filter(|segment| !segment.eq("?")).collect();
+
+            issues.push(issue(ValidationSeverity::Error, &path, err.inner().to_string()));
+
+            return Ok(issues)
+        }
+    };
+
+    // paths in configuration are relative to its directory
+    configuration.set_root_path(root_path);
+
+    issues.append(&mut validate_documents(&configuration, &content));
+
+    for (raw_style, style) in configuration.style().styles().iter().zip(configuration.style().styles_references()) {
+
+        if RemoteResource::is_valid_remote_resource(&style) {
+            continue;
+        }
+
+        if let Err(err) = File::open(&style) {
+            issues.push(ValidationIssue::new(ValidationSeverity::Error, String::from("style.styles"), format!("style '{}' cannot be read: {}", style, err), find_value_line(&content, raw_style)));
+        }
+    }
+
+    Ok(issues)
+}
+
+fn find_configuration_file(path: &Path) -> Result<PathBuf, ResourceError> {
+
+    if path.is_file() {
+        return Ok(path.to_path_buf())
+    }
+
+    DOSSIER_CONFIGURATION_FILE_NAMES.iter()
+        .map(|file_name| path.join(file_name))
+        .find(|configuration_path| configuration_path.is_file())
+        .ok_or(ResourceError::ResourceNotFound(format!("dossier configuration in {}", path.to_string_lossy())))
+}
+
+fn validate_documents(configuration: &DossierConfiguration, content: &str) -> Vec<ValidationIssue> {
+
+    let mut issues: Vec<ValidationIssue> = Vec::new();
+
+    let key = String::from("documents");
+
+    if configuration.raw_documents_paths().is_empty() {
+        issues.push(ValidationIssue::new(ValidationSeverity::Error, key.clone(), String::from("there are no documents"), find_key_line(content, std::slice::from_ref(&key))));
+    }
+
+    for raw_document in configuration.raw_documents_paths() {
+
+        if raw_document.trim_start().starts_with(EXCLUSION_PREFIX) {
+            continue;
+        }
+
+        let line = find_value_line(content, raw_document);
+
+        match dossier_configuration_documents::resolve_documents(std::slice::from_ref(raw_document), configuration.documents_order()) {
+            Ok(documents) => {
+
+                if documents.is_empty() {
+                    issues.push(ValidationIssue::new(ValidationSeverity::Warning, key.clone(), format!("no documents match '{}'", raw_document), line));
+                }
+
+                for path in documents.iter().flat_map(|document| document.paths()) {
+                    if !path.is_file() {
+                        issues.push(ValidationIssue::new(ValidationSeverity::Error, key.clone(), format!("document '{}' not found", path.to_string_lossy()), line));
+                    }
+                }
+            },
+            Err(err) => issues.push(ValidationIssue::new(ValidationSeverity::Error, key.clone(), err.to_string(), line)),
+        }
+    }
+
+    issues
+}
+
+/// Resolve `$ref`, single `allOf` and nullable `anyOf` of a schema
+fn resolve_schema<'s>(schema: &'s Value, definitions: &'s Value) -> &'s Value {
+
+    if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+        if let Some(definition) = reference.strip_prefix("#/definitions/").and_then(|name| definitions.get(name)) {
+            return resolve_schema(definition, definitions)
+        }
+    }
+
+    for combinator in ["allOf", "anyOf", "oneOf"] {
+        if let Some(subschemas) = schema.get(combinator).and_then(Value::as_array) {
+
+            let object_subschema = subschemas.iter()
+                                    .map(|subschema| resolve_schema(subschema, definitions))
+                                    .find(|subschema| subschema.get("properties").is_some() || subschema.get("additionalProperties").is_some());
+
+            if let Some(object_subschema) = object_subschema {
+                return object_subschema
+            }
+        }
+    }
+
+    schema
+}
+
+/// Return path of unknown keys of `value` with the most similar known key (if any)
+fn unknown_keys(value: &Value, schema: &Value, definitions: &Value, path: &mut Vec<String>) -> Vec<(Vec<String>, Option<String>)> {
+
+    let schema = resolve_schema(schema, definitions);
+
+    let mut unknown: Vec<(Vec<String>, Option<String>)> = Vec::new();
+
+    match value {
+        Value::Object(map) => {
+
+            let empty = Map::new();
+
+            let properties = schema.get("properties").and_then(Value::as_object).unwrap_or(&empty);
+
+            let additional_properties = schema.get("additionalProperties").filter(|additional| additional.is_object());
+
+            if properties.is_empty() && additional_properties.is_none() {
+                return unknown
+            }
+
+            for (key, value) in map {
+
+                let known_key = key.strip_suffix(APPEND_SUFFIX).unwrap_or(key);
+
+                path.push(key.clone());
+
+                if let Some(property) = properties.get(known_key) {
+
+                    unknown.append(&mut unknown_keys(value, property, definitions, path));
+
+                } else if let Some(additional_properties) = additional_properties {
+
+                    unknown.append(&mut unknown_keys(value, additional_properties, definitions, path));
+
+                } else if !key.eq(EXTEND_KEY) {
+
+                    let suggestion = properties.keys()
+                                        .map(|property| (text_utility::edit_distance(known_key, property), property))
+                                        .filter(|(distance, _)| *distance <= MAX_SUGGESTION_DISTANCE)
+                                        .min_by_key(|(distance, _)| *distance)
+                                        .map(|(_, property)| property.clone());
+
+                    unknown.push((path.clone(), suggestion));
+                }
+
+                path.pop();
+            }
+        },
+        Value::Array(items) => {
+
+            if let Some(items_schema) = schema.get("items") {
+
+                for (index, item) in items.iter().enumerate() {
+
+                    path.push(index.to_string());
+
+                    unknown.append(&mut unknown_keys(item, items_schema, definitions, path));
+
+                    path.pop();
+                }
+            }
+        },
+        _ => (),
+    }
+
+    unknown
+}
+
+/// Find line (starting from 1) of a key path in a YAML, JSON or TOML content. Indices of lists are ignored,
+/// if the full path is not found the line of its deepest found parent is returned
+fn find_key_line(content: &str, path: &[String]) -> Option<usize> {
+
+    let lines: Vec<&str> = content.lines().collect();
+
+    let mut from = 0;
+    let mut found: Option<usize> = None;
+
+    for segment in path.iter().filter(|segment| segment.parse::<usize>().is_err()) {
+
+        let key = regex::escape(segment);
+
+        let pattern = regex::Regex::new(&format!(r#"^\s*(?:-\s*)?["']?{}["']?\s*[:=]|^\s*\[+(?:[\w.-]+\.)?{}\]+\s*$"#, key, key)).unwrap();
+
+        match lines.iter().enumerate().skip(from).find(|(_, line)| pattern.is_match(line)) {
+            Some((index, _)) => {
+                found = Some(index + 1);
+                from = index + 1;
+            },
+            None => break,
+        }
+    }
+
+    found
+}
+
+/// Find line (starting from 1) which contains `value`
+fn find_value_line(content: &str, value: &str) -> Option<usize> {
+    content.lines().position(|line| line.contains(value.trim())).map(|index| index + 1)
+}
+
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+    use super::{validate_dossier_configuration, ValidationSeverity};
+
+
+    #[test]
+    fn validate_configuration() {
+
+        let dossier_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("test-resources").join("nmd-test-invalid-configuration");
+
+        let issues = validate_dossier_configuration(&dossier_dir).unwrap();
+
+        let find = |key: &str| issues.iter().find(|issue| issue.key().eq(key)).unwrap();
+
+        let unknown = find("stlye");
+        assert_eq!(unknown.severity(), ValidationSeverity::Warning);
+        assert_eq!(unknown.line(), Some(9));
+        assert!(unknown.message().contains("did you mean 'style'?"));
+
+        let unknown = find("toc.title_typo");
+        assert_eq!(unknown.line(), Some(4));
+
+        let theme = find("style.theme");
+        assert!(theme.is_error());
+        assert_eq!(theme.line(), Some(12));
+
+        let missing_documents: Vec<_> = issues.iter().filter(|issue| issue.key().eq("documents")).collect();
+        assert_eq!(missing_documents.len(), 2);
+        assert_eq!(missing_documents[0].line(), Some(16));
+        assert_eq!(missing_documents[1].severity(), ValidationSeverity::Warning);
+
+        let style = find("style.styles");
+        assert!(style.is_error());
+        assert_eq!(style.line(), Some(14));
+    }
+}
@@ -0,0 +1,116 @@
+use std::{collections::HashMap, fmt::Display};
+use getset::{Getters, Setters};
+use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+use crate::resource::{variable::{Variable, VariableType}, ResourceError};
+
+
+/// Scalar value of a variable written in `nmd.yml` (numbers and booleans are allowed without quotes)
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, JsonSchema)]
+#[serde(untagged)]
+pub enum DossierConfigurationVariableValue {
+    Boolean(bool),
+    Integer(i64),
+    Float(f64),
+    String(String),
+}
+
+impl Display for DossierConfigurationVariableValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Boolean(value) => write!(f, "{}", value),
+            Self::Integer(value) => write!(f, "{}", value),
+            Self::Float(value) => write!(f, "{}", value),
+            Self::String(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+
+/// Typed variable, e.g.:
+///
+/// ```yaml
+/// variables:
+///   audience:
+///     type: string
+///     default: developers
+///     documents:
+///       user-guide.nmd: end users
+/// ```
+#[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema, Getters, Setters)]
+pub struct DossierConfigurationVariable {
+
+    #[serde(rename = "type", default)]
+    #[getset(get = "pub", set = "pub")]
+    variable_type: VariableType,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get = "pub", set = "pub")]
+    default: Option<DossierConfigurationVariableValue>,
+
+    /// Per-document values, keys are document file names
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    #[getset(get = "pub", set = "pub")]
+    documents: HashMap<String, DossierConfigurationVariableValue>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get = "pub", set = "pub")]
+    description: Option<String>,
+}
+
+impl DossierConfigurationVariable {
+
+    pub fn new(variable_type: VariableType, default: Option<DossierConfigurationVariableValue>) -> Self {
+        Self {
+            variable_type,
+            default,
+            ..Default::default()
+        }
+    }
+
+    pub fn build_variable(&self) -> Variable {
+        Variable::new(
+            self.variable_type.clone(),
+            self.default.as_ref().map(|value| value.to_string()),
+            self.documents.iter().map(|(document, value)| (document.clone(), value.to_string())).collect()
+        )
+    }
+
+    /// Check that default and per-document values match declared type
+    pub fn check(&self, key: &str) -> Result<(), ResourceError> {
+
+        let variable = self.build_variable();
+
+        if let Some(value) = variable.invalid_values().first() {
+            return Err(ResourceError::InvalidResourceVerbose(format!("value '{}' of variable '{}' is not a valid {:?}", value, key, self.variable_type)))
+        }
+
+        Ok(())
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::DossierConfigurationVariable;
+    use crate::resource::variable::VariableType;
+
+
+    #[test]
+    fn deserialize_typed_values() {
+
+        let yaml = concat!(
+            "type: number\n",
+            "default: 3\n",
+            "documents:\n",
+            "  a.nmd: 4.5\n",
+            "  b.nmd: many\n",
+        );
+
+        let variable: DossierConfigurationVariable = serde_yaml::from_str(yaml).unwrap();
+
+        assert_eq!(variable.variable_type(), &VariableType::Number);
+        assert_eq!(variable.build_variable().value().as_ref().unwrap(), "3");
+        assert!(variable.check("count").is_err());
+    }
+}
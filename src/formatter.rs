@@ -0,0 +1,336 @@
+//! Canonical formatting of NMD sources.
+//!
+//! Sources are segmented using loading rules of codex (as documents load does), then each segment is normalized:
+//! headings are written using extended version (e.g. `###`), paragraphs are separated by a single blank line,
+//! table columns are aligned and list indentation is normalized to bullet indentation levels.
+//! Other paragraphs (e.g. code blocks, includes and conditional blocks) are kept as they are.
+
+
+use std::io;
+use std::path::{Path, PathBuf};
+use getset::{Getters, Setters};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use thiserror::Error;
+use crate::codex::Codex;
+use crate::codex::modifier::standard_paragraph_modifier::StandardParagraphModifier;
+use crate::compilation::compilation_rule::constants::SPACE_TAB_EQUIVALENCE;
+use crate::constants::NMD_EXTENSION;
+use crate::dossier::document::chapter::chapter_header::ChapterHeader;
+use crate::dossier::document::chapter::heading::HeadingLevel;
+use crate::dossier::document::document_metadata::DocumentMetadata;
+use crate::load::{LoadConfiguration, LoadError};
+use crate::load_block::source_segment::{SourceSegment, SourceSegmentKind};
+use crate::utility::file_utility;
+
+
+static LIST_ITEM_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(&StandardParagraphModifier::ListItem.modifier_pattern()).unwrap());
+
+static TABLE_DELIMITER_CELL_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"^(:?)-+(:?)(\s*\(\d+\))?$").unwrap());
+
+
+#[derive(Error, Debug)]
+pub enum FormatterError {
+
+    #[error(transparent)]
+    IoError(#[from] io::Error),
+
+    #[error(transparent)]
+    LoadError(#[from] LoadError),
+}
+
+
+#[derive(Debug, Getters, Setters)]
+pub struct Formatter {
+
+    #[getset(get = "pub", set = "pub")]
+    codex: Codex,
+}
+
+impl Formatter {
+
+    pub fn new(codex: Codex) -> Self {
+        Self {
+            codex,
+        }
+    }
+
+    /// Format NMD source, front matter is kept as it is
+    pub fn format_str(&self, content: &str) -> Result<String, FormatterError> {
+
+        let content = content.replace("\r\n", "\n");
+
+        let (_, body_start) = DocumentMetadata::load_front_matter(&content)?;
+
+        let mut outcome = String::new();
+
+        if body_start > 0 {
+            outcome.push_str(content[..body_start].trim_end());
+            outcome.push_str("\n\n");
+        }
+
+        let mut configuration = LoadConfiguration::default();
+        configuration.set_parallelization(false);
+
+        let segments = SourceSegment::segment(&content[body_start..], &self.codex, &configuration)?;
+
+        let blocks: Vec<String> = segments.iter()
+                                    .map(Self::format_segment)
+                                    .filter(|block| !block.is_empty())
+                                    .collect();
+
+        if blocks.is_empty() {
+            return Ok(outcome.trim_end().to_string() + "\n")
+        }
+
+        outcome.push_str(&blocks.join("\n\n"));
+        outcome.push('\n');
+
+        Ok(outcome)
+    }
+
+    /// Return `true` if file is not formatted
+    pub fn check_file(&self, path: &PathBuf) -> Result<bool, FormatterError> {
+
+        let content = file_utility::read_file_content(path)?;
+
+        Ok(self.format_str(&content)? != content)
+    }
+
+    /// Format file in place, return `true` if it is changed
+    pub fn format_file(&self, path: &PathBuf) -> Result<bool, FormatterError> {
+
+        let content = file_utility::read_file_content(path)?;
+
+        let formatted = self.format_str(&content)?;
+
+        if formatted == content {
+            return Ok(false)
+        }
+
+        file_utility::create_file_with_content(path, &formatted)?;
+
+        Ok(true)
+    }
+
+    /// NMD files in `path` (directories are searched recursively), sorted by path
+    pub fn nmd_files(path: &Path) -> Vec<PathBuf> {
+
+        if !path.is_dir() {
+            return vec![path.to_path_buf()]
+        }
+
+        let pattern = path.join("**").join(format!("*.{}", NMD_EXTENSION));
+
+        let mut files: Vec<PathBuf> = glob::glob(&pattern.to_string_lossy())
+                                        .map(|paths| paths.filter_map(Result::ok).collect())
+                                        .unwrap_or_default();
+
+        files.sort();
+
+        files
+    }
+
+    fn format_segment(segment: &SourceSegment) -> String {
+
+        let raw = segment.raw();
+
+        match segment.kind() {
+            SourceSegmentKind::Paragraph(identifier) if identifier.eq(&StandardParagraphModifier::List.identifier()) => Self::format_list(raw),
+            SourceSegmentKind::Paragraph(identifier) if identifier.eq(&StandardParagraphModifier::Table.identifier()) => Self::format_table(raw),
+            SourceSegmentKind::Paragraph(_) => raw.trim_matches('\n').trim_end().to_string(),
+            SourceSegmentKind::Heading(header) => Self::format_heading(header, raw),
+            SourceSegmentKind::Text => raw.trim().lines().map(|line| line.trim_end()).collect::<Vec<&str>>().join("\n"),
+        }
+    }
+
+    /// Heading is written using extended version (relative headings are kept), lines of chapter tags and styles are kept
+    fn format_heading(header: &ChapterHeader, raw: &str) -> String {
+
+        let marker = match header.heading().level() {
+            HeadingLevel::Explicit(level) => "#".repeat(*level as usize),
+            HeadingLevel::Minor => String::from("#-"),
+            HeadingLevel::Major => String::from("#+"),
+            HeadingLevel::Same => String::from("#="),
+        };
+
+        let mut lines: Vec<String> = vec![format!("{} {}", marker, header.heading().title().trim())];
+
+        lines.extend(raw.trim().lines().skip(1).map(|line| line.trim_end().to_string()));
+
+        lines.join("\n")
+    }
+
+    /// List items are indented using `SPACE_TAB_EQUIVALENCE` for each indentation level (as bullets configuration does)
+    fn format_list(raw: &str) -> String {
+
+        raw.trim_matches('\n').lines().map(|line| {
+
+            match LIST_ITEM_REGEX.captures(line) {
+                Some(captures) => {
+
+                    let mut indentation = captures[1].replace('\t', SPACE_TAB_EQUIVALENCE);
+                    let mut indentation_level: usize = 0;
+
+                    while indentation.starts_with(SPACE_TAB_EQUIVALENCE) {
+                        indentation = indentation.split_off(SPACE_TAB_EQUIVALENCE.len());
+                        indentation_level += 1;
+                    }
+
+                    format!("{}{} {}", SPACE_TAB_EQUIVALENCE.repeat(indentation_level), &captures[2], captures[3].trim()).trim_end().to_string()
+                },
+                None => line.trim_end().to_string(),
+            }
+        }).collect::<Vec<String>>().join("\n")
+    }
+
+    /// Table rows are split as table loading rule does, so columns can be aligned.
+    /// Lines which are not rows (i.e. caption, identifier and style) are kept
+    fn format_table(raw: &str) -> String {
+
+        let lines: Vec<&str> = raw.trim_matches('\n').lines().collect();
+
+        // cells and trailing content (e.g. row continuation marker) of each row
+        let rows: Vec<Option<(Vec<String>, String)>> = lines.iter().map(|line| {
+
+            let line = line.trim();
+
+            line.strip_prefix('|').map(|line| {
+
+                let mut cells: Vec<String> = line.split('|').map(|cell| cell.trim().to_string()).collect();
+
+                let trailer = cells.pop().unwrap_or_default();
+
+                (cells, trailer)
+            })
+        }).collect();
+
+        let is_delimiter_row = |cells: &Vec<String>| !cells.is_empty() && cells.iter().all(|cell| TABLE_DELIMITER_CELL_REGEX.is_match(cell));
+
+        let mut widths: Vec<usize> = Vec::new();
+
+        for (cells, _) in rows.iter().flatten() {
+
+            let delimiter = is_delimiter_row(cells);
+
+            for (index, cell) in cells.iter().enumerate() {
+
+                // delimiters have at least 3 dashes
+                let width = if delimiter { cell.chars().filter(|c| *c != '-').count() + 3 } else { cell.chars().count() };
+
+                if index >= widths.len() {
+                    widths.push(width);
+                } else {
+                    widths[index] = widths[index].max(width);
+                }
+            }
+        }
+
+        lines.iter().zip(rows.iter()).map(|(line, row)| {
+
+            let (cells, trailer) = match row {
+                Some(row) => row,
+                None => return line.trim().to_string(),
+            };
+
+            let delimiter = is_delimiter_row(cells);
+
+            let cells: Vec<String> = cells.iter().enumerate().map(|(index, cell)| {
+
+                if delimiter {
+
+                    let captures = TABLE_DELIMITER_CELL_REGEX.captures(cell).unwrap();
+
+                    let suffix = captures.get(3).map(|suffix| format!(" {}", suffix.as_str().trim())).unwrap_or_default();
+
+                    let dashes = widths[index] - captures[1].len() - captures[2].len() - suffix.chars().count();
+
+                    return format!("{}{}{}{}", &captures[1], "-".repeat(dashes.max(3)), &captures[2], suffix)
+                }
+
+                format!("{}{}", cell, " ".repeat(widths[index] - cell.chars().count()))
+            }).collect();
+
+            let row = if cells.is_empty() { String::from("|") } else { format!("| {} |", cells.join(" | ")) };
+
+            if trailer.is_empty() { row } else { format!("{} {}", row, trailer) }
+
+        }).collect::<Vec<String>>().join("\n")
+    }
+}
+
+impl Default for Formatter {
+    fn default() -> Self {
+        Self::new(Codex::of_html())
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::Formatter;
+
+
+    #[test]
+    fn format_document() {
+
+        let source = concat!(
+            "---\n",
+            "title: Test\n",
+            "---\n",
+            "#2 Title   \n",
+            "text   \n",
+            "more text\n",
+            "\n\n\n",
+            "- a\n",
+            "  - b\n",
+            "\t- c  \n",
+            "      - d\n",
+            "| Name | V |\n",
+            "|:-|--:|\n",
+            "| alpha | 1 |\n",
+            "[Caption]#table-id\n",
+            "\n",
+            "```\n",
+            "code   \n",
+            "```\n",
+            "#+ Relative\n",
+            "@include(./missing.nmd)\n",
+        );
+
+        let formatter = Formatter::default();
+
+        let formatted = formatter.format_str(source).unwrap();
+
+        assert_eq!(formatted, concat!(
+            "---\n",
+            "title: Test\n",
+            "---\n",
+            "\n",
+            "## Title\n",
+            "\n",
+            "text\n",
+            "more text\n",
+            "\n",
+            "- a\n",
+            "- b\n",
+            "   - c\n",
+            "      - d\n",
+            "\n",
+            "| Name  | V    |\n",
+            "| :---- | ---: |\n",
+            "| alpha | 1    |\n",
+            "[Caption]#table-id\n",
+            "\n",
+            "```\n",
+            "code   \n",
+            "```\n",
+            "\n",
+            "#+ Relative\n",
+            "\n",
+            "@include(./missing.nmd)\n",
+        ));
+
+        assert_eq!(formatter.format_str(&formatted).unwrap(), formatted);
+    }
+}
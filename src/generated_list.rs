@@ -0,0 +1,324 @@
+use std::collections::HashSet;
+use getset::{CopyGetters, Getters, Setters};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Serialize;
+use crate::{codex::Codex, compilable_text::{compilable_text_part::CompilableTextPart, CompilableText}, compilation::{compilable::Compilable, compilation_configuration::{compilation_configuration_overlay::CompilationConfigurationOverLay, CompilationConfiguration}, compilation_error::CompilationError, compilation_outcome::CompilationOutcome}, dossier::dossier_configuration::dossier_configuration_generated_list::DossierConfigurationGeneratedList, output_format::OutputFormat, resource::resource_reference::{ResourceReference, ResourceReferenceError}, utility::html_utility::{decode_entities, escape_xml, parse_start_tag, start_tag, HtmlElement}};
+
+
+/// Start of compiled elements which can be listed, tags are parsed to read classes and id
+static LISTABLE_ELEMENT_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"<(?:figure|table|div)[\s/>]").unwrap());
+
+static FIGURE_CAPTION_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r#"(?s)<figcaption[^>]*>(.*?)</figcaption>"#).unwrap());
+
+static TABLE_CAPTION_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r#"(?s)<div class="table-caption">(.*?)</div>"#).unwrap());
+
+static TODO_DESCRIPTION_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r#"(?s)^<div class="todo-title">(.*?)</div>(?:<div class="todo-description">(.*?)</div>)?"#).unwrap());
+
+static BOOKMARK_TITLE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r#"(?s)^<div class="(?:abridged-)?bookmark-title">(.*?)</div>"#).unwrap());
+
+static TAG_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"<[^>]+>").unwrap());
+
+static WHITESPACES_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\s+").unwrap());
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum GeneratedListKind {
+    Figures,
+    Tables,
+    Todos,
+}
+
+impl GeneratedListKind {
+
+    pub fn default_title(&self) -> &'static str {
+        match self {
+            Self::Figures => "List of figures",
+            Self::Tables => "List of tables",
+            Self::Todos => "List of todos",
+        }
+    }
+
+    pub fn css_class(&self) -> &'static str {
+        match self {
+            Self::Figures => "list-of-figures",
+            Self::Tables => "list-of-tables",
+            Self::Todos => "list-of-todos",
+        }
+    }
+
+    fn id_prefix(&self) -> &'static str {
+        match self {
+            Self::Figures => "figure",
+            Self::Tables => "table",
+            Self::Todos => "todo",
+        }
+    }
+
+    /// Kind of compiled element, todos list contains bookmarks too
+    fn of_element(element: &HtmlElement) -> Option<Self> {
+
+        match element.name.as_str() {
+            "figure" => Some(Self::Figures),
+            "table" => Some(Self::Tables),
+            _ if element.has_class("todo") || element.has_class("bookmark") || element.has_class("abridged-bookmark") => Some(Self::Todos),
+            _ => None,
+        }
+    }
+}
+
+
+/// Generated section which links dossier items (e.g. list of figures)
+#[derive(Debug, Clone, Getters, CopyGetters, Setters, Serialize)]
+pub struct GeneratedList {
+
+    #[getset(get_copy = "pub")]
+    kind: GeneratedListKind,
+
+    #[getset(get = "pub", set = "pub")]
+    title: String,
+
+    /// Listed items as (id, label)
+    #[getset(get = "pub", set = "pub")]
+    items: Vec<(String, String)>,
+}
+
+impl GeneratedList {
+
+    pub fn new(kind: GeneratedListKind, title: String) -> Self {
+        Self {
+            kind,
+            title,
+            items: Vec::new(),
+        }
+    }
+
+    pub fn from_configuration(kind: GeneratedListKind, configuration: &DossierConfigurationGeneratedList) -> Self {
+        Self::new(kind, configuration.title().clone().unwrap_or(String::from(kind.default_title())))
+    }
+
+    /// Assign an id to each listable element of compiled document which has not one, ids are unique in dossier because document name is used as prefix.
+    /// Existing ids are normalized, so they can be used in links, and made unique in document (e.g. figures with same caption)
+    pub fn assign_ids(compiled_content: &str, document_name: &str, kinds: &[GeneratedListKind]) -> Result<String, ResourceReferenceError> {
+
+        let mut result = String::new();
+        let mut last_position: usize = 0;
+        let mut counters = [0usize; 3];
+        let mut used_ids: HashSet<String> = HashSet::new();
+
+        for (start, mut element, end) in listable_elements(compiled_content) {
+
+            let Some(kind) = GeneratedListKind::of_element(&element).filter(|kind| kinds.contains(kind)) else {
+                continue;
+            };
+
+            let id = element.attribute("id").filter(|id| !id.trim().is_empty()).cloned();
+
+            // ids can be built from captions or image sources (e.g. `Figure one`), they must be valid in links
+            let mut new_id = match &id {
+                Some(id) => normalize_id(id),
+                None => {
+
+                    counters[kind as usize] += 1;
+
+                    ResourceReference::of_internal_from_without_sharp(&format!("{}-{}", kind.id_prefix(), counters[kind as usize]), Some(&document_name))?.build_without_internal_sharp()
+                }
+            };
+
+            if used_ids.contains(&new_id) {
+
+                let base_id = new_id.clone();
+
+                new_id = (2..).map(|n| format!("{}-{}", base_id, n)).find(|candidate| !used_ids.contains(candidate)).unwrap();
+            }
+
+            used_ids.insert(new_id.clone());
+
+            if id.as_ref() == Some(&new_id) {
+                continue;
+            }
+
+            element.attributes.retain(|(name, _)| name.ne("id"));
+            element.attributes.insert(0, (String::from("id"), new_id));
+
+            result.push_str(&compiled_content[last_position..start]);
+            result.push_str(&start_tag(&element));
+
+            last_position = end;
+        }
+
+        result.push_str(&compiled_content[last_position..]);
+
+        Ok(result)
+    }
+
+    /// Add items of a compiled document, figures and tables without caption are not listed
+    pub fn add_items(&mut self, compiled_content: &str) {
+
+        for (_, element, end) in listable_elements(compiled_content) {
+
+            if GeneratedListKind::of_element(&element) != Some(self.kind) {
+                continue;
+            }
+
+            let Some(id) = element.attribute("id").cloned() else {
+                continue;
+            };
+
+            let following_content = &compiled_content[end..];
+
+            let label = match self.kind {
+                GeneratedListKind::Figures => following_content.find("</figure>")
+                                                .and_then(|end| FIGURE_CAPTION_REGEX.captures(&following_content[..end]))
+                                                .map(|caption| caption[1].to_string()),
+                GeneratedListKind::Tables => following_content.find("</table>")
+                                                .and_then(|end| TABLE_CAPTION_REGEX.captures(&following_content[..end]))
+                                                .map(|caption| caption[1].to_string()),
+                GeneratedListKind::Todos => TODO_DESCRIPTION_REGEX.captures(following_content)
+                                                .map(|todo| todo.get(2).unwrap_or(todo.get(1).unwrap()).as_str().to_string())
+                                                .or(BOOKMARK_TITLE_REGEX.captures(following_content).map(|bookmark| bookmark[1].to_string())),
+            };
+
+            // labels are stored as text, they are escaped again when list is compiled
+            let Some(label) = label.map(|label| decode_entities(&WHITESPACES_REGEX.replace_all(TAG_REGEX.replace_all(&label, " ").trim(), " "))) else {
+                continue;
+            };
+
+            if label.is_empty() && self.kind != GeneratedListKind::Todos {
+                continue;
+            }
+
+            self.items.push((id, label));
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+}
+
+impl Compilable for GeneratedList {
+    fn standard_compile(&mut self, format: &OutputFormat, codex: &Codex, compilation_configuration: &CompilationConfiguration, compilation_configuration_overlay: CompilationConfigurationOverLay) -> Result<CompilationOutcome, CompilationError> {
+        log::info!("compiling {} ({} items)...", self.kind.css_class(), self.items.len());
+
+        match format {
+            OutputFormat::Html | OutputFormat::Epub | OutputFormat::Docx | OutputFormat::Text | OutputFormat::Man => {
+                let mut compilation_result = CompilableText::new_empty();
+
+                let mut compiled_title = CompilableText::from(self.title.clone());
+
+                compiled_title.compile(format, codex, compilation_configuration, compilation_configuration_overlay.clone())?;
+
+                compilation_result.parts_mut().push(CompilableTextPart::new_fixed(format!(r#"<section class="generated-list {}"><div class="generated-list-title">"#, self.kind.css_class())));
+                compilation_result.parts_mut().append(compiled_title.parts_mut());
+                compilation_result.parts_mut().push(CompilableTextPart::new_fixed(String::from(r#"</div><ol class="generated-list-body">"#)));
+
+                for (n, (id, label)) in self.items.iter().enumerate() {
+
+                    // todos without description are numbered
+                    let label = if label.is_empty() { (n + 1).to_string() } else { escape_xml(label) };
+
+                    compilation_result.parts_mut().push(CompilableTextPart::new_fixed(format!(r##"<li class="generated-list-item"><a href="#{}">{}</a></li>"##, escape_xml(id), label)));
+                }
+
+                compilation_result.parts_mut().push(CompilableTextPart::new_fixed(String::from("</ol></section>")));
+
+                log::info!("{} compiled", self.kind.css_class());
+
+                Ok(CompilationOutcome::from(&compilation_result))
+            },
+        }
+    }
+}
+
+/// Parsed start tags of elements which can be listed as (start, element, end)
+fn listable_elements(compiled_content: &str) -> impl Iterator<Item = (usize, HtmlElement, usize)> + '_ {
+    LISTABLE_ELEMENT_REGEX.find_iter(compiled_content)
+        .filter_map(|m| parse_start_tag(compiled_content, m.start()).map(|(element, end)| (m.start(), element, end)))
+}
+
+/// Replace characters which are not allowed in ids and URL fragments
+fn normalize_id(id: &str) -> String {
+    id.trim().chars().map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '-' }).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{codex::Codex, compilation::{compilable::Compilable, compilation_configuration::{compilation_configuration_overlay::CompilationConfigurationOverLay, CompilationConfiguration}}, output_format::OutputFormat};
+    use super::{GeneratedList, GeneratedListKind};
+
+
+    #[test]
+    fn collect_items() {
+
+        let content = concat!(
+            r#"<figure class="figure " style="" id="Logo caption" ><img src="logo.png"><figcaption class="image-caption">Logo <em>caption</em></figcaption></figure>"#,
+            r#"<figure class="figure "><img src="other.png"></figure>"#,
+            r#"<table class="table" data-id="x"><tr><td>1</td></tr><caption><div class="table-caption">a &lt; b &amp; c</div></caption></table>"#,
+            r#"<div class="todo"><div class="todo-title"></div><div class="todo-description">fix this</div></div>"#,
+            r#"<div class=" bookmark" ><div class="bookmark-title">Remember</div><div class="bookmark-description">later</div></div>"#,
+        );
+
+        let kinds = [GeneratedListKind::Figures, GeneratedListKind::Tables, GeneratedListKind::Todos];
+
+        let content = GeneratedList::assign_ids(content, "d1.nmd", &kinds).unwrap();
+
+        assert!(content.contains(r#"<figure id="Logo-caption" class="figure " style="">"#));
+        assert!(content.contains(r#"<figure id="d1-nmd-figure-1" class="figure ">"#));
+        assert!(content.contains(r#"<table id="d1-nmd-table-1" class="table" data-id="x">"#));
+        assert!(content.contains(r#"<div id="d1-nmd-todo-2" class=" bookmark">"#));
+
+        let mut figures = GeneratedList::new(GeneratedListKind::Figures, String::from("Figures"));
+        let mut tables = GeneratedList::new(GeneratedListKind::Tables, String::from("Tables"));
+        let mut todos = GeneratedList::new(GeneratedListKind::Todos, String::from("Todos"));
+
+        figures.add_items(&content);
+        tables.add_items(&content);
+        todos.add_items(&content);
+
+        assert_eq!(figures.items(), &vec![(String::from("Logo-caption"), String::from("Logo caption"))]);
+        assert_eq!(tables.items(), &vec![(String::from("d1-nmd-table-1"), String::from("a < b & c"))]);
+        assert_eq!(todos.items(), &vec![(String::from("d1-nmd-todo-1"), String::from("fix this")), (String::from("d1-nmd-todo-2"), String::from("Remember"))]);
+
+        let outcome = tables.compile(&OutputFormat::Html, &Codex::of_html(), &CompilationConfiguration::default(), CompilationConfigurationOverLay::default()).unwrap();
+
+        assert!(outcome.content().starts_with(r#"<section class="generated-list list-of-tables"><div class="generated-list-title">Tables</div>"#));
+        assert!(outcome.content().contains(r##"<li class="generated-list-item"><a href="#d1-nmd-table-1">a &lt; b &amp; c</a></li>"##));
+    }
+
+    #[test]
+    fn assign_unique_ids_to_same_captions() {
+
+        let content = concat!(
+            r#"<figure class="figure " style="" id="Logo" ><img src="a.png"><figcaption class="image-caption">Logo</figcaption></figure>"#,
+            r#"<figure class="figure " style="" id="Logo" ><img src="b.png"><figcaption class="image-caption">Logo</figcaption></figure>"#,
+            r#"<figure class="figure " style="" id="Logo-2" ><img src="c.png"><figcaption class="image-caption">Logo</figcaption></figure>"#,
+        );
+
+        let content = GeneratedList::assign_ids(content, "d1.nmd", &[GeneratedListKind::Figures]).unwrap();
+
+        let mut figures = GeneratedList::new(GeneratedListKind::Figures, String::from("Figures"));
+
+        figures.add_items(&content);
+
+        let ids: Vec<&str> = figures.items().iter().map(|(id, _)| id.as_str()).collect();
+
+        assert_eq!(ids, vec!["Logo", "Logo-2", "Logo-2-2"]);
+    }
+
+    #[test]
+    fn assign_ids_to_captions_with_quotes() {
+
+        let content = r#"<figure class="figure " style="" id="He said &quot;pic&quot; &lt;b&gt;" data-note='a > b' ><img src="a.png"><figcaption class="image-caption">He said &quot;pic&quot; &lt;b&gt;</figcaption></figure>"#;
+
+        let content = GeneratedList::assign_ids(content, "d1.nmd", &[GeneratedListKind::Figures]).unwrap();
+
+        assert!(content.starts_with(r#"<figure id="He-said--pic---b-" class="figure " style="" data-note="a &gt; b"><img src="a.png">"#));
+
+        let mut figures = GeneratedList::new(GeneratedListKind::Figures, String::from("Figures"));
+
+        figures.add_items(&content);
+
+        assert_eq!(figures.items(), &vec![(String::from("He-said--pic---b-"), String::from(r#"He said "pic" <b>"#))]);
+    }
+}
@@ -0,0 +1,248 @@
+pub mod glossary_entry;
+
+use std::collections::{BTreeMap, HashSet};
+use getset::{CopyGetters, Getters, Setters};
+use glossary_entry::GlossaryEntry;
+use once_cell::sync::Lazy;
+use regex::{Captures, Regex};
+use serde::Serialize;
+use crate::{codex::Codex, compilable_text::{compilable_text_part::CompilableTextPart, CompilableText}, compilation::{compilable::Compilable, compilation_configuration::{compilation_configuration_overlay::CompilationConfigurationOverLay, CompilationConfiguration}, compilation_error::CompilationError, compilation_outcome::CompilationOutcome, compilation_rule::constants::ESCAPE_HTML}, dossier::dossier_configuration::dossier_configuration_glossary::DossierConfigurationGlossary, output_format::OutputFormat, resource::resource_reference::{ResourceReference, ResourceReferenceError}, utility::{html_utility::escape_xml, text_utility}};
+
+
+pub const GLOSSARY_FICTITIOUS_DOCUMENT: &str = "glossary";
+
+/// Compiled term uses (see `Glossary::compile_use`)
+static TERM_USE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r#"<(?:a|abbr) class="glossary-term" data-glossary-key="([\w-]+)"[^>]*>.*?</(?:a|abbr)>"#).unwrap());
+
+
+#[derive(Debug, Clone, Getters, CopyGetters, Setters, Serialize)]
+pub struct Glossary {
+
+    #[getset(get = "pub", set = "pub")]
+    title: String,
+
+    #[getset(get = "pub", set = "pub")]
+    entries: BTreeMap<String, GlossaryEntry>,
+
+    /// If glossary is in output, term uses are links to their entries
+    #[getset(get_copy = "pub", set = "pub")]
+    include_in_output: bool,
+}
+
+impl Glossary {
+    pub fn new(title: String, entries: BTreeMap<String, GlossaryEntry>, include_in_output: bool) -> Self {
+        Self {
+            title,
+            entries,
+            include_in_output,
+        }
+    }
+
+    /// Displayed term of entry
+    pub fn term_of(&self, key: &str) -> Option<String> {
+        self.entries.get(key).map(|entry| entry.term().clone().unwrap_or(key.to_string()))
+    }
+
+    pub fn get_reference_from_key(&self, target_key: &str) -> Option<Result<ResourceReference, ResourceReferenceError>> {
+        if self.entries.contains_key(target_key) {
+            return Some(ResourceReference::of_internal_from_without_sharp(target_key, Some(&GLOSSARY_FICTITIOUS_DOCUMENT)))
+        }
+
+        None
+    }
+
+    /// HTML of a term use: a link to glossary entry if glossary is in output, otherwise an abbreviation.
+    /// Both have definition as tooltip
+    pub fn compile_use(&self, key: &str) -> Option<Result<String, ResourceReferenceError>> {
+
+        let entry = self.entries.get(key)?;
+        let term = escape_xml(&self.term_of(key).unwrap());
+
+        let title = entry.tooltip().map(|tooltip| format!(r#" title="{}""#, escape_xml(&tooltip))).unwrap_or_default();
+
+        if self.include_in_output {
+
+            return Some(self.get_reference_from_key(key)?.map(|reference| {
+                format!(r#"<a class="glossary-term" data-glossary-key="{}" href="{}"{}>{}</a>"#, key, reference.build(), title, term)
+            }))
+        }
+
+        Some(Ok(format!(r#"<abbr class="glossary-term" data-glossary-key="{}"{}>{}</abbr>"#, key, title, term)))
+    }
+
+    /// Expand first use of each acronym in compiled content (e.g. `Application Programming Interface (API)`),
+    /// next uses are kept as they are
+    pub fn expand_first_uses(&self, compiled_content: &str) -> String {
+
+        let mut used_keys: HashSet<String> = HashSet::new();
+
+        TERM_USE_REGEX.replace_all(compiled_content, |captures: &Captures| {
+
+            let key = &captures[1];
+
+            if !used_keys.insert(key.to_string()) {
+                return captures[0].to_string()
+            }
+
+            match self.entries.get(key) {
+                Some(entry) if entry.expansion().is_some() => {
+
+                    let title = entry.tooltip().map(|tooltip| format!(r#" title="{}""#, escape_xml(&tooltip))).unwrap_or_default();
+
+                    format!(r#"<span class="glossary-term glossary-first-use"{}>{} ({})</span>"#,
+                        title,
+                        escape_xml(entry.expansion().as_ref().unwrap()),
+                        escape_xml(&self.term_of(key).unwrap())
+                    )
+                },
+                _ => captures[0].to_string(),
+            }
+
+        }).to_string()
+    }
+}
+
+impl From<&DossierConfigurationGlossary> for Glossary {
+    fn from(dcg: &DossierConfigurationGlossary) -> Self {
+        Self {
+            title: dcg.title().clone(),
+            entries: dcg.entries().clone(),
+            include_in_output: dcg.include_in_output(),
+        }
+    }
+}
+
+impl Compilable for Glossary {
+    fn standard_compile(&mut self, format: &OutputFormat, codex: &Codex, compilation_configuration: &CompilationConfiguration, compilation_configuration_overlay: CompilationConfigurationOverLay) -> Result<CompilationOutcome, CompilationError> {
+        log::info!("compiling glossary...");
+
+        match format {
+            OutputFormat::Html | OutputFormat::Epub | OutputFormat::Docx | OutputFormat::Text | OutputFormat::Man => {
+                let mut compilation_result = CompilableText::new_empty();
+
+                let mut compiled_title = CompilableText::from(self.title.clone());
+
+                compiled_title.compile(format, codex, compilation_configuration, compilation_configuration_overlay.clone())?;
+
+                compilation_result.parts_mut().push(CompilableTextPart::new_fixed(String::from(r#"<section class="glossary"><div class="glossary-title">"#)));
+                compilation_result.parts_mut().append(compiled_title.parts_mut());
+                compilation_result.parts_mut().push(CompilableTextPart::new_fixed(String::from(r#"</div><dl class="glossary-body">"#)));
+
+                // entries are sorted by displayed term
+                let mut keys: Vec<&String> = self.entries.keys().collect();
+                keys.sort_by_cached_key(|key| self.term_of(key).unwrap().to_lowercase());
+
+                for key in keys {
+
+                    let entry = self.entries.get(key).unwrap();
+
+                    compilation_result.parts_mut().push(CompilableTextPart::new_fixed(format!(
+                        r#"<dt class="glossary-item-term" id="{}">{}</dt><dd class="glossary-item-definition">"#,
+                        ResourceReference::of_internal_from_without_sharp(key,
+                            Some(&GLOSSARY_FICTITIOUS_DOCUMENT))?.build_without_internal_sharp(),
+                            escape_xml(&self.term_of(key).unwrap())
+                        )));
+
+                    if let Some(expansion) = entry.expansion() {
+
+                        compilation_result.parts_mut().push(CompilableTextPart::new_fixed(format!(r#"<span class="glossary-item-expansion">{}</span>"#, escape_xml(expansion))));
+                    }
+
+                    if let Some(definition) = entry.definition() {
+
+                        // definition is NMD text, so its HTML is escaped as paragraphs do
+                        let mut compiled_definition = CompilableText::from(text_utility::replace(definition, &ESCAPE_HTML));
+
+                        compiled_definition.compile(format, codex, compilation_configuration, compilation_configuration_overlay.clone())?;
+
+                        let separator = if entry.expansion().is_some() { ": " } else { "" };
+
+                        compilation_result.parts_mut().push(CompilableTextPart::new_fixed(format!(r#"{}<span class="glossary-item-description">"#, separator)));
+                        compilation_result.parts_mut().append(compiled_definition.parts_mut());
+                        compilation_result.parts_mut().push(CompilableTextPart::new_fixed(String::from(r#"</span>"#)));
+                    }
+
+                    compilation_result.parts_mut().push(CompilableTextPart::new_fixed(String::from(r#"</dd>"#)));
+                }
+
+                compilation_result.parts_mut().push(CompilableTextPart::new_fixed(String::from(r#"</dl></section>"#)));
+
+                log::info!("glossary compiled");
+
+                Ok(CompilationOutcome::from(&compilation_result))
+            },
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+    use crate::{codex::Codex, compilation::{compilable::Compilable, compilation_configuration::{compilation_configuration_overlay::CompilationConfigurationOverLay, CompilationConfiguration}}, output_format::OutputFormat};
+    use super::{glossary_entry::GlossaryEntry, Glossary};
+
+
+    fn test_glossary(include_in_output: bool) -> Glossary {
+        Glossary::new(String::from("Glossary"), BTreeMap::from([
+            (String::from("api"), GlossaryEntry::new(Some(String::from("API")), Some(String::from("Application Programming Interface")), None)),
+            (String::from("crate"), GlossaryEntry::new(None, None, Some(String::from("Rust *package*")))),
+        ]), include_in_output)
+    }
+
+    #[test]
+    fn expand_first_uses() {
+
+        let glossary = test_glossary(true);
+
+        let api = glossary.compile_use("api").unwrap().unwrap();
+        let krate = glossary.compile_use("crate").unwrap().unwrap();
+
+        assert_eq!(api, r##"<a class="glossary-term" data-glossary-key="api" href="#glossary-api" title="Application Programming Interface">API</a>"##);
+
+        let expanded = glossary.expand_first_uses(&format!("<p>{} {} and {}</p><p>{}</p>", api, krate, api, krate));
+
+        assert_eq!(expanded, format!(
+            r#"<p><span class="glossary-term glossary-first-use" title="Application Programming Interface">Application Programming Interface (API)</span> {} and {}</p><p>{}</p>"#,
+            krate, api, krate
+        ));
+
+        assert!(glossary.compile_use("unknown").is_none());
+        assert!(Glossary::new(String::new(), BTreeMap::new(), false).expand_first_uses(&api).eq(&api));
+        assert!(test_glossary(false).compile_use("crate").unwrap().unwrap().starts_with("<abbr"));
+    }
+
+    #[test]
+    fn compile_glossary() {
+
+        let outcome = test_glossary(true).compile(&OutputFormat::Html, &Codex::of_html(), &CompilationConfiguration::default(), CompilationConfigurationOverLay::default()).unwrap();
+
+        let api = outcome.content().find(r#"<dt class="glossary-item-term" id="glossary-api">API</dt>"#).unwrap();
+        let krate = outcome.content().find(r#"<dt class="glossary-item-term" id="glossary-crate">crate</dt>"#).unwrap();
+
+        assert!(api < krate);
+        assert!(outcome.content().contains("<em"));
+    }
+
+    #[test]
+    fn escape_definitions_and_strip_tooltips_markup() {
+
+        let glossary = Glossary::new(String::from("Glossary"), BTreeMap::from([
+            (String::from("tag"), GlossaryEntry::new(None, Some(String::from("Markup <tag>")), Some(String::from("Element like `<x>` following **rules** and [specs](https://example.com)")))),
+        ]), true);
+
+        let term_use = glossary.compile_use("tag").unwrap().unwrap();
+
+        assert!(term_use.contains(r#"title="Markup &lt;tag&gt;: Element like &lt;x&gt; following rules and specs""#));
+
+        let expanded = glossary.expand_first_uses(&term_use);
+
+        assert!(expanded.contains(r#"title="Markup &lt;tag&gt;: Element like &lt;x&gt; following rules and specs">Markup &lt;tag&gt; (tag)</span>"#));
+
+        let outcome = glossary.clone().compile(&OutputFormat::Html, &Codex::of_html(), &CompilationConfiguration::default(), CompilationConfigurationOverLay::default()).unwrap();
+
+        assert!(!outcome.content().contains("<x>"));
+        assert!(outcome.content().contains("&lt;x&gt;"));
+        assert!(outcome.content().contains("<strong"));
+    }
+}
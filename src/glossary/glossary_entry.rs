@@ -0,0 +1,69 @@
+use getset::{Getters, Setters};
+use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+use crate::codex::modifier::standard_text_modifier::StandardTextModifier;
+
+
+/// Modifiers which are replaced by their content in tooltips, bold and strikethrough must precede italic and subscript
+const TOOLTIP_TEXT_MODIFIERS: [StandardTextModifier; 16] = [
+    StandardTextModifier::InlineCode,
+    StandardTextModifier::InlineMath,
+    StandardTextModifier::EmbeddedStyle,
+    StandardTextModifier::AbridgedEmbeddedStyle,
+    StandardTextModifier::Identifier,
+    StandardTextModifier::Link,
+    StandardTextModifier::BoldStarVersion,
+    StandardTextModifier::BoldUnderscoreVersion,
+    StandardTextModifier::ItalicStarVersion,
+    StandardTextModifier::ItalicUnderscoreVersion,
+    StandardTextModifier::Strikethrough,
+    StandardTextModifier::Underlined,
+    StandardTextModifier::Highlight,
+    StandardTextModifier::Superscript,
+    StandardTextModifier::Subscript,
+    StandardTextModifier::Escape,
+];
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema, Getters, Setters)]
+pub struct GlossaryEntry {
+
+    /// Displayed term (e.g. `API`), entry key is used if it is missing
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get = "pub", set = "pub")]
+    term: Option<String>,
+
+    /// Long form of acronyms (e.g. `Application Programming Interface`), it is shown on first use
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get = "pub", set = "pub")]
+    expansion: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get = "pub", set = "pub")]
+    definition: Option<String>,
+}
+
+impl GlossaryEntry {
+
+    pub fn new(term: Option<String>, expansion: Option<String>, definition: Option<String>) -> Self {
+        Self {
+            term,
+            expansion,
+            definition,
+        }
+    }
+
+    /// Text shown as tooltip: expansion and definition (if any), NMD markup of definition is removed
+    pub fn tooltip(&self) -> Option<String> {
+
+        let definition = self.definition.as_ref().map(|definition| {
+            TOOLTIP_TEXT_MODIFIERS.iter().fold(definition.clone(), |text, modifier| modifier.modifier_pattern_regex().replace_all(&text, "${1}").to_string())
+        });
+
+        match (&self.expansion, definition) {
+            (Some(expansion), Some(definition)) => Some(format!("{}: {}", expansion, definition)),
+            (Some(expansion), None) => Some(expansion.clone()),
+            (None, Some(definition)) => Some(definition),
+            (None, None) => None,
+        }
+    }
+}
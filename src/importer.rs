@@ -0,0 +1,29 @@
+//! Import documents written using other markup languages, converting them in NMD
+
+
+pub mod markdown_importer;
+
+
+use std::io;
+use thiserror::Error;
+use crate::{formatter::FormatterError, load::LoadError, resource::ResourceError};
+
+
+#[derive(Error, Debug)]
+pub enum ImportError {
+
+    #[error(transparent)]
+    IoError(#[from] io::Error),
+
+    #[error(transparent)]
+    LoadError(#[from] LoadError),
+
+    #[error(transparent)]
+    ResourceError(#[from] ResourceError),
+
+    #[error(transparent)]
+    FormatterError(#[from] FormatterError),
+
+    #[error("'{0}' is not a directory")]
+    NotADirectory(String),
+}
@@ -0,0 +1,1522 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use getset::{Getters, Setters};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use crate::codex::Codex;
+use crate::compilation::compilation_rule::constants::SPACE_TAB_EQUIVALENCE;
+use crate::constants::{DOSSIER_CONFIGURATION_YAML_FILE_NAME, NMD_EXTENSION};
+use crate::dossier::document::Document;
+use crate::dossier::dossier_configuration::DossierConfiguration;
+use crate::formatter::Formatter;
+use crate::load::{LoadConfiguration, LoadConfigurationOverLay};
+use crate::utility::file_utility;
+use super::ImportError;
+
+
+pub const MARKDOWN_EXTENSIONS: [&str; 2] = ["md", "markdown"];
+
+/// Prefix of identifiers assigned to footnotes
+pub const FOOTNOTE_IDENTIFIER_PREFIX: &str = "footnote-";
+
+const MARKDOWN_TAB_SIZE: usize = 4;
+
+/// NMD bullet of list items which continue previous item content
+const LIST_CONTINUATION_BULLET: &str = "|";
+
+/// Marker of Markdown hard line breaks in converted inline content, lines are split on it
+const HARD_LINE_BREAK: char = '\u{2028}';
+
+static ATX_HEADING_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"^ {0,3}(#{1,6})(?:[ \t]+(.*?))?(?:[ \t]+#+)?[ \t]*$").unwrap());
+
+static SETEXT_UNDERLINE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"^ {0,3}(=+|-+)[ \t]*$").unwrap());
+
+static FENCE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"^( {0,3})(`{3,}|~{3,})[ \t]*([^`\s]*)[^`]*$").unwrap());
+
+static THEMATIC_BREAK_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"^ {0,3}(?:(?:\*[ \t]*){3,}|(?:-[ \t]*){3,}|(?:_[ \t]*){3,})$").unwrap());
+
+static LIST_ITEM_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"^( *)([-+*]|\d{1,9}[.)])(?:( +)(.*))?$").unwrap());
+
+static TASK_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"^\[([ xX])\][ \t]+(.*)$").unwrap());
+
+static BLOCK_QUOTE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"^ {0,3}> ?(.*)$").unwrap());
+
+static HTML_BLOCK_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"^ {0,3}(?:<!--|<\?|<![A-Z]|</?[A-Za-z][A-Za-z0-9-]*(?:[ \t/>]|$))").unwrap());
+
+static TABLE_DELIMITER_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"^ {0,3}\|?[ \t]*:?-+:?[ \t]*(?:\|[ \t]*:?-+:?[ \t]*)*\|?[ \t]*$").unwrap());
+
+static REFERENCE_DEFINITION_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r#"^ {0,3}\[([^\]^][^\]]*)\]:[ \t]*<?([^\s>]+)>?(?:[ \t]+(?:"[^"]*"|'[^']*'|\([^)]*\)))?[ \t]*$"#).unwrap());
+
+static FOOTNOTE_DEFINITION_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"^ {0,3}\[\^([^\]]+)\]:[ \t]?(.*)$").unwrap());
+
+static AUTOLINK_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"^<([a-zA-Z][a-zA-Z0-9+.-]{1,31}:[^\s<>]*)>").unwrap());
+
+static EMAIL_AUTOLINK_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"^<([^\s<>@]+@[^\s<>@]+\.[^\s<>@]+)>").unwrap());
+
+static INLINE_HTML_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"^(?:<!--(?s:.*?)-->|</?[A-Za-z][A-Za-z0-9-]*(?:\s[^<>]*)?/?>)").unwrap());
+
+static GREEK_LETTER_LIKE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"^%(\w*)%").unwrap());
+
+static REFERENCE_LIKE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"^&([\w-]+)&").unwrap());
+
+static COMMAND_LIKE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"^@(?:\[|\w+\(|index\[)").unwrap());
+
+/// Line beginnings which would be read as NMD paragraph modifiers (e.g. `Mr. Smith` is a list item in NMD)
+static NMD_LINE_START_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"^(?:(?:[-*+|]|->|--|\d+[.)]?|[a-zA-Z]{1,8}[.)]|&[^;\s]+;) |#|:::|\$\$|\+{3,}|\[\[|//)").unwrap());
+
+
+/// Markdown (CommonMark and GitHub Flavored Markdown) importer.
+///
+/// Markdown is converted in NMD source: front matter, headings, fenced and indented code, block quotes and alerts,
+/// lists (task lists too), tables, links, images and footnotes are supported. Link reference definitions are resolved
+/// in inline links and NMD-only syntax (e.g. `^`, `==`, `%alpha%`) is escaped.
+#[derive(Debug, Clone, Getters, Setters)]
+pub struct MarkdownImporter {
+
+    /// Extensions of files imported by `import_directory`
+    #[getset(get = "pub", set = "pub")]
+    extensions: Vec<String>,
+}
+
+impl MarkdownImporter {
+
+    pub fn new() -> Self {
+        Self {
+            extensions: MARKDOWN_EXTENSIONS.iter().map(|extension| extension.to_string()).collect(),
+        }
+    }
+
+    /// Convert Markdown in NMD source
+    pub fn import_str(&self, markdown: &str) -> String {
+
+        let markdown = markdown.trim_start_matches('\u{feff}').replace("\r\n", "\n");
+
+        let mut lines: Vec<String> = markdown.lines().map(String::from).collect();
+
+        let mut outcome = String::new();
+
+        if let Some(front_matter_end) = front_matter_end(&lines) {
+
+            for line in lines.drain(..=front_matter_end) {
+                outcome.push_str(if line.eq("...") { "---" } else { &line });
+                outcome.push('\n');
+            }
+
+            outcome.push('\n');
+        }
+
+        let (converter, lines) = MarkdownConverter::new(lines);
+
+        let mut blocks = converter.convert_blocks(&lines);
+
+        blocks.append(&mut converter.footnotes_blocks());
+
+        outcome.push_str(&blocks.join("\n\n"));
+        outcome.push('\n');
+
+        outcome
+    }
+
+    /// Read and convert a Markdown file in NMD source, which is formatted as `nmd format` does
+    pub fn import_file(&self, path: &PathBuf) -> Result<String, ImportError> {
+        self.import_formatted_file(path, &Formatter::default())
+    }
+
+    fn import_formatted_file(&self, path: &PathBuf, formatter: &Formatter) -> Result<String, ImportError> {
+        Ok(formatter.format_str(&self.import_str(&fs::read_to_string(path)?))?)
+    }
+
+    /// Convert Markdown and load it as a document
+    pub fn import_document_from_str(&self, document_name: &str, markdown: &str, codex: &Codex, configuration: &LoadConfiguration, configuration_overlay: LoadConfigurationOverLay) -> Result<Document, ImportError> {
+        Ok(Document::load_document_from_str(document_name, &self.import_str(markdown), codex, configuration, configuration_overlay)?)
+    }
+
+    /// Read, convert and load a Markdown file as a document
+    pub fn import_document_from_path(&self, path: &PathBuf, codex: &Codex, configuration: &LoadConfiguration, mut configuration_overlay: LoadConfigurationOverLay) -> Result<Document, ImportError> {
+
+        let document_name = path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+
+        let nmd = self.import_file(path)?;
+
+        configuration_overlay.set_document_path(Some(path.clone()));
+
+        Ok(Document::load_document_from_str(&document_name, &nmd, codex, configuration, configuration_overlay)?)
+    }
+
+    /// Convert all Markdown files in `input_dir` (sub-directories too) in a dossier placed in `output_dir`.
+    /// Relative paths of files are kept, dossier configuration lists converted documents in natural order.
+    /// Converted documents paths (relative to `output_dir`) are returned
+    pub fn import_directory(&self, input_dir: &Path, output_dir: &Path) -> Result<Vec<PathBuf>, ImportError> {
+
+        if !input_dir.is_dir() {
+            return Err(ImportError::NotADirectory(input_dir.to_string_lossy().to_string()))
+        }
+
+        let mut sources: Vec<PathBuf> = Vec::new();
+
+        self.collect_markdown_files(input_dir, output_dir, &mut sources)?;
+
+        let mut documents: Vec<(PathBuf, PathBuf)> = sources.into_iter().map(|source| {
+            let document = source.strip_prefix(input_dir).unwrap_or(&source).with_extension(NMD_EXTENSION);
+            (source, document)
+        }).collect();
+
+        documents.sort_by(|(_, a), (_, b)| file_utility::natural_cmp(&a.to_string_lossy(), &b.to_string_lossy()));
+
+        let formatter = Formatter::default();
+
+        for (source, document) in &documents {
+
+            let output_path = output_dir.join(document);
+
+            if let Some(parent) = output_path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+
+            log::info!("import {:?} in {:?}", source, output_path);
+
+            file_utility::create_file_with_content(&output_path, &self.import_formatted_file(source, &formatter)?)?;
+        }
+
+        let documents: Vec<PathBuf> = documents.into_iter().map(|(_, document)| document).collect();
+
+        let name = fs::canonicalize(input_dir).ok()
+                        .and_then(|path| path.file_name().map(|name| name.to_string_lossy().to_string()))
+                        .unwrap_or(DossierConfiguration::default().name().clone());
+
+        let mut configuration = DossierConfiguration::default();
+        configuration.set_name(name);
+        configuration.set_raw_documents_paths(documents.iter().map(|document| {
+            format!("./{}", document.to_string_lossy().replace('\\', "/"))
+        }).collect());
+
+        configuration.dump_as_yaml(output_dir.join(DOSSIER_CONFIGURATION_YAML_FILE_NAME))?;
+
+        Ok(documents)
+    }
+
+    fn collect_markdown_files(&self, dir: &Path, excluded_dir: &Path, files: &mut Vec<PathBuf>) -> Result<(), ImportError> {
+
+        for entry in fs::read_dir(dir)? {
+
+            let path = entry?.path();
+
+            let hidden = path.file_name().map(|name| name.to_string_lossy().starts_with('.')).unwrap_or(false);
+
+            if hidden {
+                continue;
+            }
+
+            if path.is_dir() {
+
+                // output directory can be placed in input directory
+                if path != excluded_dir {
+                    self.collect_markdown_files(&path, excluded_dir, files)?;
+                }
+
+                continue;
+            }
+
+            let is_markdown = path.extension().map(|extension| self.extensions.iter().any(|ext| extension.to_string_lossy().eq(ext))).unwrap_or(false);
+
+            if is_markdown {
+                files.push(path);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for MarkdownImporter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+
+/// Lines of converted inline content split on hard line breaks, soft line breaks become spaces
+fn split_hard_line_breaks(text: &str) -> Vec<String> {
+    text.split(HARD_LINE_BREAK).map(|line| line.trim().replace('\n', " ")).filter(|line| !line.is_empty()).collect()
+}
+
+/// Index of front matter closing line
+fn front_matter_end(lines: &[String]) -> Option<usize> {
+
+    if !lines.first().map(|line| line.trim_end().eq("---")).unwrap_or(false) {
+        return None
+    }
+
+    lines.iter().skip(1).position(|line| {
+        let line = line.trim_end();
+        line.eq("---") || line.eq("...")
+    }).map(|index| index + 1)
+}
+
+/// Replace tabs in indentation with spaces
+fn expand_indentation_tabs(line: &str) -> String {
+
+    let mut expanded = String::new();
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.peek() {
+        match c {
+            ' ' => expanded.push(' '),
+            '\t' => {
+                let width = MARKDOWN_TAB_SIZE - expanded.len() % MARKDOWN_TAB_SIZE;
+                expanded.push_str(&" ".repeat(width));
+            },
+            _ => break,
+        }
+
+        chars.next();
+    }
+
+    expanded.extend(chars);
+
+    expanded
+}
+
+fn indentation_of(line: &str) -> usize {
+    line.len() - line.trim_start_matches(' ').len()
+}
+
+/// Remove up to `width` spaces of indentation
+fn strip_indentation(line: &str, width: usize) -> &str {
+    &line[indentation_of(line).min(width)..]
+}
+
+fn normalize_label(label: &str) -> String {
+    label.split_whitespace().collect::<Vec<&str>>().join(" ").to_lowercase()
+}
+
+fn slugify(label: &str) -> String {
+    label.to_lowercase().chars().map(|c| if c.is_alphanumeric() || c == '_' || c == '-' { c } else { '-' }).collect()
+}
+
+/// Escape line beginning which would be read as an NMD paragraph modifier
+fn escape_line_start(line: &str) -> String {
+    if NMD_LINE_START_REGEX.is_match(line) {
+        return format!("\\{}", line)
+    }
+
+    line.to_string()
+}
+
+fn is_closing_fence(line: &str, fence: &str) -> bool {
+
+    let line = line.trim();
+    let fence_char = fence.chars().next().unwrap();
+
+    line.len() >= fence.len() && line.chars().all(|c| c == fence_char)
+}
+
+fn is_table_start(lines: &[String], index: usize) -> bool {
+    lines[index].contains('|') && lines.get(index + 1).map(|line| line.contains('|') && TABLE_DELIMITER_REGEX.is_match(line)).unwrap_or(false)
+}
+
+/// Split table row in cells, escaped pipes are kept
+fn split_table_row(row: &str) -> Vec<String> {
+
+    let row = row.trim();
+    let row = row.strip_prefix('|').unwrap_or(row);
+    let row = if row.ends_with('|') && !row.ends_with("\\|") { &row[..row.len() - 1] } else { row };
+
+    let mut cells: Vec<String> = vec![String::new()];
+    let mut escaped = false;
+
+    for c in row.chars() {
+
+        if c == '|' && !escaped {
+            cells.push(String::new());
+        } else {
+            cells.last_mut().unwrap().push(c);
+        }
+
+        escaped = c == '\\' && !escaped;
+    }
+
+    cells.into_iter().map(|cell| cell.trim().to_string()).collect()
+}
+
+/// Index of `close` matching `open` placed in `start`, escaped characters are skipped
+fn matching_delimiter(chars: &[char], start: usize, open: char, close: char) -> Option<usize> {
+
+    let mut depth = 0;
+    let mut index = start;
+
+    while index < chars.len() {
+        match chars[index] {
+            '\\' => index += 1,
+            c if c == open => depth += 1,
+            c if c == close => {
+                depth -= 1;
+
+                if depth == 0 {
+                    return Some(index)
+                }
+            },
+            _ => (),
+        }
+
+        index += 1;
+    }
+
+    None
+}
+
+/// Length of run of `c` starting in `index`
+fn run_length(chars: &[char], index: usize, c: char) -> usize {
+    chars[index..].iter().take_while(|other| **other == c).count()
+}
+
+/// Return `true` if there is a single `~` (i.e. GFM strikethrough delimiter) after `index`
+fn has_single_tilde(chars: &[char], mut index: usize) -> bool {
+
+    while index < chars.len() {
+
+        if chars[index] == '~' {
+
+            let length = run_length(chars, index, '~');
+
+            if length == 1 {
+                return true
+            }
+
+            index += length;
+
+        } else {
+            index += 1;
+        }
+    }
+
+    false
+}
+
+
+#[derive(Debug, Clone, Copy)]
+struct ListLevel {
+    content_indentation: usize,
+    delimiter: char,
+    number: usize,
+}
+
+impl ListLevel {
+    fn is_ordered(&self) -> bool {
+        self.delimiter == '.' || self.delimiter == ')'
+    }
+}
+
+
+/// Converted inline content
+enum InlinePart {
+    Text(String),
+    Image(String),
+}
+
+
+/// Markdown converter of a single document, it knows link reference definitions and footnotes of the document
+struct MarkdownConverter {
+    references: HashMap<String, String>,
+    footnotes: Vec<(String, String)>,
+}
+
+impl MarkdownConverter {
+
+    /// Build converter collecting link reference definitions and footnotes, remaining lines are returned
+    fn new(lines: Vec<String>) -> (Self, Vec<String>) {
+
+        let mut references: HashMap<String, String> = HashMap::new();
+        let mut footnotes: Vec<(String, String)> = Vec::new();
+        let mut remaining: Vec<String> = Vec::new();
+
+        let mut fence: Option<String> = None;
+        let mut index = 0;
+
+        while index < lines.len() {
+
+            let line = &lines[index];
+            index += 1;
+
+            if let Some(opened_fence) = &fence {
+
+                if is_closing_fence(line, opened_fence) {
+                    fence = None;
+                }
+
+                remaining.push(line.clone());
+
+                continue;
+            }
+
+            let line = expand_indentation_tabs(line);
+
+            if let Some(captures) = FENCE_REGEX.captures(&line) {
+                fence = Some(captures[2].to_string());
+                remaining.push(line);
+                continue;
+            }
+
+            if let Some(captures) = FOOTNOTE_DEFINITION_REGEX.captures(&line) {
+
+                let mut text = vec![captures[2].trim().to_string()];
+
+                // continuation lines are indented, they can be separated by blank lines
+                while index < lines.len() {
+
+                    let next = expand_indentation_tabs(&lines[index]);
+
+                    if next.trim().is_empty() {
+
+                        let indented_follows = lines.get(index + 1).map(|line| indentation_of(&expand_indentation_tabs(line)) >= 2).unwrap_or(false);
+
+                        if !indented_follows {
+                            break;
+                        }
+
+                    } else if indentation_of(&next) >= 2 {
+                        text.push(next.trim().to_string());
+                    } else {
+                        break;
+                    }
+
+                    index += 1;
+                }
+
+                footnotes.push((normalize_label(&captures[1]), text.join(" ").trim().to_string()));
+
+                continue;
+            }
+
+            if let Some(captures) = REFERENCE_DEFINITION_REGEX.captures(&line) {
+                references.entry(normalize_label(&captures[1])).or_insert(captures[2].to_string());
+                continue;
+            }
+
+            remaining.push(line);
+        }
+
+        (Self { references, footnotes }, remaining)
+    }
+
+    fn starts_block(&self, line: &str) -> bool {
+
+        if ATX_HEADING_REGEX.is_match(line) || FENCE_REGEX.is_match(line) || THEMATIC_BREAK_REGEX.is_match(line)
+            || BLOCK_QUOTE_REGEX.is_match(line) || HTML_BLOCK_REGEX.is_match(line) {
+            return true
+        }
+
+        // only lists which start with 1 and non-empty items can interrupt a paragraph
+        if let Some(captures) = LIST_ITEM_REGEX.captures(line) {
+
+            let marker = &captures[2];
+            let has_content = captures.get(4).map(|content| !content.as_str().trim().is_empty()).unwrap_or(false);
+
+            return indentation_of(line) < MARKDOWN_TAB_SIZE && has_content && (!marker.ends_with(['.', ')']) || marker[..marker.len() - 1].eq("1"))
+        }
+
+        false
+    }
+
+    /// Convert lines in NMD blocks
+    fn convert_blocks(&self, lines: &[String]) -> Vec<String> {
+
+        let mut blocks: Vec<String> = Vec::new();
+        let mut index = 0;
+
+        while index < lines.len() {
+
+            let line = &lines[index];
+
+            if line.trim().is_empty() {
+                index += 1;
+                continue;
+            }
+
+            if let Some(captures) = FENCE_REGEX.captures(line) {
+
+                let indentation = captures[1].len();
+                let fence = captures[2].to_string();
+                let language = captures[3].to_string();
+
+                let mut content: Vec<&str> = Vec::new();
+
+                index += 1;
+
+                while index < lines.len() && !is_closing_fence(&lines[index], &fence) {
+                    content.push(strip_indentation(&lines[index], indentation));
+                    index += 1;
+                }
+
+                index += 1;
+
+                blocks.push(Self::code_block(&language, &content));
+
+                continue;
+            }
+
+            if let Some(captures) = ATX_HEADING_REGEX.captures(line) {
+
+                let text = captures.get(2).map(|text| text.as_str().trim()).unwrap_or("");
+
+                if !text.is_empty() {
+                    blocks.push(format!("{} {}", &captures[1], self.convert_inline(text)));
+                }
+
+                index += 1;
+                continue;
+            }
+
+            if THEMATIC_BREAK_REGEX.is_match(line) {
+                blocks.push(String::from("---"));
+                index += 1;
+                continue;
+            }
+
+            if indentation_of(line) >= MARKDOWN_TAB_SIZE {
+
+                let mut content: Vec<&str> = Vec::new();
+
+                while index < lines.len() && (lines[index].trim().is_empty() || indentation_of(&lines[index]) >= MARKDOWN_TAB_SIZE) {
+                    content.push(strip_indentation(&lines[index], MARKDOWN_TAB_SIZE));
+                    index += 1;
+                }
+
+                while content.last().map(|line| line.trim().is_empty()).unwrap_or(false) {
+                    content.pop();
+                }
+
+                blocks.push(Self::code_block("", &content));
+
+                continue;
+            }
+
+            if BLOCK_QUOTE_REGEX.is_match(line) {
+
+                let mut content: Vec<String> = Vec::new();
+
+                // lazy continuation lines are included
+                while index < lines.len() && !lines[index].trim().is_empty() {
+
+                    let line = &lines[index];
+
+                    match BLOCK_QUOTE_REGEX.captures(line) {
+                        Some(captures) => content.push(expand_indentation_tabs(&captures[1])),
+                        None if !self.starts_block(line) => content.push(line.clone()),
+                        None => break,
+                    }
+
+                    index += 1;
+                }
+
+                let quote = self.convert_blocks(&content).join("\n\n").lines().map(|line| {
+                    if line.is_empty() { String::from(">") } else { format!("> {}", line) }
+                }).collect::<Vec<String>>().join("\n");
+
+                blocks.push(quote);
+
+                continue;
+            }
+
+            if HTML_BLOCK_REGEX.is_match(line) {
+
+                let is_comment = line.trim_start().starts_with("<!--");
+
+                let mut content: Vec<&str> = Vec::new();
+
+                while index < lines.len() {
+
+                    let line = &lines[index];
+
+                    if !is_comment && line.trim().is_empty() {
+                        break;
+                    }
+
+                    content.push(line);
+                    index += 1;
+
+                    if is_comment && line.contains("-->") {
+                        break;
+                    }
+                }
+
+                blocks.push(content.join("\n"));
+
+                continue;
+            }
+
+            if LIST_ITEM_REGEX.is_match(line) {
+
+                let (mut list_blocks, next_index) = self.convert_list(lines, index);
+
+                blocks.append(&mut list_blocks);
+                index = next_index;
+
+                continue;
+            }
+
+            if is_table_start(lines, index) {
+
+                let mut rows: Vec<&str> = vec![&lines[index], &lines[index + 1]];
+
+                index += 2;
+
+                while index < lines.len() && lines[index].contains('|') && !lines[index].trim().is_empty() && !self.starts_block(&lines[index]) {
+                    rows.push(&lines[index]);
+                    index += 1;
+                }
+
+                blocks.push(self.convert_table(&rows));
+
+                continue;
+            }
+
+            // paragraph, it can become a setext heading (trailing spaces can be hard line breaks)
+            let mut content: Vec<&str> = vec![line.trim_start()];
+            let mut heading_level: Option<usize> = None;
+
+            index += 1;
+
+            while index < lines.len() {
+
+                let line = &lines[index];
+
+                if line.trim().is_empty() {
+                    break;
+                }
+
+                if let Some(captures) = SETEXT_UNDERLINE_REGEX.captures(line) {
+                    heading_level = Some(if captures[1].starts_with('=') { 1 } else { 2 });
+                    index += 1;
+                    break;
+                }
+
+                if self.starts_block(line) || is_table_start(lines, index) {
+                    break;
+                }
+
+                content.push(line.trim_start());
+                index += 1;
+            }
+
+            match heading_level {
+                Some(level) => blocks.push(format!("{} {}", "#".repeat(level), self.convert_inline(&content.iter().map(|line| line.trim()).collect::<Vec<&str>>().join(" ")))),
+                None => blocks.push(self.convert_paragraph(&content.join("\n"))),
+            }
+        }
+
+        blocks
+    }
+
+    fn code_block(language: &str, content: &[&str]) -> String {
+
+        if language.eq("math") {
+            return format!("$$\n{}\n$$", content.join("\n"))
+        }
+
+        format!("```{}\n{}\n```", language, content.join("\n"))
+    }
+
+    /// Convert paragraph, images are moved in their own paragraphs because NMD images are standalone paragraphs.
+    /// Lines ended by hard line breaks become `|` items (i.e. items without bullet), because NMD has not line breaks
+    fn convert_paragraph(&self, text: &str) -> String {
+
+        let escape = |text: &str| text.lines().map(escape_line_start).collect::<Vec<String>>().join("\n");
+
+        let mut paragraphs: Vec<String> = Vec::new();
+
+        for part in self.convert_inline_parts(text) {
+            match part {
+                InlinePart::Text(text) if text.trim().is_empty() => (),
+                InlinePart::Text(text) if text.trim().contains(HARD_LINE_BREAK) => {
+                    paragraphs.push(split_hard_line_breaks(text.trim()).iter().map(|line| format!("{} {}", LIST_CONTINUATION_BULLET, line)).collect::<Vec<String>>().join("\n"))
+                },
+                InlinePart::Text(text) => paragraphs.push(escape(text.trim())),
+                InlinePart::Image(image) => paragraphs.push(image),
+            }
+        }
+
+        paragraphs.join("\n\n")
+    }
+
+    /// Convert list starting in `start`, lists become flat NMD lists whose levels are given by indentation.
+    /// Code blocks in list items split list. Blocks and index of first line after list are returned
+    fn convert_list(&self, lines: &[String], start: usize) -> (Vec<String>, usize) {
+
+        let mut blocks: Vec<String> = Vec::new();
+        let mut items: Vec<(usize, String, Vec<String>)> = Vec::new();
+        let mut stack: Vec<ListLevel> = Vec::new();
+
+        let mut index = start;
+        let mut previous_blank = false;
+
+        while index < lines.len() {
+
+            let line = &lines[index];
+
+            if line.trim().is_empty() {
+                previous_blank = true;
+                index += 1;
+                continue;
+            }
+
+            if THEMATIC_BREAK_REGEX.is_match(line) {
+                break;
+            }
+
+            if let Some(captures) = LIST_ITEM_REGEX.captures(line) {
+
+                let marker_indentation = captures[1].len();
+
+                let mut sibling: Option<ListLevel> = None;
+
+                while stack.last().map(|level| marker_indentation < level.content_indentation).unwrap_or(false) {
+                    sibling = stack.pop();
+                }
+
+                let depth = stack.len();
+
+                let marker = &captures[2];
+                let spaces = captures.get(3).map(|spaces| spaces.as_str().len()).filter(|spaces| *spaces <= MARKDOWN_TAB_SIZE).unwrap_or(1);
+                let mut content = captures.get(4).map(|content| content.as_str().trim_start().to_string()).unwrap_or_default();
+
+                let delimiter = marker.chars().last().unwrap();
+                let ordered = delimiter == '.' || delimiter == ')';
+
+                let same_list = sibling.map(|sibling| sibling.delimiter == delimiter).unwrap_or(false);
+
+                // a different bullet or delimiter starts a new list
+                if depth == 0 && sibling.is_some() && !same_list {
+                    blocks.push(self.list_block(&items));
+                    items.clear();
+                }
+
+                let number = match sibling {
+                    Some(sibling) if same_list && sibling.is_ordered() => sibling.number + 1,
+                    _ => marker[..marker.len() - 1].parse().unwrap_or(1),
+                };
+
+                let bullet = if ordered {
+                    format!("{}.", number)
+
+                } else if let Some(task) = TASK_REGEX.captures(&content.clone()) {
+
+                    content = task[2].to_string();
+
+                    if task[1].eq(" ") { String::from("-[ ]") } else { String::from("-[x]") }
+
+                } else {
+                    String::from("-")
+                };
+
+                items.push((depth, bullet, vec![content]));
+
+                stack.push(ListLevel {
+                    content_indentation: marker_indentation + marker.len() + spaces,
+                    delimiter,
+                    number,
+                });
+
+                previous_blank = false;
+                index += 1;
+
+                continue;
+            }
+
+            let indentation = indentation_of(line);
+            let indented = stack.first().map(|level| indentation >= level.content_indentation).unwrap_or(false);
+
+            if !indented && (previous_blank || self.starts_block(line)) {
+                break;
+            }
+
+            let content_indentation = stack.iter().rev().map(|level| level.content_indentation).find(|width| *width <= indentation).unwrap_or(0);
+            let text = strip_indentation(line, content_indentation);
+
+            if let Some(captures) = FENCE_REGEX.captures(text) {
+
+                let fence = captures[2].to_string();
+                let language = captures[3].to_string();
+                let code_indentation = content_indentation + captures[1].len();
+
+                blocks.push(self.list_block(&items));
+                items.clear();
+
+                let mut content: Vec<&str> = Vec::new();
+
+                index += 1;
+
+                while index < lines.len() && !is_closing_fence(&lines[index], &fence) {
+                    content.push(strip_indentation(&lines[index], code_indentation));
+                    index += 1;
+                }
+
+                index += 1;
+
+                blocks.push(Self::code_block(&language, &content));
+
+            } else {
+
+                match items.last_mut() {
+                    Some((_, _, content)) => {
+
+                        // an empty line marks a new paragraph of loose item
+                        if previous_blank {
+                            content.push(String::new());
+                        }
+
+                        content.push(text.trim_start().to_string())
+                    },
+                    None => blocks.push(self.convert_paragraph(text.trim())),
+                }
+
+                index += 1;
+            }
+
+            previous_blank = false;
+        }
+
+        if !items.is_empty() {
+            blocks.push(self.list_block(&items));
+        }
+
+        (blocks.into_iter().filter(|block| !block.is_empty()).collect(), index)
+    }
+
+    /// Build NMD list, paragraphs of loose items after the first one and lines ended by hard line breaks become `|` items (i.e. items without bullet)
+    fn list_block(&self, items: &[(usize, String, Vec<String>)]) -> String {
+        items.iter().flat_map(|(depth, bullet, content)| {
+
+            let indentation = SPACE_TAB_EQUIVALENCE.repeat(*depth);
+
+            let mut paragraphs = content.split(|line| line.is_empty())
+                                    .filter(|paragraph| !paragraph.is_empty())
+                                    .flat_map(|paragraph| split_hard_line_breaks(self.convert_inline(&paragraph.join("\n")).trim()));
+
+            let first = format!("{}{} {}", indentation, bullet, paragraphs.next().unwrap_or_default());
+
+            std::iter::once(first).chain(paragraphs.map(move |paragraph| format!("{}{} {}", indentation, LIST_CONTINUATION_BULLET, paragraph))).collect::<Vec<String>>()
+        }).collect::<Vec<String>>().join("\n")
+    }
+
+    /// Convert GFM table, rows are normalized to have leading and trailing pipes and the same number of cells
+    fn convert_table(&self, rows: &[&str]) -> String {
+
+        let header = split_table_row(rows[0]);
+        let columns = header.len();
+
+        let delimiters: Vec<String> = split_table_row(rows[1]).into_iter().chain(std::iter::repeat(String::from("---"))).take(columns).map(|delimiter| {
+            match (delimiter.starts_with(':'), delimiter.ends_with(':')) {
+                (true, true) => String::from(":---:"),
+                (true, false) => String::from(":---"),
+                (false, true) => String::from("---:"),
+                (false, false) => String::from("---"),
+            }
+        }).collect();
+
+        let convert_row = |cells: Vec<String>| -> String {
+
+            let cells: Vec<String> = cells.into_iter().chain(std::iter::repeat(String::new())).take(columns).map(|cell| {
+
+                // `<` and `^` are NMD cell spans
+                if cell.eq("<") || cell.eq("^") {
+                    return format!("\\{}", cell)
+                }
+
+                // NMD rows are split on each pipe
+                self.convert_inline(&cell).replace("\\|", "&#124;")
+            }).collect();
+
+            format!("| {} |", cells.join(" | "))
+        };
+
+        let mut table: Vec<String> = vec![convert_row(header), format!("| {} |", delimiters.join(" | "))];
+
+        for row in rows.iter().skip(2) {
+            table.push(convert_row(split_table_row(row)));
+        }
+
+        table.join("\n")
+    }
+
+    /// Footnotes definitions are placed at the end of document, after a line break
+    fn footnotes_blocks(&self) -> Vec<String> {
+
+        if self.footnotes.is_empty() {
+            return Vec::new()
+        }
+
+        let mut blocks = vec![String::from("---")];
+
+        for (label, text) in &self.footnotes {
+            blocks.push(format!("[({})]#{}{} {}", label, FOOTNOTE_IDENTIFIER_PREFIX, slugify(label), self.convert_inline(text)));
+        }
+
+        blocks
+    }
+
+    fn link(&self, text: &str, destination: &str, image: bool) -> String {
+
+        let destination = destination.replace(' ', "%20").replace('(', "%28").replace(')', "%29");
+
+        let text = self.convert_inline(text);
+
+        let text = if text.trim().is_empty() && !image { destination.clone() } else { text };
+
+        format!("{}[{}]({})", if image { "!" } else { "" }, text, destination)
+    }
+
+    /// Try to convert link, image or footnote reference whose label starts in `start` (i.e. `[`).
+    /// NMD text and index after link are returned
+    fn convert_link(&self, chars: &[char], start: usize, image: bool) -> Option<(String, usize)> {
+
+        let close = matching_delimiter(chars, start, '[', ']')?;
+
+        let label: String = chars[start + 1..close].iter().collect();
+
+        let after = close + 1;
+
+        if !image && chars.get(after) != Some(&'(') {
+            if let Some(footnote) = label.strip_prefix('^') {
+
+                let footnote = normalize_label(footnote);
+
+                if self.footnotes.iter().any(|(label, _)| label.eq(&footnote)) {
+                    return Some((format!("[({})](#{}{})", footnote, FOOTNOTE_IDENTIFIER_PREFIX, slugify(&footnote)), after))
+                }
+            }
+        }
+
+        match chars.get(after) {
+            Some('(') => {
+
+                let end = matching_delimiter(chars, after, '(', ')')?;
+
+                let raw_destination: String = chars[after + 1..end].iter().collect();
+                let raw_destination = raw_destination.trim();
+
+                let (destination, title) = match raw_destination.strip_prefix('<') {
+                    Some(destination) => destination.split_once('>').unwrap_or((destination, "")),
+                    None => raw_destination.split_once(char::is_whitespace).unwrap_or((raw_destination, "")),
+                };
+
+                // NMD links and images have not a title
+                if !title.trim().is_empty() {
+                    log::warn!("title {} of {} is dropped", title.trim(), destination);
+                }
+
+                Some((self.link(&label, destination, image), end + 1))
+            },
+            Some('[') => {
+
+                let end = matching_delimiter(chars, after, '[', ']')?;
+
+                let reference: String = chars[after + 1..end].iter().collect();
+                let reference = if reference.trim().is_empty() { &label } else { &reference };
+
+                self.references.get(&normalize_label(reference)).map(|destination| (self.link(&label, destination, image), end + 1))
+            },
+            _ => self.references.get(&normalize_label(&label)).map(|destination| (self.link(&label, destination, image), after)),
+        }
+    }
+
+    /// Convert inline Markdown, NMD-only syntax is escaped
+    fn convert_inline(&self, text: &str) -> String {
+
+        let parts = self.convert_inline_parts(text);
+
+        let surrounded_image = parts.iter().any(|part| matches!(part, InlinePart::Image(_)))
+                                    && parts.iter().any(|part| matches!(part, InlinePart::Text(text) if !text.trim().is_empty()));
+
+        if surrounded_image {
+            log::warn!("images are read only as standalone paragraphs in NMD, so images of '{}' are not rendered", text);
+        }
+
+        parts.into_iter().map(|part| match part {
+            InlinePart::Text(text) | InlinePart::Image(text) => text,
+        }).collect()
+    }
+
+    /// Convert inline Markdown, converted images are split from text around them
+    fn convert_inline_parts(&self, text: &str) -> Vec<InlinePart> {
+
+        let chars: Vec<char> = text.chars().collect();
+
+        let mut parts: Vec<InlinePart> = Vec::new();
+        let mut outcome = String::new();
+        let mut index = 0;
+        let mut strikethrough_open = false;
+
+        while index < chars.len() {
+
+            let c = chars[index];
+            let rest = || chars[index..].iter().collect::<String>();
+
+            match c {
+                '\\' => {
+                    match chars.get(index + 1) {
+                        Some(next) if next.is_ascii_punctuation() => {
+                            outcome.push('\\');
+                            outcome.push(*next);
+                            index += 2;
+                        },
+                        Some('\n') => {
+                            outcome.push(HARD_LINE_BREAK);
+                            index += 2;
+                        },
+                        _ => {
+                            outcome.push_str("\\\\");
+                            index += 1;
+                        }
+                    }
+                },
+                '`' => {
+
+                    let length = run_length(&chars, index, '`');
+
+                    let mut end = index + length;
+
+                    while end < chars.len() && !(chars[end] == '`' && run_length(&chars, end, '`') == length) {
+                        end += if chars[end] == '`' { run_length(&chars, end, '`') } else { 1 };
+                    }
+
+                    if end >= chars.len() {
+                        outcome.push_str(&"`".repeat(length));
+                        index += length;
+                        continue;
+                    }
+
+                    let code: String = chars[index + length..end].iter().collect::<String>().replace('\n', " ");
+
+                    let code = if code.len() > 2 && code.starts_with(' ') && code.ends_with(' ') { &code[1..code.len() - 1] } else { &code };
+
+                    // NMD inline code cannot contain backticks, but its content is not escaped, so they are written as entities
+                    outcome.push_str(&format!("`{}`", code.replace('`', "&#96;")));
+
+                    index = end + length;
+                },
+                '<' => {
+
+                    let rest = rest();
+
+                    if let Some(captures) = AUTOLINK_REGEX.captures(&rest) {
+                        outcome.push_str(&format!("[{}]({})", &captures[1], &captures[1]));
+                        index += captures[0].chars().count();
+
+                    } else if let Some(captures) = EMAIL_AUTOLINK_REGEX.captures(&rest) {
+                        outcome.push_str(&format!("[{}](mailto:{})", &captures[1], &captures[1]));
+                        index += captures[0].chars().count();
+
+                    } else if let Some(html) = INLINE_HTML_REGEX.find(&rest) {
+                        outcome.push_str(html.as_str());
+                        index += html.as_str().chars().count();
+
+                    } else {
+                        outcome.push('<');
+                        index += 1;
+                    }
+                },
+                '!' | '[' => {
+
+                    let image = c == '!';
+
+                    if image && chars.get(index + 1) != Some(&'[') {
+                        outcome.push('!');
+                        index += 1;
+                        continue;
+                    }
+
+                    let start = if image { index + 1 } else { index };
+
+                    if let Some((link, next_index)) = self.convert_link(&chars, start, image) {
+
+                        if image {
+                            parts.push(InlinePart::Text(std::mem::take(&mut outcome)));
+                            parts.push(InlinePart::Image(link));
+                        } else {
+                            outcome.push_str(&link);
+                        }
+
+                        index = next_index;
+                        continue;
+                    }
+
+                    // NMD checkboxes
+                    let checkbox = ["[]", "[ ]", "[x]", "[X]"].iter().find(|checkbox| rest().starts_with(*checkbox));
+
+                    match checkbox {
+                        Some(checkbox) if !image => {
+                            outcome.push_str(&format!("\\[{}\\]", &checkbox[1..checkbox.len() - 1]));
+                            index += checkbox.len();
+                        },
+                        _ => {
+                            outcome.push_str(if image { "![" } else { "[" });
+                            index = start + 1;
+                        }
+                    }
+                },
+                '~' => {
+
+                    let length = run_length(&chars, index, '~');
+
+                    if length == 2 {
+                        outcome.push_str("~~");
+
+                    } else if length == 1 && strikethrough_open {
+                        outcome.push_str("~~");
+                        strikethrough_open = false;
+
+                    } else if length == 1 && has_single_tilde(&chars, index + 1) {
+                        outcome.push_str("~~");
+                        strikethrough_open = true;
+
+                    } else {
+                        outcome.push('\\');
+                        outcome.push_str(&"~".repeat(length));
+                    }
+
+                    index += length;
+                },
+                '\n' => {
+
+                    // two or more trailing spaces are a hard line break
+                    if outcome.ends_with("  ") {
+                        outcome.truncate(outcome.trim_end_matches(' ').len());
+                        outcome.push(HARD_LINE_BREAK);
+                    } else {
+                        outcome.push('\n');
+                    }
+
+                    index += 1;
+                },
+                '^' => {
+                    outcome.push_str("\\^");
+                    index += 1;
+                },
+                '?' => {
+
+                    // glossary term
+                    if chars.get(index + 1) == Some(&'[') {
+                        outcome.push('\\');
+                    }
+
+                    outcome.push('?');
+                    index += 1;
+                },
+                '=' | '+' => {
+
+                    let length = run_length(&chars, index, c);
+
+                    if length >= 2 {
+                        outcome.push('\\');
+                    }
+
+                    outcome.push_str(&c.to_string().repeat(length));
+                    index += length;
+                },
+                '%' | '&' => {
+
+                    let rest = rest();
+
+                    let regex = if c == '%' { &GREEK_LETTER_LIKE_REGEX } else { &REFERENCE_LIKE_REGEX };
+
+                    match regex.captures(&rest) {
+                        Some(captures) => {
+                            outcome.push_str(&format!("\\{}{}\\{}", c, &captures[1], c));
+                            index += captures[0].chars().count();
+                        },
+                        None => {
+                            outcome.push(c);
+                            index += 1;
+                        }
+                    }
+                },
+                '@' => {
+
+                    if COMMAND_LIKE_REGEX.is_match(&rest()) {
+                        outcome.push('\\');
+                    }
+
+                    outcome.push('@');
+                    index += 1;
+                },
+                '_' => {
+
+                    // intraword underscores are not emphasis in Markdown
+                    let intraword = index > 0 && chars[index - 1].is_alphanumeric() && chars.get(index + 1).map(|next| next.is_alphanumeric()).unwrap_or(false);
+
+                    if intraword {
+                        outcome.push('\\');
+                    }
+
+                    outcome.push('_');
+                    index += 1;
+                },
+                _ => {
+                    outcome.push(c);
+                    index += 1;
+                }
+            }
+        }
+
+        parts.push(InlinePart::Text(outcome));
+
+        parts
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use crate::codex::Codex;
+    use crate::compilation::compilable::Compilable;
+    use crate::compilation::compilation_configuration::{compilation_configuration_overlay::CompilationConfigurationOverLay, CompilationConfiguration};
+    use crate::constants::DOSSIER_CONFIGURATION_YAML_FILE_NAME;
+    use crate::dossier::dossier_configuration::DossierConfiguration;
+    use crate::formatter::Formatter;
+    use crate::utility::test_utility::TempDir;
+    use crate::load::{LoadConfiguration, LoadConfigurationOverLay};
+    use crate::output_format::OutputFormat;
+    use super::MarkdownImporter;
+
+
+    #[test]
+    fn blocks() {
+
+        let markdown = concat!(
+            "---\n",
+            "title: Imported\n",
+            "---\n",
+            "Title\n",
+            "=====\n",
+            "\n",
+            "## Section ##\n",
+            "\n",
+            "Text with `a^b` code\n",
+            "and a lazy line.\n",
+            "\n",
+            "~~~python\n",
+            "print(1)\n",
+            "~~~\n",
+            "\n",
+            "    indented code\n",
+            "\n",
+            "***\n",
+            "\n",
+            "> [!NOTE]\n",
+            "> Useful\n",
+            "\n",
+            "<!-- comment -->\n",
+        );
+
+        assert_eq!(MarkdownImporter::new().import_str(markdown), concat!(
+            "---\n",
+            "title: Imported\n",
+            "---\n",
+            "\n",
+            "# Title\n",
+            "\n",
+            "## Section\n",
+            "\n",
+            "Text with `a^b` code\n",
+            "and a lazy line.\n",
+            "\n",
+            "```python\n",
+            "print(1)\n",
+            "```\n",
+            "\n",
+            "```\n",
+            "indented code\n",
+            "```\n",
+            "\n",
+            "---\n",
+            "\n",
+            "> [!NOTE]\n",
+            "> Useful\n",
+            "\n",
+            "<!-- comment -->\n",
+        ));
+    }
+
+    #[test]
+    fn lists() {
+
+        let markdown = concat!(
+            "* one\n",
+            "  continued\n",
+            "* two\n",
+            "    1. first\n",
+            "    1. second\n",
+            "\n",
+            "       - [x] done\n",
+            "       - [ ] todo\n",
+            "* three\n",
+            "\n",
+            "  second paragraph\n",
+            "+ other list\n",
+        );
+
+        assert_eq!(MarkdownImporter::new().import_str(markdown), concat!(
+            "- one continued\n",
+            "- two\n",
+            "   1. first\n",
+            "   2. second\n",
+            "      -[x] done\n",
+            "      -[ ] todo\n",
+            "- three\n",
+            "| second paragraph\n",
+            "\n",
+            "- other list\n",
+        ));
+    }
+
+    #[test]
+    fn inlines_and_references() {
+
+        let markdown = concat!(
+            "See [docs][ref], <https://nmd.dev>, ![logo](logo.png \"Logo\") and H~2~O.\n",
+            "2^10^ is ==big==, C++ uses %d%, snake_case and &amp; are fine[^note].\n",
+            "Why?[sic] @index[sic]\n",
+            "\n",
+            "[ref]: https://example.com/docs \"Docs\"\n",
+            "[^note]: A footnote\n",
+            "    on two lines.\n",
+        );
+
+        assert_eq!(MarkdownImporter::new().import_str(markdown), concat!(
+            "See [docs](https://example.com/docs), [https://nmd.dev](https://nmd.dev),\n",
+            "\n",
+            "![logo](logo.png)\n",
+            "\n",
+            "and H~~2~~O.\n",
+            "2\\^10\\^ is \\==big\\==, C\\++ uses \\%d\\%, snake\\_case and &amp; are fine[(note)](#footnote-note).\n",
+            "Why\\?[sic] \\@index[sic]\n",
+            "\n",
+            "---\n",
+            "\n",
+            "[(note)]#footnote-note A footnote on two lines.\n",
+        ));
+    }
+
+    #[test]
+    fn code_spans_with_backticks() {
+
+        let markdown = "Use ``a`b``, `` `x` `` and ```c``d```.\n";
+
+        assert_eq!(MarkdownImporter::new().import_str(markdown), "Use `a&#96;b`, `&#96;x&#96;` and `c&#96;&#96;d`.\n");
+
+        let mut document = MarkdownImporter::new().import_document_from_str("test", markdown, &Codex::of_html(), &LoadConfiguration::default(), LoadConfigurationOverLay::default()).unwrap();
+
+        let outcome = document.compile(&OutputFormat::Html, &Codex::of_html(), &CompilationConfiguration::default(), CompilationConfigurationOverLay::default()).unwrap();
+
+        assert!(outcome.content().contains(r#"<code class="language-markup inline-code">a&#96;b</code>"#));
+    }
+
+    #[test]
+    fn hard_line_breaks() {
+
+        let markdown = concat!(
+            "Dear Alice,  \n",
+            "see you\\\n",
+            "soon.\n",
+            "\n",
+            "- first line  \n",
+            "  second line\n",
+            "- other\n",
+        );
+
+        assert_eq!(MarkdownImporter::new().import_str(markdown), concat!(
+            "| Dear Alice,\n",
+            "| see you\n",
+            "| soon.\n",
+            "\n",
+            "- first line\n",
+            "| second line\n",
+            "- other\n",
+        ));
+    }
+
+    #[test]
+    fn standalone_images() {
+
+        let markdown = "Logo ![logo](https://example.com/logo.png) here.\n\n- a\n\n  b\n- c\n";
+
+        let nmd = MarkdownImporter::new().import_str(markdown);
+
+        assert_eq!(nmd, "Logo\n\n![logo](https://example.com/logo.png)\n\nhere.\n\n- a\n| b\n- c\n");
+
+        let codex = Codex::of_html();
+
+        let mut document = MarkdownImporter::new().import_document_from_str("logo.md", markdown, &codex, &LoadConfiguration::default(), LoadConfigurationOverLay::default()).unwrap();
+
+        let mut configuration = CompilationConfiguration::default();
+        configuration.set_embed_remote_image(false);
+
+        let outcome = document.compile(&OutputFormat::Html, &codex, &configuration, CompilationConfigurationOverLay::default()).unwrap();
+
+        assert!(outcome.content().contains("<img"));
+        assert!(!outcome.content().contains("!<a"));
+        assert_eq!(outcome.content().matches("<li").count(), 3);
+    }
+
+    #[test]
+    fn tables() {
+
+        let markdown = concat!(
+            "Name | Value\n",
+            ":--- | ---:\n",
+            "a | `x \\| y`\n",
+            "^ |\n",
+        );
+
+        assert_eq!(MarkdownImporter::new().import_str(markdown), concat!(
+            "| Name | Value |\n",
+            "| :--- | ---: |\n",
+            "| a | `x &#124; y` |\n",
+            "| \\^ |  |\n",
+        ));
+    }
+
+    #[test]
+    fn import_document() {
+
+        let markdown = "# Intro\n\nSome **text**.\n\n- a\n- b\n";
+
+        let document = MarkdownImporter::new().import_document_from_str("intro.md", markdown, &Codex::of_html(), &LoadConfiguration::default(), LoadConfigurationOverLay::default()).unwrap();
+
+        assert_eq!(document.content().chapters().len(), 1);
+        assert_eq!(document.content().chapters()[0].paragraphs().len(), 2);
+    }
+
+    #[test]
+    fn import_directory() {
+
+        let root = TempDir::new("markdown-import");
+        let input = root.join("input");
+        let output = root.join("output");
+
+        std::fs::create_dir_all(input.join("chapters")).unwrap();
+        std::fs::write(input.join("10-end.md"), "# End\n\n| a | b |\n|---|---|\n| long cell | x |\n").unwrap();
+        std::fs::write(input.join("2-start.markdown"), "# Start\n").unwrap();
+        std::fs::write(input.join("chapters").join("middle.md"), "# Middle\n").unwrap();
+        std::fs::write(input.join("notes.txt"), "ignored").unwrap();
+
+        let documents = MarkdownImporter::new().import_directory(&input, &output).unwrap();
+
+        assert_eq!(documents.len(), 3);
+        assert!(output.join("chapters").join("middle.nmd").is_file());
+
+        // imported documents are formatted
+        assert!(!Formatter::default().check_file(&output.join("10-end.nmd")).unwrap());
+
+        let configuration = DossierConfiguration::try_from(&output.join(DOSSIER_CONFIGURATION_YAML_FILE_NAME)).unwrap();
+
+        assert_eq!(configuration.name(), "input");
+        assert_eq!(configuration.raw_documents_paths(), &vec![
+            String::from("./2-start.nmd"),
+            String::from("./10-end.nmd"),
+            String::from("./chapters/middle.nmd"),
+        ]);
+    }
+}
@@ -0,0 +1,299 @@
+pub mod index_entry;
+
+use std::collections::BTreeMap;
+use getset::{Getters, Setters};
+use index_entry::IndexEntry;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Serialize;
+use crate::{codex::Codex, compilable_text::{compilable_text_part::CompilableTextPart, CompilableText}, compilation::{compilable::Compilable, compilation_configuration::{compilation_configuration_overlay::CompilationConfigurationOverLay, CompilationConfiguration}, compilation_error::CompilationError, compilation_outcome::CompilationOutcome}, dossier::{document::chapter::paragraph::list_paragraph::LIST_ITEM_INDENTATION, dossier_configuration::dossier_configuration_index::DossierConfigurationIndex}, output_format::OutputFormat, resource::resource_reference::{ResourceReference, ResourceReferenceError}, utility::html_utility::{decode_entities, escape_xml}};
+
+
+pub const INDEX_FICTITIOUS_DOCUMENT: &str = "index";
+
+/// Compiled index terms without id (see `HtmlIndexTermRule`)
+static UNIDENTIFIED_OCCURRENCE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r#"<span class="index-term"( data-index-[^>]*)?></span>"#).unwrap());
+
+static HEADING_OR_OCCURRENCE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r#"(?s)<h[1-6][^>]*>(.*?)</h[1-6]>|<span class="index-term" id="([^"]+)"([^>]*)></span>"#).unwrap());
+
+static OCCURRENCE_ATTRIBUTE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r#"data-index-(term|sub|see)="([^"]*)""#).unwrap());
+
+static TAG_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"<[^>]+>").unwrap());
+
+
+#[derive(Debug, Clone, Getters, Setters, Serialize)]
+pub struct Index {
+
+    #[getset(get = "pub", set = "pub")]
+    title: String,
+
+    /// Text before related terms
+    #[getset(get = "pub", set = "pub")]
+    see_also_label: String,
+
+    /// Entries by lowercase term
+    #[getset(get = "pub", set = "pub")]
+    entries: BTreeMap<String, IndexEntry>,
+}
+
+impl Index {
+
+    pub fn new(title: String, see_also_label: String) -> Self {
+        Self {
+            title,
+            see_also_label,
+            entries: BTreeMap::new(),
+        }
+    }
+
+    /// Assign an id to each index term of compiled document, ids are unique in dossier because document name is used as prefix
+    pub fn assign_occurrence_ids(compiled_content: &str, document_name: &str) -> Result<String, ResourceReferenceError> {
+
+        let mut result = String::new();
+        let mut last_position: usize = 0;
+
+        for (n, captures) in UNIDENTIFIED_OCCURRENCE_REGEX.captures_iter(compiled_content).enumerate() {
+
+            let m = captures.get(0).unwrap();
+
+            let id = ResourceReference::of_internal_from_without_sharp(&format!("index-{}", n + 1), Some(&document_name))?.build_without_internal_sharp();
+
+            result.push_str(&compiled_content[last_position..m.start()]);
+            result.push_str(&format!(r#"<span class="index-term" id="{}"{}></span>"#, id, captures.get(1).map(|attributes| attributes.as_str()).unwrap_or_default()));
+
+            last_position = m.end();
+        }
+
+        result.push_str(&compiled_content[last_position..]);
+
+        Ok(result)
+    }
+
+    /// Add index terms of a compiled document, each occurrence is labeled using title of its chapter
+    pub fn add_occurrences(&mut self, compiled_content: &str) {
+
+        let mut last_heading: Option<String> = None;
+
+        for captures in HEADING_OR_OCCURRENCE_REGEX.captures_iter(compiled_content) {
+
+            if let Some(heading) = captures.get(1) {
+                last_heading = Some(TAG_REGEX.replace_all(heading.as_str(), "").trim().to_string());
+                continue;
+            }
+
+            let id = captures[2].to_string();
+
+            let (mut term, mut sub_entry, mut see_also) = (None, None, None);
+
+            for attribute in OCCURRENCE_ATTRIBUTE_REGEX.captures_iter(&captures[3]) {
+
+                let value = Some(decode_entities(&attribute[2]));
+
+                match &attribute[1] {
+                    "term" => term = value,
+                    "sub" => sub_entry = value,
+                    _ => see_also = value,
+                }
+            }
+
+            if let Some(term) = term {
+                self.add_occurrence(&term, sub_entry.as_deref(), see_also, id, last_heading.clone());
+            }
+        }
+    }
+
+    /// Add occurrence of term (or of its sub-entry), occurrences without label are numbered
+    pub fn add_occurrence(&mut self, term: &str, sub_entry: Option<&str>, see_also: Option<String>, id: String, label: Option<String>) {
+
+        let mut entry = self.entries.entry(term.to_lowercase()).or_insert_with(|| IndexEntry::new(term.to_string()));
+
+        if let Some(sub_entry) = sub_entry {
+            entry = entry.sub_entries_mut().entry(sub_entry.to_lowercase()).or_insert_with(|| IndexEntry::new(sub_entry.to_string()));
+        }
+
+        let label = label.unwrap_or((entry.occurrences().len() + 1).to_string());
+
+        entry.add_occurrence(id, label);
+
+        if let Some(see_also) = see_also {
+            entry.add_see_also(see_also);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Id of entry by its positions in index (sub-entries positions follow the parent one),
+    /// terms are not used because they can contain any character (e.g. `C#`) and different terms can have the same id (e.g. `C++` and `C--`)
+    fn entry_id(positions: &[usize]) -> Result<String, ResourceReferenceError> {
+
+        let raw_id = format!("entry-{}", positions.iter().map(|position| (position + 1).to_string()).collect::<Vec<String>>().join("-"));
+
+        Ok(ResourceReference::of_internal_from_without_sharp(&raw_id, Some(&INDEX_FICTITIOUS_DOCUMENT))?.build_without_internal_sharp())
+    }
+
+    /// HTML list items of entry and its sub-entries (which are indented as nested list items),
+    /// `positions` are used to build its id (see `entry_id`)
+    fn compile_entry(&self, entry: &IndexEntry, positions: &[usize]) -> Result<String, ResourceReferenceError> {
+
+        let indentation_level = positions.len() - 1;
+
+        let mut html = format!(r#"<li class="index-entry" id="{}">{}<span class="index-entry-term">{}</span> "#, Self::entry_id(positions)?, LIST_ITEM_INDENTATION.repeat(indentation_level), escape_xml(entry.term()));
+
+        html.push_str(&entry.occurrences().iter()
+                            .map(|occurrence| format!(r##"<a class="index-entry-occurrence" href="#{}">{}</a>"##, occurrence.id(), occurrence.label()))
+                            .collect::<Vec<String>>()
+                            .join(", "));
+
+        if !entry.see_also().is_empty() {
+
+            let mut see_also: Vec<String> = Vec::new();
+
+            for related_term in entry.see_also() {
+
+                if let Some(position) = self.entries.keys().position(|key| key.eq(&related_term.to_lowercase())) {
+                    see_also.push(format!(r##"<a class="index-entry-see-also-reference" href="#{}">{}</a>"##, Self::entry_id(&[position])?, escape_xml(related_term)));
+                } else {
+                    see_also.push(escape_xml(related_term));
+                }
+            }
+
+            html.push_str(&format!(r#" <span class="index-entry-see-also">({} {})</span>"#, escape_xml(&self.see_also_label), see_also.join(", ")));
+        }
+
+        html.push_str("</li>");
+
+        for (sub_position, sub_entry) in entry.sub_entries().values().enumerate() {
+            html.push_str(&self.compile_entry(sub_entry, &[positions, &[sub_position]].concat())?);
+        }
+
+        Ok(html)
+    }
+}
+
+impl From<&DossierConfigurationIndex> for Index {
+    fn from(dci: &DossierConfigurationIndex) -> Self {
+        Self::new(dci.title().clone(), dci.see_also_label().clone())
+    }
+}
+
+impl Compilable for Index {
+    fn standard_compile(&mut self, format: &OutputFormat, codex: &Codex, compilation_configuration: &CompilationConfiguration, compilation_configuration_overlay: CompilationConfigurationOverLay) -> Result<CompilationOutcome, CompilationError> {
+        log::info!("compiling index ({} entries)...", self.entries.len());
+
+        match format {
+            OutputFormat::Html | OutputFormat::Epub | OutputFormat::Docx | OutputFormat::Text | OutputFormat::Man => {
+                let mut compilation_result = CompilableText::new_empty();
+
+                let mut compiled_title = CompilableText::from(self.title.clone());
+
+                compiled_title.compile(format, codex, compilation_configuration, compilation_configuration_overlay.clone())?;
+
+                compilation_result.parts_mut().push(CompilableTextPart::new_fixed(String::from(r#"<section class="index"><div class="index-title">"#)));
+                compilation_result.parts_mut().append(compiled_title.parts_mut());
+                compilation_result.parts_mut().push(CompilableTextPart::new_fixed(String::from(r#"</div><div class="index-body">"#)));
+
+                // entries are sorted, so entries with the same initial are consecutive
+                let mut last_group: Option<String> = None;
+
+                for (position, (key, entry)) in self.entries.iter().enumerate() {
+
+                    let group = key.chars().next()
+                                    .filter(|initial| initial.is_alphabetic())
+                                    .map(|initial| initial.to_uppercase().to_string())
+                                    .unwrap_or(String::from("#"));
+
+                    if last_group.as_ref() != Some(&group) {
+
+                        if last_group.is_some() {
+                            compilation_result.parts_mut().push(CompilableTextPart::new_fixed(String::from("</ul></div>")));
+                        }
+
+                        compilation_result.parts_mut().push(CompilableTextPart::new_fixed(format!(r#"<div class="index-group"><div class="index-group-title">{}</div><ul class="index-group-body">"#, escape_xml(&group))));
+
+                        last_group = Some(group);
+                    }
+
+                    compilation_result.parts_mut().push(CompilableTextPart::new_fixed(self.compile_entry(entry, &[position])?));
+                }
+
+                if last_group.is_some() {
+                    compilation_result.parts_mut().push(CompilableTextPart::new_fixed(String::from("</ul></div>")));
+                }
+
+                compilation_result.parts_mut().push(CompilableTextPart::new_fixed(String::from("</div></section>")));
+
+                log::info!("index compiled");
+
+                Ok(CompilationOutcome::from(&compilation_result))
+            },
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use crate::{codex::Codex, compilable_text::CompilableText, compilation::{compilable::Compilable, compilation_configuration::{compilation_configuration_overlay::CompilationConfigurationOverLay, CompilationConfiguration}}, output_format::OutputFormat};
+    use super::Index;
+
+
+    #[test]
+    fn collect_occurrences() {
+
+        let mut compilable = CompilableText::from("Rust@index[Rust] has ownership@index[Rust!ownership|borrowing] and @index[borrowing]");
+
+        compilable.compile(&OutputFormat::Html, &Codex::of_html(), &CompilationConfiguration::default(), CompilationConfigurationOverLay::default()).unwrap();
+
+        assert_eq!(compilable.content(), r#"Rust<span class="index-term" data-index-term="Rust"></span> has ownership<span class="index-term" data-index-term="Rust" data-index-sub="ownership" data-index-see="borrowing"></span> and <span class="index-term" data-index-term="borrowing"></span>"#);
+
+        let content = format!(r#"<h1 class="heading-1">Intro</h1><p>{}</p><h2>Memory <em>model</em></h2><p>{}</p>"#, compilable.content(), compilable.content());
+
+        let content = Index::assign_occurrence_ids(&content, "d1.nmd").unwrap();
+
+        assert!(content.contains(r#"<span class="index-term" id="d1-nmd-index-6" data-index-term="borrowing"></span>"#));
+
+        let mut index = Index::new(String::from("Index"), String::from("see also"));
+
+        index.add_occurrences(&content);
+
+        let rust = index.entries().get("rust").unwrap();
+
+        assert_eq!(rust.occurrences().iter().map(|occurrence| occurrence.label().as_str()).collect::<Vec<&str>>(), vec!["Intro", "Memory model"]);
+
+        let ownership = rust.sub_entries().get("ownership").unwrap();
+
+        assert_eq!(ownership.occurrences()[1].id(), "d1-nmd-index-5");
+        assert_eq!(ownership.see_also(), &vec![String::from("borrowing")]);
+
+        let outcome = index.compile(&OutputFormat::Html, &Codex::of_html(), &CompilationConfiguration::default(), CompilationConfigurationOverLay::default()).unwrap();
+
+        let borrowing_group = outcome.content().find(r#"<div class="index-group-title">B</div>"#).unwrap();
+        let rust_group = outcome.content().find(r#"<div class="index-group-title">R</div>"#).unwrap();
+
+        assert!(borrowing_group < rust_group);
+        assert!(outcome.content().contains(r##"<a class="index-entry-occurrence" href="#d1-nmd-index-1">Intro</a>"##));
+        assert!(outcome.content().contains(r##"(see also <a class="index-entry-see-also-reference" href="#index-entry-1">borrowing</a>)"##));
+        assert!(outcome.content().contains(r#"<li class="index-entry" id="index-entry-2-1">"#));
+    }
+
+    #[test]
+    fn unique_entry_ids() {
+
+        let mut index = Index::new(String::from("Index"), String::from("see also"));
+
+        for term in ["C++", "C--", "C#"] {
+            index.add_occurrence(term, None, None, String::from("d1-nmd-index-1"), None);
+        }
+
+        let outcome = index.compile(&OutputFormat::Html, &Codex::of_html(), &CompilationConfiguration::default(), CompilationConfigurationOverLay::default()).unwrap();
+
+        let ids: Vec<&str> = outcome.content().match_indices(r#"<li class="index-entry" id=""#).map(|(start, m)| {
+            let id_start = start + m.len();
+            &outcome.content()[id_start..id_start + outcome.content()[id_start..].find('"').unwrap()]
+        }).collect();
+
+        assert_eq!(ids, vec!["index-entry-1", "index-entry-2", "index-entry-3"]);
+    }
+}
@@ -0,0 +1,74 @@
+use std::collections::BTreeMap;
+use getset::{Getters, MutGetters, Setters};
+use serde::Serialize;
+
+
+/// Marked occurrence of an index term
+#[derive(Debug, Clone, Getters, Setters, Serialize)]
+pub struct IndexOccurrence {
+
+    /// Internal id of occurrence anchor
+    #[getset(get = "pub", set = "pub")]
+    id: String,
+
+    /// Link text (e.g. title of chapter which contains occurrence)
+    #[getset(get = "pub", set = "pub")]
+    label: String,
+}
+
+impl IndexOccurrence {
+    pub fn new(id: String, label: String) -> Self {
+        Self {
+            id,
+            label,
+        }
+    }
+}
+
+
+#[derive(Debug, Clone, Getters, MutGetters, Setters, Serialize)]
+pub struct IndexEntry {
+
+    /// Displayed term (as written in its first occurrence)
+    #[getset(get = "pub", set = "pub")]
+    term: String,
+
+    #[getset(get = "pub", get_mut = "pub", set = "pub")]
+    occurrences: Vec<IndexOccurrence>,
+
+    /// Related terms
+    #[getset(get = "pub", get_mut = "pub", set = "pub")]
+    see_also: Vec<String>,
+
+    /// Sub-entries by lowercase term
+    #[getset(get = "pub", get_mut = "pub", set = "pub")]
+    sub_entries: BTreeMap<String, IndexEntry>,
+}
+
+impl IndexEntry {
+
+    pub fn new(term: String) -> Self {
+        Self {
+            term,
+            occurrences: Vec::new(),
+            see_also: Vec::new(),
+            sub_entries: BTreeMap::new(),
+        }
+    }
+
+    /// Add occurrence, labels already used by other occurrences are numbered (e.g. `Intro (2)`)
+    pub fn add_occurrence(&mut self, id: String, label: String) {
+
+        let same_label = self.occurrences.iter().filter(|occurrence| occurrence.label().eq(&label) || occurrence.label().starts_with(&format!("{} (", label))).count();
+
+        let label = if same_label > 0 { format!("{} ({})", label, same_label + 1) } else { label };
+
+        self.occurrences.push(IndexOccurrence::new(id, label));
+    }
+
+    pub fn add_see_also(&mut self, term: String) {
+        if !self.see_also.contains(&term) {
+            self.see_also.push(term);
+        }
+    }
+}
@@ -0,0 +1,807 @@
+//! Language server for NMD sources, it speaks Language Server Protocol over standard input and output.
+//!
+//! Supported features:
+//!
+//! - diagnostics of load, compilation and lint issues (published when a document is opened or changed)
+//! - document symbols from chapter headings
+//! - go-to-definition of internal references (e.g. `[see](#title)`) and cite keys (e.g. `^[key]`)
+//! - completion of references (`&key&`), cite keys (`^[key]`), glossary terms (`?[key]`) and greek letters (`%a%`)
+//! - hover preview of compiled HTML of paragraph under cursor
+//! - formatting (see `Formatter`)
+//!
+//! Documents are kept in sync with client sending full content on every change.
+//! If workspace root is a dossier, its configuration (e.g. references, bibliography and lint rules) is used.
+
+
+pub mod lsp_transport;
+pub mod text_document;
+
+
+use std::collections::HashMap;
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde_json::{json, Value};
+use url::Url;
+use crate::codex::Codex;
+use crate::codex::modifier::standard_text_modifier::StandardTextModifier;
+use crate::compilation::compilable::Compilable;
+use crate::compilation::compilation_configuration::compilation_configuration_overlay::CompilationConfigurationOverLay;
+use crate::compilation::compilation_configuration::CompilationConfiguration;
+use crate::compilation::compilation_rule::html_greek_letter_rule::GREEK_LETTERS;
+use crate::constants::DOSSIER_CONFIGURATION_FILE_NAMES;
+use crate::dossier::document::document_metadata::DocumentMetadata;
+use crate::dossier::document::Document;
+use crate::dossier::dossier_configuration::DossierConfiguration;
+use crate::formatter::Formatter;
+use crate::linter::{LintSeverity, Linter, EXPLICIT_IDENTIFIER_REGEX};
+use crate::load::{LoadConfiguration, LoadConfigurationOverLay};
+use crate::load_block::source_segment::{SourceSegment, SourceSegmentKind};
+use crate::output_format::OutputFormat;
+use crate::resource::resource_reference::ResourceReference;
+use crate::utility::file_utility;
+use text_document::TextDocument;
+use lsp_transport::{read_message, write_message};
+
+
+static LINK_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(&StandardTextModifier::Link.modifier_pattern()).unwrap());
+
+static CITE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(&StandardTextModifier::Cite.modifier_pattern()).unwrap());
+
+static REFERENCE_COMPLETION_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"&([\w-]*)$").unwrap());
+
+static CITE_COMPLETION_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\^\[([\w_]*)$").unwrap());
+
+static GLOSSARY_TERM_COMPLETION_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\?\[([\w-]*)$").unwrap());
+
+static GREEK_LETTER_COMPLETION_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"%(\w*)$").unwrap());
+
+
+// JSON-RPC error codes
+const PARSE_ERROR: i64 = -32700;
+const INVALID_PARAMS: i64 = -32602;
+const METHOD_NOT_FOUND: i64 = -32601;
+
+// LSP enumerations
+const DIAGNOSTIC_SEVERITY_ERROR: u32 = 1;
+const DIAGNOSTIC_SEVERITY_WARNING: u32 = 2;
+const SYMBOL_KIND_STRING: u32 = 15;
+const COMPLETION_ITEM_KIND_VARIABLE: u32 = 6;
+const COMPLETION_ITEM_KIND_REFERENCE: u32 = 18;
+const COMPLETION_ITEM_KIND_CONSTANT: u32 = 21;
+const TEXT_DOCUMENT_SYNC_KIND_FULL: u32 = 1;
+
+const DIAGNOSTIC_SOURCE: &str = "nmd";
+
+
+/// Error sent as response to a request: code and message
+type ResponseError = (i64, String);
+
+/// Heading of document: resolved level, title, start and end of heading
+type HeadingEntry = (u32, String, usize, usize);
+
+
+#[derive(Debug)]
+pub struct LanguageServer {
+
+    codex: Codex,
+
+    linter: Linter,
+
+    formatter: Formatter,
+
+    /// Root of workspace, if it is a dossier
+    dossier_path: Option<PathBuf>,
+
+    dossier_configuration: Option<DossierConfiguration>,
+
+    /// Opened documents by URI
+    documents: HashMap<String, TextDocument>,
+
+    exit: bool,
+}
+
+impl LanguageServer {
+
+    pub fn new() -> Self {
+        Self {
+            codex: Codex::of_html(),
+            linter: Linter::default(),
+            formatter: Formatter::default(),
+            dossier_path: None,
+            dossier_configuration: None,
+            documents: HashMap::new(),
+            exit: false,
+        }
+    }
+
+    /// Serve messages until `exit` notification is received or input is closed
+    pub fn run(&mut self, input: &mut impl BufRead, output: &mut impl Write) -> io::Result<()> {
+
+        while !self.exit {
+
+            let message = match read_message(input) {
+                Ok(Some(message)) => message,
+                Ok(None) => break,
+                Err(err) if err.kind() == io::ErrorKind::InvalidData => {
+
+                    log::warn!("malformed message: {}", err);
+
+                    write_message(output, &json!({ "jsonrpc": "2.0", "id": Value::Null, "error": { "code": PARSE_ERROR, "message": err.to_string() } }))?;
+
+                    continue;
+                },
+                Err(err) => return Err(err),
+            };
+
+            for outgoing in self.handle_message(&message) {
+                write_message(output, &outgoing)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Handle a message of client, return messages which must be sent to client (responses and notifications)
+    pub fn handle_message(&mut self, message: &Value) -> Vec<Value> {
+
+        let params = &message["params"];
+
+        let Some(method) = message["method"].as_str() else {
+            return Vec::new()       // responses of client are not used
+        };
+
+        let Some(id) = message.get("id") else {
+            return self.handle_notification(method, params)
+        };
+
+        let outcome = match method {
+            "initialize" => Ok(self.initialize(params)),
+            "shutdown" => Ok(Value::Null),
+            "textDocument/documentSymbol" => self.document_symbols(params),
+            "textDocument/definition" => self.definition(params),
+            "textDocument/completion" => self.completion(params),
+            "textDocument/hover" => self.hover(params),
+            "textDocument/formatting" => self.formatting(params),
+            _ => Err((METHOD_NOT_FOUND, format!("method '{}' is not supported", method))),
+        };
+
+        let response = match outcome {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err((code, message)) => json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } }),
+        };
+
+        vec![response]
+    }
+
+    fn handle_notification(&mut self, method: &str, params: &Value) -> Vec<Value> {
+
+        let uri = params["textDocument"]["uri"].as_str().unwrap_or_default().to_string();
+
+        match method {
+            "exit" => {
+                self.exit = true;
+
+                Vec::new()
+            },
+            "textDocument/didOpen" => {
+
+                let content = params["textDocument"]["text"].as_str().unwrap_or_default().to_string();
+
+                self.documents.insert(uri.clone(), TextDocument::new(uri.clone(), content));
+
+                vec![self.publish_diagnostics(&uri)]
+            },
+            "textDocument/didChange" => {
+
+                // full synchronization, so last change has the whole content
+                let content = params["contentChanges"].as_array()
+                                    .and_then(|changes| changes.last())
+                                    .and_then(|change| change["text"].as_str());
+
+                match (self.documents.get_mut(&uri), content) {
+                    (Some(document), Some(content)) => {
+                        document.set_content(content.to_string());
+
+                        vec![self.publish_diagnostics(&uri)]
+                    },
+                    _ => Vec::new(),
+                }
+            },
+            "textDocument/didSave" => {
+
+                let is_dossier_configuration = Url::parse(&uri).ok()
+                                                .and_then(|url| url.to_file_path().ok())
+                                                .map(|path| path.parent() == self.dossier_path.as_deref() && Self::is_dossier_configuration_file(&path))
+                                                .unwrap_or(false);
+
+                if !is_dossier_configuration {
+                    return Vec::new()
+                }
+
+                if let Some(dossier_path) = self.dossier_path.clone() {
+                    self.load_dossier(&dossier_path);
+                }
+
+                let uris: Vec<String> = self.documents.keys().cloned().collect();
+
+                uris.iter().map(|uri| self.publish_diagnostics(uri)).collect()
+            },
+            "textDocument/didClose" => {
+
+                self.documents.remove(&uri);
+
+                vec![json!({
+                    "jsonrpc": "2.0",
+                    "method": "textDocument/publishDiagnostics",
+                    "params": { "uri": uri, "diagnostics": [] },
+                })]
+            },
+            _ => Vec::new(),
+        }
+    }
+
+    fn initialize(&mut self, params: &Value) -> Value {
+
+        let root = params["rootUri"].as_str()
+                    .and_then(|uri| Url::parse(uri).ok())
+                    .and_then(|url| url.to_file_path().ok())
+                    .or(params["rootPath"].as_str().map(PathBuf::from));
+
+        if let Some(root) = root {
+            self.load_dossier(&root);
+        }
+
+        json!({
+            "capabilities": {
+                "textDocumentSync": {
+                    "openClose": true,
+                    "change": TEXT_DOCUMENT_SYNC_KIND_FULL,
+                    "save": true,
+                },
+                "documentSymbolProvider": true,
+                "definitionProvider": true,
+                "completionProvider": {
+                    "triggerCharacters": ["&", "[", "%"],
+                },
+                "hoverProvider": true,
+                "documentFormattingProvider": true,
+            },
+            "serverInfo": {
+                "name": "nmd",
+                "version": env!("CARGO_PKG_VERSION"),
+            },
+        })
+    }
+
+    /// Load dossier configuration in `path` (if it exists), then codex, linter and formatter are built again
+    fn load_dossier(&mut self, path: &Path) {
+
+        let dossier_configuration = match DossierConfiguration::try_from(&path.to_path_buf()) {
+            Ok(dossier_configuration) => dossier_configuration,
+            Err(err) => {
+                log::info!("{} is not used as dossier: {}", path.to_string_lossy(), err);
+
+                return
+            }
+        };
+
+        let build_codex = || {
+
+            let mut codex = Codex::of_html();
+
+            if let Err(err) = codex.insert_dossier_configuration_modifiers(dossier_configuration.modifiers()) {
+                log::error!("user-defined modifiers cannot be used: {}", err);
+
+                return Codex::of_html()
+            }
+
+            codex
+        };
+
+        self.codex = build_codex();
+        self.linter = Linter::new(build_codex(), dossier_configuration.lint().clone());
+        self.formatter = Formatter::new(build_codex());
+        self.dossier_path = Some(path.to_path_buf());
+        self.dossier_configuration = Some(dossier_configuration);
+    }
+
+    fn is_dossier_configuration_file(path: &Path) -> bool {
+        path.file_name().map(|file_name| DOSSIER_CONFIGURATION_FILE_NAMES.contains(&file_name.to_string_lossy().as_ref())).unwrap_or(false)
+    }
+
+    fn document(&self, params: &Value) -> Result<&TextDocument, ResponseError> {
+
+        let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+
+        self.documents.get(uri).ok_or((INVALID_PARAMS, format!("document '{}' is not opened", uri)))
+    }
+
+    /// Byte offset of `position` parameter
+    fn offset(document: &TextDocument, params: &Value) -> usize {
+        document.offset_of(params["position"]["line"].as_u64().unwrap_or(0) as usize, params["position"]["character"].as_u64().unwrap_or(0) as usize)
+    }
+
+    fn load_configuration(&self) -> LoadConfiguration {
+
+        let mut configuration = self.dossier_configuration.as_ref().map(LoadConfiguration::from).unwrap_or_default();
+
+        configuration.set_parallelization(false);
+
+        configuration
+    }
+
+    fn compilation_configuration(&self, document: &TextDocument) -> CompilationConfiguration {
+
+        let mut configuration = self.dossier_configuration.as_ref().map(CompilationConfiguration::from).unwrap_or_default();
+
+        // images are not embedded, they are not needed to find issues or preview paragraphs
+        configuration.set_embed_local_image(false);
+        configuration.set_embed_remote_image(false);
+        configuration.set_parallelization(false);
+
+        if let Some(parent) = document.path().as_ref().and_then(|path| path.parent()) {
+            configuration.set_input_location(parent.to_path_buf());
+        }
+
+        configuration
+    }
+
+    /// Load and compile content as HTML document
+    fn compile(&self, document: &TextDocument, content: &str) -> Result<String, String> {
+
+        let mut loaded = Document::load_document_from_str(&document.name(), content, &self.codex, &self.load_configuration(), LoadConfigurationOverLay::default())
+                            .map_err(|err| format!("load error: {}", err))?;
+
+        let outcome = loaded.compile(&OutputFormat::Html, &self.codex, &self.compilation_configuration(document), CompilationConfigurationOverLay::default())
+                            .map_err(|err| format!("compilation error: {}", err))?;
+
+        Ok(outcome.content().clone())
+    }
+
+    fn publish_diagnostics(&self, uri: &str) -> Value {
+
+        let mut diagnostics: Vec<Value> = Vec::new();
+
+        if let Some(document) = self.documents.get(uri) {
+
+            // load and compilation errors have no position
+            if let Err(err) = self.compile(document, document.content()) {
+                diagnostics.push(json!({
+                    "range": document.line_range(0),
+                    "severity": DIAGNOSTIC_SEVERITY_ERROR,
+                    "source": DIAGNOSTIC_SOURCE,
+                    "message": err,
+                }));
+            }
+
+            match self.linter.lint_str(document.content()) {
+                Ok(lint_diagnostics) => {
+                    for diagnostic in lint_diagnostics {
+
+                        let severity = match diagnostic.severity() {
+                            LintSeverity::Error => DIAGNOSTIC_SEVERITY_ERROR,
+                            _ => DIAGNOSTIC_SEVERITY_WARNING,
+                        };
+
+                        diagnostics.push(json!({
+                            "range": document.line_range(diagnostic.line().unwrap_or(1) - 1),
+                            "severity": severity,
+                            "code": diagnostic.rule().identifier(),
+                            "source": DIAGNOSTIC_SOURCE,
+                            "message": diagnostic.message(),
+                        }));
+                    }
+                },
+                Err(err) => log::warn!("{} cannot be linted: {}", uri, err),
+            }
+        }
+
+        json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/publishDiagnostics",
+            "params": { "uri": uri, "diagnostics": diagnostics },
+        })
+    }
+
+    /// Segments of document body, positions are relative to whole content
+    fn segments(&self, content: &str) -> Vec<SourceSegment> {
+
+        let body_start = DocumentMetadata::load_front_matter(content).map(|(_, body_start)| body_start).unwrap_or(0);
+
+        match SourceSegment::segment(&content[body_start..], &self.codex, &self.load_configuration()) {
+            Ok(segments) => segments.into_iter()
+                                .map(|segment| SourceSegment::new(body_start + segment.start(), segment.raw().clone(), segment.kind().clone()))
+                                .collect(),
+            Err(err) => {
+                log::warn!("document cannot be segmented: {}", err);
+
+                Vec::new()
+            }
+        }
+    }
+
+    fn headings(&self, content: &str) -> Vec<HeadingEntry> {
+
+        let mut headings: Vec<HeadingEntry> = Vec::new();
+        let mut last_heading_level: Option<u32> = None;
+
+        for segment in self.segments(content) {
+            if let SourceSegmentKind::Heading(header) = segment.kind() {
+
+                let level = header.heading().level().resolve(last_heading_level);
+
+                // heading is on first line of segment, next lines are chapter tags
+                let start = segment.start() + segment.raw().len() - segment.raw().trim_start().len();
+                let end = content[start..segment.end()].find('\n').map(|position| start + position).unwrap_or(segment.end());
+
+                headings.push((level, header.heading().title().clone(), start, end));
+
+                last_heading_level = Some(level);
+            }
+        }
+
+        headings
+    }
+
+    fn document_symbols(&self, params: &Value) -> Result<Value, ResponseError> {
+
+        let document = self.document(params)?;
+
+        let headings = self.headings(document.content());
+
+        Ok(Value::Array(Self::symbols_tree(document, &headings, &mut 0, 0)))
+    }
+
+    /// Symbols of headings starting from `index` which are deeper than `parent_level`, sub-chapters are children of their chapter
+    fn symbols_tree(document: &TextDocument, headings: &[HeadingEntry], index: &mut usize, parent_level: u32) -> Vec<Value> {
+
+        let mut symbols: Vec<Value> = Vec::new();
+
+        while *index < headings.len() && headings[*index].0 > parent_level {
+
+            let (level, title, start, end) = &headings[*index];
+
+            let chapter_end = headings[*index + 1..].iter()
+                                .find(|heading| heading.0 <= *level)
+                                .map(|heading| heading.2)
+                                .unwrap_or(document.content().len());
+
+            let chapter_end = start + document.content()[*start..chapter_end].trim_end().len();
+
+            *index += 1;
+
+            let children = Self::symbols_tree(document, headings, index, *level);
+
+            symbols.push(json!({
+                "name": title,
+                "kind": SYMBOL_KIND_STRING,
+                "range": document.range(*start, chapter_end),
+                "selectionRange": document.range(*start, *end),
+                "children": children,
+            }));
+        }
+
+        symbols
+    }
+
+    fn definition(&self, params: &Value) -> Result<Value, ResponseError> {
+
+        let document = self.document(params)?;
+        let offset = Self::offset(document, params);
+        let content = document.content();
+
+        let contains_offset = |m: &regex::Match| m.start() <= offset && offset <= m.end();
+
+        if let Some(captures) = CITE_REGEX.captures_iter(content).find(|captures| contains_offset(&captures.get(0).unwrap())) {
+            return Ok(self.cite_definition(&captures[1]).unwrap_or(Value::Null))
+        }
+
+        if let Some(captures) = LINK_REGEX.captures_iter(content).find(|captures| contains_offset(&captures.get(0).unwrap())) {
+
+            let target = &captures[2];
+
+            if !target.contains('#') {
+                return Ok(Value::Null)
+            }
+
+            let Ok(reference) = ResourceReference::of(target, Some(&document.name())) else {
+                return Ok(Value::Null)
+            };
+
+            return Ok(self.identifier_definition(document, &reference.build_without_internal_sharp()).unwrap_or(Value::Null))
+        }
+
+        Ok(Value::Null)
+    }
+
+    /// Location of bibliography record in dossier configuration
+    fn cite_definition(&self, key: &str) -> Option<Value> {
+
+        let dossier_path = self.dossier_path.as_ref()?;
+
+        let configuration_path = DOSSIER_CONFIGURATION_FILE_NAMES.iter()
+                                    .map(|file_name| dossier_path.join(file_name))
+                                    .find(|path| path.is_file())?;
+
+        let configuration = TextDocument::new(Url::from_file_path(&configuration_path).ok()?.to_string(), file_utility::read_file_content(&configuration_path).ok()?);
+
+        // records are after `records` key in each format (YAML, JSON and TOML)
+        let records_start = configuration.content().find("records")?;
+
+        let key_regex = Regex::new(&format!(r"\b{}\b", regex::escape(key))).ok()?;
+
+        let m = key_regex.find_at(configuration.content(), records_start)?;
+
+        Some(json!({ "uri": configuration.uri(), "range": configuration.range(m.start(), m.end()) }))
+    }
+
+    /// Location of heading or explicit identifier, current document is searched first, then other opened documents and dossier documents
+    fn identifier_definition(&self, document: &TextDocument, id: &str) -> Option<Value> {
+
+        let mut candidates: Vec<TextDocument> = vec![document.clone()];
+
+        candidates.extend(self.documents.values().filter(|opened| opened.uri() != document.uri()).cloned());
+
+        if let Some(dossier_configuration) = &self.dossier_configuration {
+
+            let documents_paths = dossier_configuration.resolved_documents_paths().unwrap_or_else(|err| {
+                log::warn!("dossier documents cannot be resolved, only opened documents are searched: {}", err);
+
+                Vec::new()
+            });
+
+            for document_path in documents_paths {
+
+                let Ok(url) = Url::from_file_path(&document_path) else {
+                    continue;
+                };
+
+                if self.documents.contains_key(url.as_str()) {
+                    continue;
+                }
+
+                if let Ok(content) = file_utility::read_file_content(&PathBuf::from(&document_path)) {
+                    candidates.push(TextDocument::new(url.to_string(), content));
+                }
+            }
+        }
+
+        candidates.iter().find_map(|candidate| {
+
+            let name = candidate.name();
+
+            let build_id = |raw_id: &str| ResourceReference::of_internal_from_without_sharp(raw_id, Some(&name))
+                                                .map(|reference| reference.build_without_internal_sharp())
+                                                .ok();
+
+            let heading = self.headings(candidate.content()).into_iter()
+                            .find(|(_, title, _, _)| build_id(title).as_deref() == Some(id))
+                            .map(|(_, _, start, end)| (start, end));
+
+            let explicit_identifier = || EXPLICIT_IDENTIFIER_REGEX.captures_iter(candidate.content())
+                                        .map(|captures| captures.get(1).unwrap())
+                                        .find(|m| build_id(m.as_str()).as_deref() == Some(id))
+                                        .map(|m| (m.start(), m.end()));
+
+            heading.or_else(explicit_identifier)
+                .map(|(start, end)| json!({ "uri": candidate.uri(), "range": candidate.range(start, end) }))
+        })
+    }
+
+    fn completion(&self, params: &Value) -> Result<Value, ResponseError> {
+
+        let document = self.document(params)?;
+        let offset = Self::offset(document, params);
+
+        let line_start = document.content()[..offset].rfind('\n').map(|position| position + 1).unwrap_or(0);
+        let before = &document.content()[line_start..offset];
+
+        let mut items: Vec<Value> = Vec::new();
+
+        if CITE_COMPLETION_REGEX.is_match(before) {
+
+            if let Some(dossier_configuration) = &self.dossier_configuration {
+                for (key, record) in dossier_configuration.bibliography().records() {
+                    items.push(json!({ "label": key, "kind": COMPLETION_ITEM_KIND_REFERENCE, "detail": record.title(), "insertText": format!("{}]", key) }));
+                }
+            }
+
+        } else if GLOSSARY_TERM_COMPLETION_REGEX.is_match(before) {
+
+            if let Some(dossier_configuration) = &self.dossier_configuration {
+
+                for (key, entry) in dossier_configuration.glossary().entries() {
+                    items.push(json!({ "label": key, "kind": COMPLETION_ITEM_KIND_REFERENCE, "detail": entry.term().as_ref().unwrap_or(key), "insertText": format!("{}]", key) }));
+                }
+            }
+
+        } else if REFERENCE_COMPLETION_REGEX.is_match(before) {
+
+            if let Some(dossier_configuration) = &self.dossier_configuration {
+
+                for (key, value) in dossier_configuration.references() {
+                    items.push(json!({ "label": key, "kind": COMPLETION_ITEM_KIND_VARIABLE, "detail": value, "insertText": format!("{}&", key) }));
+                }
+
+                for key in dossier_configuration.variables().keys() {
+                    items.push(json!({ "label": key, "kind": COMPLETION_ITEM_KIND_VARIABLE, "detail": "variable", "insertText": format!("{}&", key) }));
+                }
+            }
+
+        } else if GREEK_LETTER_COMPLETION_REGEX.is_match(before) {
+
+            for (key, name) in GREEK_LETTERS {
+                items.push(json!({ "label": key, "kind": COMPLETION_ITEM_KIND_CONSTANT, "detail": name, "insertText": format!("{}%", key) }));
+            }
+        }
+
+        items.sort_by(|a, b| a["label"].as_str().cmp(&b["label"].as_str()));
+
+        Ok(Value::Array(items))
+    }
+
+    fn hover(&self, params: &Value) -> Result<Value, ResponseError> {
+
+        let document = self.document(params)?;
+        let offset = Self::offset(document, params);
+
+        let Some(segment) = self.segments(document.content()).into_iter().find(|segment| segment.start() <= offset && offset < segment.end() && !segment.raw().trim().is_empty()) else {
+            return Ok(Value::Null)
+        };
+
+        let preview = match self.compile(document, segment.raw()) {
+            Ok(html) => format!("```html\n{}\n```", html.trim()),
+            Err(err) => err,
+        };
+
+        Ok(json!({
+            "contents": { "kind": "markdown", "value": preview },
+            "range": document.range(segment.start(), segment.end()),
+        }))
+    }
+
+    fn formatting(&self, params: &Value) -> Result<Value, ResponseError> {
+
+        let document = self.document(params)?;
+
+        let formatted = match self.formatter.format_str(document.content()) {
+            Ok(formatted) => formatted,
+            Err(err) => {
+                log::warn!("{} cannot be formatted: {}", document.uri(), err);
+
+                return Ok(Value::Null)
+            }
+        };
+
+        if formatted.eq(document.content()) {
+            return Ok(json!([]))
+        }
+
+        Ok(json!([{ "range": document.range(0, document.content().len()), "newText": formatted }]))
+    }
+}
+
+impl Default for LanguageServer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use std::io::BufReader;
+    use serde_json::{json, Value};
+    use url::Url;
+    use super::lsp_transport::{read_message, write_message};
+    use super::LanguageServer;
+    use crate::utility::test_utility::TempDir;
+
+
+    fn request(server: &mut LanguageServer, method: &str, params: Value) -> Value {
+        server.handle_message(&json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params })).remove(0)["result"].clone()
+    }
+
+    #[test]
+    fn serve_dossier() {
+
+        let dossier_path = TempDir::new("lsp");
+
+        std::fs::write(dossier_path.join("nmd.yml"), concat!(
+            "name: lsp-test\n",
+            "documents:\n",
+            "  - ./d1.nmd\n",
+            "  - ./d2.nmd\n",
+            "references:\n",
+            "  company: ACME\n",
+            "bibliography:\n",
+            "  title: Bibliography\n",
+            "  include_in_output: false\n",
+            "  records:\n",
+            "    knuth:\n",
+            "      title: The Art of Computer Programming\n",
+        )).unwrap();
+
+        let content = "# Introduction\n\n## Goals\n\nSee [details](d2.nmd#details) and ^[knuth]\n\n### Deep\n\nTODO: write\n";
+
+        std::fs::write(dossier_path.join("d1.nmd"), content).unwrap();
+        std::fs::write(dossier_path.join("d2.nmd"), "# Details\n\nText\n").unwrap();
+
+        let uri = Url::from_file_path(dossier_path.join("d1.nmd")).unwrap().to_string();
+        let text_document = json!({ "uri": uri });
+
+        let mut server = LanguageServer::new();
+
+        let capabilities = request(&mut server, "initialize", json!({ "rootUri": Url::from_file_path(dossier_path.path()).unwrap().to_string() }));
+
+        assert_eq!(capabilities["capabilities"]["hoverProvider"], json!(true));
+
+        let notifications = server.handle_message(&json!({ "jsonrpc": "2.0", "method": "textDocument/didOpen", "params": { "textDocument": { "uri": uri, "text": content } } }));
+
+        let diagnostics = notifications[0]["params"]["diagnostics"].as_array().unwrap();
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0]["code"], json!("todo-marker"));
+        assert_eq!(diagnostics[0]["range"]["start"]["line"], json!(8));
+
+        let symbols = request(&mut server, "textDocument/documentSymbol", json!({ "textDocument": text_document }));
+
+        assert_eq!(symbols[0]["name"], json!("Introduction"));
+        assert_eq!(symbols[0]["children"][0]["name"], json!("Goals"));
+        assert_eq!(symbols[0]["children"][0]["children"][0]["name"], json!("Deep"));
+
+        let definition = request(&mut server, "textDocument/definition", json!({ "textDocument": text_document, "position": { "line": 4, "character": 8 } }));
+
+        assert!(definition["uri"].as_str().unwrap().ends_with("d2.nmd"));
+        assert_eq!(definition["range"]["start"], json!({ "line": 0, "character": 0 }));
+
+        let definition = request(&mut server, "textDocument/definition", json!({ "textDocument": text_document, "position": { "line": 4, "character": 38 } }));
+
+        assert!(definition["uri"].as_str().unwrap().ends_with("nmd.yml"));
+        assert_eq!(definition["range"]["start"]["line"], json!(10));
+
+        server.handle_message(&json!({ "jsonrpc": "2.0", "method": "textDocument/didChange", "params": { "textDocument": { "uri": uri }, "contentChanges": [{ "text": "# Introduction\n&co\n^[\n%\n" }] } }));
+
+        let completion = |server: &mut LanguageServer, line: u32, character: u32| request(server, "textDocument/completion", json!({ "textDocument": text_document, "position": { "line": line, "character": character } }));
+
+        assert_eq!(completion(&mut server, 1, 3)[0]["label"], json!("company"));
+        assert_eq!(completion(&mut server, 2, 2)[0]["insertText"], json!("knuth]"));
+        assert_eq!(completion(&mut server, 3, 1).as_array().unwrap().len(), 48);
+
+        server.handle_message(&json!({ "jsonrpc": "2.0", "method": "textDocument/didChange", "params": { "textDocument": { "uri": uri }, "contentChanges": [{ "text": "#  Introduction\n\n\n**bold** text\n" }] } }));
+
+        let hover = request(&mut server, "textDocument/hover", json!({ "textDocument": text_document, "position": { "line": 3, "character": 2 } }));
+
+        assert!(hover["contents"]["value"].as_str().unwrap().contains("<strong"));
+
+        let edits = request(&mut server, "textDocument/formatting", json!({ "textDocument": text_document }));
+
+        assert_eq!(edits[0]["newText"], json!("# Introduction\n\n**bold** text\n"));
+    }
+
+    #[test]
+    fn run_until_exit() {
+
+        let mut input: Vec<u8> = b"Content-Length: 5\r\n\r\n{bad}".to_vec();
+
+        write_message(&mut input, &json!({ "jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {} })).unwrap();
+        write_message(&mut input, &json!({ "jsonrpc": "2.0", "id": 2, "method": "unknown" })).unwrap();
+        write_message(&mut input, &json!({ "jsonrpc": "2.0", "id": 3, "method": "shutdown" })).unwrap();
+        write_message(&mut input, &json!({ "jsonrpc": "2.0", "method": "exit" })).unwrap();
+        write_message(&mut input, &json!({ "jsonrpc": "2.0", "id": 4, "method": "shutdown" })).unwrap();
+
+        let mut output: Vec<u8> = Vec::new();
+
+        LanguageServer::new().run(&mut BufReader::new(input.as_slice()), &mut output).unwrap();
+
+        let mut output = BufReader::new(output.as_slice());
+
+        assert_eq!(read_message(&mut output).unwrap().unwrap()["error"]["code"], json!(-32700));
+        assert_eq!(read_message(&mut output).unwrap().unwrap()["id"], json!(1));
+        assert_eq!(read_message(&mut output).unwrap().unwrap()["error"]["code"], json!(-32601));
+        assert_eq!(read_message(&mut output).unwrap().unwrap()["id"], json!(3));
+        assert!(read_message(&mut output).unwrap().is_none());
+    }
+}
@@ -0,0 +1,119 @@
+//! Base protocol of Language Server Protocol: each JSON-RPC message is preceded by headers
+//! (only `Content-Length` is used) and an empty line.
+
+use std::io::{self, BufRead, Read, Write};
+use serde_json::Value;
+
+
+const CONTENT_LENGTH_HEADER: &str = "content-length:";
+
+/// Maximum size of message content (bytes), bigger messages are skipped
+pub const MAX_CONTENT_LENGTH: usize = 64 * 1024 * 1024;
+
+
+/// Read next message, `None` if input is closed.
+/// Malformed messages (i.e. invalid content length or JSON) are consumed as possible and reported
+/// as `InvalidData` errors, so next messages can be read
+pub fn read_message(input: &mut impl BufRead) -> io::Result<Option<Value>> {
+
+    let mut content_length: Option<usize> = None;
+
+    loop {
+
+        let mut header = String::new();
+
+        if input.read_line(&mut header)? == 0 {
+            return Ok(None)
+        }
+
+        let header = header.trim();
+
+        if header.is_empty() {
+
+            if content_length.is_some() {
+                break;
+            }
+
+            continue;
+        }
+
+        if header.to_lowercase().starts_with(CONTENT_LENGTH_HEADER) {
+
+            let raw_content_length = header[CONTENT_LENGTH_HEADER.len()..].trim();
+
+            content_length = Some(raw_content_length.parse().map_err(|_| io::Error::new(io::ErrorKind::InvalidData, format!("invalid content length: {}", raw_content_length)))?);
+        }
+    }
+
+    let content_length = content_length.unwrap();
+
+    if content_length > MAX_CONTENT_LENGTH {
+
+        io::copy(&mut input.take(content_length as u64), &mut io::sink())?;
+
+        return Err(io::Error::new(io::ErrorKind::InvalidData, format!("message is too long ({} bytes, maximum is {})", content_length, MAX_CONTENT_LENGTH)))
+    }
+
+    let mut content = vec![0u8; content_length];
+
+    input.read_exact(&mut content)?;
+
+    serde_json::from_slice(&content).map(Some).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
+
+pub fn write_message(output: &mut impl Write, message: &Value) -> io::Result<()> {
+
+    let content = message.to_string();
+
+    write!(output, "Content-Length: {}\r\n\r\n{}", content.len(), content)?;
+
+    output.flush()
+}
+
+
+#[cfg(test)]
+mod test {
+    use std::io::{self, BufReader};
+    use serde_json::json;
+    use super::{read_message, write_message, MAX_CONTENT_LENGTH};
+
+
+    #[test]
+    fn write_and_read() {
+
+        let message = json!({ "jsonrpc": "2.0", "id": 1, "method": "shutdown" });
+
+        let mut buffer: Vec<u8> = Vec::new();
+
+        write_message(&mut buffer, &message).unwrap();
+        write_message(&mut buffer, &message).unwrap();
+
+        assert!(String::from_utf8_lossy(&buffer).starts_with("Content-Length: 44\r\n\r\n{"));
+
+        let mut input = BufReader::new(buffer.as_slice());
+
+        assert_eq!(read_message(&mut input).unwrap(), Some(message.clone()));
+        assert_eq!(read_message(&mut input).unwrap(), Some(message));
+        assert_eq!(read_message(&mut input).unwrap(), None);
+    }
+
+    #[test]
+    fn read_malformed_messages() {
+
+        let message = json!({ "jsonrpc": "2.0", "id": 1, "method": "shutdown" });
+
+        let mut buffer: Vec<u8> = format!("Content-Length: 5\r\n\r\n{{bad}}Content-Length: {}\r\n\r\n", MAX_CONTENT_LENGTH + 1).into_bytes();
+        buffer.extend(vec![b' '; MAX_CONTENT_LENGTH + 1]);
+
+        write_message(&mut buffer, &message).unwrap();
+
+        buffer.extend(b"Content-Length: many\r\n\r\n");
+
+        let mut input = BufReader::new(buffer.as_slice());
+
+        assert_eq!(read_message(&mut input).unwrap_err().kind(), io::ErrorKind::InvalidData);
+        assert_eq!(read_message(&mut input).unwrap_err().kind(), io::ErrorKind::InvalidData);
+        assert_eq!(read_message(&mut input).unwrap(), Some(message));
+        assert_eq!(read_message(&mut input).unwrap_err().kind(), io::ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,138 @@
+use std::path::PathBuf;
+use getset::{Getters, Setters};
+use serde_json::{json, Value};
+use url::Url;
+
+
+/// Document opened by client. Positions of Language Server Protocol are (line, character)
+/// where character is counted in UTF-16 code units, while Rust strings are indexed by bytes
+#[derive(Debug, Clone, Getters, Setters)]
+pub struct TextDocument {
+
+    #[getset(get = "pub")]
+    uri: String,
+
+    /// Path of document, `None` if URI has not `file` scheme
+    #[getset(get = "pub")]
+    path: Option<PathBuf>,
+
+    #[getset(get = "pub", set = "pub")]
+    content: String,
+}
+
+impl TextDocument {
+
+    pub fn new(uri: String, content: String) -> Self {
+
+        let path = Url::parse(&uri).ok().and_then(|url| url.to_file_path().ok());
+
+        Self {
+            uri,
+            path,
+            content,
+        }
+    }
+
+    /// Name used as document name during loading and compilation (i.e. file name)
+    pub fn name(&self) -> String {
+        match &self.path {
+            Some(path) => path.file_name().map(|file_name| file_name.to_string_lossy().to_string()).unwrap_or_default(),
+            None => self.uri.rsplit('/').next().unwrap_or_default().to_string(),
+        }
+    }
+
+    /// Byte offset of position, positions out of content are moved to nearest valid offset
+    pub fn offset_of(&self, line: usize, character: usize) -> usize {
+
+        let mut offset: usize = 0;
+
+        for (index, content_line) in self.content.split_inclusive('\n').enumerate() {
+
+            if index == line {
+
+                let mut units: usize = 0;
+
+                for (byte_index, c) in content_line.char_indices() {
+
+                    if units >= character || c == '\n' {
+                        return offset + byte_index
+                    }
+
+                    units += c.len_utf16();
+                }
+
+                return offset + content_line.len()
+            }
+
+            offset += content_line.len();
+        }
+
+        self.content.len()
+    }
+
+    /// Position (line, character) of byte offset
+    pub fn position_of(&self, offset: usize) -> (usize, usize) {
+
+        let before = &self.content[..floor_char_boundary(&self.content, offset)];
+
+        let line = before.matches('\n').count();
+        let line_start = before.rfind('\n').map(|position| position + 1).unwrap_or(0);
+
+        (line, before[line_start..].encode_utf16().count())
+    }
+
+    /// LSP `Range` between byte offsets
+    pub fn range(&self, start: usize, end: usize) -> Value {
+
+        let (start_line, start_character) = self.position_of(start);
+        let (end_line, end_character) = self.position_of(end);
+
+        json!({
+            "start": { "line": start_line, "character": start_character },
+            "end": { "line": end_line, "character": end_character },
+        })
+    }
+
+    /// LSP `Range` of whole line (starting from 0)
+    pub fn line_range(&self, line: usize) -> Value {
+
+        let start = self.offset_of(line, 0);
+        let end = self.content[start..].find('\n').map(|position| start + position).unwrap_or(self.content.len());
+
+        self.range(start, end)
+    }
+}
+
+fn floor_char_boundary(content: &str, offset: usize) -> usize {
+
+    let mut offset = offset.min(content.len());
+
+    while !content.is_char_boundary(offset) {
+        offset -= 1;
+    }
+
+    offset
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::TextDocument;
+
+
+    #[test]
+    fn positions() {
+
+        let document = TextDocument::new(String::from("file:///tmp/doc.nmd"), String::from("# Title\n\nα and 𝛽 text\n"));
+
+        assert_eq!(document.name(), "doc.nmd");
+
+        // `α` is a single UTF-16 code unit, while `𝛽` is a surrogate pair
+        let offset = document.content().find("text").unwrap();
+
+        assert_eq!(document.position_of(offset), (2, 9));
+        assert_eq!(document.offset_of(2, 9), offset);
+        assert_eq!(document.offset_of(0, 100), 7);
+        assert_eq!(document.offset_of(100, 0), document.content().len());
+    }
+}
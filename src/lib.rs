@@ -4,6 +4,7 @@ pub mod content_bundle;
 pub mod output_format;
 pub mod assembler;
 pub mod dumpable;
+pub mod builder;
 pub mod artifact;
 pub mod theme;
 pub mod compilation;
@@ -12,6 +13,13 @@ pub mod load_block;
 pub mod codex;
 pub mod table_of_contents;
 pub mod bibliography;
+pub mod glossary;
+pub mod index;
+pub mod generated_list;
 pub mod resource;
+pub mod importer;
+pub mod formatter;
+pub mod linter;
+pub mod language_server;
 pub mod utility;
 pub mod constants;
\ No newline at end of file
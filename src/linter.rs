@@ -0,0 +1,687 @@
+//! Lint of NMD sources.
+//!
+//! Sources are segmented as formatter does (see `SourceSegment`), then each rule checks segments of documents.
+//! Every rule has a default severity, which can be changed (or set to `off`) in `lint` section of dossier configuration:
+//!
+//! ```yaml
+//! lint:
+//!   max_paragraph_words: 200
+//!   rules:
+//!     todo-marker: error
+//!     long-paragraph: off
+//! ```
+
+
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::Display;
+use std::io;
+use std::path::{Path, PathBuf};
+use getset::{CopyGetters, Getters, Setters};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use crate::codex::{Codex, CodexError};
+use crate::codex::modifier::constants::{IDENTIFIER_PATTERN, NEW_LINE_PATTERN};
+use crate::codex::modifier::standard_paragraph_modifier::StandardParagraphModifier;
+use crate::codex::modifier::standard_text_modifier::StandardTextModifier;
+use crate::compilation::compilation_rule::constants::SPACE_TAB_EQUIVALENCE;
+use crate::dossier::document::document_metadata::DocumentMetadata;
+use crate::dossier::dossier_configuration::dossier_configuration_lint::DossierConfigurationLint;
+use crate::dossier::dossier_configuration::DossierConfiguration;
+use crate::load::{LoadConfiguration, LoadError};
+use crate::load_block::source_segment::{SourceSegment, SourceSegmentKind};
+use crate::resource::resource_reference::ResourceReference;
+use crate::resource::ResourceError;
+use crate::utility::file_utility;
+
+
+static IMAGE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(&StandardParagraphModifier::Image.modifier_pattern()).unwrap());
+
+static ABRIDGED_IMAGE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(&StandardParagraphModifier::AbridgedImage.modifier_pattern()).unwrap());
+
+static LIST_ITEM_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(&StandardParagraphModifier::ListItem.modifier_pattern()).unwrap());
+
+static TODO_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(&StandardTextModifier::Todo.modifier_pattern()).unwrap());
+
+static CITE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(&StandardTextModifier::Cite.modifier_pattern()).unwrap());
+
+static INCLUDE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(&StandardParagraphModifier::Include.modifier_pattern()).unwrap());
+
+/// Explicit identifiers always follow a closed square bracket (e.g. `[text]#id`, `![caption]#id(src)`)
+pub static EXPLICIT_IDENTIFIER_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(&format!(r"\]{}?{}", NEW_LINE_PATTERN, IDENTIFIER_PATTERN)).unwrap());
+
+/// Paragraphs whose content is not checked (e.g. cites in code are not cites)
+const NOT_TEXT_PARAGRAPHS: [StandardParagraphModifier; 4] = [StandardParagraphModifier::CodeBlock, StandardParagraphModifier::CodeSnippet, StandardParagraphModifier::MathBlock, StandardParagraphModifier::CommentBlock];
+
+/// Bullets which have the same meaning, so only one of them should be used for each indentation level
+const EQUIVALENT_BULLETS: [&str; 3] = ["-", "*", "+"];
+
+
+#[derive(Error, Debug)]
+pub enum LinterError {
+
+    #[error(transparent)]
+    IoError(#[from] io::Error),
+
+    #[error(transparent)]
+    LoadError(#[from] LoadError),
+
+    #[error(transparent)]
+    CodexError(#[from] CodexError),
+
+    #[error(transparent)]
+    ResourceError(#[from] ResourceError),
+}
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum LintSeverity {
+    Off,
+    Warning,
+    Error,
+}
+
+impl Display for LintSeverity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Off => f.write_str("off"),
+            Self::Warning => f.write_str("warning"),
+            Self::Error => f.write_str("error"),
+        }
+    }
+}
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum LintRule {
+
+    /// Heading level is more than one level deeper than previous heading (e.g. `#` followed by `###`)
+    SkippedHeadingLevel,
+
+    /// Chapter without content and sub-chapters
+    EmptyChapter,
+
+    /// Image without caption (which is used as alternative text too)
+    ImageWithoutCaption,
+
+    /// Bibliography record which is never cited
+    UnusedBibliographyRecord,
+
+    /// Identifier (explicit or generated from heading) used more times in the same document
+    DuplicateId,
+
+    /// Paragraph which has more words than `max_paragraph_words`
+    LongParagraph,
+
+    /// Leftover todo
+    TodoMarker,
+
+    /// Different equivalent bullets (`-`, `*`, `+`) used for the same list indentation level in a document
+    InconsistentListBullets,
+}
+
+impl LintRule {
+
+    pub fn all() -> Vec<Self> {
+        vec![
+            Self::SkippedHeadingLevel,
+            Self::EmptyChapter,
+            Self::ImageWithoutCaption,
+            Self::UnusedBibliographyRecord,
+            Self::DuplicateId,
+            Self::LongParagraph,
+            Self::TodoMarker,
+            Self::InconsistentListBullets,
+        ]
+    }
+
+    pub fn identifier(&self) -> &'static str {
+        match self {
+            Self::SkippedHeadingLevel => "skipped-heading-level",
+            Self::EmptyChapter => "empty-chapter",
+            Self::ImageWithoutCaption => "image-without-caption",
+            Self::UnusedBibliographyRecord => "unused-bibliography-record",
+            Self::DuplicateId => "duplicate-id",
+            Self::LongParagraph => "long-paragraph",
+            Self::TodoMarker => "todo-marker",
+            Self::InconsistentListBullets => "inconsistent-list-bullets",
+        }
+    }
+
+    pub fn default_severity(&self) -> LintSeverity {
+        match self {
+            Self::DuplicateId => LintSeverity::Error,
+            _ => LintSeverity::Warning,
+        }
+    }
+}
+
+impl Display for LintRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.identifier())
+    }
+}
+
+
+#[derive(Debug, Clone, Getters, CopyGetters)]
+pub struct LintDiagnostic {
+
+    #[getset(get_copy = "pub")]
+    rule: LintRule,
+
+    #[getset(get_copy = "pub")]
+    severity: LintSeverity,
+
+    /// Name of document, `None` if diagnostic is about the whole dossier
+    #[getset(get = "pub")]
+    document: Option<String>,
+
+    /// Line (starting from 1) of document
+    #[getset(get_copy = "pub")]
+    line: Option<usize>,
+
+    #[getset(get = "pub")]
+    message: String,
+}
+
+impl LintDiagnostic {
+
+    pub fn new(rule: LintRule, severity: LintSeverity, document: Option<String>, line: Option<usize>, message: String) -> Self {
+        Self {
+            rule,
+            severity,
+            document,
+            line,
+            message,
+        }
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == LintSeverity::Error
+    }
+}
+
+impl Display for LintDiagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+
+        if let Some(document) = &self.document {
+            write!(f, "{}:", document)?;
+
+            if let Some(line) = self.line {
+                write!(f, "{}:", line)?;
+            }
+
+            f.write_str(" ")?;
+        }
+
+        write!(f, "{}[{}]: {}", self.severity, self.rule, self.message)
+    }
+}
+
+
+/// Chapter whose content is not found yet
+struct OpenChapter {
+    level: u32,
+    line: usize,
+    title: String,
+    has_content: bool,
+}
+
+
+#[derive(Debug, Getters, Setters)]
+pub struct Linter {
+
+    #[getset(get = "pub", set = "pub")]
+    codex: Codex,
+
+    #[getset(get = "pub", set = "pub")]
+    configuration: DossierConfigurationLint,
+}
+
+impl Linter {
+
+    pub fn new(codex: Codex, configuration: DossierConfigurationLint) -> Self {
+        Self {
+            codex,
+            configuration,
+        }
+    }
+
+    /// Linter which uses lint configuration and user-defined modifiers of dossier
+    pub fn from_dossier_configuration(dossier_configuration: &DossierConfiguration) -> Result<Self, LinterError> {
+
+        let mut codex = Codex::of_html();
+
+        codex.insert_dossier_configuration_modifiers(dossier_configuration.modifiers())?;
+
+        Ok(Self::new(codex, dossier_configuration.lint().clone()))
+    }
+
+    /// Lint single source, rules which need the whole dossier (i.e. `unused-bibliography-record`) are not applied
+    pub fn lint_str(&self, content: &str) -> Result<Vec<LintDiagnostic>, LinterError> {
+        self.lint_document(None, None, content, &mut BTreeSet::new())
+    }
+
+    /// Lint single file, rules which need the whole dossier are not applied
+    pub fn lint_file(&self, path: &PathBuf) -> Result<Vec<LintDiagnostic>, LinterError> {
+
+        let document_name = path.file_name()
+                                .map(|file_name| file_name.to_string_lossy().to_string())
+                                .unwrap_or(path.to_string_lossy().to_string());
+
+        self.lint_path(path, &document_name, &mut BTreeSet::new())
+    }
+
+    /// Lint documents of dossier
+    pub fn lint_dossier(&self, dossier_configuration: &DossierConfiguration) -> Result<Vec<LintDiagnostic>, LinterError> {
+
+        let mut diagnostics: Vec<LintDiagnostic> = Vec::new();
+        let mut cited_keys: BTreeSet<String> = BTreeSet::new();
+
+        let root_path = dossier_configuration.root_path();
+
+        for document_path in dossier_configuration.resolved_documents_paths()? {
+
+            let document_path = PathBuf::from(document_path);
+
+            // documents are named by their path in dossier, so documents with the same file name are distinguishable
+            let document_name = root_path.as_ref()
+                                    .and_then(|root_path| document_path.strip_prefix(root_path).ok())
+                                    .unwrap_or(&document_path)
+                                    .to_string_lossy()
+                                    .to_string();
+
+            diagnostics.append(&mut self.lint_path(&document_path, &document_name, &mut cited_keys)?);
+        }
+
+        for key in dossier_configuration.bibliography().records().keys() {
+            if !cited_keys.contains(key) {
+                self.report(&mut diagnostics, LintRule::UnusedBibliographyRecord, None, None, format!("bibliography record '{}' is never cited", key));
+            }
+        }
+
+        Ok(diagnostics)
+    }
+
+    fn lint_path(&self, path: &PathBuf, document_name: &str, cited_keys: &mut BTreeSet<String>) -> Result<Vec<LintDiagnostic>, LinterError> {
+
+        let content = file_utility::read_file_content(path)?;
+
+        self.lint_document(Some(document_name), Some(path), &content, cited_keys)
+    }
+
+    /// Lint document content, found cites (also of included files) are added to `cited_keys`
+    fn lint_document(&self, document_name: Option<&str>, document_path: Option<&Path>, content: &str, cited_keys: &mut BTreeSet<String>) -> Result<Vec<LintDiagnostic>, LinterError> {
+
+        let content = content.replace("\r\n", "\n");
+
+        let (_, body_start) = DocumentMetadata::load_front_matter(&content)?;
+
+        let mut configuration = LoadConfiguration::default();
+        configuration.set_parallelization(false);
+
+        let segments = SourceSegment::segment(&content[body_start..], &self.codex, &configuration)?;
+
+        let mut diagnostics: Vec<LintDiagnostic> = Vec::new();
+
+        let mut last_heading_level: Option<u32> = None;
+        let mut open_chapter: Option<OpenChapter> = None;
+        let mut ids: HashMap<String, usize> = HashMap::new();
+        let mut bullets: HashMap<usize, (String, usize)> = HashMap::new();
+
+        // files whose cites are already collected
+        let mut visited: Vec<PathBuf> = document_path.and_then(|document_path| document_path.canonicalize().ok()).into_iter().collect();
+
+        for segment in segments.iter() {
+
+            let raw = segment.raw();
+
+            // paragraphs can start with new lines matched by their modifiers
+            let leading_new_lines = raw.len() - raw.trim_start_matches('\n').len();
+
+            let line = Self::line_of(&content, body_start + segment.start() + leading_new_lines);
+
+            let line_at = |position: usize| line + raw[leading_new_lines..position.max(leading_new_lines)].matches('\n').count();
+
+            if let SourceSegmentKind::Heading(header) = segment.kind() {
+
+                let level = header.heading().level().resolve(last_heading_level);
+
+                if let Some(last_heading_level) = last_heading_level {
+                    if level > last_heading_level + 1 {
+                        self.report(&mut diagnostics, LintRule::SkippedHeadingLevel, document_name, Some(line), format!("heading level jumps from {} to {}", last_heading_level, level));
+                    }
+                }
+
+                if let Some(chapter) = open_chapter.take() {
+                    if !chapter.has_content && level <= chapter.level {
+                        self.report(&mut diagnostics, LintRule::EmptyChapter, document_name, Some(chapter.line), format!("chapter '{}' is empty", chapter.title));
+                    }
+                }
+
+                let title = header.heading().title();
+
+                self.check_id(&mut diagnostics, &mut ids, document_name, title, line);
+
+                last_heading_level = Some(level);
+                open_chapter = Some(OpenChapter {
+                    level,
+                    line,
+                    title: title.clone(),
+                    has_content: false,
+                });
+
+                continue;
+            }
+
+            if raw.trim().is_empty() {
+                continue;
+            }
+
+            if let Some(chapter) = open_chapter.as_mut() {
+                chapter.has_content = true;
+            }
+
+            let is_paragraph_of = |modifiers: &[StandardParagraphModifier]| modifiers.iter().any(|modifier| segment.is_paragraph_of(&modifier.identifier()));
+
+            if is_paragraph_of(&NOT_TEXT_PARAGRAPHS) {
+                continue;
+            }
+
+            if is_paragraph_of(&[StandardParagraphModifier::Image, StandardParagraphModifier::AbridgedImage, StandardParagraphModifier::MultiImage]) {
+                self.check_images(&mut diagnostics, document_name, raw, line_at);
+            }
+
+            if is_paragraph_of(&[StandardParagraphModifier::Todo, StandardParagraphModifier::AbridgedTodo, StandardParagraphModifier::MultilineTodo]) {
+                self.report(&mut diagnostics, LintRule::TodoMarker, document_name, Some(line), String::from("todo is found"));
+            }
+
+            if is_paragraph_of(&[StandardParagraphModifier::List]) {
+                self.check_bullets(&mut diagnostics, &mut bullets, document_name, raw, line);
+            }
+
+            if matches!(segment.kind(), SourceSegmentKind::Text) || is_paragraph_of(&[StandardParagraphModifier::CommonParagraph]) {
+
+                let words = raw.split_whitespace().count();
+
+                if words > self.configuration.max_paragraph_words() {
+                    self.report(&mut diagnostics, LintRule::LongParagraph, document_name, Some(line), format!("paragraph has {} words (maximum is {})", words, self.configuration.max_paragraph_words()));
+                }
+            }
+
+            for m in TODO_REGEX.find_iter(raw) {
+                self.report(&mut diagnostics, LintRule::TodoMarker, document_name, Some(line_at(m.start())), String::from("todo is found"));
+            }
+
+            for captures in EXPLICIT_IDENTIFIER_REGEX.captures_iter(raw) {
+                self.check_id(&mut diagnostics, &mut ids, document_name, &captures[1], line_at(captures.get(1).unwrap().start()));
+            }
+
+            self.collect_cites(raw, document_path, cited_keys, &mut visited);
+        }
+
+        if let Some(chapter) = open_chapter {
+            if !chapter.has_content {
+                self.report(&mut diagnostics, LintRule::EmptyChapter, document_name, Some(chapter.line), format!("chapter '{}' is empty", chapter.title));
+            }
+        }
+
+        diagnostics.sort_by_key(|diagnostic| diagnostic.line());
+
+        Ok(diagnostics)
+    }
+
+    /// Add cites of `raw` to `cited_keys`, included files (also in conditional blocks) are followed.
+    /// `visited` are canonical paths of already followed files
+    fn collect_cites(&self, raw: &str, document_path: Option<&Path>, cited_keys: &mut BTreeSet<String>, visited: &mut Vec<PathBuf>) {
+
+        for captures in CITE_REGEX.captures_iter(raw) {
+            cited_keys.insert(captures[1].to_string());
+        }
+
+        for captures in INCLUDE_REGEX.captures_iter(raw) {
+
+            let included_path = PathBuf::from(captures[1].trim());
+
+            let included_path = match document_path.and_then(|document_path| document_path.parent()) {
+                Some(parent) if included_path.is_relative() => parent.join(included_path),
+                _ => included_path,
+            };
+
+            let Ok(canonical_path) = included_path.canonicalize() else {
+                log::warn!("included file {} not found", included_path.to_string_lossy());
+                continue;
+            };
+
+            if visited.contains(&canonical_path) {
+                continue;
+            }
+
+            visited.push(canonical_path.clone());
+
+            if let Err(err) = self.collect_file_cites(&canonical_path, cited_keys, visited) {
+                log::warn!("cites of included file {} cannot be collected: {}", included_path.to_string_lossy(), err);
+            }
+        }
+    }
+
+    fn collect_file_cites(&self, path: &Path, cited_keys: &mut BTreeSet<String>, visited: &mut Vec<PathBuf>) -> Result<(), LinterError> {
+
+        let content = file_utility::read_file_content(&path.to_path_buf())?.replace("\r\n", "\n");
+
+        let (_, body_start) = DocumentMetadata::load_front_matter(&content)?;
+
+        let mut configuration = LoadConfiguration::default();
+        configuration.set_parallelization(false);
+
+        for segment in SourceSegment::segment(&content[body_start..], &self.codex, &configuration)? {
+
+            if NOT_TEXT_PARAGRAPHS.iter().any(|modifier| segment.is_paragraph_of(&modifier.identifier())) {
+                continue;
+            }
+
+            self.collect_cites(segment.raw(), Some(path), cited_keys, visited);
+        }
+
+        Ok(())
+    }
+
+    fn check_id(&self, diagnostics: &mut Vec<LintDiagnostic>, ids: &mut HashMap<String, usize>, document_name: Option<&str>, raw_id: &str, line: usize) {
+
+        // ids are compared as they are built during compilation
+        let id = ResourceReference::of_internal_from_without_sharp(raw_id, document_name.or(Some("document")).as_ref())
+                    .map(|reference| reference.build_without_internal_sharp())
+                    .unwrap_or(raw_id.to_lowercase());
+
+        if let Some(first_line) = ids.get(&id) {
+            self.report(diagnostics, LintRule::DuplicateId, document_name, Some(line), format!("id '{}' is already used at line {}", id, first_line));
+        } else {
+            ids.insert(id, line);
+        }
+    }
+
+    fn check_images(&self, diagnostics: &mut Vec<LintDiagnostic>, document_name: Option<&str>, raw: &str, line_at: impl Fn(usize) -> usize) {
+
+        // groups of image pattern are caption, identifier and source
+        for captures in IMAGE_REGEX.captures_iter(raw) {
+            if captures[1].trim().is_empty() {
+                self.report(diagnostics, LintRule::ImageWithoutCaption, document_name, Some(line_at(captures.get(0).unwrap().start())), format!("image '{}' has no caption", &captures[3]));
+            }
+        }
+
+        for captures in ABRIDGED_IMAGE_REGEX.captures_iter(raw) {
+            self.report(diagnostics, LintRule::ImageWithoutCaption, document_name, Some(line_at(captures.get(0).unwrap().start())), format!("image '{}' has no caption", &captures[1]));
+        }
+    }
+
+    fn check_bullets(&self, diagnostics: &mut Vec<LintDiagnostic>, bullets: &mut HashMap<usize, (String, usize)>, document_name: Option<&str>, raw: &str, line: usize) {
+
+        for (index, item_line) in raw.trim_start_matches('\n').lines().enumerate() {
+
+            let Some(captures) = LIST_ITEM_REGEX.captures(item_line) else {
+                continue;
+            };
+
+            let bullet = &captures[2];
+
+            if !EQUIVALENT_BULLETS.contains(&bullet) {
+                continue;
+            }
+
+            let indentation_level = captures[1].replace('\t', SPACE_TAB_EQUIVALENCE).len() / SPACE_TAB_EQUIVALENCE.len();
+
+            match bullets.get(&indentation_level) {
+                Some((first_bullet, first_line)) if first_bullet.ne(bullet) => {
+                    self.report(diagnostics, LintRule::InconsistentListBullets, document_name, Some(line + index), format!("bullet '{}' is used, but '{}' is used for the same indentation level at line {}", bullet, first_bullet, first_line));
+                },
+                Some(_) => (),
+                None => {
+                    bullets.insert(indentation_level, (bullet.to_string(), line + index));
+                },
+            }
+        }
+    }
+
+    fn report(&self, diagnostics: &mut Vec<LintDiagnostic>, rule: LintRule, document_name: Option<&str>, line: Option<usize>, message: String) {
+
+        let severity = self.configuration.severity_of(&rule);
+
+        if severity == LintSeverity::Off {
+            return
+        }
+
+        diagnostics.push(LintDiagnostic::new(rule, severity, document_name.map(|name| name.to_string()), line, message));
+    }
+
+    /// Line (starting from 1) of byte position
+    fn line_of(content: &str, position: usize) -> usize {
+        content[..position.min(content.len())].matches('\n').count() + 1
+    }
+}
+
+impl Default for Linter {
+    fn default() -> Self {
+        Self::new(Codex::of_html(), DossierConfigurationLint::default())
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+    use std::path::PathBuf;
+    use crate::dossier::dossier_configuration::dossier_configuration_lint::DossierConfigurationLint;
+    use crate::dossier::dossier_configuration::DossierConfiguration;
+    use crate::codex::Codex;
+    use crate::utility::test_utility::TempDir;
+    use super::{LintRule, LintSeverity, Linter, LinterError};
+
+
+    #[test]
+    fn lint_document() {
+
+        let content = concat!(
+            "# Introduction\n\n",
+            "### Details\n\n",
+            "![](images/a.png)\n\n",
+            "TODO: complete this section\n\n",
+            "- first\n",
+            "- second\n\n",
+            "* third\n\n",
+            "# Introduction\n\n",
+            "one two three four five six\n\n",
+            "## Empty\n\n",
+            "# Last\n\n",
+            "Ids [a]#intro [b]#intro\n",
+        );
+
+        let mut configuration = DossierConfigurationLint::default();
+        configuration.set_max_paragraph_words(5);
+
+        let linter = Linter::new(Codex::of_html(), configuration);
+
+        let diagnostics = linter.lint_str(content).unwrap();
+
+        let found: Vec<(LintRule, usize)> = diagnostics.iter().map(|diagnostic| (diagnostic.rule(), diagnostic.line().unwrap())).collect();
+
+        assert_eq!(found, vec![
+            (LintRule::SkippedHeadingLevel, 3),
+            (LintRule::ImageWithoutCaption, 5),
+            (LintRule::TodoMarker, 7),
+            (LintRule::InconsistentListBullets, 12),
+            (LintRule::DuplicateId, 14),
+            (LintRule::LongParagraph, 16),
+            (LintRule::EmptyChapter, 18),
+            (LintRule::DuplicateId, 22),
+        ]);
+
+        assert!(diagnostics.iter().any(|diagnostic| diagnostic.is_error()));
+
+        let mut configuration = DossierConfigurationLint::default();
+        configuration.set_rules(BTreeMap::from([
+            (LintRule::DuplicateId, LintSeverity::Off),
+            (LintRule::TodoMarker, LintSeverity::Error),
+        ]));
+
+        let linter = Linter::new(Codex::of_html(), configuration);
+
+        let diagnostics = linter.lint_str(content).unwrap();
+
+        assert!(diagnostics.iter().all(|diagnostic| diagnostic.rule() != LintRule::DuplicateId));
+        assert!(diagnostics.iter().find(|diagnostic| diagnostic.rule() == LintRule::TodoMarker).unwrap().is_error());
+    }
+
+    #[test]
+    fn lint_dossier() {
+
+        let dossier_path = TempDir::new("lint");
+
+        std::fs::write(dossier_path.join("nmd.yml"), concat!(
+            "name: lint-test\n",
+            "documents:\n",
+            "  - ./d1.nmd\n",
+            "  - ./chapters/d2.nmd\n",
+            "bibliography:\n",
+            "  title: Bibliography\n",
+            "  include_in_output: false\n",
+            "  records:\n",
+            "    cited:\n",
+            "      title: Cited\n",
+            "    included:\n",
+            "      title: Included\n",
+            "    uncited:\n",
+            "      title: Uncited\n",
+            "lint:\n",
+            "  rules:\n",
+            "    empty-chapter: off\n",
+        )).unwrap();
+
+        std::fs::create_dir_all(dossier_path.join("chapters").join("parts")).unwrap();
+
+        std::fs::write(dossier_path.join("d1.nmd"), "# Title\n\nAs shown in ^[cited].\n\n# Empty\n").unwrap();
+        std::fs::write(dossier_path.join("chapters").join("d2.nmd"), "# Other\n\nTODO: check\n\n@if(internal)\n@include(parts/part.nmd)\n@endif\n").unwrap();
+        std::fs::write(dossier_path.join("chapters").join("parts").join("part.nmd"), "As shown in ^[included].\n").unwrap();
+
+        let configuration = DossierConfiguration::try_from(dossier_path.path()).unwrap();
+
+        let linter = Linter::from_dossier_configuration(&configuration).unwrap();
+
+        let diagnostics = linter.lint_dossier(&configuration).unwrap();
+
+        // documents which cannot be resolved are an error, not an empty dossier
+        std::fs::write(dossier_path.join("nmd.yml"), "name: lint-test\ndocuments:\n  - ./d1.nmd\n  - \"!./[\"\n").unwrap();
+
+        let unresolvable = DossierConfiguration::try_from(dossier_path.path()).map(|configuration| linter.lint_dossier(&configuration));
+
+        assert!(matches!(unresolvable, Ok(Err(LinterError::ResourceError(_)))));
+
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[0].to_string(), format!("{}:3: warning[todo-marker]: todo is found", PathBuf::from("chapters").join("d2.nmd").to_string_lossy()));
+        assert_eq!(diagnostics[1].rule(), LintRule::UnusedBibliographyRecord);
+        assert_eq!(diagnostics[1].to_string(), "warning[unused-bibliography-record]: bibliography record 'uncited' is never cited");
+    }
+}
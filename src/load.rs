@@ -1,11 +1,16 @@
+use std::collections::{HashMap, HashSet};
 use std::io;
 use std::path::PathBuf;
-use getset::{CopyGetters, Getters, Setters};
+use getset::{CopyGetters, Getters, MutGetters, Setters};
 use thiserror::Error;
+use crate::dossier::dossier_configuration::DossierConfiguration;
 use crate::resource::resource_reference::ResourceReferenceError;
+use crate::resource::text_reference::TextReferenceMap;
+use crate::resource::variable::{self, VariableMap};
 use crate::resource::ResourceError;
 
 
+pub const DEFAULT_MAX_INCLUDE_DEPTH: usize = 16;
 
 
 #[derive(Error, Debug)]
@@ -30,14 +35,37 @@ pub enum LoadError {
     BlockError(String),
 
     #[error("invalid tag: {0}")]
-    InvalidTag(String)
+    InvalidTag(String),
+
+    /// Error of included file, with line of include directive in including file (if known)
+    #[error("error in included file '{0}'{}: {2}", .1.map(|line| format!(" (included at line {})", line)).unwrap_or_default())]
+    IncludeError(String, Option<usize>, Box<LoadError>),
+
+    /// Error of block which starts at line of loaded content
+    #[error("line {0}: {1}")]
+    LineError(usize, Box<LoadError>),
+
+    #[error("include cycle detected: {0}")]
+    IncludeCycle(String),
+
+    #[error("include depth limit ({0}) exceeded")]
+    IncludeDepthExceeded(usize),
 }
 
 impl Clone for LoadError {
     fn clone(&self) -> Self {
         match self {
+            Self::BucketOfErrors(errors) => Self::BucketOfErrors(errors.clone()),
+            Self::ResourceError(e) => Self::ResourceError(e.clone()),
+            Self::ResourceReferenceError(e) => Self::ResourceReferenceError(e.clone()),
+            Self::ElaborationError(s) => Self::ElaborationError(s.clone()),
             Self::IoError(e) => Self::ElaborationError(e.to_string()),
-            other => other.clone()
+            Self::BlockError(s) => Self::BlockError(s.clone()),
+            Self::InvalidTag(s) => Self::InvalidTag(s.clone()),
+            Self::IncludeError(path, line, e) => Self::IncludeError(path.clone(), *line, e.clone()),
+            Self::LineError(line, e) => Self::LineError(*line, e.clone()),
+            Self::IncludeCycle(s) => Self::IncludeCycle(s.clone()),
+            Self::IncludeDepthExceeded(d) => Self::IncludeDepthExceeded(*d),
         }
     }
 }
@@ -57,6 +85,25 @@ pub struct LoadConfiguration {
 
     #[getset(get_copy = "pub", set = "pub")]
     parallelization: bool,
+
+    #[getset(get_copy = "pub", set = "pub")]
+    strict_code_snippet_check: bool,
+
+    /// Maximum number of nested includes
+    #[getset(get_copy = "pub", set = "pub")]
+    max_include_depth: usize,
+
+    /// Build tags used by conditional content
+    #[getset(get = "pub", set = "pub")]
+    tags: HashSet<String>,
+
+    /// Variables used by conditional content
+    #[getset(get = "pub", set = "pub")]
+    variables: VariableMap,
+
+    /// References used by conditional content if a key is not a variable
+    #[getset(get = "pub", set = "pub")]
+    references: TextReferenceMap,
 }
 
 impl Default for LoadConfiguration {
@@ -65,13 +112,41 @@ impl Default for LoadConfiguration {
             input_location: PathBuf::from("."),
             strict_focus_block_check: false,
             strict_paragraphs_loading_rules_check: true,
-            parallelization: true
+            parallelization: true,
+            strict_code_snippet_check: true,
+            max_include_depth: DEFAULT_MAX_INCLUDE_DEPTH,
+            tags: HashSet::new(),
+            variables: HashMap::new(),
+            references: HashMap::new(),
         }
     }
 }
 
+impl From<&DossierConfiguration> for LoadConfiguration {
+    fn from(dossier_configuration: &DossierConfiguration) -> Self {
+        Self {
+            strict_paragraphs_loading_rules_check: dossier_configuration.compilation().strict_paragraph_loading_rules_check(),
+            parallelization: dossier_configuration.compilation().parallelization(),
+            strict_code_snippet_check: dossier_configuration.compilation().strict_code_snippet_check(),
+            tags: dossier_configuration.tags().iter().cloned().collect(),
+            variables: dossier_configuration.variables().iter().map(|(key, variable)| (key.clone(), variable.build_variable())).collect(),
+            references: dossier_configuration.references().clone(),
+
+            ..Default::default()
+        }
+    }
+}
+
+impl LoadConfiguration {
 
-#[derive(Debug, Getters, Setters, Default, Clone)]
+    /// Value of user variable `key` for a document, if `key` is not a variable references are used
+    pub fn resolve_variable(&self, key: &str, document_name: Option<&String>) -> Option<&String> {
+        variable::resolve_variable(&self.variables, &self.references, key, document_name)
+    }
+}
+
+
+#[derive(Debug, Getters, MutGetters, Setters, Default, Clone)]
 pub struct LoadConfigurationOverLay {
 
     #[getset(get = "pub", set = "pub")]
@@ -79,4 +154,12 @@ pub struct LoadConfigurationOverLay {
 
     #[getset(get = "pub", set = "pub")]
     document_name: Option<String>,
+
+    /// Path of the file which is currently loaded (document or included file)
+    #[getset(get = "pub", set = "pub")]
+    document_path: Option<PathBuf>,
+
+    /// Files which include the current one, from the outermost
+    #[getset(get = "pub", get_mut = "pub", set = "pub")]
+    include_stack: Vec<PathBuf>,
 }
@@ -1,4 +1,6 @@
-use std::sync::RwLock;
+pub mod source_segment;
+
+use std::{path::PathBuf, sync::RwLock};
 
 use getset::{CopyGetters, Getters, MutGetters, Setters};
 use rayon::{iter::{IntoParallelIterator, ParallelIterator}, slice::ParallelSliceMut};
@@ -18,7 +20,11 @@ pub struct LoadBlock {
     end: usize,
 
     #[getset(get = "pub", get_mut = "pub", set = "pub")]
-    content: LoadBlockContent
+    content: LoadBlockContent,
+
+    /// Location of block in included file (`None` if block comes from loaded content itself)
+    #[getset(get = "pub", set = "pub")]
+    source: Option<LoadBlockSource>,
 }
 
 impl LoadBlock {
@@ -27,6 +33,7 @@ impl LoadBlock {
             start,
             end,
             content,
+            source: None,
         }
     }
 
@@ -40,13 +47,13 @@ impl LoadBlock {
     /// `blocks.par_sort_by(|a, b| a.start().cmp(&b.start()));``
     /// 
     pub fn load_from_str(content: &str, codex: &Codex, configuration: &LoadConfiguration, configuration_overlay: LoadConfigurationOverLay) -> Result<Vec<LoadBlock>, LoadError> {
-        Self::inner_load_from_str(content, 0, codex, 0, configuration, configuration_overlay.clone())
+        Self::inner_load_from_str(content, content, 0, codex, 0, configuration, configuration_overlay.clone())
     }
 
     /// Inner load method to load content from `&str` based on `Codex`
     /// 
-    /// This method uses recursive algorithm, use `content_offset=0` and `paragraph_modifier_index=0` to start.
-    fn inner_load_from_str(current_content: &str, offset: usize, codex: &Codex, paragraph_modifier_index: usize, configuration: &LoadConfiguration, configuration_overlay: LoadConfigurationOverLay) -> Result<Vec<LoadBlock>, LoadError> {
+    /// This method uses recursive algorithm, use `current_content=global_content`, `content_offset=0` and `paragraph_modifier_index=0` to start.
+    fn inner_load_from_str(global_content: &str, current_content: &str, offset: usize, codex: &Codex, paragraph_modifier_index: usize, configuration: &LoadConfiguration, configuration_overlay: LoadConfigurationOverLay) -> Result<Vec<LoadBlock>, LoadError> {
 
         let position_in_global_content = |position_in_current: usize| position_in_current + offset;
 
@@ -76,15 +83,35 @@ impl LoadBlock {
 
                 last_position = m_end;
 
-                let paragraph = paragraph_loading_rule.load(m.as_str(), codex, configuration, configuration_overlay.clone())?;
+                // blocks generated by the same match share its position (they are kept in order by stable sort)
+                let blocks = paragraph_loading_rule.load_blocks(m.as_str(), codex, configuration, configuration_overlay.clone())
+                                            .map_err(|err| {
+
+                                                let block_start = position_in_global_content(m_start) + m.as_str().len() - m.as_str().trim_start_matches('\n').len();
+                                                let line = global_content[..block_start].matches('\n').count() + 1;
+
+                                                match err {
 
-                if !paragraph.is_empty() {
+                                                    // errors of included files refer to line of include directive
+                                                    LoadError::IncludeError(path, None, err) => LoadError::IncludeError(path, Some(line), err),
+
+                                                    // lines of nested blocks are relative to matched content
+                                                    LoadError::IncludeError(path, Some(nested_line), err) => LoadError::IncludeError(path, Some(global_content[..position_in_global_content(m_start)].matches('\n').count() + nested_line), err),
+                                                    LoadError::LineError(nested_line, err) => LoadError::LineError(global_content[..position_in_global_content(m_start)].matches('\n').count() + nested_line, err),
+                                                    err => LoadError::LineError(line, Box::new(err)),
+                                                }
+                                            })?;
+
+                for mut block in blocks {
+
+                    if let LoadBlockContent::Paragraph(paragraph) = block.content() {
+                        if paragraph.is_empty() {
+                            continue;
+                        }
+                    }
 
-                    let block = LoadBlock::new(
-                        position_in_global_content(m_start),
-                        position_in_global_content(m_end),
-                        LoadBlockContent::Paragraph(paragraph)
-                    );
+                    block.set_start(position_in_global_content(m_start));
+                    block.set_end(position_in_global_content(m_end));
 
                     log::debug!("added block:\n{:#?}", block);
 
@@ -107,7 +134,7 @@ impl LoadBlock {
 
                     log::debug!("no matches using paragraph modifier {} on:\n{}\n(offset: {})", modifier_identifier, unmatched_slice, offset);
     
-                    let mut blocks = Self::inner_load_from_str(unmatched_slice, offset, codex, paragraph_modifier_index + 1, configuration, configuration_overlay.clone())?;
+                    let mut blocks = Self::inner_load_from_str(global_content, unmatched_slice, offset, codex, paragraph_modifier_index + 1, configuration, configuration_overlay.clone())?;
                 
                     unmatched_slices_blocks.write().unwrap().append(&mut blocks);
 
@@ -133,7 +160,7 @@ impl LoadBlock {
     
                     log::debug!("try next paragraph modifier on:\n{}\n(offset: {})", unmatched_slice, offset);
     
-                    let mut blocks = Self::inner_load_from_str(unmatched_slice, offset, codex, paragraph_modifier_index + 1, configuration, configuration_overlay.clone())?;
+                    let mut blocks = Self::inner_load_from_str(global_content, unmatched_slice, offset, codex, paragraph_modifier_index + 1, configuration, configuration_overlay.clone())?;
                 
                     unmatched_slices_blocks.append(&mut blocks);
                 }
@@ -165,10 +192,18 @@ impl LoadBlock {
 
                     log::debug!("fallback rule {} will be used to load:\n{}", fb_id, raw_fb_paragraph);
 
-                    let paragraphs = fallback_loading_rule.load(raw_fb_paragraph, codex, configuration, configuration_overlay.clone())?;
+                    let paragraphs = fallback_loading_rule.load(raw_fb_paragraph, codex, configuration, configuration_overlay.clone())
+                                        .map_err(|err| {
 
+                                            let paragraph_start = start + raw_fb_paragraph.len() - raw_fb_paragraph.trim_start_matches('\n').len();
+
+                                            LoadError::LineError(global_content[..paragraph_start].matches('\n').count() + 1, Box::new(err))
+                                        })?;
+
+                    // each paragraph needs at least one position, content of only one character
+                    // (e.g. a table cell) is a single paragraph with a single position
                     let len = paragraphs.len();
-                    assert!((end - start) > len);
+                    assert!((end - start) >= len);
 
                     for (index, paragraph) in paragraphs.into_iter().enumerate() {
 
@@ -259,6 +294,30 @@ impl TryInto<Box<dyn Paragraph>> for LoadBlock {
     }
 }
 
+/// Path of included file and block position in its content
+#[derive(Debug, Clone, PartialEq, Getters, CopyGetters)]
+pub struct LoadBlockSource {
+
+    #[getset(get = "pub")]
+    path: PathBuf,
+
+    #[getset(get_copy = "pub")]
+    start: usize,
+
+    #[getset(get_copy = "pub")]
+    end: usize,
+}
+
+impl LoadBlockSource {
+    pub fn new(path: PathBuf, start: usize, end: usize) -> Self {
+        Self {
+            path,
+            start,
+            end,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub enum LoadBlockContent {
     Paragraph(Box<dyn Paragraph>),
@@ -288,4 +347,15 @@ mod test {
 
         assert_eq!(paragraphs.len(), 3)
     }
+
+    #[test]
+    fn single_character_paragraph() {
+
+        let codex = Codex::of_html();
+
+        let paragraphs = LoadBlock::load_from_str("a", &codex, &LoadConfiguration::default(), LoadConfigurationOverLay::default()).unwrap();
+
+        assert_eq!(paragraphs.len(), 1);
+        assert_eq!((paragraphs[0].start(), paragraphs[0].end()), (0, 1));
+    }
 }
\ No newline at end of file
@@ -0,0 +1,176 @@
+//! Segmentation of NMD sources without loading.
+//!
+//! Content is split as `LoadBlock` does (paragraph modifiers in order, then headings and fallback paragraphs),
+//! but loading rules of paragraphs are not applied, so includes and conditional blocks are not expanded.
+//! Each segment keeps its position in source, so tools which work on sources (e.g. formatter and linter)
+//! are able to refer to original lines.
+
+use getset::{CopyGetters, Getters};
+use crate::codex::Codex;
+use crate::codex::modifier::ModifierIdentifier;
+use crate::dossier::document::chapter::chapter_header::ChapterHeader;
+use crate::load::{LoadConfiguration, LoadConfigurationOverLay, LoadError};
+use super::LoadBlockContent;
+
+
+#[derive(Debug, Clone)]
+pub enum SourceSegmentKind {
+
+    /// Paragraph found using paragraph modifier with this identifier
+    Paragraph(ModifierIdentifier),
+
+    Heading(ChapterHeader),
+
+    /// Text loaded as fallback paragraph
+    Text,
+}
+
+
+#[derive(Debug, Clone, Getters, CopyGetters)]
+pub struct SourceSegment {
+
+    /// Byte position in source
+    #[getset(get_copy = "pub")]
+    start: usize,
+
+    #[getset(get = "pub")]
+    raw: String,
+
+    #[getset(get = "pub")]
+    kind: SourceSegmentKind,
+}
+
+impl SourceSegment {
+
+    pub fn new(start: usize, raw: String, kind: SourceSegmentKind) -> Self {
+        Self {
+            start,
+            raw,
+            kind,
+        }
+    }
+
+    pub fn end(&self) -> usize {
+        self.start + self.raw.len()
+    }
+
+    pub fn is_paragraph_of(&self, identifier: &ModifierIdentifier) -> bool {
+        matches!(&self.kind, SourceSegmentKind::Paragraph(id) if id.eq(identifier))
+    }
+
+    /// Split content in segments sorted by position
+    pub fn segment(content: &str, codex: &Codex, configuration: &LoadConfiguration) -> Result<Vec<Self>, LoadError> {
+
+        let mut segments: Vec<Self> = Vec::new();
+
+        Self::segment_with_modifier(content, 0, 0, codex, configuration, &mut segments)?;
+
+        segments.sort_by_key(|segment| segment.start());
+
+        Ok(segments)
+    }
+
+    fn segment_with_modifier(content: &str, offset: usize, paragraph_modifier_index: usize, codex: &Codex, configuration: &LoadConfiguration, segments: &mut Vec<Self>) -> Result<(), LoadError> {
+
+        if let Some((modifier_identifier, (paragraph_modifier, _))) = codex.paragraph_modifiers().get_index(paragraph_modifier_index) {
+
+            let mut last_position: usize = 0;
+
+            for m in paragraph_modifier.modifier_pattern_regex().find_iter(content) {
+
+                if m.start() > last_position {
+                    Self::segment_with_modifier(&content[last_position..m.start()], offset + last_position, paragraph_modifier_index + 1, codex, configuration, segments)?;
+                }
+
+                segments.push(Self::new(offset + m.start(), m.as_str().to_string(), SourceSegmentKind::Paragraph(modifier_identifier.clone())));
+
+                last_position = m.end();
+            }
+
+            if content.len() > last_position {
+                Self::segment_with_modifier(&content[last_position..], offset + last_position, paragraph_modifier_index + 1, codex, configuration, segments)?;
+            }
+
+            return Ok(())
+        }
+
+        let mut headers = ChapterHeader::load(content, codex, configuration)?;
+
+        headers.sort_by_key(|header| header.start());
+
+        let mut last_position: usize = 0;
+
+        for header in headers {
+
+            if header.start() > last_position {
+                Self::segment_text(&content[last_position..header.start()], offset + last_position, codex, configuration, segments)?;
+            }
+
+            let (start, end) = (header.start(), header.end());
+
+            if let LoadBlockContent::ChapterHeader(chapter_header) = header.into() {
+                segments.push(Self::new(offset + start, content[start..end].to_string(), SourceSegmentKind::Heading(chapter_header)));
+            }
+
+            last_position = end;
+        }
+
+        if content.len() > last_position {
+            Self::segment_text(&content[last_position..], offset + last_position, codex, configuration, segments)?;
+        }
+
+        Ok(())
+    }
+
+    fn segment_text(text: &str, offset: usize, codex: &Codex, configuration: &LoadConfiguration, segments: &mut Vec<Self>) -> Result<(), LoadError> {
+
+        if let Some((_, fallback_loading_rule)) = codex.fallback_paragraph() {
+
+            let mut cursor: usize = 0;
+
+            for paragraph in fallback_loading_rule.load(text, codex, configuration, LoadConfigurationOverLay::default())? {
+
+                let raw = paragraph.raw_content();
+
+                // fallback paragraphs don't have positions, they are searched in text (they are in order)
+                let start = text[cursor..].find(raw.trim()).map(|position| cursor + position).unwrap_or(cursor);
+
+                cursor = (start + raw.trim().len()).min(text.len());
+
+                segments.push(Self::new(offset + start, raw.clone(), SourceSegmentKind::Text));
+            }
+
+        } else {
+            segments.push(Self::new(offset, text.to_string(), SourceSegmentKind::Text));
+        }
+
+        Ok(())
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use crate::codex::{modifier::standard_paragraph_modifier::StandardParagraphModifier, Codex};
+    use crate::load::LoadConfiguration;
+    use super::{SourceSegment, SourceSegmentKind};
+
+
+    #[test]
+    fn segment_source() {
+
+        let content = "# Title\n\nFirst paragraph\n\n- a\n- b\n\nSecond paragraph\n";
+
+        let segments = SourceSegment::segment(content, &Codex::of_html(), &LoadConfiguration::default()).unwrap();
+
+        assert!(matches!(segments[0].kind(), SourceSegmentKind::Heading(_)));
+
+        let list = segments.iter().find(|segment| segment.is_paragraph_of(&StandardParagraphModifier::List.identifier())).unwrap();
+
+        assert_eq!(&content[list.start()..list.start() + 3], "- a");
+
+        let second = segments.iter().rfind(|segment| matches!(segment.kind(), SourceSegmentKind::Text)).unwrap();
+
+        assert_eq!(&content[second.start()..second.start() + 6], "Second");
+    }
+}
@@ -12,15 +12,39 @@ pub enum OutputFormatError {
 #[derive(PartialEq, Debug, Default, Clone)]
 pub enum OutputFormat {
     #[default]
-    Html
+    Html,
+
+    /// EPUB 3 package, its documents are compiled as HTML
+    Epub,
+
+    /// Office Open XML (Word) package, its documents are compiled as HTML and then converted
+    Docx,
+
+    /// Plain text wrapped at a configurable width, its documents are compiled as HTML and then converted
+    Text,
+
+    /// roff source of a man page, its documents are compiled as HTML and then converted
+    Man,
 }
 
 impl OutputFormat {
     pub fn get_extension(&self) -> String {
         match self {
             OutputFormat::Html => String::from("html"),
+            OutputFormat::Epub => String::from("epub"),
+            OutputFormat::Docx => String::from("docx"),
+            OutputFormat::Text => String::from("txt"),
+            OutputFormat::Man => String::from("man"),
         }
     } 
+
+    /// Return `true` if output is a binary package of files (e.g. EPUB)
+    pub fn is_package(&self) -> bool {
+        match self {
+            OutputFormat::Html | OutputFormat::Text | OutputFormat::Man => false,
+            OutputFormat::Epub | OutputFormat::Docx => true,
+        }
+    }
 }
 
 impl FromStr for OutputFormat {
@@ -30,6 +54,10 @@ impl FromStr for OutputFormat {
     fn from_str(format: &str) -> Result<Self, Self::Err> {
         match format.to_lowercase().as_str() {
             "html" => Ok(Self::Html),
+            "epub" => Ok(Self::Epub),
+            "docx" => Ok(Self::Docx),
+            "text" | "txt" => Ok(Self::Text),
+            "man" | "roff" => Ok(Self::Man),
             
             _ => Err(OutputFormatError::Unsupported(String::from(format))),
         }
@@ -50,6 +78,25 @@ mod tests {
         }
     }
 
+    #[test]
+    fn epub_support() {
+        assert_eq!(OutputFormat::from_str("EPUB").unwrap(), OutputFormat::Epub);
+        assert_eq!(OutputFormat::Epub.get_extension(), "epub");
+    }
+
+    #[test]
+    fn docx_support() {
+        assert_eq!(OutputFormat::from_str("docx").unwrap(), OutputFormat::Docx);
+        assert!(OutputFormat::Docx.is_package());
+    }
+
+    #[test]
+    fn plain_text_support() {
+        assert_eq!(OutputFormat::from_str("txt").unwrap(), OutputFormat::Text);
+        assert_eq!(OutputFormat::from_str("roff").unwrap(), OutputFormat::Man);
+        assert!(!OutputFormat::Man.is_package());
+    }
+
     #[test]
     fn unsupported_format() {
         assert!(OutputFormat::from_str("htm").is_err())
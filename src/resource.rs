@@ -8,6 +8,8 @@ pub mod text_reference;
 pub mod table;
 pub mod source;
 pub mod bucket;
+pub mod variable;
+pub mod condition;
 
 
 use std::{str::FromStr, io::{self}};
@@ -49,8 +51,15 @@ pub enum ResourceError {
 impl Clone for ResourceError {
     fn clone(&self) -> Self {
         match self {
+            Self::ResourceNotFound(s) => Self::ResourceNotFound(s.clone()),
+            Self::WrongElaboration(s) => Self::WrongElaboration(s.clone()),
+            Self::InvalidResource => Self::InvalidResource,
+            Self::InvalidResourceVerbose(s) => Self::InvalidResourceVerbose(s.clone()),
+            Self::Creation(s) => Self::Creation(s.clone()),
+            Self::ReadError(s) => Self::ReadError(s.clone()),
             Self::IoError(e) => Self::ElaborationError(e.to_string()),
-            other => other.clone()
+            Self::ElaborationError(s) => Self::ElaborationError(s.clone()),
+            Self::ResourceReferenceError(e) => Self::ResourceReferenceError(e.clone()),
         }
     }
 }
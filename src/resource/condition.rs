@@ -0,0 +1,123 @@
+use std::{collections::HashSet, str::FromStr};
+use super::ResourceError;
+
+
+/// Separator of alternatives (logical OR)
+const OR_SEPARATOR: char = '|';
+
+/// Separator of terms of an alternative (logical AND)
+const AND_SEPARATOR: char = ',';
+
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConditionTerm {
+
+    /// Build tag is set, e.g. `internal`
+    Tag(String),
+
+    /// Build tag is not set, e.g. `!internal`
+    NotTag(String),
+
+    /// Variable has value, e.g. `audience=customers`
+    Equal(String, String),
+
+    /// Variable has not value, e.g. `audience!=customers`
+    NotEqual(String, String),
+}
+
+impl ConditionTerm {
+
+    fn evaluate<'a>(&self, tags: &HashSet<String>, variable: &impl Fn(&str) -> Option<&'a String>) -> bool {
+        match self {
+            Self::Tag(tag) => tags.contains(tag),
+            Self::NotTag(tag) => !tags.contains(tag),
+            Self::Equal(key, value) => variable(key).is_some_and(|v| v.eq(value)),
+            Self::NotEqual(key, value) => !variable(key).is_some_and(|v| v.eq(value)),
+        }
+    }
+}
+
+impl FromStr for ConditionTerm {
+    type Err = ResourceError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+
+        let s = s.trim();
+
+        let term = if let Some((key, value)) = s.split_once("!=") {
+            Self::NotEqual(key.trim().to_string(), value.trim().to_string())
+
+        } else if let Some((key, value)) = s.split_once('=') {
+            Self::Equal(key.trim().to_string(), value.trim().to_string())
+
+        } else if let Some(tag) = s.strip_prefix('!') {
+            Self::NotTag(tag.trim().to_string())
+
+        } else {
+            Self::Tag(s.to_string())
+        };
+
+        let key = match &term {
+            Self::Tag(key) | Self::NotTag(key) | Self::Equal(key, _) | Self::NotEqual(key, _) => key,
+        };
+
+        if key.is_empty() {
+            return Err(ResourceError::InvalidResourceVerbose(format!("invalid condition term '{}'", s)))
+        }
+
+        Ok(term)
+    }
+}
+
+
+/// Condition of conditional content, e.g. `internal`, `!customer`, `audience=developers, draft | release`.
+/// Terms separated by `,` must be all true, alternatives separated by `|` are in OR
+#[derive(Debug, Clone, PartialEq)]
+pub struct Condition {
+    alternatives: Vec<Vec<ConditionTerm>>,
+}
+
+impl Condition {
+
+    /// Evaluate condition using build tags and a variable resolver
+    pub fn evaluate<'a>(&self, tags: &HashSet<String>, variable: impl Fn(&str) -> Option<&'a String>) -> bool {
+        self.alternatives.iter().any(|terms| terms.iter().all(|term| term.evaluate(tags, &variable)))
+    }
+}
+
+impl FromStr for Condition {
+    type Err = ResourceError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+
+        let alternatives = s.split(OR_SEPARATOR)
+                            .map(|alternative| alternative.split(AND_SEPARATOR).map(ConditionTerm::from_str).collect::<Result<Vec<_>, _>>())
+                            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            alternatives
+        })
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use std::{collections::{HashMap, HashSet}, str::FromStr};
+    use super::Condition;
+
+
+    #[test]
+    fn evaluate() {
+
+        let tags = HashSet::from([String::from("internal")]);
+        let variables = HashMap::from([(String::from("audience"), String::from("developers"))]);
+        let variable = |key: &str| variables.get(key);
+
+        assert!(Condition::from_str("internal").unwrap().evaluate(&tags, variable));
+        assert!(!Condition::from_str("!internal").unwrap().evaluate(&tags, variable));
+        assert!(Condition::from_str("customer | audience=developers").unwrap().evaluate(&tags, variable));
+        assert!(!Condition::from_str("internal, audience!=developers").unwrap().evaluate(&tags, variable));
+        assert!(Condition::from_str("internal,").is_err());
+    }
+}
@@ -29,6 +29,25 @@ impl DiskResource {
 
         Ok(())
     }
+
+    /// Write binary content, replacing the previous one
+    pub fn write_bytes(&mut self, content: &[u8]) -> Result<(), ResourceError> {
+
+        let file_path = &self.location;
+
+        let mut file = OpenOptions::new()
+            .write(true)
+            .truncate(true)
+            .create(true)
+            .open(file_path)?;
+
+        file.write_all(content)?;
+
+        file.flush()?;
+        file.sync_all()?;
+
+        Ok(())
+    }
 }
 
 impl FromStr for DiskResource {
@@ -83,21 +102,7 @@ impl Resource for DiskResource {
     }
 
     fn write(&mut self, content: &str) -> Result<(), ResourceError> {
-
-        let file_path = &self.location;
-
-        let mut file = OpenOptions::new()
-            .write(true)
-            .truncate(true)
-            .create(true)
-            .open(file_path)?;
-
-        file.write_all(content.as_bytes())?;
-
-        file.flush()?;
-        file.sync_all()?;
-
-        Ok(())
+        self.write_bytes(content.as_bytes())
     }
 
     fn append(&mut self, content: &str) -> Result<(), ResourceError> {
@@ -12,7 +12,7 @@ const VALUE_SEPARATOR: &str = "-";
 const SPACE_REPLACER: char = '-';
 
 
-#[derive(Error, Debug)]
+#[derive(Error, Debug, Clone)]
 pub enum ResourceReferenceError {
     #[error("invalid URL reference")]
     InvalidUrlReference,
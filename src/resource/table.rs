@@ -13,7 +13,32 @@ pub enum TableCellAlignment {
 #[derive(Debug, Clone, Default)]
 pub enum TableCell<T> {
     #[default] None,
-    ContentCell{content: T, alignment: TableCellAlignment}
+    ContentCell{content: T, alignment: TableCellAlignment, column_span: usize, row_span: usize},
+
+    /// Cell covered by a spanning cell
+    Spanned,
+}
+
+impl<T> TableCell<T> {
+
+    /// Create a content cell which spans only one column and one row
+    pub fn new_content_cell(content: T, alignment: TableCellAlignment) -> Self {
+        Self::ContentCell { content, alignment, column_span: 1, row_span: 1 }
+    }
+
+    fn map_content<U>(self) -> TableCell<U>
+    where T: Into<U> {
+        match self {
+            Self::None => TableCell::None,
+            Self::ContentCell { content, alignment, column_span, row_span } => TableCell::ContentCell {
+                content: Into::<U>::into(content),
+                alignment,
+                column_span,
+                row_span
+            },
+            Self::Spanned => TableCell::Spanned,
+        }
+    }
 }
 
 
@@ -28,7 +53,11 @@ pub struct Table<H, B, F> {
     body: Vec<Vec<TableCell<B>>>,
 
     #[getset(get = "pub", get_mut = "pub", set = "pub")]
-    footer: Option<Vec<TableCell<F>>>
+    footer: Option<Vec<TableCell<F>>>,
+
+    /// Relative width of each column
+    #[getset(get = "pub", get_mut = "pub", set = "pub")]
+    column_width_ratios: Option<Vec<u32>>,
 }
 
 impl<H, B, F> Table<H, B, F>
@@ -38,7 +67,8 @@ where B: Into<H> + Into<F> {
         Self {
             header,
             body,
-            footer
+            footer,
+            column_width_ratios: None,
         }
     }
 
@@ -46,7 +76,8 @@ where B: Into<H> + Into<F> {
         Self {
             header: None,
             body: Vec::new(),
-            footer: None
+            footer: None,
+            column_width_ratios: None,
         }
     }
 
@@ -59,20 +90,7 @@ where B: Into<H> + Into<F> {
 
         let first_row = self.body.remove(0);
 
-        let mut header: Vec<TableCell<H>> = Vec::new();
-
-        for table_cell in first_row {
-
-            match table_cell {
-                TableCell::None => header.push(TableCell::None),
-                TableCell::ContentCell { content, alignment } => {
-                    header.push(TableCell::ContentCell {
-                        content: Into::<H>::into(content),
-                        alignment
-                    })
-                },
-            }
-        }
+        let header: Vec<TableCell<H>> = first_row.into_iter().map(|table_cell| table_cell.map_content()).collect();
 
         self.header = Some(header);
 
@@ -82,22 +100,23 @@ where B: Into<H> + Into<F> {
 
         let last_row = self.body.remove(self.body.len() - 1);
 
-        let mut footer: Vec<TableCell<F>> = Vec::new();
+        let footer: Vec<TableCell<F>> = last_row.into_iter().map(|table_cell| table_cell.map_content()).collect();
 
-        for table_cell in last_row {
+        self.footer = Some(footer);
 
-            match table_cell {
-                TableCell::None => footer.push(TableCell::None),
-                TableCell::ContentCell { content, alignment } => {
-                    footer.push(TableCell::ContentCell {
-                        content: Into::<F>::into(content),
-                        alignment
-                    })
-                },
-            }
-        }
+    }
 
-        self.footer = Some(footer);
+    /// Column widths as percentages (based on `column_width_ratios`)
+    pub fn column_width_percentages(&self) -> Option<Vec<f64>> {
+
+        let ratios = self.column_width_ratios.as_ref()?;
+
+        let total: u32 = ratios.iter().sum();
+
+        if total == 0 {
+            return None
+        }
 
+        Some(ratios.iter().map(|ratio| *ratio as f64 * 100.0 / total as f64).collect())
     }
 }
\ No newline at end of file
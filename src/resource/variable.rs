@@ -0,0 +1,262 @@
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+use getset::{Getters, MutGetters, Setters};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+use crate::constants::VERSION;
+use super::text_reference::TextReferenceMap;
+
+
+static DATE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"^(\d{4})-(\d{2})-(\d{2})$").unwrap());
+
+/// Prefix of environment variables which override dossier variables, e.g. `NMD_VAR_AUDIENCE`
+pub const VARIABLE_ENVIRONMENT_PREFIX: &str = "NMD_VAR_";
+
+/// Environment variable used to set a reproducible build date (UNIX timestamp)
+const SOURCE_DATE_EPOCH: &str = "SOURCE_DATE_EPOCH";
+
+
+pub type VariableKey = String;
+
+pub type VariableMap = HashMap<VariableKey, Variable>;
+
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum VariableType {
+    #[default] String,
+    Number,
+    Boolean,
+
+    /// `YYYY-MM-DD`
+    Date,
+}
+
+impl VariableType {
+
+    /// `true` if `value` is a valid value of this type
+    pub fn accept(&self, value: &str) -> bool {
+        match self {
+            Self::String => true,
+            Self::Number => value.trim().parse::<f64>().is_ok(),
+            Self::Boolean => value.trim().parse::<bool>().is_ok(),
+            Self::Date => is_valid_date(value.trim()),
+        }
+    }
+}
+
+/// `true` if `value` is an existing date in `YYYY-MM-DD` format
+fn is_valid_date(value: &str) -> bool {
+
+    let Some(captures) = DATE_REGEX.captures(value) else {
+        return false
+    };
+
+    let year: u32 = captures[1].parse().unwrap();
+    let month: u32 = captures[2].parse().unwrap();
+    let day: u32 = captures[3].parse().unwrap();
+
+    // `u32::is_multiple_of` would require Rust 1.87
+    #[allow(clippy::manual_is_multiple_of)]
+    let is_leap_year = (year % 4 == 0 && year % 100 != 0) || year % 400 == 0;
+
+    let days_in_month = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if is_leap_year => 29,
+        2 => 28,
+        _ => return false,
+    };
+
+    (1..=days_in_month).contains(&day)
+}
+
+
+/// Variable with its (overridden) value and per-document values
+#[derive(Debug, Clone, Default, Getters, MutGetters, Setters)]
+pub struct Variable {
+
+    #[getset(get = "pub", set = "pub")]
+    variable_type: VariableType,
+
+    #[getset(get = "pub", set = "pub")]
+    value: Option<String>,
+
+    #[getset(get = "pub", get_mut = "pub", set = "pub")]
+    document_values: HashMap<String, String>,
+}
+
+impl Variable {
+
+    pub fn new(variable_type: VariableType, value: Option<String>, document_values: HashMap<String, String>) -> Self {
+        Self {
+            variable_type,
+            value,
+            document_values,
+        }
+    }
+
+    /// Value for a document: its own value if any, otherwise the global one
+    pub fn value_for(&self, document_name: Option<&String>) -> Option<&String> {
+
+        if let Some(document_name) = document_name {
+            if let Some(value) = self.document_values.get(document_name) {
+                return Some(value)
+            }
+        }
+
+        self.value.as_ref()
+    }
+
+    /// Override value for every document
+    pub fn override_value(&mut self, value: String) {
+        self.value = Some(value);
+        self.document_values.clear();
+    }
+
+    /// Values which don't match variable type
+    pub fn invalid_values(&self) -> Vec<&String> {
+        self.value.iter()
+            .chain(self.document_values.values())
+            .filter(|value| !self.variable_type.accept(value))
+            .collect()
+    }
+}
+
+
+/// Built-in values computed during compilation
+#[derive(Debug, Clone, PartialEq)]
+pub enum ComputedVariable {
+    BuildDate,
+    DossierName,
+    DocumentName,
+    ChapterCount,
+    WordCount,
+    Version,
+}
+
+impl ComputedVariable {
+
+    pub fn all() -> Vec<Self> {
+        vec![
+            Self::BuildDate,
+            Self::DossierName,
+            Self::DocumentName,
+            Self::ChapterCount,
+            Self::WordCount,
+            Self::Version,
+        ]
+    }
+
+    pub fn key(&self) -> VariableKey {
+        match self {
+            Self::BuildDate => String::from("build_date"),
+            Self::DossierName => String::from("dossier_name"),
+            Self::DocumentName => String::from("document_name"),
+            Self::ChapterCount => String::from("chapter_count"),
+            Self::WordCount => String::from("word_count"),
+            Self::Version => String::from("version"),
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        Self::all().into_iter().find(|v| v.key().eq(key))
+    }
+}
+
+
+/// Convert days from UNIX epoch in a `YYYY-MM-DD` date (proleptic Gregorian calendar)
+fn date_from_unix_days(days: i64) -> String {
+
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let day_of_era = z.rem_euclid(146097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+/// Build time as seconds from Unix epoch, `SOURCE_DATE_EPOCH` is used if present to have reproducible builds
+fn build_unix_timestamp() -> i64 {
+    std::env::var(SOURCE_DATE_EPOCH).ok()
+        .and_then(|epoch| epoch.trim().parse().ok())
+        .unwrap_or_else(|| SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs() as i64).unwrap_or(0))
+}
+
+/// Build date (`YYYY-MM-DD`), `SOURCE_DATE_EPOCH` is used if present to have reproducible builds
+pub fn build_date() -> String {
+    date_from_unix_days(build_unix_timestamp().div_euclid(86400))
+}
+
+/// Build date and time in UTC (`YYYY-MM-DDThh:mm:ssZ`), `SOURCE_DATE_EPOCH` is used if present
+pub fn build_timestamp() -> String {
+
+    let timestamp = build_unix_timestamp();
+
+    let seconds = timestamp.rem_euclid(86400);
+
+    format!("{}T{:02}:{:02}:{:02}Z", date_from_unix_days(timestamp.div_euclid(86400)), seconds / 3600, (seconds % 3600) / 60, seconds % 60)
+}
+
+pub fn version() -> String {
+    VERSION.unwrap_or_default().to_string()
+}
+
+/// Value of user variable `key` for a document, if `key` is not a variable references are used.
+/// It is shared by every conditional content (blocks and inline)
+pub fn resolve_variable<'a>(variables: &'a VariableMap, references: &'a TextReferenceMap, key: &str, document_name: Option<&String>) -> Option<&'a String> {
+
+    if let Some(variable) = variables.get(key) {
+        return variable.value_for(document_name)
+    }
+
+    references.get(key)
+}
+
+/// Name of environment variable which overrides `key`
+pub fn environment_variable_name(key: &str) -> String {
+    format!("{}{}", VARIABLE_ENVIRONMENT_PREFIX, key.to_uppercase().replace('-', "_"))
+}
+
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+    use super::{date_from_unix_days, environment_variable_name, Variable, VariableType};
+
+
+    #[test]
+    fn dates() {
+        assert_eq!(date_from_unix_days(0), "1970-01-01");
+        assert_eq!(date_from_unix_days(19844), "2024-05-01");
+
+        assert!(VariableType::Date.accept("2024-02-29"));
+        assert!(VariableType::Date.accept("2000-02-29"));
+        assert!(!VariableType::Date.accept("1900-02-29"));
+        assert!(!VariableType::Date.accept("2024-02-31"));
+        assert!(!VariableType::Date.accept("2024-13-01"));
+        assert!(!VariableType::Date.accept("2024-04-00"));
+    }
+
+    #[test]
+    fn document_values_and_types() {
+
+        let mut variable = Variable::new(VariableType::Number, Some(String::from("1")), HashMap::from([(String::from("a.nmd"), String::from("x"))]));
+
+        assert_eq!(variable.value_for(Some(&String::from("a.nmd"))).unwrap(), "x");
+        assert_eq!(variable.value_for(Some(&String::from("b.nmd"))).unwrap(), "1");
+        assert_eq!(variable.invalid_values(), vec!["x"]);
+
+        variable.override_value(String::from("2"));
+
+        assert_eq!(variable.value_for(Some(&String::from("a.nmd"))).unwrap(), "2");
+        assert_eq!(environment_variable_name("release-name"), "NMD_VAR_RELEASE_NAME");
+    }
+}
@@ -78,7 +78,7 @@ impl Compilable for TableOfContents {
         let min_heading_lv = Self::min_headers_lv(self.headings())?;
         
         match format {
-            OutputFormat::Html => {
+            OutputFormat::Html | OutputFormat::Epub | OutputFormat::Docx | OutputFormat::Text | OutputFormat::Man => {
                 let mut outcome = CompilableText::new_empty();
 
                 let mut compiled_title = CompilableText::from(self.title.clone());
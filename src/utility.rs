@@ -1,4 +1,7 @@
 pub mod file_utility;
 pub mod text_utility;
 pub mod image_utility;
-pub mod nmd_unique_identifier;
\ No newline at end of file
+pub mod html_utility;
+pub mod nmd_unique_identifier;
+#[cfg(test)]
+pub mod test_utility;
@@ -1,6 +1,8 @@
+use std::cmp::Ordering;
 use std::fs::{self, File};
 use std::io::{self, Read, Write};
 use std::path::{Path, PathBuf};
+use crate::dossier::dossier_configuration::dossier_configuration_path_reference_manager::{DOSSIER_CONFIGURATION_RAW_REFERENCE_MANAGER, PATH_START_FOR_RELATIVE};
 
 
 /// Return entirely file content 
@@ -21,6 +23,32 @@ pub fn is_file_path(s: &str) -> bool {
     Path::new(s).is_absolute() || Path::new(s).is_relative()
 }
 
+/// Return true if path is an existing directory or it ends with a path separator (i.e. it is meant to be a directory)
+pub fn is_directory_like(path: &Path) -> bool {
+    path.is_dir() || path.to_string_lossy().ends_with(['/', std::path::MAIN_SEPARATOR])
+}
+
+/// Resolve a path referenced in a document: paths which start with `./` are relative to dossier root
+/// (see `DossierConfigurationRawReferenceManager`), other relative paths are joined to `fallback_root`
+pub fn resolve_dossier_path(raw_path: &str, fallback_root: &Path) -> PathBuf {
+    resolve_dossier_path_from(DOSSIER_CONFIGURATION_RAW_REFERENCE_MANAGER.lock().unwrap().root_path(), raw_path, fallback_root)
+}
+
+fn resolve_dossier_path_from(dossier_root: Option<&PathBuf>, raw_path: &str, fallback_root: &Path) -> PathBuf {
+
+    let raw_path = raw_path.trim();
+
+    if Path::new(raw_path).is_absolute() {
+        return PathBuf::from(raw_path)
+    }
+
+    // root is applied once, also if it is relative (e.g. `docs`)
+    match raw_path.strip_prefix(PATH_START_FOR_RELATIVE).or(raw_path.strip_prefix(r".\")) {
+        Some(path) => dossier_root.map(|root| root.as_path()).unwrap_or(fallback_root).join(path),
+        None => fallback_root.join(raw_path),
+    }
+}
+
 pub fn create_directory(path: &PathBuf) -> Result<(), io::Error> {
    fs::create_dir(path)
 }
@@ -97,4 +125,90 @@ pub fn all_files_in_dir(dir_path: &PathBuf, exts: &Vec<String>) -> Result<Vec<Pa
     }   
 
     Ok(files)
-}
\ No newline at end of file
+}
+
+/// Compare two strings using natural order, i.e. digit sequences are compared as numbers (`2-intro` < `10-end`).
+/// Strings are compared ignoring case, case is used only if they are equal otherwise (`B-intro` > `b-end`)
+pub fn natural_cmp(a: &str, b: &str) -> Ordering {
+    natural_cmp_by_case(a, b, false).then_with(|| natural_cmp_by_case(a, b, true))
+}
+
+fn natural_cmp_by_case(a: &str, b: &str, case_sensitive: bool) -> Ordering {
+
+    let mut a_chars = a.chars().peekable();
+    let mut b_chars = b.chars().peekable();
+
+    loop {
+        match (a_chars.peek().copied(), b_chars.peek().copied()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(a_char), Some(b_char)) if a_char.is_ascii_digit() && b_char.is_ascii_digit() => {
+
+                let take_number = |chars: &mut std::iter::Peekable<std::str::Chars>| {
+                    let mut digits = String::new();
+
+                    while let Some(c) = chars.peek().copied().filter(char::is_ascii_digit) {
+                        digits.push(c);
+                        chars.next();
+                    }
+
+                    digits
+                };
+
+                let a_number = take_number(&mut a_chars);
+                let b_number = take_number(&mut b_chars);
+
+                let a_trimmed = a_number.trim_start_matches('0');
+                let b_trimmed = b_number.trim_start_matches('0');
+
+                let ordering = a_trimmed.len().cmp(&b_trimmed.len()).then_with(|| a_trimmed.cmp(b_trimmed));
+
+                if ordering != Ordering::Equal {
+                    return ordering
+                }
+            },
+            (Some(a_char), Some(b_char)) => {
+
+                let ordering = match case_sensitive {
+                    true => a_char.cmp(&b_char),
+                    false => a_char.to_lowercase().cmp(b_char.to_lowercase()),
+                };
+
+                if ordering != Ordering::Equal {
+                    return ordering
+                }
+
+                a_chars.next();
+                b_chars.next();
+            },
+        }
+    }
+}
+
+/// Leading number of a file name (e.g. `10` for `10-conclusion.nmd`)
+pub fn numeric_prefix(file_name: &str) -> Option<u64> {
+
+    let digits: String = file_name.chars().take_while(char::is_ascii_digit).collect();
+
+    digits.parse().ok()
+}
+
+
+#[cfg(test)]
+mod test {
+    use std::path::{Path, PathBuf};
+    use super::resolve_dossier_path_from;
+
+
+    #[test]
+    fn resolve_path_from_relative_dossier_root() {
+
+        let root = PathBuf::from("docs");
+
+        assert_eq!(resolve_dossier_path_from(Some(&root), "./data.csv", Path::new("docs")), PathBuf::from("docs").join("data.csv"));
+        assert_eq!(resolve_dossier_path_from(Some(&root), "data/sales.csv", Path::new("docs")), PathBuf::from("docs").join("data/sales.csv"));
+        assert_eq!(resolve_dossier_path_from(None, "./data.csv", Path::new("docs")), PathBuf::from("docs").join("data.csv"));
+        assert_eq!(resolve_dossier_path_from(Some(&root), "/abs/data.csv", Path::new("docs")), PathBuf::from("/abs/data.csv"));
+    }
+}
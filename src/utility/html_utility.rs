@@ -0,0 +1,495 @@
+use once_cell::sync::Lazy;
+use regex::{Captures, Regex};
+
+
+/// Elements which have no content (and no end tag)
+pub const VOID_ELEMENTS: [&str; 14] = ["area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source", "track", "wbr"];
+
+/// Elements which are converted as blocks by assemblers of HTML based formats (e.g. plain text)
+pub const BLOCK_ELEMENTS: [&str; 28] = [
+    "address", "article", "aside", "blockquote", "caption", "dd", "div", "dl", "dt", "figcaption", "figure", "footer", "h1", "h2",
+    "h3", "h4", "h5", "h6", "header", "hr", "li", "nav", "ol", "p", "pre", "section", "table", "ul",
+];
+
+/// Classes of bibliography record fields, in output order
+pub const BIBLIOGRAPHY_ITEM_FIELD_CLASSES: [&str; 5] = ["bibliography-item-authors", "bibliography-item-title", "bibliography-item-year", "bibliography-item-description", "bibliography-item-url"];
+
+/// Elements whose content is not parsed
+pub const RAW_TEXT_ELEMENTS: [&str; 2] = ["script", "style"];
+
+/// Entities predefined by XML
+pub const XML_ENTITIES: [(&str, u32); 5] = [("amp", 38), ("lt", 60), ("gt", 62), ("quot", 34), ("apos", 39)];
+
+/// Common HTML named entities (entities used by codex output are always present)
+pub const HTML_ENTITIES: [(&str, u32); 36] = [
+    ("nbsp", 160), ("iexcl", 161), ("cent", 162), ("pound", 163), ("yen", 165), ("sect", 167), ("copy", 169), ("laquo", 171),
+    ("reg", 174), ("deg", 176), ("plusmn", 177), ("middot", 183), ("raquo", 187), ("frac12", 189), ("times", 215), ("divide", 247),
+    ("ndash", 8211), ("mdash", 8212), ("lsquo", 8216), ("rsquo", 8217), ("ldquo", 8220), ("rdquo", 8221), ("bull", 8226), ("hellip", 8230),
+    ("euro", 8364), ("trade", 8482), ("larr", 8592), ("rarr", 8594), ("harr", 8596), ("rArr", 8658), ("hArr", 8660), ("ne", 8800),
+    ("le", 8804), ("ge", 8805), ("alpha", 945), ("beta", 946),
+];
+
+static ENTITY_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"&(#[0-9]+|#[xX][0-9a-fA-F]+|[A-Za-z][A-Za-z0-9]*);").unwrap());
+
+static TAG_NAME_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"^</?([A-Za-z][A-Za-z0-9:-]*)").unwrap());
+
+/// HTML whitespaces, which are collapsed in a single space
+pub static WHITESPACES_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"[ \t\r\n]+").unwrap());
+
+static ATTRIBUTE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r#"([^\s=>/"']+)(?:\s*=\s*("[^"]*"|'[^']*'|[^\s"'=<>`]+))?"#).unwrap());
+
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum HtmlNode {
+    Element(HtmlElement),
+    Text(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HtmlElement {
+    pub name: String,
+    pub attributes: Vec<(String, String)>,
+    pub children: Vec<HtmlNode>,
+}
+
+impl HtmlElement {
+
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_lowercase(),
+            attributes: Vec::new(),
+            children: Vec::new(),
+        }
+    }
+
+    pub fn attribute(&self, name: &str) -> Option<&String> {
+        self.attributes.iter().find(|(attribute_name, _)| attribute_name.eq(name)).map(|(_, value)| value)
+    }
+
+    pub fn classes(&self) -> Vec<&str> {
+        self.attribute("class").map(|classes| classes.split_whitespace().collect()).unwrap_or_default()
+    }
+
+    pub fn has_class(&self, class: &str) -> bool {
+        self.classes().contains(&class)
+    }
+
+    pub fn child_elements(&self) -> impl Iterator<Item = &HtmlElement> {
+        self.children.iter().filter_map(|child| match child {
+            HtmlNode::Element(element) => Some(element),
+            HtmlNode::Text(_) => None,
+        })
+    }
+
+    /// Concatenated text of all descendants
+    pub fn text(&self) -> String {
+        self.children.iter().map(|child| match child {
+            HtmlNode::Element(element) => element.text(),
+            HtmlNode::Text(text) => text.clone(),
+        }).collect()
+    }
+}
+
+/// Escape text to be used in XML content or attribute values
+pub fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Replace numeric entities and known named entities with their characters, unknown entities are kept
+pub fn decode_entities(s: &str) -> String {
+    ENTITY_REGEX.replace_all(s, |captures: &Captures| {
+
+        let entity = &captures[1];
+
+        let code = if let Some(hex) = entity.strip_prefix("#x").or(entity.strip_prefix("#X")) {
+            u32::from_str_radix(hex, 16).ok()
+        } else if let Some(decimal) = entity.strip_prefix('#') {
+            decimal.parse().ok()
+        } else {
+            XML_ENTITIES.iter().chain(HTML_ENTITIES.iter()).find(|(name, _)| *name == entity).map(|(_, code)| *code)
+        };
+
+        code.and_then(char::from_u32).map(String::from).unwrap_or(captures[0].to_string())
+    }).to_string()
+}
+
+fn parse_attributes(raw: &str) -> Vec<(String, String)> {
+    ATTRIBUTE_REGEX.captures_iter(raw).map(|attribute| {
+
+        let value = match attribute.get(2).map(|value| value.as_str()) {
+            Some(value) if value.starts_with('"') || value.starts_with('\'') => &value[1..value.len() - 1],
+            Some(value) => value,
+            None => "",
+        };
+
+        (attribute[1].to_lowercase(), decode_entities(value))
+    }).collect()
+}
+
+/// Index of `>` which ends tag starting at `start`, quoted values are skipped
+pub fn tag_end(html: &str, start: usize) -> Option<usize> {
+
+    let mut quote: Option<char> = None;
+
+    for (index, c) in html[start..].char_indices() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => (),
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c == '>' => return Some(start + index),
+            None => (),
+        }
+    }
+
+    None
+}
+
+/// Parse start tag which begins at `start` (e.g. `<figure class="figure">`), returning element without children and index after tag
+pub fn parse_start_tag(html: &str, start: usize) -> Option<(HtmlElement, usize)> {
+
+    let name = TAG_NAME_REGEX.captures(&html[start..]).filter(|_| !html[start..].starts_with("</"))?[1].to_lowercase();
+
+    let end = tag_end(html, start)?;
+
+    let mut element = HtmlElement::new(&name);
+    element.attributes = parse_attributes(html[start + 1 + name.len()..end].trim_end().trim_end_matches('/'));
+
+    Some((element, end + 1))
+}
+
+/// Start tag of element, attribute values are escaped
+pub fn start_tag(element: &HtmlElement) -> String {
+
+    let attributes: String = element.attributes.iter().map(|(name, value)| format!(r#" {}="{}""#, name, escape_xml(value))).collect();
+
+    format!("<{}{}>", element.name, attributes)
+}
+
+fn close_element(stack: &mut Vec<HtmlElement>) {
+    let element = stack.pop().unwrap();
+    stack.last_mut().unwrap().children.push(HtmlNode::Element(element));
+}
+
+/// Parse HTML in a tree (root element is named `root`). Parsing is tolerant: unexpected end tags are ignored,
+/// elements not closed are closed by end tag of their ancestors and by the end of input
+pub fn parse_html(html: &str) -> HtmlElement {
+
+    let mut stack: Vec<HtmlElement> = vec![HtmlElement::new("root")];
+
+    let mut position: usize = 0;
+
+    let push_text = |stack: &mut Vec<HtmlElement>, text: &str| {
+        if !text.is_empty() {
+            stack.last_mut().unwrap().children.push(HtmlNode::Text(decode_entities(text)));
+        }
+    };
+
+    while let Some(offset) = html[position..].find('<') {
+
+        let start = position + offset;
+        let rest = &html[start..];
+
+        if rest.starts_with("<!--") {
+
+            push_text(&mut stack, &html[position..start]);
+
+            position = rest.find("-->").map(|end| start + end + 3).unwrap_or(html.len());
+
+            continue;
+        }
+
+        if rest.starts_with("<!") || rest.starts_with("<?") {
+
+            push_text(&mut stack, &html[position..start]);
+
+            position = rest.find('>').map(|end| start + end + 1).unwrap_or(html.len());
+
+            continue;
+        }
+
+        let name = match TAG_NAME_REGEX.captures(rest) {
+            Some(captures) => captures[1].to_lowercase(),
+            None => {
+                // it is not a tag (e.g. `a < b`)
+                push_text(&mut stack, &html[position..start + 1]);
+                position = start + 1;
+                continue;
+            }
+        };
+
+        let end = match tag_end(html, start) {
+            Some(end) => end,
+            None => break,
+        };
+
+        push_text(&mut stack, &html[position..start]);
+
+        position = end + 1;
+
+        if rest.starts_with("</") {
+
+            if let Some(index) = stack.iter().rposition(|element| element.name == name) {
+                if index > 0 {
+                    while stack.len() > index {
+                        close_element(&mut stack);
+                    }
+                }
+            }
+
+            continue;
+        }
+
+        let raw_tag = &html[start + 1 + name.len()..end];
+        let self_closed = raw_tag.trim_end().ends_with('/');
+
+        let mut element = HtmlElement::new(&name);
+        element.attributes = parse_attributes(raw_tag.trim_end().trim_end_matches('/'));
+
+        if RAW_TEXT_ELEMENTS.contains(&name.as_str()) {
+
+            // raw text elements content is discarded
+            let close_tag = format!("</{}", name);
+
+            position = html[position..].to_ascii_lowercase().find(&close_tag)
+                            .and_then(|index| tag_end(html, position + index))
+                            .map(|index| index + 1)
+                            .unwrap_or(html.len());
+
+            continue;
+        }
+
+        if self_closed || VOID_ELEMENTS.contains(&name.as_str()) {
+            stack.last_mut().unwrap().children.push(HtmlNode::Element(element));
+        } else {
+            stack.push(element);
+        }
+    }
+
+    push_text(&mut stack, &html[position..]);
+
+    while stack.len() > 1 {
+        close_element(&mut stack);
+    }
+
+    stack.pop().unwrap()
+}
+
+/// Block nodes, checkboxes are inline even if they are `div`
+pub fn is_block(node: &HtmlNode) -> bool {
+    match node {
+        HtmlNode::Element(element) => BLOCK_ELEMENTS.contains(&element.name.as_str()) && !element.has_class("checkbox"),
+        HtmlNode::Text(_) => false,
+    }
+}
+
+/// Type of focus block (e.g. `warning`), todo and quote blocks are focus blocks too
+pub fn focus_block_kind(element: &HtmlElement) -> Option<String> {
+
+    if element.has_class("focus-block") || element.has_class("focus-quote-block") {
+        return Some(element.classes().iter()
+                        .find_map(|class| class.strip_prefix("focus-block-").or(class.strip_prefix("focus-quote-block-")))
+                        .unwrap_or("quote")
+                        .to_string())
+    }
+
+    if element.has_class("todo") || element.has_class("abridged-todo") || element.has_class("multiline-todo") {
+        return Some(String::from("todo"))
+    }
+
+    None
+}
+
+/// Split focus block in its title (trimmed text of first `*-title` child, if it is not empty) and its content without titles
+pub fn split_focus_block_title(element: &HtmlElement) -> (Option<String>, HtmlElement) {
+
+    let is_title = |child: &HtmlNode| matches!(child, HtmlNode::Element(e) if e.classes().iter().any(|class| class.ends_with("-title")));
+
+    let mut content = element.clone();
+
+    let title = content.children.iter().find(|child| is_title(child)).map(|child| match child {
+        HtmlNode::Element(title) => title.text().trim().to_string(),
+        HtmlNode::Text(_) => String::new(),
+    });
+
+    content.children.retain(|child| !is_title(child));
+
+    (title.filter(|title| !title.is_empty()), content)
+}
+
+
+/// Numbered footnotes of link URLs, the same URL has always the same number
+#[derive(Debug, Clone, Default)]
+pub struct LinkFootnotes {
+    links: Vec<String>,
+}
+
+impl LinkFootnotes {
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of footnote of `url` (starting from 1)
+    pub fn footnote(&mut self, url: &str) -> usize {
+
+        if let Some(index) = self.links.iter().position(|link| link.eq(url)) {
+            return index + 1
+        }
+
+        self.links.push(url.to_string());
+
+        self.links.len()
+    }
+
+    pub fn links(&self) -> &Vec<String> {
+        &self.links
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.links.is_empty()
+    }
+}
+
+
+/// Slot of a table grid: a cell or the continuation of a cell of previous rows (i.e. a cell with `rowspan`)
+#[derive(Debug)]
+pub enum TableSlot<'a> {
+    Cell { element: &'a HtmlElement, span: usize, row_span: usize },
+    Continuation { span: usize },
+}
+
+impl TableSlot<'_> {
+    pub fn span(&self) -> usize {
+        match self {
+            Self::Cell { span, .. } | Self::Continuation { span } => *span,
+        }
+    }
+}
+
+/// Rows of table (flagged if they are header rows) and captions
+pub fn table_rows(element: &HtmlElement) -> (Vec<(&HtmlElement, bool)>, Vec<&HtmlElement>) {
+
+    let mut rows: Vec<(&HtmlElement, bool)> = Vec::new();
+    let mut captions: Vec<&HtmlElement> = Vec::new();
+
+    for child in element.child_elements() {
+        match child.name.as_str() {
+            "thead" => rows.extend(child.child_elements().filter(|row| row.name.eq("tr")).map(|row| (row, true))),
+            "tbody" | "tfoot" => rows.extend(child.child_elements().filter(|row| row.name.eq("tr")).map(|row| (row, false))),
+            "tr" => rows.push((child, false)),
+            "caption" => captions.push(child),
+            _ => (),
+        }
+    }
+
+    (rows, captions)
+}
+
+/// Push continuations of cells (of previous rows) which start at `column`
+fn push_continuations(slots: &mut Vec<TableSlot>, column: &mut usize, merges: &mut [(usize, usize)]) {
+    while *column < merges.len() && merges[*column].0 > 0 {
+
+        merges[*column].0 -= 1;
+
+        let span = merges[*column].1;
+
+        slots.push(TableSlot::Continuation { span });
+
+        *column += span;
+    }
+}
+
+/// Grid of table rows, cells which span more rows are continued in next rows
+pub fn layout_table<'a>(rows: &[&'a HtmlElement]) -> Vec<Vec<TableSlot<'a>>> {
+
+    // remaining rows and span of cells which span more rows, by column
+    let mut merges: Vec<(usize, usize)> = Vec::new();
+
+    let mut layout = Vec::new();
+
+    for row in rows {
+
+        let mut slots: Vec<TableSlot> = Vec::new();
+        let mut column: usize = 0;
+
+        for cell in row.child_elements().filter(|cell| cell.name.eq("td") || cell.name.eq("th")) {
+
+            push_continuations(&mut slots, &mut column, &mut merges);
+
+            let span: usize = cell.attribute("colspan").and_then(|span| span.parse().ok()).unwrap_or(1).max(1);
+            let row_span: usize = cell.attribute("rowspan").and_then(|span| span.parse().ok()).unwrap_or(1).max(1);
+
+            if merges.len() < column + span {
+                merges.resize(column + span, (0, 1));
+            }
+
+            if row_span > 1 {
+                merges[column] = (row_span - 1, span);
+            }
+
+            slots.push(TableSlot::Cell { element: cell, span, row_span });
+
+            column += span;
+        }
+
+        // continuations after last cell
+        push_continuations(&mut slots, &mut column, &mut merges);
+
+        layout.push(slots);
+    }
+
+    layout
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::{decode_entities, layout_table, parse_html, table_rows, HtmlNode, TableSlot};
+
+
+    #[test]
+    fn decode() {
+        assert_eq!(decode_entities("a&nbsp;b &amp; &#8226; &#x41; &unknown; & c"), "a\u{a0}b & \u{2022} A &unknown; & c");
+    }
+
+    #[test]
+    fn tolerant_parsing() {
+
+        let root = parse_html(r#"<p class="paragraph x">a <strong>b <em>c</strong></em> d<br>e</p><!-- comment --><hr><script>x < y</script><div>f"#);
+
+        assert_eq!(root.children.len(), 3);
+
+        let paragraph = root.child_elements().next().unwrap();
+
+        assert!(paragraph.has_class("x"));
+        assert_eq!(paragraph.text(), "a b c de");
+        assert_eq!(paragraph.child_elements().map(|element| element.name.as_str()).collect::<Vec<&str>>(), vec!["strong", "br"]);
+
+        match &root.children[2] {
+            HtmlNode::Element(div) => assert_eq!(div.text(), "f"),
+            HtmlNode::Text(_) => panic!("div expected"),
+        }
+    }
+
+    #[test]
+    fn table_layout() {
+
+        let table = parse_html(r#"<table><thead><tr><th>a</th><th>b</th></tr></thead><tbody><tr><td rowspan="2">c</td><td>d</td></tr><tr><td>e</td></tr></tbody><caption>t</caption></table>"#);
+
+        let table = table.child_elements().next().unwrap();
+
+        let (rows, captions) = table_rows(table);
+
+        assert_eq!(rows.iter().map(|(_, header)| *header).collect::<Vec<bool>>(), vec![true, false, false]);
+        assert_eq!(captions.len(), 1);
+
+        let layout = layout_table(&rows.iter().map(|(row, _)| *row).collect::<Vec<_>>());
+
+        assert!(matches!(layout[1][0], TableSlot::Cell { row_span: 2, .. }));
+        assert!(matches!(layout[2][0], TableSlot::Continuation { span: 1 }));
+        assert!(matches!(layout[2][1], TableSlot::Cell { element, .. } if element.text() == "e"));
+    }
+}
@@ -1,6 +1,13 @@
+use std::path::PathBuf;
+use base64::Engine;
+use once_cell::sync::Lazy;
 use oxipng::Options;
+use regex::Regex;
 use crate::{codex::{modifier::ModifiersBucket, Codex}, compilable_text::{compilable_text_part::CompilableTextPart, CompilableText}, compilation::{compilable::Compilable, compilation_configuration::{compilation_configuration_overlay::CompilationConfigurationOverLay, CompilationConfiguration}, compilation_error::CompilationError, compilation_outcome::CompilationOutcome}, output_format::OutputFormat, resource::{image_resource::ImageResource, source::Source, ResourceError}};
-use super::{nmd_unique_identifier::NmdUniqueIdentifier, text_utility};
+use super::{html_utility::escape_xml, nmd_unique_identifier::NmdUniqueIdentifier, text_utility};
+
+
+static DATA_URI_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"^data:([^;,]+)?((?:;[^;,]+)*?)(;base64)?,(.*)$").unwrap());
 
 
 pub fn set_image_base64_embed_src(image: &mut ImageResource, compression: bool) -> Result<(), ResourceError> {
@@ -47,7 +54,7 @@ pub fn compile_image_resource_in_html(image: &ImageResource, img_classes: Vec<&s
     let id_attr: String;
 
     if let Some(id) = image.id() {
-        id_attr = format!(r#"id="{}""#, id.build_without_internal_sharp());
+        id_attr = format!(r#"id="{}""#, escape_xml(&id.build_without_internal_sharp()));
     } else {
         id_attr = String::new();
     }
@@ -110,4 +117,112 @@ pub fn compile_image_resource_in_html(image: &ImageResource, img_classes: Vec<&s
     outcome.push_str("</figure>");
 
     Ok(CompilationOutcome::from(outcome))
-} 
\ No newline at end of file
+} 
+
+
+/// Media type of image, inferred by file extension or by content
+pub fn image_media_type(extension: &str, bytes: &[u8]) -> Option<String> {
+    match extension.to_lowercase().as_str() {
+        "png" => Some(String::from("image/png")),
+        "jpg" | "jpeg" => Some(String::from("image/jpeg")),
+        "gif" => Some(String::from("image/gif")),
+        "svg" => Some(String::from("image/svg+xml")),
+        "webp" => Some(String::from("image/webp")),
+        _ => infer::get(bytes).map(|kind| kind.mime_type().to_string()),
+    }
+}
+
+pub fn media_type_extension(media_type: &str) -> &str {
+    match media_type {
+        "image/jpeg" => "jpg",
+        "image/svg+xml" => "svg",
+        _ => media_type.rsplit('/').next().unwrap_or("bin"),
+    }
+}
+
+/// Read media type and bytes of a compiled image `src`, i.e. a data URI or a local path
+pub fn read_image_src(src: &str) -> Option<(String, Vec<u8>)> {
+
+    if let Some(captures) = DATA_URI_REGEX.captures(src) {
+
+        let media_type = captures.get(1).map(|m| m.as_str().to_string()).unwrap_or(String::from("text/plain"));
+        let data = captures.get(4).unwrap().as_str();
+
+        let bytes = if captures.get(3).is_some() {
+            base64::engine::general_purpose::STANDARD.decode(data).ok()?
+        } else {
+            data.as_bytes().to_vec()
+        };
+
+        return Some((media_type, bytes))
+    }
+
+    let path = PathBuf::from(src.trim_start_matches("file://"));
+
+    let bytes = std::fs::read(&path).ok()?;
+
+    let extension = path.extension().map(|extension| extension.to_string_lossy().to_string()).unwrap_or_default();
+
+    Some((image_media_type(&extension, &bytes)?, bytes))
+}
+
+/// Download remote image, returning its media type and bytes
+pub fn download_image(url: &str) -> Result<(String, Vec<u8>), ResourceError> {
+
+    let response = reqwest::blocking::get(url)
+                        .and_then(|response| response.error_for_status())
+                        .map_err(|err| ResourceError::ReadError(format!("{} ({})", url, err)))?;
+
+    let content_type = response.headers().get(reqwest::header::CONTENT_TYPE)
+                            .and_then(|content_type| content_type.to_str().ok())
+                            .map(|content_type| content_type.split(';').next().unwrap().trim().to_string())
+                            .filter(|content_type| content_type.starts_with("image/"));
+
+    let bytes = response.bytes().map_err(|err| ResourceError::ReadError(format!("{} ({})", url, err)))?.to_vec();
+
+    let extension = url.split(['?', '#']).next().unwrap().rsplit_once('.').map(|(_, extension)| extension).unwrap_or_default();
+
+    let media_type = content_type.or(image_media_type(extension, &bytes))
+                        .ok_or(ResourceError::InvalidResourceVerbose(format!("'{}' is not an image", url)))?;
+
+    Ok((media_type, bytes))
+}
+
+/// Width and height (in pixels) of PNG, GIF and JPEG images
+pub fn image_dimensions(bytes: &[u8]) -> Option<(u32, u32)> {
+
+    let be_u16 = |index: usize| -> Option<u32> { Some(u16::from_be_bytes([*bytes.get(index)?, *bytes.get(index + 1)?]) as u32) };
+    let le_u16 = |index: usize| -> Option<u32> { Some(u16::from_le_bytes([*bytes.get(index)?, *bytes.get(index + 1)?]) as u32) };
+    let be_u32 = |index: usize| -> Option<u32> { Some(u32::from_be_bytes(bytes.get(index..index + 4)?.try_into().ok()?)) };
+
+    if bytes.starts_with(b"\x89PNG") {
+        return Some((be_u32(16)?, be_u32(20)?))
+    }
+
+    if bytes.starts_with(b"GIF8") {
+        return Some((le_u16(6)?, le_u16(8)?))
+    }
+
+    if bytes.starts_with(&[0xFF, 0xD8]) {
+
+        let mut index: usize = 2;
+
+        while index + 9 < bytes.len() {
+
+            if bytes[index] != 0xFF {
+                return None
+            }
+
+            let marker = bytes[index + 1];
+
+            // start of frame markers (excluding DHT, JPG and DAC)
+            if (0xC0..=0xCF).contains(&marker) && ![0xC4, 0xC8, 0xCC].contains(&marker) {
+                return Some((be_u16(index + 7)?, be_u16(index + 5)?))
+            }
+
+            index += 2 + be_u16(index + 2)? as usize;
+        }
+    }
+
+    None
+}
@@ -0,0 +1,45 @@
+use std::fs;
+use std::ops::Deref;
+use std::path::PathBuf;
+
+
+/// Empty directory under system temporary directory, unique for name and process.
+/// Directory (and its content) is removed when dropped, also if test panics
+#[derive(Debug)]
+pub struct TempDir {
+    path: PathBuf,
+}
+
+impl TempDir {
+
+    pub fn new(name: &str) -> Self {
+
+        let path = std::env::temp_dir().join(format!("nmd-test-{}-{}", name, std::process::id()));
+
+        let _ = fs::remove_dir_all(&path);
+
+        fs::create_dir_all(&path).unwrap();
+
+        Self {
+            path,
+        }
+    }
+
+    pub fn path(&self) -> &PathBuf {
+        &self.path
+    }
+}
+
+impl Deref for TempDir {
+    type Target = PathBuf;
+
+    fn deref(&self) -> &Self::Target {
+        &self.path
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
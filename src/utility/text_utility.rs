@@ -17,6 +17,30 @@ pub fn replace(content: &str, replacements: &Vec<(Regex, String)>) -> String {
     result
 }
 
+/// Levenshtein distance between two strings (in chars)
+pub fn edit_distance(a: &str, b: &str) -> usize {
+
+    let b_chars: Vec<char> = b.chars().collect();
+
+    let mut previous_row: Vec<usize> = (0..=b_chars.len()).collect();
+
+    for (i, a_char) in a.chars().enumerate() {
+
+        let mut current_row = vec![i + 1];
+
+        for (j, b_char) in b_chars.iter().enumerate() {
+
+            let substitution = previous_row[j] + if a_char.eq(b_char) { 0 } else { 1 };
+
+            current_row.push(substitution.min(previous_row[j + 1] + 1).min(current_row[j] + 1));
+        }
+
+        previous_row = current_row;
+    }
+
+    previous_row[b_chars.len()]
+}
+
 pub fn html_nuid_tag_or_nothing(nuid: Option<&NmdUniqueIdentifier>) -> String {
     if let Some(nuid) = nuid {
         return format!(r#" data-nuid="{}""#, nuid);
@@ -92,4 +116,12 @@ mod test {
         assert_eq!(styles.unwrap(), "style1:value1; style2:value2;");
         assert_eq!(classes, None);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn edit_distance() {
+        assert_eq!(super::edit_distance("documents", "documents"), 0);
+        assert_eq!(super::edit_distance("documnets", "documents"), 2);
+        assert_eq!(super::edit_distance("stlye", "style"), 2);
+        assert_eq!(super::edit_distance("", "toc"), 3);
+    }
+}
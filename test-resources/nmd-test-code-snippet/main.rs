@@ -0,0 +1,5 @@
+fn main() {
+    // #region greet
+    println!("hello");
+    // #endregion
+}